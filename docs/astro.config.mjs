@@ -46,6 +46,9 @@ export default defineConfig({
           items: [
             { label: "Keyring", slug: "providers/keyring" },
             { label: "Dotenv", slug: "providers/dotenv" },
+            { label: "age", slug: "providers/age" },
+            { label: "SOPS", slug: "providers/sops" },
+            { label: "Vault / OpenBao", slug: "providers/vault" },
             { label: "Environment Variables", slug: "providers/env" },
             { label: "LastPass", slug: "providers/lastpass" },
             { label: "1Password", slug: "providers/onepassword" },
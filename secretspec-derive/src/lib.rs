@@ -23,7 +23,8 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use secretspec::{Config, Secret};
 use std::collections::{BTreeMap, HashSet};
-use syn::{LitStr, parse_macro_input};
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token, parse_macro_input};
 
 /// Holds metadata about a field in the generated struct.
 ///
@@ -35,13 +36,15 @@ use syn::{LitStr, parse_macro_input};
 /// # Fields
 ///
 /// * `name` - The original secret name (e.g., "DATABASE_URL")
-/// * `field_type` - The Rust type for this field (String or Option<String>)
+/// * `field_type` - The Rust type for this field (String or Option<String>, see [`secret_value_type`])
 /// * `is_optional` - Whether this field is optional across all profiles
+/// * `description` - The secret's description, if any profile declares one
 #[derive(Clone)]
 struct FieldInfo {
     name: String,
     field_type: proc_macro2::TokenStream,
     is_optional: bool,
+    description: Option<String>,
 }
 
 impl FieldInfo {
@@ -50,13 +53,20 @@ impl FieldInfo {
     /// # Arguments
     ///
     /// * `name` - The secret name as defined in the config
-    /// * `field_type` - The generated Rust type (String or Option<String>)
+    /// * `field_type` - The generated Rust type (String or Option<String>, see [`secret_value_type`])
     /// * `is_optional` - Whether the field should be optional
-    fn new(name: String, field_type: proc_macro2::TokenStream, is_optional: bool) -> Self {
+    /// * `description` - The description to expose through `SecretSpec::description`
+    fn new(
+        name: String,
+        field_type: proc_macro2::TokenStream,
+        is_optional: bool,
+        description: Option<String>,
+    ) -> Self {
         Self {
             name,
             field_type,
             is_optional,
+            description,
         }
     }
 
@@ -76,7 +86,8 @@ impl FieldInfo {
 
     /// Generate the struct field declaration.
     ///
-    /// Creates a public field declaration for use in the generated struct.
+    /// Creates a public field declaration for use in the generated struct,
+    /// documented with the secret's description.
     ///
     /// # Returns
     ///
@@ -85,13 +96,15 @@ impl FieldInfo {
     /// # Example Output
     ///
     /// ```ignore
+    /// #[doc = "Database connection string"]
     /// pub database_url: String
     /// pub api_key: Option<String>
     /// ```
     fn generate_struct_field(&self) -> proc_macro2::TokenStream {
         let field_name = self.field_name();
         let field_type = &self.field_type;
-        quote! { pub #field_name: #field_type }
+        let doc = generate_doc(self.description.as_deref());
+        quote! { #doc pub #field_name: #field_type }
     }
 
     /// Generate a field assignment from a secrets map.
@@ -110,16 +123,71 @@ impl FieldInfo {
         generate_secret_assignment(&self.field_name(), &self.name, source, self.is_optional)
     }
 
+    /// Generate a redacted `Debug` field entry.
+    ///
+    /// Creates a `.field(...)` call for a `debug_struct` builder that never
+    /// exposes the secret value. See [`generate_redacted_debug_field`].
+    fn generate_debug_field(&self) -> proc_macro2::TokenStream {
+        let field_name = self.field_name();
+        generate_redacted_debug_field(&field_name, quote! { self.#field_name }, self.is_optional)
+    }
+
+    /// Generate a match arm for `SecretSpecSecretsExt::get`.
+    ///
+    /// Maps the original secret name to the field value as a `String`,
+    /// wrapping required fields in `Some`. With the `zeroize` option fields
+    /// are not plain strings, so the value is copied out through `as_str`.
+    ///
+    /// # Example Output
+    ///
+    /// ```ignore
+    /// "DATABASE_URL" => Some(self.database_url.as_str().to_owned())
+    /// "API_KEY" => self.api_key.as_ref().map(|value| value.as_str().to_owned())
+    /// ```
+    fn generate_get_arm(&self) -> proc_macro2::TokenStream {
+        let field_name = self.field_name();
+        let name = &self.name;
+
+        if self.is_optional {
+            quote! { #name => self.#field_name.as_ref().map(|value| value.as_str().to_owned()) }
+        } else {
+            quote! { #name => Some(self.#field_name.as_str().to_owned()) }
+        }
+    }
+
+    /// Generate a field initializer for `SecretSpec::merge`.
+    ///
+    /// Optional fields fall back to `other` when `self` has no value;
+    /// required fields always have one, so `self` is kept.
+    ///
+    /// # Example Output
+    ///
+    /// ```ignore
+    /// database_url: self.database_url
+    /// api_key: self.api_key.or(other.api_key)
+    /// ```
+    fn generate_merge_field(&self) -> proc_macro2::TokenStream {
+        let field_name = self.field_name();
+
+        if self.is_optional {
+            quote! { #field_name: self.#field_name.or(other.#field_name) }
+        } else {
+            quote! { #field_name: self.#field_name }
+        }
+    }
+
     /// Generate environment variable setter.
     ///
     /// Creates code to set an environment variable from this field's value.
     /// For optional fields, only sets the variable if a value is present.
+    /// The variable name is the secret name prefixed with a `prefix: &str`
+    /// that must be in scope where the code is used.
     ///
     /// # Safety
     ///
     /// The generated code uses `unsafe` because `std::env::set_var` is unsafe
     /// in multi-threaded contexts. Users should ensure thread safety when calling
-    /// the generated `set_as_env_vars` method.
+    /// the generated `set_as_env_vars` and `set_as_env_vars_with_prefix` methods.
     ///
     /// # Returns
     ///
@@ -132,14 +200,14 @@ impl FieldInfo {
             quote! {
                 if let Some(ref value) = self.#field_name {
                     unsafe {
-                        std::env::set_var(#env_name, value);
+                        std::env::set_var(format!("{}{}", prefix, #env_name), value);
                     }
                 }
             }
         } else {
             quote! {
                 unsafe {
-                    std::env::set_var(#env_name, &self.#field_name);
+                    std::env::set_var(format!("{}{}", prefix, #env_name), &self.#field_name);
                 }
             }
         }
@@ -189,6 +257,32 @@ impl ProfileVariant {
     }
 }
 
+/// Arguments of `declare_secrets!`: the path of `secretspec.toml`,
+/// optionally followed by `zeroize`.
+struct MacroInput {
+    path: LitStr,
+    zeroize: bool,
+}
+
+impl Parse for MacroInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut zeroize = false;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let option: Ident = input.parse()?;
+            if option != "zeroize" {
+                return Err(syn::Error::new(
+                    option.span(),
+                    "unknown option, expected `zeroize`",
+                ));
+            }
+            zeroize = true;
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { path, zeroize })
+    }
+}
+
 /// Generates typed SecretSpec structs from your secretspec.toml file.
 ///
 /// # Example
@@ -221,9 +315,16 @@ impl ProfileVariant {
 ///     Ok(())
 /// }
 /// ```
+///
+/// # Zeroizing values
+///
+/// `declare_secrets!("secretspec.toml", zeroize)` makes the fields
+/// `secretspec::Zeroizing<String>`, which overwrite their value in memory
+/// when dropped. It requires secretspec's `zeroize` feature.
 #[proc_macro]
 pub fn declare_secrets(input: TokenStream) -> TokenStream {
-    let path = parse_macro_input!(input as LitStr).value();
+    let input = parse_macro_input!(input as MacroInput);
+    let path = input.path.value();
 
     // Get the manifest directory of the crate using the macro
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
@@ -247,7 +348,7 @@ pub fn declare_secrets(input: TokenStream) -> TokenStream {
     }
 
     // Generate all the code
-    let output = generate_secret_spec_code(config);
+    let output = generate_secret_spec_code(config, input.zeroize);
     output.into()
 }
 
@@ -443,7 +544,8 @@ fn field_name_ident(name: &str) -> proc_macro2::Ident {
 ///
 /// A secret is considered optional if:
 /// - It has `required = false` in the config, OR
-/// - It has a default value specified
+/// - It has a default value specified, OR
+/// - It is only conditionally required via `required_if`
 ///
 /// # Arguments
 ///
@@ -453,7 +555,9 @@ fn field_name_ident(name: &str) -> proc_macro2::Ident {
 ///
 /// `true` if the secret is optional, `false` if required
 fn is_secret_optional(secret_config: &Secret) -> bool {
-    !secret_config.required || secret_config.default.is_some()
+    !secret_config.required
+        || secret_config.default.is_some()
+        || secret_config.required_if.is_some()
 }
 
 /// Determines if a field should be optional across all profiles.
@@ -535,6 +639,122 @@ fn generate_secret_assignment(
     }
 }
 
+/// Generates a redacted `Debug` field entry for a secret.
+///
+/// Required fields are always printed as `"<redacted>"`. Optional fields keep
+/// their `Some`/`None` shape so it is still visible which secrets were loaded,
+/// but the contained value is replaced with `"<redacted>"`.
+///
+/// # Arguments
+///
+/// * `field_name` - The field identifier, also used as the printed label
+/// * `value` - Expression evaluating to the field value (or a reference to it)
+/// * `is_optional` - Whether the field is an `Option<String>`
+///
+/// # Generated Code Example
+///
+/// ```ignore
+/// .field("database_url", &"<redacted>")
+/// .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+/// ```
+fn generate_redacted_debug_field(
+    field_name: &proc_macro2::Ident,
+    value: proc_macro2::TokenStream,
+    is_optional: bool,
+) -> proc_macro2::TokenStream {
+    let label = field_name.to_string();
+    if is_optional {
+        quote! { .field(#label, &#value.as_ref().map(|_| "<redacted>")) }
+    } else {
+        quote! { .field(#label, &"<redacted>") }
+    }
+}
+
+/// Returns the type of a secret's value in generated code.
+///
+/// `String`, or with the `zeroize` option `secretspec::Zeroizing<String>`,
+/// which overwrites the value in memory when it is dropped.
+fn secret_value_type(zeroize: bool) -> proc_macro2::TokenStream {
+    if zeroize {
+        quote! { secretspec::Zeroizing<String> }
+    } else {
+        quote! { String }
+    }
+}
+
+/// Generates a `#[doc]` attribute carrying `description`.
+///
+/// # Returns
+///
+/// An empty token stream if there is no non-empty description
+fn generate_doc(description: Option<&str>) -> proc_macro2::TokenStream {
+    match description {
+        Some(description) if !description.is_empty() => quote! { #[doc = #description] },
+        _ => quote! {},
+    }
+}
+
+/// Generates a compile-time warning for every secret without a description.
+///
+/// Stable proc macros cannot emit warnings directly, so each warning is the
+/// deprecation notice of a constant that the generated code then uses.
+///
+/// # Arguments
+///
+/// * `field_info` - Field information, whose descriptions are checked
+///
+/// # Returns
+///
+/// An empty token stream if every secret has a description
+fn generate_description_warnings(
+    field_info: &BTreeMap<String, FieldInfo>,
+) -> proc_macro2::TokenStream {
+    let warnings = field_info
+        .values()
+        .filter(|info| info.description.is_none())
+        .map(|info| {
+            let note = format!(
+                "secret `{}` has an empty description in secretspec.toml, so `SecretSpec::description` returns `None` for it",
+                info.name
+            );
+            quote! {
+                const _: () = {
+                    #[deprecated(note = #note)]
+                    const EMPTY_DESCRIPTION: () = ();
+                    EMPTY_DESCRIPTION
+                };
+            }
+        });
+    quote! { #(#warnings)* }
+}
+
+/// Determines the description of a secret across profiles.
+///
+/// Profiles that override a secret often omit its description, so the
+/// `default` profile's description is preferred, falling back to the first
+/// non-empty description in profile name order.
+///
+/// # Arguments
+///
+/// * `secret_name` - The name of the secret
+/// * `config` - The project configuration
+///
+/// # Returns
+///
+/// The description, or `None` if no profile gives a non-empty one
+fn secret_description(secret_name: &str, config: &Config) -> Option<String> {
+    let mut profile_names: Vec<&String> = config.profiles.keys().collect();
+    profile_names.sort_by_key(|name| (name.as_str() != "default", name.as_str()));
+
+    profile_names
+        .into_iter()
+        .filter_map(|name| config.profiles[name].secrets.get(secret_name))
+        .filter_map(|secret| secret.description.as_deref())
+        .map(str::trim)
+        .find(|description| !description.is_empty())
+        .map(str::to_string)
+}
+
 /// Analyzes all profiles to determine field types for the union struct.
 ///
 /// This function examines all secrets across all profiles to determine:
@@ -545,6 +765,7 @@ fn generate_secret_assignment(
 /// # Arguments
 ///
 /// * `config` - The project configuration
+/// * `zeroize` - Whether values are `secretspec::Zeroizing<String>`
 ///
 /// # Returns
 ///
@@ -554,9 +775,9 @@ fn generate_secret_assignment(
 ///
 /// 1. Collect all unique secret names from all profiles
 /// 2. For each secret, determine if it's optional across profiles
-/// 3. Generate appropriate type (String or Option<String>)
+/// 3. Generate appropriate type (String or Option<String>, see [`secret_value_type`])
 /// 4. Create FieldInfo with all metadata needed for code generation
-fn analyze_field_types(config: &Config) -> BTreeMap<String, FieldInfo> {
+fn analyze_field_types(config: &Config, zeroize: bool) -> BTreeMap<String, FieldInfo> {
     let mut field_info = BTreeMap::new();
 
     // Collect all unique secrets across all profiles
@@ -564,12 +785,18 @@ fn analyze_field_types(config: &Config) -> BTreeMap<String, FieldInfo> {
         for secret_name in profile_config.secrets.keys() {
             field_info.entry(secret_name.clone()).or_insert_with(|| {
                 let is_optional = is_field_optional_across_profiles(secret_name, config);
+                let value_type = secret_value_type(zeroize);
                 let field_type = if is_optional {
-                    quote! { Option<String> }
+                    quote! { Option<#value_type> }
                 } else {
-                    quote! { String }
+                    value_type
                 };
-                FieldInfo::new(secret_name.clone(), field_type, is_optional)
+                FieldInfo::new(
+                    secret_name.clone(),
+                    field_type,
+                    is_optional,
+                    secret_description(secret_name, config),
+                )
             });
         }
     }
@@ -577,6 +804,27 @@ fn analyze_field_types(config: &Config) -> BTreeMap<String, FieldInfo> {
     field_info
 }
 
+/// Get the names of the profiles that can be selected.
+///
+/// Abstract profiles only provide secrets to other profiles, so they get
+/// no `Profile` variant.
+///
+/// # Arguments
+///
+/// * `config` - The parsed configuration
+///
+/// # Returns
+///
+/// The names of all non-abstract profiles
+fn selectable_profiles(config: &Config) -> HashSet<String> {
+    config
+        .profiles
+        .iter()
+        .filter(|(_, profile)| !profile.is_abstract)
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
 /// Get normalized profile variants for enum generation.
 ///
 /// Converts profile names into ProfileVariant structs, handling the special
@@ -588,12 +836,13 @@ fn analyze_field_types(config: &Config) -> BTreeMap<String, FieldInfo> {
 ///
 /// # Returns
 ///
-/// A sorted vector of ProfileVariant structs
+/// A vector of ProfileVariant structs, `default` first and the rest sorted
+/// by name
 ///
 /// # Special Cases
 ///
 /// - Empty profiles → returns vec![ProfileVariant("default", "Default")]
-/// - Otherwise → sorted list of profile variants
+/// - Otherwise → `default` (if defined), then the other variants by name
 fn get_profile_variants(profiles: &HashSet<String>) -> Vec<ProfileVariant> {
     if profiles.is_empty() {
         vec![ProfileVariant::new("default".to_string())]
@@ -602,7 +851,8 @@ fn get_profile_variants(profiles: &HashSet<String>) -> Vec<ProfileVariant> {
             .iter()
             .map(|name| ProfileVariant::new(name.clone()))
             .collect();
-        variants.sort_by(|a, b| a.name.cmp(&b.name));
+        variants
+            .sort_by(|a, b| (a.name != "default", &a.name).cmp(&(b.name != "default", &b.name)));
         variants
     }
 }
@@ -613,7 +863,7 @@ fn get_profile_variants(profiles: &HashSet<String>) -> Vec<ProfileVariant> {
 ///
 /// This module handles:
 /// - Profile enum definition
-/// - TryFrom implementations for string conversion
+/// - all() and name() methods for enumerating and serializing profiles
 /// - as_str() method for profile serialization
 mod profile_generation {
     use super::*;
@@ -631,15 +881,19 @@ mod profile_generation {
     /// ```ignore
     /// #[derive(Debug, Clone, Copy)]
     /// pub enum Profile {
+    ///     /// The `development` profile
     ///     Development,
+    ///     /// The `production` profile
     ///     Production,
+    ///     /// The `staging` profile
     ///     Staging,
     /// }
     /// ```
     pub fn generate_enum(variants: &[ProfileVariant]) -> proc_macro2::TokenStream {
         let enum_variants = variants.iter().map(|v| {
             let ident = v.as_ident();
-            quote! { #ident }
+            let doc = format!("The `{}` profile", v.name);
+            quote! { #[doc = #doc] #ident }
         });
 
         quote! {
@@ -693,9 +947,10 @@ mod profile_generation {
         }
     }
 
-    /// Generate as_str implementation for Profile.
+    /// Generate the inherent methods of Profile.
     ///
-    /// Creates a method to convert Profile enum variants back to their string representation.
+    /// Creates `all()` to enumerate the profiles at runtime and `name()` to
+    /// convert a variant back to its string representation.
     ///
     /// # Arguments
     ///
@@ -705,15 +960,27 @@ mod profile_generation {
     ///
     /// ```ignore
     /// impl Profile {
-    ///     fn as_str(&self) -> &'static str {
+    ///     pub fn all() -> &'static [Profile] {
+    ///         &[Profile::Development, Profile::Production]
+    ///     }
+    ///
+    ///     pub fn name(&self) -> &'static str {
     ///         match self {
     ///             Profile::Development => "development",
     ///             Profile::Production => "production",
     ///         }
     ///     }
+    ///
+    ///     fn as_str(&self) -> &'static str {
+    ///         self.name()
+    ///     }
     /// }
     /// ```
-    pub fn generate_as_str_impl(variants: &[ProfileVariant]) -> proc_macro2::TokenStream {
+    pub fn generate_methods(variants: &[ProfileVariant]) -> proc_macro2::TokenStream {
+        let all_variants = variants.iter().map(|v| {
+            let ident = v.as_ident();
+            quote! { Profile::#ident }
+        });
         let to_str_arms = variants.iter().map(|v| {
             let ident = v.as_ident();
             let str_val = &v.name;
@@ -722,11 +989,21 @@ mod profile_generation {
 
         quote! {
             impl Profile {
-                fn as_str(&self) -> &'static str {
+                /// Returns every profile: `default` first, then the others sorted by name
+                pub fn all() -> &'static [Profile] {
+                    &[#(#all_variants,)*]
+                }
+
+                /// Returns the profile's name as written in secretspec.toml
+                pub fn name(&self) -> &'static str {
                     match self {
                         #(#to_str_arms,)*
                     }
                 }
+
+                fn as_str(&self) -> &'static str {
+                    self.name()
+                }
             }
         }
     }
@@ -744,16 +1021,16 @@ mod profile_generation {
     /// Complete token stream containing:
     /// - Profile enum definition
     /// - TryFrom implementations
-    /// - as_str() method
+    /// - all(), name() and as_str() methods
     pub fn generate_all(variants: &[ProfileVariant]) -> proc_macro2::TokenStream {
         let enum_def = generate_enum(variants);
         let try_from_impls = generate_try_from_impls(variants);
-        let as_str_impl = generate_as_str_impl(variants);
+        let methods = generate_methods(variants);
 
         quote! {
             #enum_def
             #try_from_impls
-            #as_str_impl
+            #methods
         }
     }
 }
@@ -782,21 +1059,77 @@ mod secret_spec_generation {
     /// # Generated Code Example
     ///
     /// ```ignore
-    /// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    /// #[derive(serde::Serialize, serde::Deserialize)]
     /// pub struct SecretSpec {
     ///     pub database_url: String,
     ///     pub api_key: Option<String>,
     ///     pub redis_url: Option<String>,
     /// }
+    ///
+    /// impl std::fmt::Debug for SecretSpec {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         f.debug_struct("SecretSpec")
+    ///             .field("database_url", &"<redacted>")
+    ///             .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+    ///             .field("redis_url", &self.redis_url.as_ref().map(|_| "<redacted>"))
+    ///             .finish()
+    ///     }
+    /// }
     /// ```
+    ///
+    /// `Debug` is implemented by hand so that secret values never end up in
+    /// logs or panic messages.
     pub fn generate_struct(field_info: &BTreeMap<String, FieldInfo>) -> proc_macro2::TokenStream {
         let fields = field_info.values().map(|info| info.generate_struct_field());
+        let debug_fields = field_info.values().map(|info| info.generate_debug_field());
 
         quote! {
-            #[derive(Debug, serde::Serialize, serde::Deserialize)]
+            #[derive(serde::Serialize, serde::Deserialize)]
             pub struct SecretSpec {
                 #(#fields,)*
             }
+
+            impl std::fmt::Debug for SecretSpec {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("SecretSpec")
+                        #(#debug_fields)*
+                        .finish()
+                }
+            }
+        }
+    }
+
+    /// Generate the `SecretSpecSecretsExt` implementation for SecretSpec.
+    ///
+    /// Allows looking up secrets by their original name at runtime.
+    ///
+    /// # Generated Code Example
+    ///
+    /// ```ignore
+    /// impl secretspec::SecretSpecSecretsExt for SecretSpec {
+    ///     fn get(&self, name: &str) -> Option<String> {
+    ///         match name {
+    ///             "DATABASE_URL" => Some(self.database_url.clone()),
+    ///             "API_KEY" => self.api_key.clone(),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn generate_secrets_ext_impl(
+        field_info: &BTreeMap<String, FieldInfo>,
+    ) -> proc_macro2::TokenStream {
+        let get_arms = field_info.values().map(|info| info.generate_get_arm());
+
+        quote! {
+            impl secretspec::SecretSpecSecretsExt for SecretSpec {
+                fn get(&self, name: &str) -> Option<String> {
+                    match name {
+                        #(#get_arms,)*
+                        _ => None,
+                    }
+                }
+            }
         }
     }
 
@@ -813,7 +1146,7 @@ mod secret_spec_generation {
     /// # Generated Code Example
     ///
     /// ```ignore
-    /// #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    /// #[derive(serde::Serialize, serde::Deserialize)]
     /// pub enum SecretSpecProfile {
     ///     Development {
     ///         database_url: String,
@@ -826,14 +1159,92 @@ mod secret_spec_generation {
     ///     },
     /// }
     /// ```
+    ///
+    /// Like `SecretSpec`, the `Debug` impl is generated from `debug_arms`
+    /// and redacts every secret value.
     pub fn generate_profile_enum(
         profile_variants: &[proc_macro2::TokenStream],
+        debug_arms: &[proc_macro2::TokenStream],
     ) -> proc_macro2::TokenStream {
         quote! {
-            #[derive(Debug, serde::Serialize, serde::Deserialize)]
+            #[derive(serde::Serialize, serde::Deserialize)]
             pub enum SecretSpecProfile {
                 #(#profile_variants,)*
             }
+
+            impl std::fmt::Debug for SecretSpecProfile {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(#debug_arms,)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate the redacted `Debug` match arms for SecretSpecProfile.
+    ///
+    /// Mirrors [`generate_profile_enum_variants`], producing one arm per
+    /// variant that prints the variant's fields without their values.
+    ///
+    /// # Generated Code Example
+    ///
+    /// ```ignore
+    /// SecretSpecProfile::Development { database_url: _, redis_url } => f
+    ///     .debug_struct("Development")
+    ///     .field("database_url", &"<redacted>")
+    ///     .field("redis_url", &redis_url.as_ref().map(|_| "<redacted>"))
+    ///     .finish()
+    /// ```
+    pub fn generate_profile_debug_arms(
+        config: &Config,
+        field_info: &BTreeMap<String, FieldInfo>,
+        variants: &[ProfileVariant],
+    ) -> Vec<proc_macro2::TokenStream> {
+        let generate_arm = |variant_ident: proc_macro2::Ident, fields: Vec<(String, bool)>| {
+            let label = variant_ident.to_string();
+            let bindings = fields.iter().map(|(name, is_optional)| {
+                let field_name = field_name_ident(name);
+                if *is_optional {
+                    quote! { #field_name }
+                } else {
+                    quote! { #field_name: _ }
+                }
+            });
+            let debug_fields = fields.iter().map(|(name, is_optional)| {
+                let field_name = field_name_ident(name);
+                generate_redacted_debug_field(&field_name, quote! { #field_name }, *is_optional)
+            });
+            quote! {
+                SecretSpecProfile::#variant_ident { #(#bindings,)* } => f
+                    .debug_struct(#label)
+                    #(#debug_fields)*
+                    .finish()
+            }
+        };
+
+        if config.profiles.is_empty() {
+            let fields = field_info
+                .values()
+                .map(|info| (info.name.clone(), info.is_optional))
+                .collect();
+            vec![generate_arm(format_ident!("Default"), fields)]
+        } else {
+            variants
+                .iter()
+                .filter_map(|variant| {
+                    config.profiles.get(&variant.name).map(|profile_config| {
+                        let fields = profile_config
+                            .secrets
+                            .iter()
+                            .map(|(secret_name, secret_config)| {
+                                (secret_name.clone(), is_secret_optional(secret_config))
+                            })
+                            .collect();
+                        generate_arm(variant.as_ident(), fields)
+                    })
+                })
+                .collect()
         }
     }
 
@@ -847,6 +1258,7 @@ mod secret_spec_generation {
     /// * `config` - The project configuration
     /// * `field_info` - Field information (used for empty profile case)
     /// * `variants` - Profile variants to generate
+    /// * `zeroize` - Whether values are `secretspec::Zeroizing<String>`
     ///
     /// # Returns
     ///
@@ -860,6 +1272,7 @@ mod secret_spec_generation {
         config: &Config,
         field_info: &BTreeMap<String, FieldInfo>,
         variants: &[ProfileVariant],
+        zeroize: bool,
     ) -> Vec<proc_macro2::TokenStream> {
         if config.profiles.is_empty() {
             // If no profiles, create a Default variant with all fields
@@ -881,12 +1294,25 @@ mod secret_spec_generation {
                                 .iter()
                                 .map(|(secret_name, secret_config)| {
                                     let field_name = field_name_ident(secret_name);
+                                    let value_type = secret_value_type(zeroize);
                                     let field_type = if is_secret_optional(secret_config) {
-                                        quote! { Option<String> }
+                                        quote! { Option<#value_type> }
                                     } else {
-                                        quote! { String }
+                                        value_type
                                     };
-                                    quote! { #field_name: #field_type }
+                                    // Overrides often leave the description to
+                                    // the default profile
+                                    let description = secret_config
+                                        .description
+                                        .clone()
+                                        .filter(|description| !description.is_empty())
+                                        .or_else(|| {
+                                            field_info
+                                                .get(secret_name)
+                                                .and_then(|info| info.description.clone())
+                                        });
+                                    let doc = generate_doc(description.as_deref());
+                                    quote! { #doc #field_name: #field_type }
                                 });
 
                         quote! {
@@ -974,17 +1400,45 @@ mod secret_spec_generation {
 
     /// Generate the shared load_internal implementation.
     ///
-    /// Creates a helper function that handles the common loading logic
+    /// Creates helper functions that handle the common loading logic
     /// for both SecretSpec and SecretSpecProfile loading methods.
     ///
-    /// # Generated Function
+    /// # Arguments
+    ///
+    /// * `zeroize` - Whether values are `secretspec::Zeroizing<String>`
     ///
-    /// The function:
+    /// # Generated Functions
+    ///
+    /// `load_internal`:
     /// 1. Loads the SecretSpec configuration
     /// 2. Validates it with the given provider and profile
     /// 3. Returns the validation result containing loaded secrets
-    pub fn generate_load_internal() -> proc_macro2::TokenStream {
+    ///
+    /// `secret_values` converts the loaded `secretspec::SecretValue`s to the
+    /// field type. The `zeroize` option requires the `zeroize` feature, which
+    /// makes them `Zeroizing<String>` already, so they are moved without
+    /// copying. Otherwise they are copied out as `String`s, which works with
+    /// or without the feature.
+    pub fn generate_load_internal(zeroize: bool) -> proc_macro2::TokenStream {
+        let value_type = secret_value_type(zeroize);
+        let convert = if zeroize {
+            quote! { secrets }
+        } else {
+            quote! {
+                secrets
+                    .into_iter()
+                    .map(|(name, value)| (name, value.as_str().to_owned()))
+                    .collect()
+            }
+        };
+
         quote! {
+            fn secret_values(
+                secrets: std::collections::HashMap<String, secretspec::SecretValue>,
+            ) -> std::collections::HashMap<String, #value_type> {
+                #convert
+            }
+
             fn load_internal(
                 provider_str: Option<String>,
                 profile_str: Option<String>,
@@ -998,9 +1452,7 @@ mod secret_spec_generation {
                 }
                 match spec.validate()? {
                     Ok(valid_secrets) => Ok(valid_secrets),
-                    Err(validation_errors) => Err(secretspec::SecretSpecError::RequiredSecretMissing(
-                        validation_errors.missing_required.join(", ")
-                    ))
+                    Err(validation_errors) => Err(validation_errors.into_error())
                 }
             }
         }
@@ -1017,18 +1469,29 @@ mod secret_spec_generation {
     ///
     /// * `load_assignments` - Field assignments for the load method
     /// * `env_setters` - Environment variable setter statements
-    /// * `_field_info` - Field information (currently unused)
+    /// * `field_info` - Field information, for descriptions and merging
     ///
     /// # Generated Methods
     ///
     /// - `builder()` - Creates a new SecretSpecBuilder
     /// - `load()` - Loads secrets with optional provider/profile
     /// - `set_as_env_vars()` - Sets all secrets as environment variables
+    /// - `set_as_env_vars_with_prefix()` - Same, with a prefix on every variable name
+    /// - `description()` - Returns the description of a secret by name
+    /// - `merge()` - Layers the secrets over those of another `SecretSpec`
     pub fn generate_impl(
         load_assignments: &[proc_macro2::TokenStream],
         env_setters: Vec<proc_macro2::TokenStream>,
-        _field_info: &BTreeMap<String, FieldInfo>,
+        field_info: &BTreeMap<String, FieldInfo>,
     ) -> proc_macro2::TokenStream {
+        let description_arms = field_info.values().filter_map(|info| {
+            info.description.as_ref().map(|description| {
+                let name = &info.name;
+                quote! { #name => Some(#description) }
+            })
+        });
+        let merge_fields = field_info.values().map(FieldInfo::generate_merge_field);
+
         quote! {
             impl SecretSpec {
                 /// Create a new builder for loading secrets
@@ -1055,7 +1518,7 @@ mod secret_spec_generation {
                     let validation_result = load_internal(provider_str, profile_str)?;
                     let provider_name = validation_result.resolved.provider.clone();
                     let profile = validation_result.resolved.profile.clone();
-                    let secrets = validation_result.resolved.secrets;
+                    let secrets = secret_values(validation_result.resolved.secrets);
 
                     let data = Self {
                         #(#load_assignments,)*
@@ -1068,9 +1531,49 @@ mod secret_spec_generation {
                     ))
                 }
 
+                /// Sets every secret that has a value as an environment variable
+                /// named after the secret
+                ///
+                /// # Safety
+                ///
+                /// This calls `std::env::set_var`, which is not thread-safe: call it
+                /// before spawning threads that read the environment.
                 pub fn set_as_env_vars(&self) {
+                    self.set_as_env_vars_with_prefix("");
+                }
+
+                /// Sets every secret that has a value as an environment variable
+                /// named `prefix` followed by the secret name, e.g. `APP_DATABASE_URL`
+                ///
+                /// # Safety
+                ///
+                /// This calls `std::env::set_var`, which is not thread-safe: call it
+                /// before spawning threads that read the environment.
+                pub fn set_as_env_vars_with_prefix(&self, prefix: &str) {
                     #(#env_setters)*
                 }
+
+                /// Returns the description of a secret as declared in secretspec.toml
+                ///
+                /// `name` is the secret name (e.g. "DATABASE_URL"). Returns `None`
+                /// for unknown secrets and secrets without a description.
+                pub fn description(name: &str) -> Option<&'static str> {
+                    match name {
+                        #(#description_arms,)*
+                        _ => None,
+                    }
+                }
+
+                /// Combines these secrets with `other`, e.g. those of a base profile
+                ///
+                /// Values in `self` take precedence: `other` only fills in optional
+                /// secrets that have no value in `self`.
+                #[allow(unused_variables)]
+                pub fn merge(self, other: Self) -> Self {
+                    Self {
+                        #(#merge_fields,)*
+                    }
+                }
             }
         }
     }
@@ -1201,7 +1704,7 @@ mod builder_generation {
         quote! {
             let provider_str = if let Some(provider_fn) = #provider_expr {
                 let uri = provider_fn()
-                    .map_err(|e| secretspec::SecretSpecError::ProviderOperationFailed(e))?;
+                    .map_err(|e| secretspec::SecretSpecError::Provider(secretspec::ProviderError::Other(e)))?;
                 Some(uri.to_string())
             } else {
                 None
@@ -1271,7 +1774,7 @@ mod builder_generation {
                     let validation_result = load_internal(provider_str, profile_str)?;
                     let provider_name = validation_result.resolved.provider.clone();
                     let profile = validation_result.resolved.profile.clone();
-                    let secrets = validation_result.resolved.secrets;
+                    let secrets = secret_values(validation_result.resolved.secrets);
 
                     let data = SecretSpec {
                         #(#load_assignments,)*
@@ -1305,7 +1808,7 @@ mod builder_generation {
                     let validation_result = load_internal(provider_str, profile_str)?;
                     let provider_name = validation_result.resolved.provider.clone();
                     let profile = validation_result.resolved.profile.clone();
-                    let secrets = validation_result.resolved.secrets;
+                    let secrets = secret_values(validation_result.resolved.secrets);
 
                     let data_result: LoadResult<SecretSpecProfile> = match selected_profile {
                         #(#load_profile_arms,)*
@@ -1364,6 +1867,7 @@ mod builder_generation {
 /// # Arguments
 ///
 /// * `config` - The validated project configuration
+/// * `zeroize` - Whether values are `secretspec::Zeroizing<String>`
 ///
 /// # Returns
 ///
@@ -1377,13 +1881,13 @@ mod builder_generation {
 /// 4. Generate SecretSpecProfile enum (profile-specific types)
 /// 5. Generate builder pattern implementation
 /// 6. Combine all components with necessary imports
-fn generate_secret_spec_code(config: Config) -> proc_macro2::TokenStream {
+fn generate_secret_spec_code(config: Config, zeroize: bool) -> proc_macro2::TokenStream {
     // Collect all profiles
-    let all_profiles: HashSet<String> = config.profiles.keys().cloned().collect();
+    let all_profiles = selectable_profiles(&config);
     let profile_variants = get_profile_variants(&all_profiles);
 
     // Analyze field types
-    let field_info = analyze_field_types(&config);
+    let field_info = analyze_field_types(&config, zeroize);
 
     // Generate field assignments for load()
     let load_assignments: Vec<_> = field_info
@@ -1402,18 +1906,26 @@ fn generate_secret_spec_code(config: Config) -> proc_macro2::TokenStream {
 
     // Generate SecretSpec components
     let secret_spec_struct = secret_spec_generation::generate_struct(&field_info);
+    let secrets_ext_impl = secret_spec_generation::generate_secrets_ext_impl(&field_info);
     let profile_enum_variants = secret_spec_generation::generate_profile_enum_variants(
         &config,
         &field_info,
         &profile_variants,
+        zeroize,
+    );
+    let profile_debug_arms = secret_spec_generation::generate_profile_debug_arms(
+        &config,
+        &field_info,
+        &profile_variants,
     );
     let secret_spec_profile_enum =
-        secret_spec_generation::generate_profile_enum(&profile_enum_variants);
+        secret_spec_generation::generate_profile_enum(&profile_enum_variants, &profile_debug_arms);
     let load_profile_arms =
         secret_spec_generation::generate_load_profile_arms(&config, &field_info, &profile_variants);
-    let load_internal = secret_spec_generation::generate_load_internal();
+    let load_internal = secret_spec_generation::generate_load_internal(zeroize);
     let secret_spec_impl =
         secret_spec_generation::generate_impl(&load_assignments, env_setters, &field_info);
+    let description_warnings = generate_description_warnings(&field_info);
 
     // Get first profile variant for defaults
     // Get first profile variant for defaults
@@ -1432,6 +1944,7 @@ fn generate_secret_spec_code(config: Config) -> proc_macro2::TokenStream {
     // Combine all components
     quote! {
         #secret_spec_struct
+        #secrets_ext_impl
         #secret_spec_profile_enum
         #profile_code
 
@@ -1442,6 +1955,7 @@ fn generate_secret_spec_code(config: Config) -> proc_macro2::TokenStream {
         #load_internal
         #builder_code
         #secret_spec_impl
+        #description_warnings
     }
 }
 
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::capitalize_first;
     use secretspec::Config;
@@ -91,7 +92,7 @@ SOMETIMES_REQUIRED = { description = "Sometimes required secret", required = fal
         // Simulate the logic from the macro - check if secret is optional across all profiles
         let mut is_ever_optional = false;
 
-        for (_profile_name, profile_config) in &config.profiles {
+        for profile_config in config.profiles.values() {
             if let Some(secret_config) = profile_config.secrets.get("SOMETIMES_REQUIRED") {
                 if !secret_config.required || secret_config.default.is_some() {
                     is_ever_optional = true;
@@ -142,24 +143,42 @@ ALWAYS_REQUIRED = { description = "Always required secret", required = true }
 
     #[test]
     fn test_default_makes_optional() {
+        use crate::is_secret_optional;
+
         let toml_str = r#"[project]
 name = "test"
 revision = "1.0"
 
 [profiles.default]
-HAS_DEFAULT = { description = "Secret with default", required = true, default = "some-default" }
+HAS_DEFAULT = { description = "Secret with default", default = "some-default" }
 "#;
 
         let config: Config = toml::from_str(toml_str).unwrap();
         let secret_config = &config.profiles["default"].secrets["HAS_DEFAULT"];
 
-        let is_ever_optional = !secret_config.required || secret_config.default.is_some();
+        assert!(!secret_config.required);
         assert!(
-            is_ever_optional,
+            is_secret_optional(secret_config),
             "Field with default should be treated as optional"
         );
     }
 
+    #[test]
+    fn test_required_with_default_is_rejected() {
+        let toml_str = r#"[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+HAS_DEFAULT = { description = "Secret with default", required = true, default = "some-default" }
+"#;
+
+        // Rejected by the same parser the macro uses, so codegen never sees it
+        let error = toml_str.parse::<Config>().unwrap_err().to_string();
+        assert!(error.contains("secret 'HAS_DEFAULT'"), "{}", error);
+        assert!(error.contains("required = true conflicts with default"));
+    }
+
     // ===== STAGE 1: HELPER FUNCTION TESTS =====
 
     #[test]
@@ -200,6 +219,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         valid_secrets.insert(
@@ -208,6 +228,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Database URL".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -216,6 +237,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: valid_secrets,
+                ..Default::default()
             },
         );
 
@@ -223,9 +245,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: valid_profiles,
+            secrets: HashMap::new(),
         };
 
         validate_rust_identifiers(&valid_config, &mut errors);
@@ -242,6 +266,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Invalid name".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         invalid_secrets.insert(
@@ -250,6 +275,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Invalid name".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -258,6 +284,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: invalid_secrets,
+                ..Default::default()
             },
         );
 
@@ -265,9 +292,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: invalid_profiles,
+            secrets: HashMap::new(),
         };
 
         errors.clear();
@@ -307,6 +336,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Function keyword".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         keyword_secrets.insert(
@@ -315,6 +345,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Struct keyword".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         keyword_secrets.insert(
@@ -323,6 +354,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Async keyword".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -331,6 +363,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: keyword_secrets,
+                ..Default::default()
             },
         );
 
@@ -338,9 +371,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: keyword_profiles,
+            secrets: HashMap::new(),
         };
 
         validate_rust_identifiers(&keyword_config, &mut errors);
@@ -379,6 +414,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key upper".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         duplicate_secrets.insert(
@@ -387,6 +423,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key lower".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         duplicate_secrets.insert(
@@ -395,6 +432,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key mixed".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -403,6 +441,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: duplicate_secrets,
+                ..Default::default()
             },
         );
 
@@ -410,9 +449,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: duplicate_profiles,
+            secrets: HashMap::new(),
         };
 
         validate_rust_identifiers(&duplicate_config, &mut errors);
@@ -442,18 +483,21 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
         valid_profiles.insert(
             "development".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
         valid_profiles.insert(
             "production".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
 
@@ -461,9 +505,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: valid_profiles,
+            secrets: HashMap::new(),
         };
 
         validate_profile_identifiers(&valid_config, &mut errors);
@@ -478,12 +524,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "123invalid".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
         invalid_profiles.insert(
             "invalid-name".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
 
@@ -491,9 +539,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: invalid_profiles,
+            secrets: HashMap::new(),
         };
 
         errors.clear();
@@ -528,6 +578,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             description: Some("Required".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         };
         assert!(!is_secret_optional(&required_no_default));
 
@@ -536,6 +587,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             description: Some("Required with default".to_string()),
             required: true,
             default: Some("default_value".to_string()),
+            ..Default::default()
         };
         assert!(is_secret_optional(&required_with_default));
 
@@ -544,6 +596,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             description: Some("Not required".to_string()),
             required: false,
             default: None,
+            ..Default::default()
         };
         assert!(is_secret_optional(&not_required));
 
@@ -552,6 +605,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             description: Some("Not required with default".to_string()),
             required: false,
             default: Some("default_value".to_string()),
+            ..Default::default()
         };
         assert!(is_secret_optional(&not_required_with_default));
     }
@@ -573,6 +627,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         default_secrets.insert(
@@ -581,12 +636,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Database URL".to_string()),
                 required: false,
                 default: None,
+                ..Default::default()
             },
         );
         profiles.insert(
             "default".to_string(),
             Profile {
                 secrets: default_secrets,
+                ..Default::default()
             },
         );
 
@@ -598,6 +655,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key".to_string()),
                 required: true,
                 default: Some("dev-key".to_string()),
+                ..Default::default()
             },
         );
         dev_secrets.insert(
@@ -606,6 +664,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Database URL".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         // Note: CACHE_URL only exists in development
@@ -615,12 +674,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Cache URL".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         profiles.insert(
             "development".to_string(),
             Profile {
                 secrets: dev_secrets,
+                ..Default::default()
             },
         );
 
@@ -628,9 +689,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         };
 
         // API_KEY is optional because it has default in development
@@ -651,6 +714,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Always required".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         let mut strict_dev = HashMap::new();
@@ -660,18 +724,21 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Always required".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         strict_profiles.insert(
             "default".to_string(),
             Profile {
                 secrets: strict_default,
+                ..Default::default()
             },
         );
         strict_profiles.insert(
             "development".to_string(),
             Profile {
                 secrets: strict_dev,
+                ..Default::default()
             },
         );
 
@@ -679,9 +746,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: strict_profiles,
+            secrets: HashMap::new(),
         };
 
         // ALWAYS_REQUIRED should not be optional
@@ -691,6 +760,30 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
         ));
     }
 
+    #[test]
+    fn test_shared_secrets_required_in_all_profiles() {
+        use crate::is_field_optional_across_profiles;
+
+        let config: Config = r#"[project]
+name = "test"
+revision = "1.0"
+
+[secrets]
+API_KEY = { description = "API key" }
+
+[profiles.default]
+
+[profiles.production]
+DATABASE_URL = { description = "Database URL" }
+"#
+        .parse()
+        .unwrap();
+
+        // A shared secret is defined in every profile, so it stays required
+        assert!(!is_field_optional_across_profiles("API_KEY", &config));
+        assert!(is_field_optional_across_profiles("DATABASE_URL", &config));
+    }
+
     #[test]
     fn test_analyze_field_types() {
         use crate::analyze_field_types;
@@ -707,6 +800,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Always required".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         default_secrets.insert(
@@ -715,6 +809,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Optional".to_string()),
                 required: false,
                 default: None,
+                ..Default::default()
             },
         );
         default_secrets.insert(
@@ -723,12 +818,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Has default".to_string()),
                 required: true,
                 default: Some("default_value".to_string()),
+                ..Default::default()
             },
         );
         profiles.insert(
             "default".to_string(),
             Profile {
                 secrets: default_secrets,
+                ..Default::default()
             },
         );
 
@@ -740,6 +837,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Always required".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         dev_secrets.insert(
@@ -748,12 +846,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Development only".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         profiles.insert(
             "development".to_string(),
             Profile {
                 secrets: dev_secrets,
+                ..Default::default()
             },
         );
 
@@ -761,12 +861,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         };
 
-        let field_info = analyze_field_types(&config);
+        let field_info = analyze_field_types(&config, false);
 
         // Should have 4 unique secrets across all profiles
         assert_eq!(field_info.len(), 4);
@@ -794,7 +896,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
         use quote::quote;
 
         // Test required field
-        let required_field = FieldInfo::new("API_KEY".to_string(), quote! { String }, false);
+        let required_field = FieldInfo::new("API_KEY".to_string(), quote! { String }, false, None);
 
         assert_eq!(required_field.name, "API_KEY");
         assert!(!required_field.is_optional);
@@ -806,8 +908,12 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
         assert_eq!(struct_field.to_string(), expected_struct.to_string());
 
         // Test optional field
-        let optional_field =
-            FieldInfo::new("DATABASE_URL".to_string(), quote! { Option<String> }, true);
+        let optional_field = FieldInfo::new(
+            "DATABASE_URL".to_string(),
+            quote! { Option<String> },
+            true,
+            None,
+        );
 
         assert!(optional_field.is_optional);
         assert_eq!(optional_field.field_name().to_string(), "database_url");
@@ -818,6 +924,96 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             optional_struct_field.to_string(),
             expected_optional_struct.to_string()
         );
+
+        // The description becomes the field's documentation
+        let documented_field = FieldInfo::new(
+            "API_KEY".to_string(),
+            quote! { String },
+            false,
+            Some("API key".to_string()),
+        );
+        let expected_documented = quote! { #[doc = "API key"] pub api_key: String };
+        assert_eq!(
+            documented_field.generate_struct_field().to_string(),
+            expected_documented.to_string()
+        );
+    }
+
+    #[test]
+    fn test_description_warnings() {
+        use crate::{FieldInfo, generate_description_warnings};
+        use quote::quote;
+        use std::collections::BTreeMap;
+
+        let mut field_info = BTreeMap::new();
+        field_info.insert(
+            "API_KEY".to_string(),
+            FieldInfo::new(
+                "API_KEY".to_string(),
+                quote! { String },
+                false,
+                Some("API key".to_string()),
+            ),
+        );
+        assert!(generate_description_warnings(&field_info).is_empty());
+
+        // A secret without a description is reported through a deprecation notice
+        field_info.insert(
+            "DATABASE_URL".to_string(),
+            FieldInfo::new("DATABASE_URL".to_string(), quote! { String }, false, None),
+        );
+        let warnings = generate_description_warnings(&field_info).to_string();
+        assert!(warnings.contains("deprecated"));
+        assert!(warnings.contains("secret `DATABASE_URL` has an empty description"));
+        assert!(!warnings.contains("API_KEY"));
+    }
+
+    #[test]
+    fn test_generated_docs() {
+        use crate::{
+            analyze_field_types, get_profile_variants, profile_generation, secret_spec_generation,
+            selectable_profiles,
+        };
+
+        let config: Config = toml::from_str(
+            r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.production]
+API_KEY = { required = true }
+"#,
+        )
+        .unwrap();
+        let variants = get_profile_variants(&selectable_profiles(&config));
+
+        let profile_enum = profile_generation::generate_enum(&variants).to_string();
+        assert!(
+            profile_enum.contains(r#"# [doc = "The `production` profile"] Production"#),
+            "{}",
+            profile_enum
+        );
+
+        // An override without a description inherits the default profile's
+        let field_info = analyze_field_types(&config, false);
+        let profile_variants = secret_spec_generation::generate_profile_enum_variants(
+            &config,
+            &field_info,
+            &variants,
+            false,
+        );
+        for variant in profile_variants {
+            let variant = variant.to_string();
+            assert!(
+                variant.contains(r#"# [doc = "API key"] api_key"#),
+                "{}",
+                variant
+            );
+        }
     }
 
     #[test]
@@ -859,7 +1055,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
         let variants = get_profile_variants(&profiles);
         assert_eq!(variants.len(), 4);
 
-        // Should be sorted alphabetically
+        // Should be sorted by name, `default` first
         let names: Vec<&String> = variants.iter().map(|v| &v.name).collect();
         assert_eq!(
             names,
@@ -871,6 +1067,46 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
         assert_eq!(variants[1].capitalized, "Development");
         assert_eq!(variants[2].capitalized, "Production");
         assert_eq!(variants[3].capitalized, "Staging");
+
+        // `default` comes first even when another name sorts before it
+        profiles.insert("alpha".to_string());
+        let names: Vec<String> = get_profile_variants(&profiles)
+            .into_iter()
+            .map(|v| v.name)
+            .collect();
+        assert_eq!(
+            names,
+            ["default", "alpha", "development", "production", "staging"]
+        );
+    }
+
+    #[test]
+    fn test_selectable_profiles_skip_abstract() {
+        use crate::selectable_profiles;
+
+        let config: Config = toml::from_str(
+            r#"
+            [project]
+            name = "test"
+            revision = "1.0"
+
+            [profiles.default]
+            abstract = true
+            API_KEY = { description = "API key" }
+
+            [profiles.production]
+            DATABASE_URL = { description = "Database URL" }
+        "#,
+        )
+        .unwrap();
+
+        let profiles = selectable_profiles(&config);
+        assert_eq!(profiles.len(), 1);
+        assert!(profiles.contains("production"));
+
+        // Secrets of the abstract profile are still generated as fields
+        let field_info = crate::analyze_field_types(&config, false);
+        assert!(field_info.contains_key("API_KEY"));
     }
 
     #[test]
@@ -887,6 +1123,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("API Key".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         valid_secrets.insert(
@@ -895,6 +1132,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Database URL".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -903,12 +1141,14 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "default".to_string(),
             Profile {
                 secrets: valid_secrets,
+                ..Default::default()
             },
         );
         valid_profiles.insert(
             "development".to_string(),
             Profile {
                 secrets: HashMap::new(),
+                ..Default::default()
             },
         );
 
@@ -916,9 +1156,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: valid_profiles,
+            secrets: HashMap::new(),
         };
 
         let result = validate_config_for_codegen(&valid_config);
@@ -932,6 +1174,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Invalid name".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
         invalid_secrets.insert(
@@ -940,6 +1183,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
                 description: Some("Rust keyword".to_string()),
                 required: true,
                 default: None,
+                ..Default::default()
             },
         );
 
@@ -948,6 +1192,7 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             "123invalid-profile".to_string(),
             Profile {
                 secrets: invalid_secrets,
+                ..Default::default()
             },
         );
 
@@ -955,9 +1200,11 @@ HAS_DEFAULT = { description = "Secret with default", required = true, default =
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: invalid_profiles,
+            secrets: HashMap::new(),
         };
 
         let result = validate_config_for_codegen(&invalid_config);
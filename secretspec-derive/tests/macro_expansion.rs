@@ -8,8 +8,5 @@ fn test_macro_generates_valid_code() {
 
     // We can't easily test the macro directly here because it needs to read files
     // and generate code at compile time. The integration tests in the main crate
-    // will provide better coverage.
-
-    // For now, we just verify the crate compiles correctly
-    assert!(true);
+    // will provide better coverage. Reaching this point means the crate compiled.
 }
@@ -1,5 +1,3 @@
-use miette::Result;
-
-fn main() -> Result<()> {
+fn main() {
     secretspec::cli::main()
 }
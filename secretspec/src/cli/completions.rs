@@ -0,0 +1,103 @@
+//! Shell completion scripts.
+//!
+//! The scripts complete command names, and secret names for `get`, `set` and
+//! `rotate` by running the hidden `secretspec __complete` command, so the
+//! names always match the `secretspec.toml` of the current directory and the
+//! selected profile.
+
+/// Shells `secretspec completions` can write a script for
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub(super) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const BASH: &str = r#"_secretspec() {
+    local cur=${COMP_WORDS[COMP_CWORD]}
+    local command="" profile="" positionals=0 i word
+    for (( i = 1; i < COMP_CWORD; i++ )); do
+        word=${COMP_WORDS[i]}
+        case $word in
+            -p|--provider) (( i++ )) ;;
+            -P|--profile) profile=${COMP_WORDS[i+1]}; (( i++ )) ;;
+            -*) ;;
+            *) if [[ -z $command ]]; then command=$word; else (( positionals++ )); fi ;;
+        esac
+    done
+
+    if [[ -z $command ]]; then
+        COMPREPLY=($(compgen -W "@COMMANDS@" -- "$cur"))
+        return
+    fi
+    [[ $cur == -* ]] && return
+    case $command in
+        get) ;;
+        set|rotate) (( positionals == 0 )) || return ;;
+        *) return ;;
+    esac
+    COMPREPLY=($(secretspec ${profile:+--profile="$profile"} __complete "$cur" 2>/dev/null))
+}
+
+complete -F _secretspec secretspec
+"#;
+
+const ZSH: &str = r#"#compdef secretspec
+
+_secretspec() {
+    local command="" profile="" i word
+    local -a positionals names
+    for (( i = 2; i < CURRENT; i++ )); do
+        word=${words[i]}
+        case $word in
+            -p|--provider) (( i++ )) ;;
+            -P|--profile) profile=${words[i+1]}; (( i++ )) ;;
+            -*) ;;
+            *) if [[ -z $command ]]; then command=$word; else positionals+=($word); fi ;;
+        esac
+    done
+
+    if [[ -z $command ]]; then
+        compadd -- @COMMANDS@
+        return
+    fi
+    [[ $PREFIX == -* ]] && return
+    case $command in
+        get) ;;
+        set|rotate) (( ${#positionals} == 0 )) || return ;;
+        *) return ;;
+    esac
+    names=(${(f)"$(secretspec ${profile:+--profile=$profile} __complete $PREFIX 2>/dev/null)"})
+    compadd -- $names
+}
+
+compdef _secretspec secretspec
+"#;
+
+const FISH: &str = r#"function __secretspec_profile
+    set -l tokens (commandline -opc)
+    for i in (seq (count $tokens))
+        if contains -- $tokens[$i] -P --profile; and test $i -lt (count $tokens)
+            echo --profile=$tokens[(math $i + 1)]
+        end
+    end
+end
+
+complete -c secretspec -n __fish_use_subcommand -f -a "@COMMANDS@"
+complete -c secretspec -n "__fish_seen_subcommand_from get set rotate" -f -a "(secretspec (__secretspec_profile) __complete (commandline -ct) 2>/dev/null)"
+"#;
+
+/// Returns the completion script for `shell`
+///
+/// # Arguments
+///
+/// * `shell` - The shell to write the script for
+/// * `commands` - The command names to complete
+pub(super) fn script(shell: Shell, commands: &[&str]) -> String {
+    let template = match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    };
+    template.replace("@COMMANDS@", &commands.join(" "))
+}
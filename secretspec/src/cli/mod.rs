@@ -1,12 +1,19 @@
-use crate::provider::{dotenv::DotEnvProvider, providers};
-use crate::{Config, GlobalConfig, GlobalDefaults, Profile, Project, Secrets};
-use clap::{Parser, Subcommand};
+mod completions;
+
+use crate::config::{ParseError, is_valid_identifier};
+use crate::document::SpecDocument;
+use crate::provider::{disabled_providers, dotenv::DotEnvProvider, providers};
+use crate::{
+    Charset, CheckFormat, Config, ExportFormat, GlobalConfig, GlobalDefaults, Profile, Project,
+    ProviderError, Secret, SecretFilter, SecretSpecError, Secrets, Verbosity, find_manifest,
+};
+use clap::{CommandFactory, Parser, Subcommand};
 use miette::{IntoDiagnostic, Result, WrapErr, miette};
 use std::collections::HashMap;
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main CLI structure for the secretspec application.
 ///
@@ -17,6 +24,33 @@ use std::path::PathBuf;
 #[command(about = "Declarative secrets, every environment, any provider - https://secretspec.dev", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Only print prompts, requested values, warnings and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Also print which provider and profile are used and why
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    /// Provider backend to use [default: $SECRETSPEC_PROVIDER, then the
+    /// configured default]
+    #[arg(short, long, global = true)]
+    provider: Option<String>,
+    /// Profile to use [default: $SECRETSPEC_PROFILE, then the configured
+    /// default]
+    #[arg(short = 'P', long, global = true)]
+    profile: Option<String>,
+    /// Ignore the global configuration, so only secretspec.toml, the
+    /// environment and flags are used
+    #[arg(long, global = true)]
+    no_global_config: bool,
+    /// Read a secretspec.toml of this unsupported revision anyway, with a
+    /// warning, as long as it only uses fields this version understands
+    #[arg(
+        long,
+        global = true,
+        env = "SECRETSPEC_ALLOW_REVISION",
+        value_name = "REVISION"
+    )]
+    allow_revision: Option<String>,
     /// The subcommand to execute
     #[command(subcommand)]
     command: Commands,
@@ -34,64 +68,228 @@ enum Commands {
         /// Currently only dotenv provider is supported.
         #[arg(short, long, default_value = "dotenv://.env")]
         from: String,
+        /// Prompt for secret definitions instead of importing from a provider
+        #[arg(short, long)]
+        interactive: bool,
+        /// File to write, or a directory to create secretspec.toml in
+        #[arg(short, long, default_value = "secretspec.toml")]
+        output: PathBuf,
     },
     /// Set a secret value
     Set {
         /// Name of the secret
         name: String,
-        /// Value of the secret (will prompt if not provided)
+        /// Value of the secret, or `-` to read it from stdin (will prompt if
+        /// not provided)
         value: Option<String>,
-        /// Provider backend to use
-        #[arg(short, long, env = "SECRETSPEC_PROVIDER")]
-        provider: Option<String>,
-        /// Profile to use
-        #[arg(short = 'P', long, env = "SECRETSPEC_PROFILE")]
-        profile: Option<String>,
     },
     /// Get a secret value
     Get {
+        /// Names of the secrets; more than one requires `--format json`
+        #[arg(required_unless_present = "prefix")]
+        names: Vec<String>,
+        /// Also get every declared secret whose name starts with this prefix
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        format: GetFormat,
+        /// Leave secrets without a value out of the JSON output instead of
+        /// printing them as null
+        #[arg(long)]
+        omit_missing: bool,
+        /// Print nothing and succeed if the secret has neither a value nor a default
+        #[arg(long)]
+        allow_missing: bool,
+        /// Print where each secret is stored instead of its value
+        #[arg(long = "where", conflicts_with_all = ["format", "omit_missing", "allow_missing"])]
+        location: bool,
+    },
+    /// Generate a new random value for a secret and store it
+    Rotate {
         /// Name of the secret
         name: String,
-        /// Provider backend to use
-        #[arg(short, long, env = "SECRETSPEC_PROVIDER")]
-        provider: Option<String>,
-        /// Profile to use
-        #[arg(short = 'P', long, env = "SECRETSPEC_PROFILE")]
-        profile: Option<String>,
+        /// Number of characters to generate
+        #[arg(short, long, default_value_t = 32)]
+        length: usize,
+        /// Characters to draw the value from
+        #[arg(short, long, value_enum, default_value_t = Charset::Alphanumeric)]
+        charset: Charset,
+        /// Print the generated value
+        #[arg(long)]
+        show: bool,
     },
     /// Run a command with secrets injected
     Run {
-        /// Provider backend to use
-        #[arg(short, long, env = "SECRETSPEC_PROVIDER")]
-        provider: Option<String>,
-        /// Profile to use
-        #[arg(short = 'P', long, env = "SECRETSPEC_PROFILE")]
-        profile: Option<String>,
+        /// Restart the command when the provider's secrets file changes (dotenv only)
+        #[arg(short, long)]
+        watch: bool,
+        /// Only consider these secrets (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "KEYS")]
+        only: Vec<String>,
+        /// Ignore these secrets (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "KEYS")]
+        except: Vec<String>,
+        /// Only consider secrets in this group
+        #[arg(long)]
+        group: Option<String>,
+        /// Print how long each provider lookup took to stderr
+        #[arg(long, env = "SECRETSPEC_TIMING", value_parser = clap::builder::BoolishValueParser::new())]
+        timing: bool,
+        /// Inject only required secrets, leaving out optional ones even if set
+        #[arg(long)]
+        required_only: bool,
+        /// Write the secrets to this env file for docker-compose instead of running a command
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["watch", "command"])]
+        dotenv_out: Option<PathBuf>,
+        /// Fail if any secret would use its default value instead of a provider value
+        #[arg(long)]
+        strict: bool,
+        /// Use the defaults of optional secrets without reading them from the provider
+        #[arg(long, conflicts_with = "strict")]
+        prefer_defaults: bool,
+        /// Run the command in this directory
+        #[arg(long, value_name = "DIR", conflicts_with = "dotenv_out")]
+        cd: Option<PathBuf>,
         /// Command and arguments to run
         #[arg(trailing_var_arg = true)]
         command: Vec<String>,
     },
     /// Check if all required secrets are in the provider, if not set them
     Check {
-        /// Provider backend to use
-        #[arg(short, long, env = "SECRETSPEC_PROVIDER")]
-        provider: Option<String>,
-        /// Profile to use
-        #[arg(short = 'P', long, env = "SECRETSPEC_PROFILE")]
-        profile: Option<String>,
+        /// Only consider these secrets (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "KEYS")]
+        only: Vec<String>,
+        /// Ignore these secrets (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "KEYS")]
+        except: Vec<String>,
+        /// Only consider secrets in this group
+        #[arg(long)]
+        group: Option<String>,
+        /// Print how long each provider lookup took to stderr
+        #[arg(long, env = "SECRETSPEC_TIMING", value_parser = clap::builder::BoolishValueParser::new())]
+        timing: bool,
+        /// Store the defaults of secrets the provider doesn't hold, after confirmation
+        #[arg(long)]
+        fix: bool,
+        /// Leave optional secrets that aren't set out of the listing
+        #[arg(long)]
+        hide_optional: bool,
+        /// Listing format [default: pretty on a terminal, plain otherwise]
+        #[arg(short, long, value_enum)]
+        format: Option<CheckFormat>,
+        /// Write the status of every secret, without values, to this JSON file
+        #[arg(long, value_name = "PATH")]
+        audit_out: Option<PathBuf>,
+        /// Print `STATUS<TAB>NAME<TAB>PROFILE` per secret for scripts, where
+        /// STATUS is PRESENT, DEFAULT, MISSING_REQUIRED or MISSING_OPTIONAL,
+        /// without prompting
+        #[arg(long, conflicts_with_all = ["format", "fix", "hide_optional"])]
+        porcelain: bool,
     },
     /// Init or show ~/.config/secretspec/config.toml
     Config {
         #[command(subcommand)]
         action: ConfigAction,
     },
+    /// Print all secrets in a format other tools can load (e.g. direnv)
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// Export only required secrets, leaving out optional ones even if set
+        #[arg(long)]
+        required_only: bool,
+        /// Print a dotenv template such as a .env.example, with defaults or
+        /// placeholders instead of values; the provider is not read
+        #[arg(long, conflicts_with = "format")]
+        template: bool,
+    },
+    /// List the providers compiled into this build and their URI formats
+    Providers {
+        /// Print the providers as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Edit secretspec.toml in $EDITOR, validating it before saving
+    Edit,
+    /// Add a secret definition to secretspec.toml
+    AddSecret {
+        /// Name of the secret
+        name: String,
+        /// Description of the secret
+        #[arg(short, long)]
+        description: String,
+        /// Whether the secret must be set [default: true, or false with --default]
+        #[arg(short, long)]
+        required: Option<bool>,
+        /// Default value, used when the secret is not set
+        #[arg(long)]
+        default: Option<String>,
+        /// Replace an existing definition of the secret in the profile
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Show how another secretspec.toml differs from this project's
+    SpecDiff {
+        /// Path to the secretspec.toml to compare against
+        other: PathBuf,
+    },
+    /// Validate secretspec.toml and warn about secrets defined inconsistently
+    /// across profiles
+    ValidateSpec {
+        /// Fail if any secret is defined inconsistently across profiles
+        #[arg(long)]
+        strict: bool,
+        /// List which file contributed each secret definition once
+        /// `extends` is resolved
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Summarize the project: description, profiles, secrets and provider
+    Info,
+    /// Check every provider the profile reads secrets from, concurrently
+    Doctor,
+    /// Render the secrets of every profile as a Markdown document
+    Docs {
+        /// Write the document to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Import secrets from a provider to another provider
     Import {
         /// Provider backend to import from (secrets will be imported to the default provider)
         from_provider: String,
+        /// Read back each imported value and fail if the target stored it differently
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Print a shell completion script, e.g. `source <(secretspec completions bash)`
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+    /// Print the declared secret names starting with a prefix, for the
+    /// completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Start of the secret name
+        #[arg(default_value = "")]
+        prefix: String,
     },
 }
 
+/// Output formats of `secretspec get`.
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+enum GetFormat {
+    /// The value alone, for a single secret
+    #[default]
+    Text,
+    /// A JSON object mapping names to values, null if a secret has no value
+    Json,
+}
+
 /// Configuration-related subcommands.
 ///
 /// These actions handle the user's global configuration settings,
@@ -101,7 +299,25 @@ enum ConfigAction {
     /// Initialize user configuration
     Init,
     /// Show current configuration
-    Show,
+    Show {
+        /// Print the configuration and its file path as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show which provider and profile would be used, and why
+    Resolve,
+    /// Set a configuration value (provider, profile, retry_attempts, retry_backoff_ms, cache_ttl)
+    Set {
+        /// Configuration key
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Clear a configuration value
+    Unset {
+        /// Configuration key
+        key: String,
+    },
 }
 
 /// Returns an example TOML configuration string
@@ -142,63 +358,493 @@ fn get_example_toml() -> &'static str {
 ///
 /// Returns an error if the configuration cannot be serialized
 fn generate_toml_with_comments(config: &Config) -> crate::Result<String> {
-    let mut output = String::new();
-
-    // Project section
-    output.push_str("[project]\n");
-    output.push_str(&format!("name = \"{}\"\n", config.project.name));
-    output.push_str(&format!("revision = \"{}\"\n", config.project.revision));
-
-    // Add extends comment and field if needed
-    output.push_str("# Extend configurations from subdirectories\n");
-    output.push_str("# extends = [ \"subdir1\", \"subdir2\" ]\n");
-
-    // Profile sections
-    for (profile_name, profile_config) in &config.profiles {
-        output.push_str(&format!("\n[profiles.{}]\n", profile_name));
-
-        for (secret_name, secret_config) in &profile_config.secrets {
-            output.push_str(&format!(
-                "{} = {{ description = \"{}\", required = {}",
-                secret_name,
-                secret_config.description.as_deref().unwrap_or(""),
-                secret_config.required
-            ));
+    Ok(SpecDocument::from_config(config)?.to_string())
+}
+
+/// Adds a secret definition to a specification, keeping its comments
+///
+/// # Arguments
+///
+/// * `path` - The specification to modify
+/// * `profile` - The profile to add the secret to, created if missing
+/// * `name` - The secret name, which must be a valid identifier
+/// * `secret` - The secret definition
+/// * `force` - Whether to replace an existing definition in the profile
+/// * `quiet` - Whether to skip the confirmation message
+///
+/// # Errors
+///
+/// Returns an error if the name or definition is invalid, the secret is
+/// already defined and `force` is not set, or the file cannot be updated
+fn add_secret(
+    path: &Path,
+    profile: &str,
+    name: &str,
+    secret: Secret,
+    force: bool,
+    quiet: bool,
+) -> Result<()> {
+    if !is_valid_identifier(name) {
+        return Err(miette!(
+            "Invalid secret name '{}': must start with a letter or underscore and contain only letters, digits and underscores",
+            name
+        ));
+    }
+    secret.validate().map_err(|e| miette!("{}", e))?;
+
+    let mut document =
+        SpecDocument::load(path).wrap_err_with(|| format!("Failed to load {}", path.display()))?;
+    let exists = document.has_secret(profile, name);
+    if exists && !force {
+        return Err(miette!(
+            "Secret '{}' is already defined in profile '{}'. Use --force to replace it",
+            name,
+            profile
+        ));
+    }
+
+    document.set_secret(profile, name, &secret)?;
+    document.config()?;
+    document.save(path)?;
+
+    if quiet {
+        return Ok(());
+    }
+    if exists {
+        println!("✓ Updated {} in profile '{}'", name, profile);
+    } else {
+        println!("✓ Added {} to profile '{}'", name, profile);
+    }
+    Ok(())
+}
 
-            if let Some(default) = &secret_config.default {
-                output.push_str(&format!(", default = \"{}\"", default));
+/// Interactively prompts for secret definitions
+///
+/// Loops asking for a secret name, description, whether it is required and,
+/// for optional secrets, a default value. An empty name finishes the loop.
+///
+/// # Returns
+///
+/// The secrets entered by the user, keyed by name
+///
+/// # Errors
+///
+/// Returns an error if a prompt fails or is cancelled
+fn prompt_secret_definitions() -> Result<HashMap<String, Secret>> {
+    use inquire::validator::Validation;
+    use inquire::{Confirm, Text};
+
+    let mut secrets: HashMap<String, Secret> = HashMap::new();
+
+    loop {
+        let existing: Vec<String> = secrets.keys().cloned().collect();
+        let name = Text::new("Secret name (leave empty to finish):")
+            .with_validator(move |input: &str| {
+                let input = input.trim();
+                if input.is_empty() {
+                    Ok(Validation::Valid)
+                } else if !is_valid_identifier(input) {
+                    Ok(Validation::Invalid(
+                        "Must start with a letter or underscore and contain only letters, digits and underscores".into(),
+                    ))
+                } else if existing.iter().any(|name| name == input) {
+                    Ok(Validation::Invalid("Secret is already defined".into()))
+                } else {
+                    Ok(Validation::Valid)
+                }
+            })
+            .prompt()
+            .into_diagnostic()?;
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            break;
+        }
+
+        let description = Text::new("Description:")
+            .with_validator(|input: &str| {
+                if input.trim().is_empty() {
+                    Ok(Validation::Invalid("Description cannot be empty".into()))
+                } else {
+                    Ok(Validation::Valid)
+                }
+            })
+            .prompt()
+            .into_diagnostic()?;
+        let required = Confirm::new("Required?")
+            .with_default(true)
+            .prompt()
+            .into_diagnostic()?;
+
+        // Required secrets cannot have defaults
+        let default = if required {
+            None
+        } else {
+            Some(
+                Text::new("Default value (leave empty for none):")
+                    .prompt()
+                    .into_diagnostic()?,
+            )
+            .filter(|value| !value.is_empty())
+        };
+
+        secrets.insert(
+            name,
+            Secret {
+                description: Some(description.trim().to_string()),
+                required,
+                default,
+                ..Default::default()
+            },
+        );
+    }
+
+    Ok(secrets)
+}
+
+/// Updates a single value in the global defaults
+///
+/// # Arguments
+///
+/// * `defaults` - The defaults to update
+/// * `key` - The configuration key, e.g. `provider`
+/// * `value` - The new value, or `None` to clear it
+///
+/// # Errors
+///
+/// Returns an error if the key is unknown or the value is invalid for it
+fn set_global_default(
+    defaults: &mut GlobalDefaults,
+    key: &str,
+    value: Option<String>,
+) -> Result<()> {
+    match key {
+        "provider" => {
+            if let Some(provider) = &value {
+                // Reject typos early instead of on the next command
+                Box::<dyn crate::provider::Provider>::try_from(provider.as_str())
+                    .wrap_err_with(|| format!("Invalid provider '{}'", provider))?;
             }
+            defaults.provider = value;
+        }
+        "profile" => defaults.profile = value,
+        "retry_attempts" => {
+            defaults.retry_attempts = value
+                .map(|v| v.parse())
+                .transpose()
+                .into_diagnostic()
+                .wrap_err("retry_attempts must be a positive integer")?;
+        }
+        "retry_backoff_ms" => {
+            defaults.retry_backoff_ms = value
+                .map(|v| v.parse())
+                .transpose()
+                .into_diagnostic()
+                .wrap_err("retry_backoff_ms must be a non-negative integer")?;
+        }
+        "cache_ttl" => {
+            defaults.cache_ttl = value
+                .map(|v| v.parse())
+                .transpose()
+                .into_diagnostic()
+                .wrap_err("cache_ttl must be a non-negative number of seconds")?;
+        }
+        _ => {
+            return Err(miette!(
+                "Unknown configuration key '{}'. Valid keys: provider, profile, retry_attempts, retry_backoff_ms, cache_ttl",
+                key
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Stores the `defaults` that `check` used, for `check --fix`, after
+/// listing them and asking for confirmation.
+///
+/// Nothing is written without an interactive confirmation;
+/// [`Secrets::store_defaults`] skips read-only providers and defaults built
+/// from other secrets.
+fn store_defaults(app: &Secrets, defaults: &[(String, String)]) -> Result<()> {
+    use inquire::Confirm;
+    use std::io::IsTerminal;
+
+    if defaults.is_empty() {
+        println!("\nNo defaults to store");
+        return Ok(());
+    }
+
+    // Only names are listed, as defaults may hold credentials
+    println!("\nDefaults to store in the provider:");
+    for (name, _) in defaults {
+        println!("  {}", name);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(miette!(
+            "Not storing defaults: --fix asks for confirmation and needs an interactive terminal"
+        ));
+    }
+    let confirmed = Confirm::new(&format!("Store {} default values?", defaults.len()))
+        .with_default(false)
+        .prompt()
+        .into_diagnostic()?;
+    if !confirmed {
+        println!("No defaults stored");
+        return Ok(());
+    }
+
+    app.store_defaults(defaults)
+        .wrap_err("Failed to store defaults")?;
+    Ok(())
+}
+
+/// Builds the secret filter for the `--only`, `--except` and `--group` flags.
+fn secret_filter(only: Vec<String>, except: Vec<String>, group: Option<String>) -> SecretFilter {
+    let mut filter = SecretFilter::default().except(except);
+    if !only.is_empty() {
+        filter = filter.only(only);
+    }
+    match group {
+        Some(group) => filter.group(group),
+        None => filter,
+    }
+}
 
-            output.push_str(" }\n");
+/// Opens `secretspec.toml` in the user's editor and replaces it once the
+/// edited copy parses and validates.
+///
+/// The file is edited as a temporary copy in the same directory, so relative
+/// `extends` paths resolve as usual and a broken edit never touches the
+/// original. On a validation error the user can re-open the editor to fix it.
+fn edit_spec(path: &Path, allow_revision: Option<&str>) -> Result<()> {
+    use inquire::Confirm;
+    use std::io::IsTerminal;
+
+    let original = fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let temp = tempfile::Builder::new()
+        .prefix(".secretspec.edit.")
+        .suffix(".toml")
+        .tempfile_in(dir)
+        .into_diagnostic()?;
+    fs::write(temp.path(), &original).into_diagnostic()?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let args: Vec<&str> = words.collect();
+
+    loop {
+        let status = std::process::Command::new(program)
+            .args(&args)
+            .arg(temp.path())
+            .status()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to launch editor '{}'", editor))?;
+        if !status.success() {
+            return Err(miette!(
+                "Editor exited with {}; {} was left unchanged",
+                status,
+                path.display()
+            ));
+        }
+
+        let edited = fs::read_to_string(temp.path()).into_diagnostic()?;
+        if edited == original {
+            println!("No changes");
+            return Ok(());
+        }
+
+        let result = load_config(temp.path(), allow_revision).and_then(|config| config.validate());
+        match result {
+            Ok(()) => break,
+            Err(e) => {
+                eprintln!("✗ {}", e);
+                let reopen = std::io::stdin().is_terminal()
+                    && Confirm::new("Re-open the editor?")
+                        .with_default(true)
+                        .prompt()
+                        .into_diagnostic()?;
+                if !reopen {
+                    return Err(miette!(
+                        "{} was left unchanged because the edited copy is invalid",
+                        path.display()
+                    ));
+                }
+            }
         }
     }
 
-    Ok(output)
+    // Keep the original file's permissions on the replacement
+    let permissions = fs::metadata(path).into_diagnostic()?.permissions();
+    fs::set_permissions(temp.path(), permissions).into_diagnostic()?;
+    temp.persist(path).into_diagnostic()?;
+    println!("✓ Saved {}", path.display());
+    Ok(())
+}
+
+/// Loads the project's secrets with the output level of `--quiet` and
+/// `--verbose`, without the global configuration if `no_global_config`.
+fn load_secrets(
+    verbosity: Verbosity,
+    no_global_config: bool,
+    allow_revision: Option<&str>,
+) -> Result<Secrets> {
+    let global_config = if no_global_config {
+        None
+    } else {
+        GlobalConfig::load().wrap_err("Failed to load secretspec configuration")?
+    };
+    let mut app = Secrets::load_manifest(&find_manifest()?, global_config, allow_revision)
+        .wrap_err("Failed to load secretspec configuration")?;
+    print_warnings(app.warnings());
+    app.set_verbosity(verbosity);
+    Ok(app)
+}
+
+/// Loads the `secretspec.toml` at `path` without validating it, printing
+/// the warnings raised while reading it.
+fn load_config(
+    path: &Path,
+    allow_revision: Option<&str>,
+) -> std::result::Result<Config, ParseError> {
+    let (config, report) = Config::from_path_with_report(path, allow_revision)?;
+    print_warnings(&report.warnings);
+    Ok(config)
+}
+
+/// Prints warnings raised while loading `secretspec.toml`.
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// Picks the `check` format when none is given: plain when the output is
+/// piped or logged
+fn default_check_format() -> CheckFormat {
+    use std::io::IsTerminal;
+
+    if std::io::stdout().is_terminal() {
+        CheckFormat::Pretty
+    } else {
+        CheckFormat::Plain
+    }
+}
+
+/// Exit code when required secrets are missing or have invalid values
+const EXIT_MISSING_SECRETS: i32 = 2;
+
+/// Exit code when the provider cannot be reached or rejects the login
+const EXIT_PROVIDER_UNAVAILABLE: i32 = 3;
+
+/// Exit code when secretspec.toml or the provider selection is invalid
+const EXIT_BAD_CONFIG: i32 = 4;
+
+/// Maps a failed command to its exit code
+///
+/// Errors are classified by the [`SecretSpecError`] or [`ParseError`] they
+/// were caused by; anything else exits with 1.
+fn exit_code(report: &miette::Report) -> i32 {
+    if let Some(err) = report.downcast_ref::<SecretSpecError>() {
+        return match err {
+            SecretSpecError::RequiredSecretMissing(_)
+            | SecretSpecError::SecretNotFound(_)
+            | SecretSpecError::ValidationFailed(_)
+            | SecretSpecError::DefaultsNotAllowed(_) => EXIT_MISSING_SECRETS,
+            SecretSpecError::Provider(
+                ProviderError::AuthFailed(_) | ProviderError::BackendUnavailable(_),
+            )
+            | SecretSpecError::Keyring(_) => EXIT_PROVIDER_UNAVAILABLE,
+            SecretSpecError::Toml(_)
+            | SecretSpecError::UnsupportedRevision(_)
+            | SecretSpecError::NoProviderConfigured
+            | SecretSpecError::ProviderNotFound(_)
+            | SecretSpecError::ProviderNotEnabled(..)
+            | SecretSpecError::ProfileNotFound(..)
+            | SecretSpecError::GroupNotFound(..)
+            | SecretSpecError::UndeclaredSecret(..)
+            | SecretSpecError::CircularDependency(_)
+            | SecretSpecError::NoManifest
+            | SecretSpecError::NoProjectName
+            | SecretSpecError::NoProfiles
+            | SecretSpecError::InvalidProfile(_)
+            | SecretSpecError::InvalidConfig(_) => EXIT_BAD_CONFIG,
+            _ => 1,
+        };
+    }
+    match report.downcast_ref::<ParseError>() {
+        Some(ParseError::Io(_) | ParseError::Remote(_)) | None => 1,
+        Some(_) => EXIT_BAD_CONFIG,
+    }
 }
 
 /// Main entry point for the secretspec CLI application.
 ///
+/// Runs the command and, if it fails, prints the error and exits with a
+/// code describing the kind of failure: 2 for missing secrets, 3 for an
+/// unavailable provider, 4 for invalid configuration and 1 otherwise.
+#[doc(hidden)]
+pub fn main() {
+    if let Err(report) = run() {
+        eprintln!("Error: {:?}", report);
+        std::process::exit(exit_code(&report));
+    }
+}
+
 /// Parses command-line arguments and executes the appropriate command.
-/// All commands are delegated to the SecretSpec library for processing.
-///
-/// # Returns
 ///
-/// * `Ok(())` - If the command executed successfully
-/// * `Err` - If any error occurred during execution
-#[doc(hidden)]
-pub fn main() -> Result<()> {
+/// All commands are delegated to the SecretSpec library for processing.
+fn run() -> Result<()> {
     let cli = Cli::parse();
+    let Cli {
+        quiet,
+        verbose,
+        provider,
+        profile,
+        no_global_config,
+        allow_revision,
+        command,
+    } = cli;
+    let allow_revision = allow_revision.as_deref();
+    let verbosity = if quiet {
+        Verbosity::Quiet
+    } else if verbose {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    };
+    let quiet = verbosity == Verbosity::Quiet;
 
-    match cli.command {
+    match command {
         // Initialize a new secretspec.toml configuration file
-        Commands::Init { from } => {
-            // Check if secretspec.toml already exists
-            if PathBuf::from("secretspec.toml").exists() {
+        Commands::Init {
+            from,
+            interactive,
+            output,
+        } => {
+            let output = if output.is_dir() {
+                output.join("secretspec.toml")
+            } else {
+                output
+            };
+
+            // Check if the output file already exists
+            if output.exists() {
                 use inquire::Confirm;
-                let overwrite = Confirm::new("secretspec.toml already exists. Overwrite?")
-                    .with_default(false)
-                    .prompt()
-                    .into_diagnostic()?;
+                let overwrite =
+                    Confirm::new(&format!("{} already exists. Overwrite?", output.display()))
+                        .with_default(false)
+                        .prompt()
+                        .into_diagnostic()?;
 
                 if !overwrite {
                     println!("Cancelled.");
@@ -206,58 +852,78 @@ pub fn main() -> Result<()> {
                 }
             }
 
-            // Parse the provider URL
-            let uri = from
-                .parse::<url::Url>()
-                .map_err(|e| miette!("Invalid provider URL '{}': {}", from, e))?;
+            let secrets = if interactive {
+                prompt_secret_definitions()?
+            } else {
+                // Parse the provider URL
+                let uri = from
+                    .parse::<url::Url>()
+                    .map_err(|e| miette!("Invalid provider URL '{}': {}", from, e))?;
 
-            // Extract scheme from URI to validate provider
-            let scheme = uri.scheme();
+                // Extract scheme from URI to validate provider
+                let scheme = uri.scheme();
 
-            // Currently only support dotenv provider
-            if scheme != "dotenv" {
-                return Err(miette!(
-                    "Only 'dotenv://' provider URLs are currently supported for init --from. Got: {}",
-                    from
-                ));
-            }
+                // Currently only support dotenv provider
+                if scheme != "dotenv" {
+                    return Err(miette!(
+                        "Only 'dotenv://' provider URLs are currently supported for init --from. Got: {}",
+                        from
+                    ));
+                }
 
-            // Create dotenv provider and reflect secrets
-            let dotenv_config = (&uri).try_into().into_diagnostic()?;
-            let dotenv_provider = DotEnvProvider::new(dotenv_config);
-            let secrets = dotenv_provider.reflect().into_diagnostic()?;
+                // Create dotenv provider and reflect secrets
+                let dotenv_config = (&uri).try_into()?;
+                let dotenv_provider = DotEnvProvider::new(dotenv_config);
+                dotenv_provider.reflect()?
+            };
 
             // Create a new project config
             let mut profiles = HashMap::new();
-            profiles.insert("default".to_string(), Profile { secrets });
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
+                },
+            );
 
+            // Name the project after the directory the file is written to
+            let output_dir = output
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            fs::create_dir_all(output_dir)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Failed to create {}", output_dir.display()))?;
             let project_config = Config {
                 project: Project {
-                    name: std::env::current_dir()
+                    name: fs::canonicalize(output_dir)
                         .into_diagnostic()?
                         .file_name()
                         .unwrap_or_default()
                         .to_string_lossy()
                         .to_string(),
                     revision: "1.0".to_string(),
+                    description: None,
                     extends: None,
                 },
                 profiles,
+                secrets: HashMap::new(),
             };
             let mut content = generate_toml_with_comments(&project_config).into_diagnostic()?;
 
             // Append comprehensive example
             content.push_str(get_example_toml());
 
-            fs::write("secretspec.toml", content).into_diagnostic()?;
+            fs::write(&output, content).into_diagnostic()?;
 
             // Set file permissions to 600 (owner read/write only) on Unix systems
             #[cfg(unix)]
             {
-                let metadata = fs::metadata("secretspec.toml").into_diagnostic()?;
+                let metadata = fs::metadata(&output).into_diagnostic()?;
                 let mut permissions = metadata.permissions();
                 permissions.set_mode(0o600);
-                fs::set_permissions("secretspec.toml", permissions).into_diagnostic()?;
+                fs::set_permissions(&output, permissions).into_diagnostic()?;
             }
 
             let secret_count = project_config
@@ -265,7 +931,11 @@ pub fn main() -> Result<()> {
                 .values()
                 .map(|p| p.secrets.len())
                 .sum::<usize>();
-            println!("✓ Created secretspec.toml with {} secrets", secret_count);
+            println!(
+                "✓ Created {} with {} secrets",
+                output.display(),
+                secret_count
+            );
 
             println!("\nNext steps:");
             println!("  1. secretspec config init    # Set up user configuration");
@@ -312,6 +982,7 @@ pub fn main() -> Result<()> {
                     defaults: GlobalDefaults {
                         provider: Some(provider.to_string()),
                         profile,
+                        ..Default::default()
                     },
                 };
 
@@ -322,9 +993,64 @@ pub fn main() -> Result<()> {
                 );
                 Ok(())
             }
+            // Set or clear a single value without prompting
+            ConfigAction::Set { key, value } => {
+                let mut config = GlobalConfig::load()?.unwrap_or_default();
+                set_global_default(&mut config.defaults, &key, Some(value.clone()))?;
+                config.save().into_diagnostic()?;
+                if !quiet {
+                    println!("✓ Set {} = {}", key, value);
+                }
+                Ok(())
+            }
+            ConfigAction::Unset { key } => {
+                let mut config = GlobalConfig::load()?.unwrap_or_default();
+                set_global_default(&mut config.defaults, &key, None)?;
+                config.save().into_diagnostic()?;
+                if !quiet {
+                    println!("✓ Unset {}", key);
+                }
+                Ok(())
+            }
+            // Explain the effective provider and profile without fetching anything
+            ConfigAction::Resolve => {
+                let app = load_secrets(verbosity, no_global_config, allow_revision)?;
+                let info = app.resolution(provider, profile);
+                match info.provider {
+                    Some(provider) => {
+                        println!("Provider: {} (from {})", provider.value, provider.source)
+                    }
+                    None => println!("Provider: (none configured)"),
+                }
+                println!(
+                    "Profile: {} (from {})",
+                    info.profile.value, info.profile.source
+                );
+                Ok(())
+            }
             // Display current user configuration
-            ConfigAction::Show => {
-                match GlobalConfig::load().into_diagnostic()? {
+            ConfigAction::Show { json: true } => {
+                let path = GlobalConfig::path().into_diagnostic()?;
+                let config = GlobalConfig::load()?;
+                let exists = config.is_some();
+                let defaults = config.unwrap_or_default().defaults;
+                let object = serde_json::json!({
+                    "path": path,
+                    "exists": exists,
+                    "provider": defaults.provider,
+                    "profile": defaults.profile,
+                    "retry_attempts": defaults.retry_attempts,
+                    "retry_backoff_ms": defaults.retry_backoff_ms,
+                    "cache_ttl": defaults.cache_ttl,
+                });
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&object).into_diagnostic()?
+                );
+                Ok(())
+            }
+            ConfigAction::Show { json: false } => {
+                match GlobalConfig::load()? {
                     Some(config) => {
                         println!(
                             "Configuration file: {}\n",
@@ -349,91 +1075,455 @@ pub fn main() -> Result<()> {
             }
         },
         // Set a secret value in the specified provider
-        Commands::Set {
-            name,
-            value,
-            provider,
-            profile,
-        } => {
-            let mut app = Secrets::load()
-                .into_diagnostic()
-                .wrap_err("Failed to load secretspec configuration")?;
+        Commands::Set { name, value } => {
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
             if let Some(p) = provider {
                 app.set_provider(p);
             }
-            if let Some(p) = profile {
-                app.set_profile(p);
+            // A comma-separated list sets the value in each profile
+            let profiles: Vec<String> = profile
+                .iter()
+                .flat_map(|p| p.split(','))
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            match profiles.as_slice() {
+                [] => app.set(&name, value),
+                [profile] => {
+                    app.set_profile(profile);
+                    app.set(&name, value)
+                }
+                _ => app.set_in_profiles(&name, value, &profiles),
             }
-            app.set(&name, value)
-                .into_diagnostic()
-                .wrap_err("Failed to set secret")?;
+            .wrap_err("Failed to set secret")?;
             Ok(())
         }
         // Retrieve and display a secret value
         Commands::Get {
-            name,
-            provider,
-            profile,
+            mut names,
+            prefix,
+            format,
+            omit_missing,
+            allow_missing,
+            location,
         } => {
-            let mut app = Secrets::load()
-                .into_diagnostic()
-                .wrap_err("Failed to load secretspec configuration")?;
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
             if let Some(p) = provider {
                 app.set_provider(p);
             }
             if let Some(p) = profile {
                 app.set_profile(p);
             }
-            app.get(&name)
-                .into_diagnostic()
-                .wrap_err("Failed to get secret")?;
+            if let Some(prefix) = &prefix {
+                for name in app.secret_names()? {
+                    if name.starts_with(prefix.as_str()) && !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+
+            if location {
+                for name in &names {
+                    let location = app.location(name).wrap_err("Failed to locate secret")?;
+                    if names.len() == 1 {
+                        println!("{}", location);
+                    } else {
+                        println!("{}: {}", name, location);
+                    }
+                }
+                return Ok(());
+            }
+
+            match format {
+                GetFormat::Text => {
+                    let [name] = names.as_slice() else {
+                        return Err(miette!(
+                            "Getting several secrets requires --format json ({} selected)",
+                            names.len()
+                        ));
+                    };
+                    if allow_missing {
+                        // Empty output with success means "not set"
+                        if let Some(value) = app.get_value(name).wrap_err("Failed to get secret")? {
+                            println!("{}", value);
+                        }
+                    } else {
+                        app.get(name).wrap_err("Failed to get secret")?;
+                    }
+                }
+                GetFormat::Json => {
+                    let values = app.get_many(&names).wrap_err("Failed to get secrets")?;
+                    let object: serde_json::Map<String, serde_json::Value> = values
+                        .into_iter()
+                        .filter(|(_, value)| !omit_missing || value.is_some())
+                        .map(|(name, value)| (name, value.into()))
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&object).into_diagnostic()?
+                    );
+                }
+            }
+            Ok(())
+        }
+        // Generate and store a new value for a secret
+        Commands::Rotate {
+            name,
+            length,
+            charset,
+            show,
+        } => {
+            let app = load_secrets(verbosity, no_global_config, allow_revision)?;
+            let value = app
+                .rotate(&name, length, charset, provider, profile.clone())
+                .wrap_err("Failed to rotate secret")?;
+            if !quiet {
+                println!(
+                    "✓ Rotated {} (profile: {})",
+                    name,
+                    app.resolve_profile(profile.as_deref())
+                );
+            }
+            if show {
+                println!("{}", value);
+            }
             Ok(())
         }
         // Execute a command with secrets injected as environment variables
         Commands::Run {
             command,
-            provider,
-            profile,
+            watch,
+            only,
+            except,
+            group,
+            timing,
+            required_only,
+            dotenv_out,
+            strict,
+            prefer_defaults,
+            cd,
         } => {
-            let mut app = Secrets::load()
-                .into_diagnostic()
-                .wrap_err("Failed to load secretspec configuration")?;
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
             if let Some(p) = provider {
                 app.set_provider(p);
             }
             if let Some(p) = profile {
                 app.set_profile(p);
             }
-            app.run(command)
-                .into_diagnostic()
-                .wrap_err("Failed to run command")?;
+            app.set_filter(secret_filter(only, except, group).required_only(required_only));
+            app.set_timing(timing);
+            app.set_strict(strict);
+            app.set_prefer_defaults(prefer_defaults);
+            if let Some(dir) = cd {
+                app.set_working_dir(dir);
+            }
+            if let Some(path) = dotenv_out {
+                app.write_env_file(&path)
+                    .wrap_err("Failed to write env file")?;
+                return Ok(());
+            }
+            let result = if watch {
+                app.run_watch(command)
+            } else {
+                app.run(command)
+            };
+            result.wrap_err("Failed to run command")?;
             Ok(())
         }
         // Verify all required secrets are available
-        Commands::Check { provider, profile } => {
-            let mut app = Secrets::load()
-                .into_diagnostic()
+        Commands::Check {
+            only,
+            except,
+            group,
+            timing,
+            fix,
+            hide_optional,
+            format,
+            audit_out,
+            porcelain,
+        } => {
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
+            if let Some(p) = provider {
+                app.set_provider(p);
+            }
+            if let Some(p) = profile {
+                app.set_profile(p);
+            }
+            app.set_filter(secret_filter(only, except, group));
+            app.set_timing(timing);
+            app.set_hide_optional(hide_optional);
+            if let Some(path) = audit_out {
+                app.set_audit_out(path);
+            }
+            if porcelain {
+                return app.check_porcelain().wrap_err("Failed to check secrets");
+            }
+            let format = format.unwrap_or_else(default_check_format);
+            // Warnings and prompts around a plain listing aren't colored either
+            if format == CheckFormat::Plain {
+                colored::control::set_override(false);
+            }
+            app.set_check_format(format);
+            let validated = app.check().wrap_err("Failed to check secrets")?;
+            if fix {
+                store_defaults(&app, &validated.with_defaults)?;
+            }
+            Ok(())
+        }
+        // Print secrets for other tools; only the secrets go to stdout
+        Commands::Export {
+            format,
+            required_only,
+            template,
+        } => {
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
+            if let Some(p) = provider {
+                app.set_provider(p);
+            }
+            if let Some(p) = profile {
+                app.set_profile(p);
+            }
+            app.set_filter(SecretFilter::default().required_only(required_only));
+            let output = if template {
+                app.to_template_string()
+            } else {
+                app.to_env_string(format)
+            }
+            .wrap_err("Failed to export secrets")?;
+            print!("{}", output);
+            Ok(())
+        }
+        Commands::Providers { json } => {
+            let mut infos = providers();
+            infos.sort_by_key(|info| info.name);
+            let disabled = disabled_providers();
+            let listing = if json {
+                let mut entries: Vec<serde_json::Value> = infos
+                    .iter()
+                    .map(|info| {
+                        serde_json::json!({
+                            "name": info.name,
+                            "description": info.description,
+                            "examples": info.examples,
+                            "enabled": true,
+                            "feature": null,
+                        })
+                    })
+                    .collect();
+                entries.extend(disabled.iter().map(|(info, feature)| {
+                    serde_json::json!({
+                        "name": info.name,
+                        "description": info.description,
+                        "examples": info.examples,
+                        "enabled": false,
+                        "feature": feature,
+                    })
+                }));
+                serde_json::to_string_pretty(&entries).into_diagnostic()? + "\n"
+            } else {
+                let mut listing = String::new();
+                for info in infos {
+                    listing.push_str(&format!("{} - {}\n", info.name, info.description));
+                    for example in info.examples {
+                        listing.push_str(&format!("  {}\n", example));
+                    }
+                }
+                for (info, feature) in disabled {
+                    listing.push_str(&format!(
+                        "{} - not enabled in this build (requires the '{}' feature)\n",
+                        info.name, feature
+                    ));
+                }
+                listing
+            };
+            // Print the listing in one write so `providers | grep -q` doesn't
+            // hit a closed pipe partway through
+            print!("{}", listing);
+            Ok(())
+        }
+        Commands::Edit => edit_spec(&find_manifest()?, allow_revision),
+        Commands::AddSecret {
+            name,
+            description,
+            required,
+            default,
+            force,
+        } => {
+            let secret = Secret {
+                description: Some(description),
+                required: required.unwrap_or(default.is_none()),
+                default,
+                ..Default::default()
+            };
+            let profile = profile.as_deref().unwrap_or("default");
+            add_secret(&find_manifest()?, profile, &name, secret, force, quiet)
+        }
+        Commands::SpecDiff { other } => {
+            let current = load_config(&find_manifest()?, allow_revision)
                 .wrap_err("Failed to load secretspec configuration")?;
+            let other_config = load_config(&other, allow_revision)
+                .wrap_err_with(|| format!("Failed to load {}", other.display()))?;
+            let diff = current.diff(&other_config);
+            if diff.is_empty() {
+                println!("No differences");
+            } else {
+                print!("{}", diff);
+            }
+            Ok(())
+        }
+        Commands::ValidateSpec { strict, explain } => {
+            let path = find_manifest()?;
+            let (config, report) = Config::from_path_with_report(&path, allow_revision)
+                .and_then(|(config, report)| config.validate().map(|()| (config, report)))
+                .wrap_err("Failed to load secretspec configuration")?;
+            print_warnings(&report.warnings);
+            if explain {
+                println!("{}", report);
+            }
+            let warnings = config.inconsistencies();
+            for warning in &warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            if strict && !warnings.is_empty() {
+                return Err(miette!(
+                    "{} inconsistent secret definition(s) in {}",
+                    warnings.len(),
+                    path.display()
+                ));
+            }
+            if !quiet {
+                println!("✓ {} is valid", path.display());
+            }
+            Ok(())
+        }
+        Commands::Info => {
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
             if let Some(p) = provider {
                 app.set_provider(p);
             }
             if let Some(p) = profile {
                 app.set_profile(p);
             }
-            app.check()
-                .into_diagnostic()
-                .wrap_err("Failed to check secrets")?;
+            let summary = app.summary();
+            println!("Project: {}", summary.name);
+            if let Some(description) = summary.description {
+                println!("Description: {}", description);
+            }
+            println!(
+                "Profiles: {} ({})",
+                summary.profiles.len(),
+                summary.profiles.join(", ")
+            );
+            println!("Secrets: {}", summary.secret_count);
+            match summary.resolution.provider {
+                Some(provider) => {
+                    println!("Provider: {} (from {})", provider.value, provider.source)
+                }
+                None => println!("Provider: (none configured)"),
+            }
             Ok(())
         }
-        // Import secrets from one provider to another
-        Commands::Import { from_provider } => {
-            let app = Secrets::load()
-                .into_diagnostic()
+        Commands::Doctor => {
+            let app = load_secrets(verbosity, no_global_config, allow_revision)?;
+            let checks = app
+                .provider_health(provider, profile)
+                .wrap_err("Failed to check providers")?;
+            let width = checks
+                .iter()
+                .map(|health| health.provider.len())
+                .max()
+                .unwrap_or(0);
+            for health in &checks {
+                let status = match &health.error {
+                    None => "✓ ok".to_string(),
+                    // Keep the table to one line per provider
+                    Some(error) => format!("✗ {}", error.lines().next().unwrap_or_default()),
+                };
+                println!(
+                    "{:width$}  {}  {} ({} ms)",
+                    health.provider,
+                    health.project,
+                    status,
+                    health.elapsed.as_millis(),
+                    width = width
+                );
+            }
+            let failed = checks
+                .iter()
+                .filter(|health| health.error.is_some())
+                .count();
+            if failed > 0 {
+                return Err(
+                    SecretSpecError::Provider(ProviderError::BackendUnavailable(format!(
+                        "{} of {} providers failed their health check",
+                        failed,
+                        checks.len()
+                    )))
+                    .into(),
+                );
+            }
+            Ok(())
+        }
+        Commands::Docs { output } => {
+            let config = load_config(&find_manifest()?, allow_revision)
                 .wrap_err("Failed to load secretspec configuration")?;
+            let markdown = config.to_markdown();
+            match output {
+                Some(path) => {
+                    fs::write(&path, markdown)
+                        .into_diagnostic()
+                        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+                    if !quiet {
+                        eprintln!("✓ Wrote documentation to {}", path.display());
+                    }
+                }
+                None => print!("{}", markdown),
+            }
+            Ok(())
+        }
+        // Import secrets from one provider to another
+        Commands::Import {
+            from_provider,
+            verify,
+        } => {
+            let mut app = load_secrets(verbosity, no_global_config, allow_revision)?;
+            if let Some(p) = provider {
+                app.set_provider(p);
+            }
+            if let Some(p) = profile {
+                app.set_profile(p);
+            }
+            app.set_verify_import(verify);
             app.import(&from_provider)
-                .into_diagnostic()
                 .wrap_err("Failed to import secrets")?;
             Ok(())
         }
+        Commands::Completions { shell } => {
+            let cli = Cli::command();
+            let commands: Vec<&str> = cli
+                .get_subcommands()
+                .filter(|command| !command.is_hide_set())
+                .map(|command| command.get_name())
+                .collect();
+            print!("{}", completions::script(shell, &commands));
+            Ok(())
+        }
+        // Completion only reads secretspec.toml: it never prompts or contacts
+        // the provider, and prints nothing if the spec can't be loaded
+        Commands::Complete { prefix } => {
+            let Ok(mut app) = load_secrets(Verbosity::Quiet, no_global_config, allow_revision)
+            else {
+                return Ok(());
+            };
+            if let Some(p) = profile {
+                app.set_profile(p);
+            }
+            for name in app.secret_names().unwrap_or_default() {
+                if name.starts_with(&prefix) {
+                    println!("{}", name);
+                }
+            }
+            Ok(())
+        }
     }
 }
@@ -33,8 +33,12 @@
 //! DATABASE_URL = { description = "Production database", required = true }
 //! ```
 
+use crate::reference;
+use crate::remote::RemoteSpec;
+use crate::transform::Transform;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -49,7 +53,16 @@ pub struct Config {
     /// Project metadata including name, revision, and optional inheritance
     pub project: Project,
     /// Map of profile names to their configurations (e.g., "default", "production", "staging")
+    #[serde(default)]
     pub profiles: HashMap<String, Profile>,
+    /// Secrets shared by every profile, declared in a top-level `[secrets]`
+    /// table. Profiles that define a secret of the same name override it.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_secrets",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub secrets: HashMap<String, Secret>,
 }
 
 impl Config {
@@ -58,6 +71,8 @@ impl Config {
     /// Ensures that:
     /// - Project name is not empty
     /// - At least one profile is defined
+    /// - The `default` profile, or every profile if there is none, defines
+    ///   at least one secret
     /// - All secrets have valid configurations
     /// - Secret names are valid identifiers
     ///
@@ -77,11 +92,44 @@ impl Config {
             ));
         }
 
+        if self.profiles.values().all(|profile| profile.is_abstract) {
+            return Err(ParseError::Validation(
+                "At least one profile must not be abstract".into(),
+            ));
+        }
+
         // Validate each profile
         for (profile_name, profile) in &self.profiles {
+            // Other profiles inherit the secrets of `default`, so they may
+            // only set e.g. a provider
+            if profile.secrets.is_empty()
+                && (profile_name == "default" || !self.profiles.contains_key("default"))
+            {
+                return Err(ParseError::Validation(format!(
+                    "Profile '{}': Profile must define at least one secret",
+                    profile_name
+                )));
+            }
             profile.validate().map_err(|e| {
                 ParseError::Validation(format!("Profile '{}': {}", profile_name, e))
             })?;
+
+            // A trigger may be declared in the profile or inherited from `default`
+            let declares = |profile: &Profile, name: &str| profile.secrets.contains_key(name);
+            for (name, secret) in &profile.secrets {
+                if let Some(trigger) = &secret.required_if
+                    && !declares(profile, trigger)
+                    && !self
+                        .profiles
+                        .get("default")
+                        .is_some_and(|default| declares(default, trigger))
+                {
+                    return Err(ParseError::Validation(format!(
+                        "Profile '{}': Secret '{}': required_if references undeclared secret '{}'",
+                        profile_name, name, trigger
+                    )));
+                }
+            }
         }
 
         Ok(())
@@ -102,6 +150,10 @@ impl Config {
     /// The current configuration takes precedence - values from `other`
     /// are only used if not already present.
     pub fn merge_with(&mut self, other: Config) {
+        for (name, secret) in other.secrets {
+            self.secrets.entry(name).or_insert(secret);
+        }
+
         // Merge profiles
         for (profile_name, profile_config) in other.profiles {
             match self.profiles.get_mut(&profile_name) {
@@ -115,12 +167,282 @@ impl Config {
         }
     }
 
+    /// Compute the structural differences between this specification and `other`.
+    ///
+    /// Secrets are compared per profile by description, `required` and
+    /// default; `other` is treated as the newer version, so secrets only
+    /// present there are reported as added. Providers are not consulted.
+    pub fn diff(&self, other: &Config) -> SpecDiff {
+        let empty = Profile::new();
+        let profile_names: HashSet<&String> =
+            self.profiles.keys().chain(other.profiles.keys()).collect();
+
+        let mut diff = SpecDiff::default();
+        for profile_name in profile_names {
+            let before = self.profiles.get(profile_name).unwrap_or(&empty);
+            let after = other.profiles.get(profile_name).unwrap_or(&empty);
+
+            for (name, secret) in &after.secrets {
+                match before.secrets.get(name) {
+                    None => {
+                        diff.added
+                            .entry(profile_name.clone())
+                            .or_default()
+                            .insert(name.clone(), secret.clone());
+                    }
+                    Some(previous)
+                        if previous.description != secret.description
+                            || previous.severity() != secret.severity()
+                            || previous.default != secret.default =>
+                    {
+                        diff.changed
+                            .entry(profile_name.clone())
+                            .or_default()
+                            .insert(
+                                name.clone(),
+                                SecretChange {
+                                    before: previous.clone(),
+                                    after: secret.clone(),
+                                },
+                            );
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            for (name, secret) in &before.secrets {
+                if !after.secrets.contains_key(name) {
+                    diff.removed
+                        .entry(profile_name.clone())
+                        .or_default()
+                        .insert(name.clone(), secret.clone());
+                }
+            }
+        }
+
+        diff
+    }
+
+    /// Find secrets that are defined differently across profiles.
+    ///
+    /// A secret whose description, `transform`, `min_length` or `max_length`
+    /// differs between the profiles defining it is usually a copy-paste
+    /// mistake. Returns one message per secret and field, ordered by secret
+    /// name; `required` and defaults are expected to vary and are ignored.
+    pub fn inconsistencies(&self) -> Vec<String> {
+        let mut by_name: BTreeMap<&String, BTreeMap<&String, &Secret>> = BTreeMap::new();
+        for (profile_name, profile) in &self.profiles {
+            for (name, secret) in &profile.secrets {
+                by_name
+                    .entry(name)
+                    .or_default()
+                    .insert(profile_name, secret);
+            }
+        }
+
+        let mut messages = Vec::new();
+        for (name, definitions) in by_name {
+            for field in ComparedField::value_variants() {
+                let values: Vec<(&String, String)> = definitions
+                    .iter()
+                    .map(|(profile_name, secret)| (*profile_name, field.show(secret)))
+                    .collect();
+                if values.iter().any(|(_, value)| *value != values[0].1) {
+                    let listed: Vec<String> = values
+                        .iter()
+                        .map(|(profile_name, value)| format!("{} {}", profile_name, value))
+                        .collect();
+                    messages.push(format!(
+                        "{} has a different {} across profiles: {}",
+                        name,
+                        field.name(),
+                        listed.join(", ")
+                    ));
+                }
+            }
+        }
+        messages
+    }
+
+    /// Render the specification as a Markdown document.
+    ///
+    /// Each profile gets a section, `default` first and the others in
+    /// alphabetical order, with a table of its secrets as written in the
+    /// specification. A "Help" column is added when any secret has a
+    /// `help_url`. Providers are not consulted.
+    pub fn to_markdown(&self) -> String {
+        use std::fmt::Write;
+
+        let with_help = self
+            .profiles
+            .values()
+            .flat_map(|profile| profile.secrets.values())
+            .any(|secret| secret.help_url.is_some());
+
+        let mut profile_names: Vec<&String> = self.profiles.keys().collect();
+        profile_names.sort_by_key(|name| (name.as_str() != "default", name.as_str()));
+
+        let mut out = String::new();
+        let _ = writeln!(out, "# {} secrets", escape_markdown(&self.project.name));
+        for profile_name in profile_names {
+            let profile = &self.profiles[profile_name];
+            let _ = writeln!(out, "\n## Profile `{}`\n", profile_name);
+            if profile.is_abstract {
+                let _ = writeln!(out, "Abstract: only provides secrets to other profiles.\n");
+            }
+            if let Some(provider) = &profile.provider {
+                let _ = writeln!(out, "Provider: {}\n", markdown_code(provider));
+            }
+            if profile.secrets.is_empty() {
+                let _ = writeln!(out, "No secrets.");
+                continue;
+            }
+
+            let mut header = "| Name | Description | Required | Default |".to_string();
+            let mut separator = "|------|-------------|----------|---------|".to_string();
+            if with_help {
+                header.push_str(" Help |");
+                separator.push_str("------|");
+            }
+            let _ = writeln!(out, "{}\n{}", header, separator);
+
+            let mut names: Vec<&String> = profile.secrets.keys().collect();
+            names.sort();
+            for name in names {
+                let secret = &profile.secrets[name];
+                let required = match &secret.required_if {
+                    Some(trigger) => format!("✓ if `{}` is set", trigger),
+                    None if secret.required => "✓".to_string(),
+                    None if secret.recommended => "recommended".to_string(),
+                    None => "✗".to_string(),
+                };
+                let _ = write!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    name,
+                    secret
+                        .description
+                        .as_deref()
+                        .map(escape_markdown)
+                        .unwrap_or_default(),
+                    required,
+                    secret
+                        .default
+                        .as_deref()
+                        .map(markdown_code)
+                        .unwrap_or_default(),
+                );
+                if with_help {
+                    let _ = write!(
+                        out,
+                        " {} |",
+                        secret
+                            .help_url
+                            .as_deref()
+                            .map(|url| format!("<{}>", url.replace('|', "%7C")))
+                            .unwrap_or_default()
+                    );
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Load the configuration at `path` and report which file contributed
+    /// each secret definition once `extends` is resolved.
+    ///
+    /// Merging keeps the first definition of a secret, so definitions in
+    /// `path` win over extended configurations and earlier `extends` entries
+    /// win over later ones. The report names the winning source of every
+    /// secret and the sources whose definitions it overrides.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as loading the configuration with
+    /// [`Config::from_path_allowing_revision`].
+    pub fn merge_report(
+        path: &Path,
+        allowed_revision: Option<&str>,
+    ) -> Result<MergeReport, ParseError> {
+        let (_, report) = Self::from_path_with_report(path, allowed_revision)?;
+        Ok(report)
+    }
+
+    /// Load the configuration at `path` like
+    /// [`Config::from_path_allowing_revision`], along with the
+    /// [`MergeReport`] of the files it was read from.
+    ///
+    /// Nothing is printed; warnings such as reading an allowed unsupported
+    /// revision are returned in [`MergeReport::warnings`] for the caller to
+    /// show.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Config::from_path_allowing_revision`].
+    pub fn from_path_with_report(
+        path: &Path,
+        allowed_revision: Option<&str>,
+    ) -> Result<(Self, MergeReport), ParseError> {
+        let mut visited = HashSet::new();
+        let (mut config, report) =
+            Self::from_path_with_visited(path, &mut visited, allowed_revision)?;
+        config.apply_shared_secrets();
+        Ok((config, report))
+    }
+
+    /// Load the configuration at `path` like `Config::try_from`, reading it
+    /// even if it, or a configuration it extends, has revision
+    /// `allowed_revision`.
+    ///
+    /// Files of an unsupported revision are normally rejected. An allowed one
+    /// is parsed instead, which works as long as it only uses fields this
+    /// version understands; use [`Config::from_path_with_report`] to get the
+    /// warning about it. `Config::try_from` allows the revision in
+    /// `SECRETSPEC_ALLOW_REVISION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `Config::try_from`, including
+    /// `UnsupportedRevision` for revisions other than `1.0` and
+    /// `allowed_revision`.
+    pub fn from_path_allowing_revision(
+        path: &Path,
+        allowed_revision: Option<&str>,
+    ) -> Result<Self, ParseError> {
+        let (config, _) = Self::from_path_with_report(path, allowed_revision)?;
+        Ok(config)
+    }
+
     // Internal methods
 
+    /// Copy the shared secrets into every profile that does not define them
+    /// itself, creating the `default` profile if needed.
+    ///
+    /// Runs once the whole configuration, including `extends`, is loaded, so
+    /// a secret is looked up in its profile, then in the shared secrets and
+    /// only then in the `default` profile.
+    fn apply_shared_secrets(&mut self) {
+        if self.secrets.is_empty() {
+            return;
+        }
+
+        self.profiles.entry("default".to_string()).or_default();
+        for profile in self.profiles.values_mut() {
+            for (name, secret) in &self.secrets {
+                profile
+                    .secrets
+                    .entry(name.clone())
+                    .or_insert_with(|| secret.clone());
+            }
+        }
+    }
+
     fn from_path_with_visited(
         path: &Path,
-        visited: &mut HashSet<PathBuf>,
-    ) -> Result<Self, ParseError> {
+        visited: &mut HashSet<String>,
+        allowed_revision: Option<&str>,
+    ) -> Result<(Self, MergeReport), ParseError> {
         // Get canonical path to handle symlinks and relative paths consistently
         let canonical_path = path.canonicalize().map_err(|e| {
             ParseError::Io(io::Error::new(
@@ -130,7 +452,7 @@ impl Config {
         })?;
 
         // Check for circular dependency
-        if !visited.insert(canonical_path.clone()) {
+        if !visited.insert(canonical_path.to_string_lossy().into_owned()) {
             return Err(ParseError::CircularDependency(format!(
                 "Configuration file {} is part of a circular dependency chain",
                 canonical_path.display()
@@ -138,39 +460,69 @@ impl Config {
         }
 
         let content = fs::read_to_string(path)?;
-        Self::from_str_with_visited(&content, Some(path), visited)
+        let source = path.display().to_string();
+        Self::from_str_with_visited(&content, Some(path), &source, visited, allowed_revision)
     }
 
+    /// Parses `content` and merges the configurations it extends.
+    ///
+    /// `source` names the file or URL the content came from in the returned
+    /// [`MergeReport`].
     fn from_str_with_visited(
         content: &str,
         base_path: Option<&Path>,
-        visited: &mut HashSet<PathBuf>,
-    ) -> Result<Self, ParseError> {
+        source: &str,
+        visited: &mut HashSet<String>,
+        allowed_revision: Option<&str>,
+    ) -> Result<(Self, MergeReport), ParseError> {
         let mut config: Config = toml::from_str(content)?;
 
+        let mut report = MergeReport::from_config(&config, source);
+
         // Validate revision
         if config.project.revision != "1.0" {
-            return Err(ParseError::UnsupportedRevision(config.project.revision));
+            if allowed_revision != Some(config.project.revision.as_str()) {
+                return Err(ParseError::UnsupportedRevision(config.project.revision));
+            }
+            report.warnings.push(format!(
+                "{} has revision '{}', which this version of secretspec doesn't support; reading it anyway",
+                source, config.project.revision
+            ));
         }
 
         // Process extends if present
-        if let Some(extends_paths) = config.project.extends.clone() {
-            if let Some(base) = base_path {
-                let base_dir = base.parent().unwrap_or(Path::new("."));
-                config = Self::merge_extended_configs(config, &extends_paths, base_dir, visited)?;
-            }
+        if let Some(extends_paths) = config.project.extends.clone()
+            && let Some(base) = base_path
+        {
+            let base_dir = base.parent().unwrap_or(Path::new("."));
+            (config, report) = Self::merge_extended_configs(
+                (config, report),
+                &extends_paths,
+                base_dir,
+                visited,
+                allowed_revision,
+            )?;
         }
 
-        Ok(config)
+        Ok((config, report))
     }
 
     fn merge_extended_configs(
-        mut base_config: Config,
+        (mut base_config, mut report): (Config, MergeReport),
         extends_paths: &[String],
         base_dir: &Path,
-        visited: &mut HashSet<PathBuf>,
-    ) -> Result<Config, ParseError> {
+        visited: &mut HashSet<String>,
+        allowed_revision: Option<&str>,
+    ) -> Result<(Config, MergeReport), ParseError> {
         for extend_path in extends_paths {
+            if let Some(remote) = RemoteSpec::parse(extend_path) {
+                let (extended_config, extended_report) =
+                    Self::from_remote_with_visited(&remote, visited, allowed_revision)?;
+                base_config.merge_with(extended_config);
+                report.merge_with(extended_report);
+                continue;
+            }
+
             let full_path = base_dir.join(extend_path).join("secretspec.toml");
 
             if !full_path.exists() {
@@ -180,11 +532,107 @@ impl Config {
                 )));
             }
 
-            let extended_config = Self::from_path_with_visited(&full_path, visited)?;
+            let (extended_config, extended_report) =
+                Self::from_path_with_visited(&full_path, visited, allowed_revision)?;
             base_config.merge_with(extended_config);
+            report.merge_with(extended_report);
         }
 
-        Ok(base_config)
+        Ok((base_config, report))
+    }
+
+    fn from_remote_with_visited(
+        remote: &RemoteSpec,
+        visited: &mut HashSet<String>,
+        allowed_revision: Option<&str>,
+    ) -> Result<(Self, MergeReport), ParseError> {
+        remote.validate()?;
+        // Remote configs are identified by URL and ref rather than by the
+        // cache path, so the same repository at two refs is not a cycle
+        let key = remote.key();
+        if !visited.insert(key.clone()) {
+            return Err(ParseError::CircularDependency(format!(
+                "Configuration {} is part of a circular dependency chain",
+                key
+            )));
+        }
+
+        let mut warnings = Vec::new();
+        let path = remote.fetch(&mut warnings)?;
+        let content = fs::read_to_string(&path)?;
+        let (config, mut report) =
+            Self::from_str_with_visited(&content, Some(&path), &key, visited, allowed_revision)?;
+        report.warnings.splice(0..0, warnings);
+        Ok((config, report))
+    }
+}
+
+/// Fields of a secret compared across profiles by [`Config::inconsistencies`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "snake_case")]
+enum ComparedField {
+    Description,
+    Transform,
+    MinLength,
+    MaxLength,
+}
+
+impl ComparedField {
+    /// The field's name in `secretspec.toml`
+    fn name(self) -> String {
+        self.to_possible_value()
+            .expect("no variant is skipped")
+            .get_name()
+            .to_string()
+    }
+
+    /// Formats the field's value in `secret`
+    fn show(self, secret: &Secret) -> String {
+        let show_length = |length: Option<usize>| match length {
+            Some(length) => length.to_string(),
+            None => "(none)".to_string(),
+        };
+        match self {
+            ComparedField::Description => {
+                format!("{:?}", secret.description.as_deref().unwrap_or(""))
+            }
+            ComparedField::Transform => {
+                let chain: Vec<String> =
+                    secret.transform.iter().map(Transform::to_string).collect();
+                format!("{:?}", chain.join("+"))
+            }
+            ComparedField::MinLength => show_length(secret.min_length),
+            ComparedField::MaxLength => show_length(secret.max_length),
+        }
+    }
+}
+
+/// Escapes characters with a meaning in Markdown, and line breaks, so that
+/// `text` renders literally inside a table cell.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '{' | '}' | '[' | ']' | '<' | '>' | '(' | ')' | '#' | '+'
+            | '-' | '!' | '|' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats `text` as inline code for a table cell, falling back to escaped
+/// text when it contains backticks or line breaks.
+fn markdown_code(text: &str) -> String {
+    if text.is_empty() || text.contains(['`', '\n', '\r']) {
+        escape_markdown(text)
+    } else {
+        format!("`{}`", text.replace('|', "\\|"))
     }
 }
 
@@ -195,9 +643,13 @@ impl FromStr for Config {
     ///
     /// Note: Configuration inheritance (`extends`) is not supported when parsing
     /// from a string since there's no base path to resolve relative paths.
+    /// Revisions other than `1.0` are always rejected.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut visited = HashSet::new();
-        Self::from_str_with_visited(s, None, &mut visited)
+        let (mut config, _) =
+            Self::from_str_with_visited(s, None, "secretspec.toml", &mut visited, None)?;
+        config.apply_shared_secrets();
+        Ok(config)
     }
 }
 
@@ -207,12 +659,21 @@ impl TryFrom<&Path> for Config {
     /// Load configuration from a file path.
     ///
     /// This supports configuration inheritance via `extends` and circular dependency detection.
+    /// A revision named in `SECRETSPEC_ALLOW_REVISION` is read despite being
+    /// unsupported, see [`Config::from_path_allowing_revision`].
     fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let mut visited = HashSet::new();
-        Self::from_path_with_visited(path, &mut visited)
+        Self::from_path_allowing_revision(path, allowed_revision_from_env().as_deref())
     }
 }
 
+/// Returns the unsupported revision `SECRETSPEC_ALLOW_REVISION` allows
+/// reading anyway, if set.
+pub(crate) fn allowed_revision_from_env() -> Option<String> {
+    std::env::var("SECRETSPEC_ALLOW_REVISION")
+        .ok()
+        .filter(|revision| !revision.is_empty())
+}
+
 /// Project metadata and inheritance configuration.
 ///
 /// Contains essential project information and optional configuration inheritance.
@@ -224,19 +685,233 @@ pub struct Project {
     pub name: String,
     /// Configuration format revision (currently must be "1.0")
     pub revision: String,
-    /// Optional list of relative paths to other SecretSpec projects to inherit from
+    /// Short summary of the project, shown by `check` and `info`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Optional list of other SecretSpec projects to inherit from: relative
+    /// paths, `git+<url>#<ref>` repositories or HTTPS URLs to a `secretspec.toml`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extends: Option<Vec<String>>,
 }
 
+/// Where a secret definition came from after `extends` was resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretOrigin {
+    /// The file or URL whose definition is used
+    pub source: String,
+    /// Files or URLs whose definitions of the same secret were overridden,
+    /// in merge order
+    pub overridden: Vec<String>,
+}
+
+/// Which configuration contributed each secret definition, produced by
+/// [`Config::merge_report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Secrets of each profile, keyed by profile name, then by secret name
+    pub profiles: BTreeMap<String, BTreeMap<String, SecretOrigin>>,
+    /// Secrets of the top-level `[secrets]` table, keyed by name
+    pub secrets: BTreeMap<String, SecretOrigin>,
+    /// Warnings about the files that were read, such as an allowed
+    /// unsupported revision
+    pub warnings: Vec<String>,
+}
+
+impl MergeReport {
+    /// Attributes every secret defined in `config` itself to `source`.
+    fn from_config(config: &Config, source: &str) -> Self {
+        let origin = || SecretOrigin {
+            source: source.to_string(),
+            overridden: Vec::new(),
+        };
+        Self {
+            profiles: config
+                .profiles
+                .iter()
+                .map(|(profile, p)| {
+                    let secrets = p.secrets.keys().map(|name| (name.clone(), origin()));
+                    (profile.clone(), secrets.collect())
+                })
+                .collect(),
+            secrets: config
+                .secrets
+                .keys()
+                .map(|name| (name.clone(), origin()))
+                .collect(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Merges the report of an extended configuration the same way
+    /// [`Config::merge_with`] merges the configuration: existing entries
+    /// win and record the sources they override.
+    fn merge_with(&mut self, other: MergeReport) {
+        fn merge(into: &mut BTreeMap<String, SecretOrigin>, other: BTreeMap<String, SecretOrigin>) {
+            for (name, origin) in other {
+                match into.get_mut(&name) {
+                    Some(existing) => {
+                        existing.overridden.push(origin.source);
+                        existing.overridden.extend(origin.overridden);
+                    }
+                    None => {
+                        into.insert(name, origin);
+                    }
+                }
+            }
+        }
+
+        merge(&mut self.secrets, other.secrets);
+        self.warnings.extend(other.warnings);
+        for (profile, secrets) in other.profiles {
+            merge(self.profiles.entry(profile).or_default(), secrets);
+        }
+    }
+}
+
+impl std::fmt::Display for MergeReport {
+    /// Formats one `NAME  source` line per secret, followed by the sources
+    /// it overrides, grouped under `[secrets]` and `[profiles.<name>]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections = (!self.secrets.is_empty())
+            .then(|| ("[secrets]".to_string(), &self.secrets))
+            .into_iter()
+            .chain(
+                self.profiles
+                    .iter()
+                    .map(|(profile, secrets)| (format!("[profiles.{}]", profile), secrets)),
+            );
+
+        for (i, (header, secrets)) in sections.enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", header)?;
+            for (name, origin) in secrets {
+                write!(f, "{}  {}", name, origin.source)?;
+                if !origin.overridden.is_empty() {
+                    write!(f, " (overrides {})", origin.overridden.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Structural differences between two specifications.
+///
+/// Produced by [`Config::diff`]. Each map is keyed by profile name, then by
+/// secret name; profiles without differences are absent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecDiff {
+    /// Secrets only present in the newer specification
+    pub added: BTreeMap<String, BTreeMap<String, Secret>>,
+    /// Secrets only present in the older specification
+    pub removed: BTreeMap<String, BTreeMap<String, Secret>>,
+    /// Secrets whose description, `required` flag or default changed
+    pub changed: BTreeMap<String, BTreeMap<String, SecretChange>>,
+}
+
+impl SpecDiff {
+    /// Returns whether the specifications are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for SpecDiff {
+    /// Formats the differences as `+ NAME`, `- NAME` and `~ NAME: ...`
+    /// lines, grouped under a `[profiles.<name>]` header per profile.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let profiles: std::collections::BTreeSet<&String> = self
+            .added
+            .keys()
+            .chain(self.removed.keys())
+            .chain(self.changed.keys())
+            .collect();
+
+        for (i, profile) in profiles.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[profiles.{}]", profile)?;
+            for name in self.added.get(profile).into_iter().flat_map(|s| s.keys()) {
+                writeln!(f, "+ {}", name)?;
+            }
+            for name in self.removed.get(profile).into_iter().flat_map(|s| s.keys()) {
+                writeln!(f, "- {}", name)?;
+            }
+            for (name, change) in self.changed.get(profile).into_iter().flatten() {
+                writeln!(f, "~ {}: {}", name, change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A secret whose definition differs between two specifications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretChange {
+    /// The definition in the older specification
+    pub before: Secret,
+    /// The definition in the newer specification
+    pub after: Secret,
+}
+
+impl std::fmt::Display for SecretChange {
+    /// Formats the changed fields as `field old -> new`, separated by `; `.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &Option<String>| match value {
+            Some(value) => format!("{:?}", value),
+            None => "(none)".to_string(),
+        };
+
+        let mut fields = Vec::new();
+        if self.before.description != self.after.description {
+            fields.push(format!(
+                "description {} -> {}",
+                show(&self.before.description),
+                show(&self.after.description)
+            ));
+        }
+        if self.before.severity() != self.after.severity() {
+            fields.push(format!(
+                "required {} -> {}",
+                self.before.severity().as_toml(),
+                self.after.severity().as_toml()
+            ));
+        }
+        if self.before.default != self.after.default {
+            fields.push(format!(
+                "default {} -> {}",
+                show(&self.before.default),
+                show(&self.after.default)
+            ));
+        }
+        write!(f, "{}", fields.join("; "))
+    }
+}
+
 /// Configuration for a specific profile (environment).
 ///
 /// A profile represents a specific environment or context (e.g., "default", "production", "staging").
 /// Each profile contains its own set of secret definitions with their requirements.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
+    /// Whether the profile only provides secrets to other profiles and
+    /// cannot be selected itself, set with `abstract = true`
+    #[serde(
+        rename = "abstract",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub is_abstract: bool,
+    /// Provider to use for this profile unless one is given with
+    /// `--provider`, `SECRETSPEC_PROVIDER` or `Secrets::set_provider`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
     /// Map of secret names to their configurations, flattened in TOML for cleaner syntax
-    #[serde(flatten)]
+    #[serde(flatten, deserialize_with = "deserialize_secrets")]
     pub secrets: HashMap<String, Secret>,
 }
 
@@ -244,6 +919,8 @@ impl Profile {
     /// Create a new empty profile configuration.
     pub fn new() -> Self {
         Self {
+            is_abstract: false,
+            provider: None,
             secrets: HashMap::new(),
         }
     }
@@ -252,8 +929,12 @@ impl Profile {
     ///
     /// Ensures all secrets have valid names and configurations.
     pub fn validate(&self) -> Result<(), String> {
-        if self.secrets.is_empty() {
-            return Err("Profile must define at least one secret".into());
+        if self
+            .provider
+            .as_deref()
+            .is_some_and(|provider| provider.trim().is_empty())
+        {
+            return Err("provider cannot be empty".into());
         }
 
         for (name, secret) in &self.secrets {
@@ -268,6 +949,13 @@ impl Profile {
             secret
                 .validate()
                 .map_err(|e| format!("Secret '{}': {}", name, e))?;
+
+            if secret.required_if.as_deref() == Some(name.as_str()) {
+                return Err(format!(
+                    "Secret '{}': required_if cannot reference itself",
+                    name
+                ));
+            }
         }
 
         Ok(())
@@ -276,8 +964,12 @@ impl Profile {
     /// Merge another profile configuration into this one.
     ///
     /// The current profile takes precedence - secrets from `other`
-    /// are only added if they don't already exist.
+    /// are only added if they don't already exist, and its provider is only
+    /// used if this profile has none.
     pub fn merge_with(&mut self, other: Profile) {
+        if self.provider.is_none() {
+            self.provider = other.provider;
+        }
         for (secret_name, secret_config) in other.secrets {
             self.secrets.entry(secret_name).or_insert(secret_config);
         }
@@ -290,24 +982,308 @@ impl Default for Profile {
     }
 }
 
+/// Why a secret cannot be both required and have a default value.
+const REQUIRED_WITH_DEFAULT: &str = "required = true conflicts with default: a secret with a default is optional, so set required = false or remove the default";
+
+/// How strongly a secret is needed, written as the `required` key.
+///
+/// `required = true` and `required = false` are shorthands for
+/// `"required"` and `"optional"`. A `"recommended"` secret (also written
+/// `"warn"`) that is missing is reported with a warning, but doesn't fail
+/// validation or `run`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The secret must have a value
+    Required,
+    /// The secret should have a value; a warning is shown if it doesn't
+    Recommended,
+    /// The secret may be left unset
+    Optional,
+}
+
+impl Severity {
+    /// The severity as written in `secretspec.toml`, using the boolean
+    /// shorthands where they exist
+    fn as_toml(self) -> &'static str {
+        match self {
+            Severity::Required => "true",
+            Severity::Recommended => "\"recommended\"",
+            Severity::Optional => "false",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Required => "required",
+            Severity::Recommended => "recommended",
+            Severity::Optional => "optional",
+        })
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "required" => Ok(Severity::Required),
+            "recommended" | "warn" => Ok(Severity::Recommended),
+            "optional" => Ok(Severity::Optional),
+            _ => Err(format!(
+                "invalid severity '{}', expected true, false, \"required\", \"recommended\" or \"optional\"",
+                s
+            )),
+        }
+    }
+}
+
+impl Serialize for Severity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Severity::Required => serializer.serialize_bool(true),
+            Severity::Optional => serializer.serialize_bool(false),
+            Severity::Recommended => serializer.serialize_str("recommended"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Severity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SeverityVisitor;
+
+        impl serde::de::Visitor<'_> for SeverityVisitor {
+            type Value = Severity;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a boolean or one of \"required\", \"recommended\" or \"optional\"")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, required: bool) -> Result<Severity, E> {
+                Ok(if required {
+                    Severity::Required
+                } else {
+                    Severity::Optional
+                })
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Severity, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(SeverityVisitor)
+    }
+}
+
 /// Configuration for an individual secret.
 ///
 /// Defines the properties of a secret including its documentation,
 /// whether it's required, and an optional default value.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// When parsed, `required` defaults to `true` unless the secret has a
+/// default; an explicit `required = true` next to a default is rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawSecret", into = "RawSecret")]
 pub struct Secret {
     /// Human-readable description of what this secret is used for
     pub description: Option<String>,
+    /// Where to obtain the value, e.g. a 1Password item or a docs page,
+    /// shown when `check` prompts for the secret
+    pub help_url: Option<String>,
     /// Whether this secret must be provided (no default value)
-    /// Defaults to true if not specified
-    #[serde(default = "default_true")]
+    /// Defaults to true if not specified, or false if a default is set
     pub required: bool,
+    /// Whether a missing value is reported with a warning, for secrets that
+    /// are not required; written as `required = "recommended"`
+    pub recommended: bool,
     /// Optional default value if the secret is not provided
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<String>,
+    /// Name of another secret that makes this one required when it resolves to a value.
+    ///
+    /// When set, this takes precedence over `required`: the secret is only
+    /// required if the referenced secret is present.
+    pub required_if: Option<String>,
+    /// Minimum length of the value, in characters
+    pub min_length: Option<usize>,
+    /// Maximum length of the value, in characters
+    pub max_length: Option<usize>,
+    /// Values the secret may take, e.g. `["debug", "info"]`; any value is
+    /// accepted when empty
+    pub allowed: Vec<String>,
+    /// Whether an empty value, such as an unset CI variable exported as
+    /// `""`, is rejected as invalid; unset means inherited from the
+    /// `default` profile, and allowed if set nowhere
+    pub disallow_empty: Option<bool>,
+    /// Transformations applied to values read from the provider, written
+    /// as a `+`-separated list such as `"trim+base64-decode"`
+    pub transform: Vec<Transform>,
+    /// Former names the value may still be stored under, tried in order
+    /// when the provider has no value under the secret's own name
+    pub aliases: Vec<String>,
+    /// Group the secret belongs to, e.g. `"database"`, for commands that
+    /// operate on a group with `--group`
+    pub group: Option<String>,
+    /// Another project's secret to read the value from, written as
+    /// `"<path>#<NAME>"`, e.g. `"../service-a#DATABASE_URL"`; the path is
+    /// the project's directory or its `secretspec.toml`, relative to this
+    /// file
+    pub source: Option<String>,
+    /// Days after which a stored value is due for rotation; `check` warns
+    /// about older values when the provider records when they were set
+    pub rotate_after_days: Option<u64>,
+}
+
+/// A [`Secret`] as written in `secretspec.toml`, before `required` is
+/// derived from whether it has a default.
+#[derive(Serialize, Deserialize)]
+struct RawSecret {
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    help_url: Option<String>,
+    #[serde(default)]
+    required: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    required_if: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disallow_empty: Option<bool>,
+    #[serde(
+        default,
+        with = "crate::transform::chain",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    transform: Vec<Transform>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rotate_after_days: Option<u64>,
+}
+
+impl TryFrom<RawSecret> for Secret {
+    type Error = String;
+
+    fn try_from(raw: RawSecret) -> Result<Self, Self::Error> {
+        // `required_if` takes precedence over `required`, so a default is
+        // only contradictory for unconditionally required secrets
+        if raw.required == Some(Severity::Required)
+            && raw.default.is_some()
+            && raw.required_if.is_none()
+        {
+            return Err(REQUIRED_WITH_DEFAULT.into());
+        }
+
+        let severity = raw.required.unwrap_or(if raw.default.is_none() {
+            Severity::Required
+        } else {
+            Severity::Optional
+        });
+        Ok(Self {
+            required: severity == Severity::Required,
+            recommended: severity == Severity::Recommended,
+            description: raw.description,
+            help_url: raw.help_url,
+            default: raw.default,
+            required_if: raw.required_if,
+            min_length: raw.min_length,
+            max_length: raw.max_length,
+            allowed: raw.allowed,
+            disallow_empty: raw.disallow_empty,
+            transform: raw.transform,
+            aliases: raw.aliases,
+            group: raw.group,
+            source: raw.source,
+            rotate_after_days: raw.rotate_after_days,
+        })
+    }
+}
+
+impl From<Secret> for RawSecret {
+    fn from(secret: Secret) -> Self {
+        Self {
+            required: Some(secret.severity()),
+            description: secret.description,
+            help_url: secret.help_url,
+            default: secret.default,
+            required_if: secret.required_if,
+            min_length: secret.min_length,
+            max_length: secret.max_length,
+            allowed: secret.allowed,
+            disallow_empty: secret.disallow_empty,
+            transform: secret.transform,
+            aliases: secret.aliases,
+            group: secret.group,
+            source: secret.source,
+            rotate_after_days: secret.rotate_after_days,
+        }
+    }
+}
+
+/// Deserializes a table of secrets, naming the offending secret in errors.
+fn deserialize_secrets<'de, D>(deserializer: D) -> Result<HashMap<String, Secret>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    HashMap::<String, RawSecret>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, raw)| match Secret::try_from(raw) {
+            Ok(secret) => Ok((name, secret)),
+            Err(e) => Err(serde::de::Error::custom(format!(
+                "secret '{}': {}",
+                name, e
+            ))),
+        })
+        .collect()
+}
+
+impl Default for Secret {
+    fn default() -> Self {
+        Self {
+            description: None,
+            help_url: None,
+            required: true,
+            recommended: false,
+            default: None,
+            required_if: None,
+            min_length: None,
+            max_length: None,
+            allowed: Vec::new(),
+            disallow_empty: None,
+            transform: Vec::new(),
+            aliases: Vec::new(),
+            group: None,
+            source: None,
+            rotate_after_days: None,
+        }
+    }
 }
 
 impl Secret {
+    /// Returns how strongly the secret is needed.
+    ///
+    /// `required` takes precedence over `recommended`.
+    pub fn severity(&self) -> Severity {
+        if self.required {
+            Severity::Required
+        } else if self.recommended {
+            Severity::Recommended
+        } else {
+            Severity::Optional
+        }
+    }
+
     /// Validate the secret configuration.
     ///
     /// Ensures that required secrets don't have default values.
@@ -320,29 +1296,137 @@ impl Secret {
             return Err("missing description".into());
         }
 
-        if self.required && self.default.is_some() {
-            return Err("Required secrets cannot have default values".into());
+        if let Some(help_url) = &self.help_url
+            && url::Url::parse(help_url).is_err()
+        {
+            return Err(format!("help_url '{}' is not a valid URL", help_url));
+        }
+
+        if let Some(trigger) = &self.required_if {
+            if !is_valid_identifier(trigger) {
+                return Err(format!(
+                    "required_if references invalid secret name '{}'",
+                    trigger
+                ));
+            }
+        } else if self.required && self.default.is_some() {
+            return Err(REQUIRED_WITH_DEFAULT.into());
+        }
+
+        if let Some(name) = self.default.as_deref().and_then(|default| {
+            reference::references(default)
+                .into_iter()
+                .find(|name| !is_valid_identifier(name))
+        }) {
+            return Err(format!("default references invalid secret name '{}'", name));
+        }
+
+        if let Some(alias) = self
+            .aliases
+            .iter()
+            .find(|alias| !is_valid_identifier(alias))
+        {
+            return Err(format!("invalid alias '{}'", alias));
+        }
+
+        if self.group.as_deref().is_some_and(str::is_empty) {
+            return Err("group cannot be empty".into());
+        }
+
+        if let Some(source) = &self.source
+            && self.source_parts().is_none()
+        {
+            return Err(format!(
+                "source '{}' must be written as \"<path>#<SECRET_NAME>\", e.g. \"../service-a#DATABASE_URL\"",
+                source
+            ));
+        }
+
+        if self.rotate_after_days == Some(0) {
+            return Err("rotate_after_days must be at least 1".into());
+        }
+
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length)
+            && min > max
+        {
+            return Err(format!(
+                "min_length ({}) cannot be greater than max_length ({})",
+                min, max
+            ));
+        }
+
+        // Defaults referencing other secrets are only known once resolved
+        if let Some(default) = &self.default
+            && !self.allowed.is_empty()
+            && reference::references(default).is_empty()
+            && !self.allowed.contains(default)
+        {
+            return Err(format!(
+                "default '{}' is not one of the allowed values: {}",
+                default,
+                self.allowed.join(", ")
+            ));
         }
 
         Ok(())
     }
-}
 
-fn default_true() -> bool {
-    true
+    /// Splits `source` into the path of the other project and the name of
+    /// its secret.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the secret has no source or it isn't of the form
+    /// `<path>#<NAME>` with a valid secret name
+    pub(crate) fn source_parts(&self) -> Option<(&str, &str)> {
+        self.source
+            .as_deref()?
+            .rsplit_once('#')
+            .filter(|(path, name)| !path.is_empty() && is_valid_identifier(name))
+    }
+
+    /// Check a resolved value against `disallow_empty`, the length
+    /// constraints and the allowed values.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the value is acceptable, otherwise the reason it is not,
+    /// e.g. `"is 6 chars, minimum 12"`
+    pub fn check_value(&self, value: &str) -> Option<String> {
+        if self.disallow_empty == Some(true) && value.is_empty() {
+            return Some("is empty".to_string());
+        }
+        let length = value.chars().count();
+        if let Some(min) = self.min_length
+            && length < min
+        {
+            return Some(format!("is {} chars, minimum {}", length, min));
+        }
+        if let Some(max) = self.max_length
+            && length > max
+        {
+            return Some(format!("is {} chars, maximum {}", length, max));
+        }
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|allowed| allowed == value) {
+            // The value itself is not repeated, as it may be sensitive
+            return Some(format!("is not one of: {}", self.allowed.join(", ")));
+        }
+        None
+    }
 }
 
 /// Check if a string is a valid identifier.
-fn is_valid_identifier(s: &str) -> bool {
+pub(crate) fn is_valid_identifier(s: &str) -> bool {
     if s.is_empty() {
         return false;
     }
 
     let mut chars = s.chars();
-    if let Some(first) = chars.next() {
-        if !first.is_alphabetic() && first != '_' {
-            return false;
-        }
+    if let Some(first) = chars.next()
+        && !first.is_alphabetic()
+        && first != '_'
+    {
+        return false;
     }
 
     chars.all(|c| c.is_alphanumeric() || c == '_')
@@ -370,6 +1454,15 @@ pub struct GlobalDefaults {
     /// Default profile to use when not specified
     #[serde(skip_serializing_if = "Option::is_none")]
     pub profile: Option<String>,
+    /// Number of attempts for remote provider operations (default: 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u32>,
+    /// Delay in milliseconds before the first retry, doubled on each retry (default: 250)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_backoff_ms: Option<u64>,
+    /// Seconds to cache provider values on disk; caching is disabled when unset or 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
 }
 
 impl GlobalConfig {
@@ -438,6 +1531,59 @@ impl GlobalConfig {
     }
 }
 
+/// Per-checkout overrides read from `.secretspec.local.toml`.
+///
+/// The file lives next to `secretspec.toml`, is meant to be gitignored and
+/// may only contain a `[defaults]` section. Its settings take precedence
+/// over the global configuration but not over explicit CLI flags.
+///
+/// ```toml
+/// [defaults]
+/// provider = "dotenv://.env.local"
+/// profile = "development"
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LocalConfig {
+    /// Default settings for this checkout
+    #[serde(default)]
+    pub defaults: LocalDefaults,
+}
+
+/// Default settings in the project-local configuration.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LocalDefaults {
+    /// Provider to use when not specified
+    pub provider: Option<String>,
+    /// Profile to use when not specified
+    pub profile: Option<String>,
+}
+
+impl LocalConfig {
+    /// Name of the project-local configuration file.
+    pub const FILE_NAME: &'static str = ".secretspec.local.toml";
+
+    /// Loads the project-local configuration from `dir`.
+    ///
+    /// # Returns
+    ///
+    /// The loaded configuration, or `None` if `dir` has no local configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be parsed, including
+    /// when it contains anything other than `[defaults]`
+    pub fn load(dir: &Path) -> Result<Option<Self>, ParseError> {
+        let path = dir.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map(Some).map_err(ParseError::Toml)
+    }
+}
+
 /// Container for resolved secrets with their context.
 ///
 /// This generic struct wraps the actual secret values along with
@@ -471,6 +1617,52 @@ impl<T> Resolved<T> {
     }
 }
 
+impl<V> Resolved<HashMap<String, V>> {
+    /// Combines these secrets with `other`, e.g. those of a base profile.
+    ///
+    /// Values in `self` take precedence: `other` only adds secrets that
+    /// `self` has no value for. The provider and profile of `self` are kept.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// let base = spec.validate().unwrap().unwrap().resolved;
+    /// spec.set_profile("production");
+    /// let production = spec.validate().unwrap().unwrap().resolved;
+    /// let merged = production.merge(base);
+    /// ```
+    pub fn merge(mut self, other: Self) -> Self {
+        for (name, value) in other.secrets {
+            self.secrets.entry(name).or_insert(value);
+        }
+        self
+    }
+}
+
+/// Dynamic lookup of secrets by their original name.
+///
+/// Implemented by the `SecretSpec` struct generated by the `secretspec-derive`
+/// macro, as an escape hatch next to typed field access when the secret name
+/// is only known at runtime.
+///
+/// # Example
+///
+/// ```ignore
+/// use secretspec::SecretSpecSecretsExt;
+///
+/// let resolved = SecretSpec::load(None::<String>, None)?;
+/// let database_url = resolved.secrets.get("DATABASE_URL");
+/// ```
+pub trait SecretSpecSecretsExt {
+    /// Returns the value of the secret named `name` as declared in
+    /// `secretspec.toml` (e.g. `"DATABASE_URL"`), or `None` if the secret is
+    /// unknown or has no value.
+    fn get(&self, name: &str) -> Option<String>;
+}
+
 /// Errors that can occur when parsing SecretSpec configuration files.
 ///
 /// This enum represents various failure modes when loading and parsing
@@ -488,6 +1680,8 @@ pub enum ParseError {
     CircularDependency(String),
     /// Validation error
     Validation(String),
+    /// Failure to fetch a remote configuration referenced from `extends`
+    Remote(String),
 }
 
 impl std::fmt::Display for ParseError {
@@ -506,6 +1700,7 @@ impl std::fmt::Display for ParseError {
                 write!(f, "Circular dependency detected: {}", msg)
             }
             ParseError::Validation(msg) => write!(f, "Validation error: {}", msg),
+            ParseError::Remote(msg) => write!(f, "Remote configuration error: {}", msg),
         }
     }
 }
@@ -520,6 +1715,10 @@ impl std::error::Error for ParseError {
     }
 }
 
+/// Lets the CLI keep parse errors as they are, so its exit code can tell
+/// an invalid configuration apart from other failures.
+impl miette::Diagnostic for ParseError {}
+
 impl From<io::Error> for ParseError {
     fn from(e: io::Error) -> Self {
         ParseError::Io(e)
@@ -0,0 +1,197 @@
+//! Comment-preserving edits to `secretspec.toml`
+//!
+//! [`Config`] is deserialized with `toml`, which drops comments and
+//! formatting. Commands that modify the specification instead load it into a
+//! [`SpecDocument`], backed by a `toml_edit` document, so everything they do
+//! not touch is written back unchanged.
+
+use crate::config::{Config, ParseError, Secret};
+use std::fmt;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// An editable `secretspec.toml` that keeps comments, ordering and whitespace
+pub(crate) struct SpecDocument {
+    doc: DocumentMut,
+}
+
+impl SpecDocument {
+    /// Parses a specification, checking that it deserializes into a [`Config`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::Toml` if the content is not a valid specification
+    pub(crate) fn parse(content: &str) -> Result<Self, ParseError> {
+        toml::from_str::<Config>(content)?;
+        let doc = content
+            .parse::<DocumentMut>()
+            .map_err(|e| ParseError::Validation(e.to_string()))?;
+        Ok(Self { doc })
+    }
+
+    /// Loads the specification at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub(crate) fn load(path: &Path) -> Result<Self, ParseError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Builds a new document from `config`, with a commented-out `extends`
+    /// hint below the project metadata.
+    ///
+    /// Profiles are written with `default` first and the rest sorted by
+    /// name; secrets are sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a secret cannot be serialized
+    pub(crate) fn from_config(config: &Config) -> Result<Self, ParseError> {
+        let mut project = Table::new();
+        project.insert("name", toml_edit::value(config.project.name.as_str()));
+        project.insert(
+            "revision",
+            toml_edit::value(config.project.revision.as_str()),
+        );
+        if let Some(description) = &config.project.description {
+            project.insert("description", toml_edit::value(description.as_str()));
+        }
+        if let Some(extends) = &config.project.extends {
+            project.insert(
+                "extends",
+                toml_edit::value(extends.iter().collect::<toml_edit::Array>()),
+            );
+        }
+
+        let mut document = Self {
+            doc: DocumentMut::new(),
+        };
+        document.doc.insert("project", Item::Table(project));
+
+        let mut profile_names: Vec<&String> = config.profiles.keys().collect();
+        profile_names.sort_by_key(|name| (name.as_str() != "default", name.as_str()));
+        for profile_name in profile_names {
+            let profile = &config.profiles[profile_name];
+            let table = document.profile_table(profile_name)?;
+            if profile.is_abstract {
+                table.insert("abstract", toml_edit::value(true));
+            }
+            if let Some(provider) = &profile.provider {
+                table.insert("provider", toml_edit::value(provider.as_str()));
+            }
+
+            let mut secret_names: Vec<&String> = profile.secrets.keys().collect();
+            secret_names.sort();
+            for secret_name in secret_names {
+                document.set_secret(profile_name, secret_name, &profile.secrets[secret_name])?;
+            }
+        }
+
+        if let Some(first) = document
+            .doc
+            .get_mut("profiles")
+            .and_then(Item::as_table_mut)
+            .and_then(|profiles| profiles.iter_mut().next())
+            .and_then(|(_, item)| item.as_table_mut())
+        {
+            first.decor_mut().set_prefix(
+                "# Extend configurations from subdirectories\n# extends = [ \"subdir1\", \"subdir2\" ]\n\n",
+            );
+        }
+
+        Ok(document)
+    }
+
+    /// Returns whether `profile` declares `name` directly.
+    pub(crate) fn has_secret(&self, profile: &str, name: &str) -> bool {
+        self.doc
+            .get("profiles")
+            .and_then(|profiles| profiles.get(profile))
+            .and_then(|table| table.get(name))
+            .is_some()
+    }
+
+    /// Adds `name` to `profile`, creating the profile if needed, or replaces
+    /// an existing definition in place, keeping its comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret cannot be serialized or the profile is
+    /// not a table
+    pub(crate) fn set_secret(
+        &mut self,
+        profile: &str,
+        name: &str,
+        secret: &Secret,
+    ) -> Result<(), ParseError> {
+        let mut inline = toml_edit::ser::to_document(secret)
+            .map_err(|e| ParseError::Validation(e.to_string()))?
+            .as_table()
+            .clone()
+            .into_inline_table();
+        inline.fmt();
+
+        let table = self.profile_table(profile)?;
+        match table.get_mut(name).and_then(Item::as_value_mut) {
+            Some(existing) => {
+                let decor = existing.decor().clone();
+                *existing = Value::InlineTable(inline);
+                *existing.decor_mut() = decor;
+            }
+            None => {
+                table.insert(name, toml_edit::value(inline));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the table of `profile`, appending an empty one if missing.
+    fn profile_table(&mut self, profile: &str) -> Result<&mut Table, ParseError> {
+        let profiles = self
+            .doc
+            .entry("profiles")
+            .or_insert_with(|| {
+                let mut profiles = Table::new();
+                profiles.set_implicit(true);
+                Item::Table(profiles)
+            })
+            .as_table_mut()
+            .ok_or_else(|| ParseError::Validation("'profiles' must be a table".to_string()))?;
+
+        profiles
+            .entry(profile)
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| ParseError::Validation(format!("Profile '{}' must be a table", profile)))
+    }
+
+    /// Returns the [`Config`] described by the document, without resolving
+    /// `extends`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document no longer deserializes or fails
+    /// validation
+    pub(crate) fn config(&self) -> Result<Config, ParseError> {
+        let config: Config = toml::from_str(&self.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Writes the document to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub(crate) fn save(&self, path: &Path) -> Result<(), ParseError> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for SpecDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
@@ -6,6 +6,7 @@ use thiserror::Error;
 
 // Internal use only
 use crate::config::ParseError;
+use crate::transform::Transform;
 use crate::validation::ValidationErrors;
 
 /// The main error type for secretspec operations
@@ -34,24 +35,84 @@ pub enum SecretSpecError {
     NoProviderConfigured,
     #[error("Provider backend '{0}' not found")]
     ProviderNotFound(String),
+    #[error(
+        "Provider '{0}' is not enabled in this build. Rebuild secretspec with the '{1}' feature (e.g., 'cargo install secretspec --features {1}')"
+    )]
+    ProviderNotEnabled(String, &'static str),
     #[error("Secret '{0}' not found")]
     SecretNotFound(String),
+    #[error(
+        "Profile '{0}' is not defined in secretspec.toml. Available profiles: {}",
+        .1.join(", ")
+    )]
+    ProfileNotFound(String, Vec<String>),
+    #[error(
+        "Secret '{0}' is not defined in profile '{1}'. Available secrets: {}",
+        .2.join(", ")
+    )]
+    UndeclaredSecret(String, String, Vec<String>),
+    #[error("No secret in profile '{1}' belongs to group '{0}'")]
+    GroupNotFound(String, String),
+    #[error("Circular dependency detected: {0}")]
+    CircularDependency(String),
     #[error("Secret '{0}' is required but not set")]
     RequiredSecretMissing(String),
-    #[error("No secretspec.toml found in current directory")]
+    #[error("No secretspec.toml found in the current directory or its parents")]
     NoManifest,
     #[error("Project name not found in secretspec.toml")]
     NoProjectName,
+    #[error(
+        "secretspec.toml defines no profiles. Declare secrets in at least one, for example:\n\n  [profiles.default]\n  DATABASE_URL = {{ description = \"Database connection string\" }}\n\nSee https://secretspec.dev/reference/configuration/"
+    )]
+    NoProfiles,
     #[error("Provider operation failed: {0}")]
-    ProviderOperationFailed(String),
+    Provider(#[from] ProviderError),
     #[error("User interaction error: {0}")]
     InquireError(#[from] inquire::InquireError),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
     #[error("Invalid profile: {0}")]
     InvalidProfile(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("{0} failed: {1}")]
+    TransformFailed(Transform, String),
     #[error("Validation failed: {0}")]
-    ValidationFailed(ValidationErrors),
+    ValidationFailed(Box<ValidationErrors>),
+    #[error(
+        "Strict mode does not allow default values; set these secrets in the provider: {}",
+        .0.join(", ")
+    )]
+    DefaultsNotAllowed(Vec<String>),
+}
+
+/// Errors reported by provider backends
+///
+/// Each variant carries a human-readable message; the variant itself lets
+/// callers tell apart failures such as a missing secret and an expired
+/// login without inspecting the message.
+#[derive(Error, Debug, Diagnostic)]
+pub enum ProviderError {
+    /// The requested item does not exist in the backend
+    #[error("{0}")]
+    NotFound(String),
+    /// The provider does not support writing values
+    #[error("Provider '{provider}' is read-only and does not support '{operation}'")]
+    ReadOnly {
+        /// Name of the provider, e.g. `env`
+        provider: String,
+        /// The rejected operation, e.g. `set`
+        operation: &'static str,
+    },
+    /// The backend rejected the request because the user is not authenticated
+    #[error("{0}")]
+    AuthFailed(String),
+    /// The backend could not be reached, e.g. its CLI is not installed
+    #[error("{0}")]
+    BackendUnavailable(String),
+    /// Any other provider failure
+    #[error("{0}")]
+    Other(String),
 }
 
 /// A type alias for `Result<T, SecretSpecError>`
@@ -72,12 +133,9 @@ impl From<ParseError> for SecretSpecError {
             }
             ParseError::Toml(toml_err) => SecretSpecError::Toml(toml_err),
             ParseError::UnsupportedRevision(rev) => SecretSpecError::UnsupportedRevision(rev),
-            ParseError::CircularDependency(msg) => {
-                SecretSpecError::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
-            }
-            ParseError::Validation(msg) => {
-                SecretSpecError::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
-            }
+            ParseError::CircularDependency(msg) => SecretSpecError::CircularDependency(msg),
+            ParseError::Validation(msg) => SecretSpecError::InvalidConfig(msg),
+            ParseError::Remote(msg) => SecretSpecError::Io(io::Error::other(msg)),
         }
     }
 }
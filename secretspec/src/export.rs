@@ -0,0 +1,245 @@
+//! Formatting of resolved secrets for other tools
+
+use crate::config::Secret;
+use std::collections::{BTreeMap, HashMap};
+
+/// Output formats supported by `secretspec export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// `KEY="value"` lines, as read by dotenv loaders
+    #[default]
+    Dotenv,
+    /// `export KEY='value'` lines for sourcing from a shell or a direnv `.envrc`
+    Direnv,
+    /// `KEY='value'` lines for docker-compose `env_file:`, read without
+    /// variable interpolation
+    Compose,
+}
+
+/// Formats secrets in the given format, sorted by name
+///
+/// # Arguments
+///
+/// * `secrets` - The resolved secrets to format
+/// * `format` - The output format
+///
+/// # Returns
+///
+/// The formatted secrets, one per line
+pub(crate) fn format_secrets<V: AsRef<str>>(
+    secrets: &HashMap<String, V>,
+    format: ExportFormat,
+) -> String {
+    let sorted: BTreeMap<&String, &str> = secrets
+        .iter()
+        .map(|(name, value)| (name, value.as_ref()))
+        .collect();
+
+    sorted
+        .into_iter()
+        .map(|(name, value)| match format {
+            ExportFormat::Dotenv => format!("{}={}\n", name, dotenv_quote(value)),
+            ExportFormat::Direnv => format!("export {}={}\n", name, shell_quote(value)),
+            ExportFormat::Compose => format!("{}={}\n", name, compose_quote(value)),
+        })
+        .collect()
+}
+
+/// Formats secret definitions as a dotenv template, such as a `.env.example`
+///
+/// Each secret is written with its description as a `#` comment and its
+/// default as the value, or a placeholder derived from the description if it
+/// has none. No values are read from a provider.
+///
+/// # Arguments
+///
+/// * `secrets` - The secrets to include, with their resolved definitions
+///
+/// # Returns
+///
+/// The template, one secret per paragraph, sorted by name
+pub(crate) fn format_template(secrets: &BTreeMap<String, Secret>) -> String {
+    secrets
+        .iter()
+        .map(|(name, secret)| {
+            let mut entry = String::new();
+            if let Some(description) = &secret.description {
+                let optional = if secret.required || secret.required_if.is_some() {
+                    ""
+                } else if secret.recommended {
+                    " (recommended)"
+                } else {
+                    " (optional)"
+                };
+                entry.push_str(&format!(
+                    "# {}{}\n",
+                    description.trim_end().replace('\n', "\n# "),
+                    optional
+                ));
+            }
+            let value = match &secret.default {
+                Some(default) => dotenv_quote(default),
+                None => placeholder(secret.description.as_deref()),
+            };
+            entry.push_str(&format!("{}={}\n", name, value));
+            entry
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Derives a placeholder value from a secret's description
+///
+/// `Stripe API key` becomes `<stripe-api-key>`; without a usable
+/// description the placeholder is `<your-value-here>`.
+fn placeholder(description: Option<&str>) -> String {
+    let words: Vec<String> = description
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        "<your-value-here>".to_string()
+    } else {
+        format!("<{}>", words.join("-"))
+    }
+}
+
+/// Quotes a value for dotenv files
+///
+/// The value is wrapped in double quotes with backslashes, double quotes,
+/// dollar signs and newlines escaped, so dotenv loaders read it back verbatim.
+fn dotenv_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' | '"' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes a value for POSIX shells
+///
+/// The value is wrapped in single quotes, so nothing inside it is expanded;
+/// embedded single quotes are written as `'\''`.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Quotes a value for docker-compose env files
+///
+/// Single-quoted values are taken literally by compose, so they are used
+/// whenever possible. Values containing a single quote or a newline are
+/// double-quoted instead, with `\\`, `"` and newlines escaped and `$` written
+/// as `$$` so compose does not interpolate it.
+fn compose_quote(value: &str) -> String {
+    if !value.contains(['\'', '\n']) {
+        return format!("'{}'", value);
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' | '"' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '$' => quoted.push_str("$$"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direnv_format_quotes_values() {
+        let secrets = HashMap::from([
+            ("B_KEY".to_string(), "it's $HOME `pwd`".to_string()),
+            ("A_KEY".to_string(), "plain".to_string()),
+        ]);
+
+        let output = format_secrets(&secrets, ExportFormat::Direnv);
+        assert_eq!(
+            output,
+            "export A_KEY='plain'\nexport B_KEY='it'\\''s $HOME `pwd`'\n"
+        );
+    }
+
+    #[test]
+    fn test_dotenv_format_round_trips() {
+        let secrets = HashMap::from([
+            (
+                "API_KEY".to_string(),
+                "abc \"quoted\" $HOME \\n".to_string(),
+            ),
+            ("MULTILINE".to_string(), "line1\nline2".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]);
+
+        let output = format_secrets(&secrets, ExportFormat::Dotenv);
+        let parsed: HashMap<String, String> = dotenvy::from_read_iter(output.as_bytes())
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(parsed, secrets);
+    }
+
+    #[test]
+    fn test_template_uses_defaults_and_placeholders() {
+        let secret = |description: Option<&str>, required: bool, default: Option<&str>| Secret {
+            description: description.map(str::to_string),
+            required,
+            default: default.map(str::to_string),
+            ..Secret::default()
+        };
+        let secrets = BTreeMap::from([
+            (
+                "API_KEY".to_string(),
+                secret(Some("Stripe API key (live)"), true, None),
+            ),
+            (
+                "PORT".to_string(),
+                secret(Some("Port to listen on"), false, Some("8080")),
+            ),
+            ("TOKEN".to_string(), secret(None, true, None)),
+        ]);
+
+        assert_eq!(
+            format_template(&secrets),
+            "# Stripe API key (live)\nAPI_KEY=<stripe-api-key-live>\n\n\
+             # Port to listen on (optional)\nPORT=\"8080\"\n\n\
+             TOKEN=<your-value-here>\n"
+        );
+    }
+
+    #[test]
+    fn test_compose_format_avoids_interpolation() {
+        let secrets = HashMap::from([
+            ("PLAIN".to_string(), "p@ss $HOME \"x\"".to_string()),
+            ("QUOTE".to_string(), "it's $HOME".to_string()),
+            ("MULTILINE".to_string(), "line1\nline2\\".to_string()),
+        ]);
+
+        let output = format_secrets(&secrets, ExportFormat::Compose);
+        assert_eq!(
+            output,
+            "MULTILINE=\"line1\\nline2\\\\\"\nPLAIN='p@ss $HOME \"x\"'\nQUOTE=\"it's $$HOME\"\n"
+        );
+        assert!(!output.contains("export "));
+    }
+}
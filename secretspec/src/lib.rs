@@ -42,8 +42,15 @@
 
 // Internal modules
 mod config;
+mod document;
 mod error;
+mod export;
+mod reference;
+mod remote;
+mod report;
+mod rotate;
 mod secrets;
+mod transform;
 mod validation;
 
 pub(crate) mod provider;
@@ -53,7 +60,9 @@ pub(crate) mod provider;
 pub mod cli;
 
 // Re-export only the types needed by users and generated code
-pub use config::Resolved;
+pub use config::{
+    MergeReport, Resolved, SecretChange, SecretOrigin, SecretSpecSecretsExt, Severity, SpecDiff,
+};
 
 // Re-export config types for CLI usage only - these are marked #[doc(hidden)]
 #[doc(hidden)]
@@ -64,9 +73,25 @@ pub use config::{Config, GlobalConfig, GlobalDefaults, Profile, Project};
 pub use config::Secret;
 
 // Public API exports
-pub use error::{Result, SecretSpecError};
-pub use secrets::Secrets;
-pub use validation::ValidatedSecrets;
+pub use error::{ProviderError, Result, SecretSpecError};
+pub use export::ExportFormat;
+pub use provider::{Provider, register_provider};
+pub use report::CheckFormat;
+pub use rotate::Charset;
+pub use secrets::{
+    DEFAULT_MAX_CONCURRENCY, ProjectSummary, ProviderHealth, Resolution, ResolutionInfo,
+    ResolutionSource, Secrets, Verbosity, find_manifest,
+};
+pub use transform::Transform;
+
+#[cfg(any(test, feature = "testing"))]
+pub use provider::memory::MemoryProvider;
+pub use validation::{AuditSnapshot, AuditStatus, SecretFilter, SecretValue, ValidatedSecrets};
+
+// Resolved values with the `zeroize` feature, and the field type of
+// `declare_secrets!(..., zeroize)`
+#[cfg(feature = "zeroize")]
+pub use zeroize::Zeroizing;
 
 #[cfg(test)]
 mod tests;
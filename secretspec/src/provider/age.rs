@@ -0,0 +1,501 @@
+use super::Provider;
+use crate::{ProviderError, Result, SecretSpecError};
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::x25519::{Identity, Recipient};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Environment variable holding an age identity, or the path to an identity file.
+const AGE_KEY_ENV: &str = "SECRETSPEC_AGE_KEY";
+
+/// Environment variable listing additional recipients, separated by commas.
+const AGE_RECIPIENTS_ENV: &str = "SECRETSPEC_AGE_RECIPIENTS";
+
+/// Configuration for the age provider.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::path::PathBuf;
+/// use secretspec::provider::age::AgeConfig;
+///
+/// let config = AgeConfig {
+///     path: PathBuf::from("secrets.age"),
+///     identity: None,
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgeConfig {
+    /// Path to the encrypted file.
+    pub path: PathBuf,
+    /// Path to the identity file used for decryption.
+    ///
+    /// When not set, the identity is read from `SECRETSPEC_AGE_KEY` or
+    /// `~/.config/secretspec/age.key`.
+    pub identity: Option<PathBuf>,
+}
+
+impl Default for AgeConfig {
+    /// Creates a default configuration with path set to `secrets.age`.
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("secrets.age"),
+            identity: None,
+        }
+    }
+}
+
+impl TryFrom<&Url> for AgeConfig {
+    type Error = SecretSpecError;
+
+    /// Creates an AgeConfig from a URL.
+    ///
+    /// # URL Formats
+    ///
+    /// - `age:///absolute/path/secrets.age` - Absolute path
+    /// - `age://secrets.age` - Relative path (authority as filename)
+    /// - `age://` - Uses `secrets.age` in the current directory
+    /// - `age://secrets.age?identity=/path/to/key.txt` - Explicit identity file
+    fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
+        if url.scheme() != "age" {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Invalid scheme '{}' for age provider",
+                url.scheme()
+            ))));
+        }
+
+        let path = if url.path() != "" && url.path() != "/" {
+            if let Some(host) = url.host_str() {
+                format!("{}{}", host, url.path())
+            } else {
+                url.path().to_string()
+            }
+        } else if let Some(host) = url.host_str() {
+            host.to_string()
+        } else {
+            "secrets.age".to_string()
+        };
+
+        let identity = url
+            .query_pairs()
+            .find(|(key, _)| key == "identity")
+            .map(|(_, value)| PathBuf::from(value.into_owned()));
+
+        Ok(Self {
+            path: PathBuf::from(path),
+            identity,
+        })
+    }
+}
+
+/// The decrypted contents of an age-encrypted secrets file.
+///
+/// Recipients are stored inside the encrypted payload, since age files
+/// don't record them in a recoverable form. This lets `set` re-encrypt the
+/// file to everyone who could read it before.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AgeFile {
+    /// Public keys the file is encrypted to
+    #[serde(default)]
+    recipients: BTreeSet<String>,
+    /// Secret values keyed by `{profile}/{key}`
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+/// Provider for secrets stored in an age-encrypted file.
+///
+/// The file holds the secrets of every profile, encrypted with
+/// [age](https://age-encryption.org) and ASCII-armored so it can be
+/// committed to version control. Decryption uses an X25519 identity from
+/// the `identity` URL parameter, `SECRETSPEC_AGE_KEY` (either the
+/// `AGE-SECRET-KEY-...` string or a path to an identity file) or
+/// `~/.config/secretspec/age.key`, in that order.
+///
+/// When writing, the file is re-encrypted to all of its previous recipients,
+/// the current identity, and any recipients listed in
+/// `SECRETSPEC_AGE_RECIPIENTS`.
+///
+/// # Note
+///
+/// The project parameter is ignored as the file belongs to a single project.
+pub struct AgeProvider {
+    /// Configuration containing the path to the encrypted file
+    config: AgeConfig,
+}
+
+crate::register_provider! {
+    struct: AgeProvider,
+    config: AgeConfig,
+    name: "age",
+    description: "age-encrypted file, safe to commit",
+    schemes: ["age"],
+    examples: ["age://secrets.age", "age://secrets.age?identity=/path/to/key.txt"],
+}
+
+impl AgeProvider {
+    /// Creates a new AgeProvider with the given configuration.
+    pub fn new(config: AgeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Loads the identities used for decryption.
+    fn identities(&self) -> Result<Vec<Identity>> {
+        let content = if let Some(path) = &self.config.identity {
+            read_identity_file(path)?
+        } else if let Ok(value) = std::env::var(AGE_KEY_ENV) {
+            if value.trim_start().starts_with("AGE-SECRET-KEY-") {
+                value
+            } else {
+                read_identity_file(&PathBuf::from(value))?
+            }
+        } else {
+            let path = default_identity_path()?;
+            if !path.exists() {
+                return Err(ProviderError::AuthFailed(format!(
+                    "No age identity found. Set {} or create {}",
+                    AGE_KEY_ENV,
+                    path.display()
+                ))
+                .into());
+            }
+            read_identity_file(&path)?
+        };
+
+        let identities = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.parse::<Identity>().map_err(|e| {
+                    SecretSpecError::from(ProviderError::AuthFailed(format!(
+                        "Invalid age identity: {}",
+                        e
+                    )))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if identities.is_empty() {
+            return Err(ProviderError::AuthFailed("The age identity file is empty".into()).into());
+        }
+        Ok(identities)
+    }
+
+    /// Decrypts the secrets file, returning an empty one if it doesn't exist.
+    fn read(&self, identities: &[Identity]) -> Result<AgeFile> {
+        if !self.config.path.exists() {
+            return Ok(AgeFile::default());
+        }
+
+        let ciphertext = fs::read(&self.config.path)?;
+        let decryptor = age::Decryptor::new_buffered(ArmoredReader::new(&ciphertext[..]))
+            .map_err(|e| self.error(e))?;
+        let mut reader = decryptor
+            .decrypt(identities.iter().map(|i| i as &dyn age::Identity))
+            .map_err(|e| match e {
+                age::DecryptError::NoMatchingKeys => ProviderError::AuthFailed(format!(
+                    "None of the age identities can decrypt {}",
+                    self.config.path.display()
+                ))
+                .into(),
+                e => self.error(e),
+            })?;
+
+        let mut plaintext = String::new();
+        reader.read_to_string(&mut plaintext)?;
+        toml::from_str(&plaintext).map_err(|e| self.error(e))
+    }
+
+    /// Encrypts `file` to its recipients and writes it to disk.
+    fn write(&self, file: &AgeFile) -> Result<()> {
+        let recipients = file
+            .recipients
+            .iter()
+            .map(|r| {
+                r.parse::<Recipient>().map_err(|e| {
+                    SecretSpecError::from(ProviderError::Other(format!(
+                        "Invalid age recipient '{}': {}",
+                        r, e
+                    )))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plaintext = toml::to_string_pretty(file).map_err(|e| self.error(e))?;
+        let encryptor =
+            age::Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn age::Recipient))
+                .map_err(|e| self.error(e))?;
+
+        let mut ciphertext = Vec::new();
+        let mut writer = encryptor
+            .wrap_output(ArmoredWriter::wrap_output(
+                &mut ciphertext,
+                Format::AsciiArmor,
+            )?)
+            .map_err(|e| self.error(e))?;
+        writer.write_all(plaintext.as_bytes())?;
+        writer.finish()?.finish()?;
+
+        fs::write(&self.config.path, ciphertext)?;
+        Ok(())
+    }
+
+    /// Wraps an age or serialization error with the file path.
+    fn error(&self, e: impl std::fmt::Display) -> SecretSpecError {
+        ProviderError::Other(format!("{}: {}", self.config.path.display(), e)).into()
+    }
+}
+
+impl Provider for AgeProvider {
+    fn name(&self) -> &'static str {
+        Self::PROVIDER_NAME
+    }
+
+    /// Retrieves a secret by decrypting the whole file.
+    fn get(&self, _project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        if !self.config.path.exists() {
+            return Ok(None);
+        }
+
+        let file = self.read(&self.identities()?)?;
+        Ok(file.secrets.get(&format!("{}/{}", profile, key)).cloned())
+    }
+
+    fn describe_location(&self, _project: &str, key: &str, profile: &str) -> String {
+        format!(
+            "{}, entry '{}/{}'",
+            self.config.path.display(),
+            profile,
+            key
+        )
+    }
+
+    /// Sets a secret by decrypting the file, updating it and re-encrypting
+    /// it to all previous recipients plus the current identity.
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.set_many(
+            project,
+            &HashMap::from([(key.to_string(), value.to_string())]),
+            profile,
+        )
+    }
+
+    /// Stores several values with a single decryption and re-encryption of
+    /// the file.
+    fn set_many(
+        &self,
+        _project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        let identities = self.identities()?;
+        let mut file = self.read(&identities)?;
+
+        file.recipients.extend(
+            identities
+                .iter()
+                .map(|identity| identity.to_public().to_string()),
+        );
+        if let Ok(extra) = std::env::var(AGE_RECIPIENTS_ENV) {
+            file.recipients.extend(
+                extra
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|r| !r.is_empty())
+                    .map(String::from),
+            );
+        }
+
+        for (key, value) in values {
+            file.secrets
+                .insert(format!("{}/{}", profile, key), value.clone());
+        }
+        self.write(&file)
+    }
+
+    /// Lists the keys stored for `profile`.
+    fn list_keys(&self, _project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        if !self.config.path.exists() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let file = self.read(&self.identities()?)?;
+        let prefix = format!("{}/", profile);
+        Ok(Some(
+            file.secrets
+                .keys()
+                .filter_map(|k| k.strip_prefix(&prefix).map(String::from))
+                .collect(),
+        ))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.config.path.clone()]
+    }
+
+    fn resolve_paths_from(&mut self, dir: &Path) {
+        self.config.path = dir.join(&self.config.path);
+        if let Some(identity) = &mut self.config.identity {
+            *identity = dir.join(&*identity);
+        }
+    }
+}
+
+/// Reads an identity file, reporting a missing file as an authentication failure.
+fn read_identity_file(path: &PathBuf) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| {
+        ProviderError::AuthFailed(format!(
+            "Failed to read age identity {}: {}",
+            path.display(),
+            e
+        ))
+        .into()
+    })
+}
+
+/// Returns `~/.config/secretspec/age.key` (or the platform equivalent).
+fn default_identity_path() -> Result<PathBuf> {
+    use directories::ProjectDirs;
+    let dirs = ProjectDirs::from("", "", "secretspec").ok_or_else(|| {
+        ProviderError::Other("Could not determine the config directory".to_string())
+    })?;
+    Ok(dirs.config_dir().join("age.key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_identity(dir: &std::path::Path) -> (PathBuf, Identity) {
+        let identity = Identity::generate();
+        let path = dir.join("key.txt");
+        fs::write(
+            &path,
+            format!(
+                "# test key\n{}\n",
+                age::secrecy::ExposeSecret::expose_secret(&identity.to_string())
+            ),
+        )
+        .unwrap();
+        (path, identity)
+    }
+
+    #[test]
+    fn test_age_url_parsing() {
+        let url = Url::parse("age:///tmp/secrets.age").unwrap();
+        let config: AgeConfig = (&url).try_into().unwrap();
+        assert_eq!(config.path.to_str().unwrap(), "/tmp/secrets.age");
+        assert_eq!(config.identity, None);
+
+        let url = Url::parse("age://secrets.age?identity=/keys/me.txt").unwrap();
+        let config: AgeConfig = (&url).try_into().unwrap();
+        assert_eq!(config.path.to_str().unwrap(), "secrets.age");
+        assert_eq!(config.identity, Some(PathBuf::from("/keys/me.txt")));
+
+        let url = Url::parse("age://").unwrap();
+        let config: AgeConfig = (&url).try_into().unwrap();
+        assert_eq!(config.path.to_str().unwrap(), "secrets.age");
+    }
+
+    #[test]
+    fn test_round_trip_by_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let (identity_path, _) = write_identity(dir.path());
+        let provider = AgeProvider::new(AgeConfig {
+            path: dir.path().join("secrets.age"),
+            identity: Some(identity_path),
+        });
+
+        assert_eq!(provider.get("project", "API_KEY", "default").unwrap(), None);
+        provider
+            .set("project", "API_KEY", "dev-key", "default")
+            .unwrap();
+        provider
+            .set("project", "API_KEY", "prod-key", "production")
+            .unwrap();
+
+        assert_eq!(
+            provider.get("project", "API_KEY", "default").unwrap(),
+            Some("dev-key".to_string())
+        );
+        assert_eq!(
+            provider.get("project", "API_KEY", "production").unwrap(),
+            Some("prod-key".to_string())
+        );
+        assert_eq!(
+            provider.list_keys("project", "production").unwrap(),
+            Some(vec!["API_KEY".to_string()])
+        );
+
+        // The file is armored and doesn't leak values
+        let content = fs::read_to_string(dir.path().join("secrets.age")).unwrap();
+        assert!(content.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!content.contains("dev-key"));
+    }
+
+    #[test]
+    fn test_set_preserves_recipients() {
+        let dir = tempfile::tempdir().unwrap();
+        let (alice_path, alice_identity) = write_identity(dir.path());
+        let bob_dir = tempfile::tempdir().unwrap();
+        let (bob_path, bob) = write_identity(bob_dir.path());
+        let path = dir.path().join("secrets.age");
+
+        // Alice creates the file, encrypted to herself and Bob
+        let alice = AgeProvider::new(AgeConfig {
+            path: path.clone(),
+            identity: Some(alice_path),
+        });
+        let mut file = AgeFile::default();
+        file.recipients
+            .insert(alice_identity.to_public().to_string());
+        file.recipients.insert(bob.to_public().to_string());
+        alice.write(&file).unwrap();
+        alice.set("project", "API_KEY", "one", "default").unwrap();
+
+        // Bob can read it and update it without dropping Alice
+        let bob = AgeProvider::new(AgeConfig {
+            path: path.clone(),
+            identity: Some(bob_path),
+        });
+        assert_eq!(
+            bob.get("project", "API_KEY", "default").unwrap(),
+            Some("one".to_string())
+        );
+        bob.set("project", "API_KEY", "two", "default").unwrap();
+        assert_eq!(
+            alice.get("project", "API_KEY", "default").unwrap(),
+            Some("two".to_string())
+        );
+    }
+
+    #[test]
+    fn test_wrong_identity_is_auth_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let (alice_path, _) = write_identity(dir.path());
+        let other_dir = tempfile::tempdir().unwrap();
+        let (mallory_path, _) = write_identity(other_dir.path());
+        let path = dir.path().join("secrets.age");
+
+        AgeProvider::new(AgeConfig {
+            path: path.clone(),
+            identity: Some(alice_path),
+        })
+        .set("project", "API_KEY", "value", "default")
+        .unwrap();
+
+        let mallory = AgeProvider::new(AgeConfig {
+            path,
+            identity: Some(mallory_path),
+        });
+        assert!(matches!(
+            mallory.get("project", "API_KEY", "default"),
+            Err(SecretSpecError::Provider(ProviderError::AuthFailed(_)))
+        ));
+    }
+}
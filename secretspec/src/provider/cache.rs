@@ -0,0 +1,263 @@
+//! On-disk caching of secret values.
+//!
+//! [`CachingProvider`] wraps another provider and keeps the values it returns
+//! in a cache file for a limited time, so repeated commands do not query a
+//! slow backend again. Caching stores secret values on disk, so it is opt-in:
+//! [`Secrets`](crate::Secrets) only installs it when `SECRETSPEC_CACHE_TTL` or
+//! `cache_ttl` in the global configuration is set, and never for providers
+//! whose [`allows_caching`](Provider::allows_caching) is `false`, such as
+//! `env` and `keyring://?encrypt=1`.
+
+use super::Provider;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A cached value and when it stops being valid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    provider: String,
+    project: String,
+    profile: String,
+    key: String,
+    value: String,
+    /// Expiry time in seconds since the Unix epoch
+    expires_at: u64,
+}
+
+impl CacheEntry {
+    fn is_for(&self, provider: &str, project: &str, profile: &str, key: &str) -> bool {
+        self.provider == provider
+            && self.project == project
+            && self.profile == profile
+            && self.key == key
+    }
+}
+
+/// Contents of the cache file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: Vec<CacheEntry>,
+}
+
+/// A provider decorator that caches retrieved values on disk.
+///
+/// Entries are keyed by provider, project, profile and secret name and expire
+/// after the configured TTL. Only values that were found are cached. Writes
+/// go to the wrapped provider and drop the affected entries.
+///
+/// New entries are written to the cache file once, when the provider is
+/// dropped, rather than after every lookup.
+///
+/// The cache is a best-effort optimization: a cache file that cannot be read
+/// is treated as empty and failures to write it are ignored.
+pub struct CachingProvider {
+    inner: Box<dyn Provider>,
+    provider: String,
+    ttl: Duration,
+    path: PathBuf,
+    entries: Mutex<Vec<CacheEntry>>,
+    /// Whether `entries` has values the cache file doesn't
+    dirty: AtomicBool,
+}
+
+impl CachingProvider {
+    /// Wraps `inner`, caching its values in the file at `path` for `ttl`.
+    ///
+    /// `provider` identifies the wrapped provider in the cache, e.g. its URI,
+    /// so that different providers never share entries.
+    pub fn new(
+        inner: Box<dyn Provider>,
+        provider: impl Into<String>,
+        ttl: Duration,
+        path: PathBuf,
+    ) -> Self {
+        let now = now();
+        let mut entries = load(&path).entries;
+        entries.retain(|entry| entry.expires_at > now);
+        Self {
+            inner,
+            provider: provider.into(),
+            ttl,
+            path,
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the default cache file,
+    /// `~/.cache/secretspec/provider-cache.json` on Linux.
+    pub fn default_path() -> Option<PathBuf> {
+        let dirs = directories::ProjectDirs::from("", "", "secretspec")?;
+        Some(dirs.cache_dir().join("provider-cache.json"))
+    }
+
+    fn lookup(&self, project: &str, key: &str, profile: &str) -> Option<String> {
+        let now = now();
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| {
+                entry.expires_at > now && entry.is_for(&self.provider, project, profile, key)
+            })
+            .map(|entry| entry.value.clone())
+    }
+
+    fn store(&self, project: &str, key: &str, value: &str, profile: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| !entry.is_for(&self.provider, project, profile, key));
+        entries.push(CacheEntry {
+            provider: self.provider.clone(),
+            project: project.to_string(),
+            profile: profile.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            expires_at: now().saturating_add(self.ttl.as_secs()),
+        });
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    fn invalidate<'a>(
+        &self,
+        project: &str,
+        keys: impl IntoIterator<Item = &'a str>,
+        profile: &str,
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        for key in keys {
+            entries.retain(|entry| !entry.is_for(&self.provider, project, profile, key));
+        }
+        // Written right away, so a stale value is never served if the
+        // process ends before the provider is dropped
+        if entries.len() != before || self.dirty.swap(false, Ordering::Relaxed) {
+            save(&self.path, &entries);
+        }
+    }
+}
+
+impl Drop for CachingProvider {
+    fn drop(&mut self) {
+        if *self.dirty.get_mut() {
+            save(&self.path, self.entries.get_mut().unwrap());
+        }
+    }
+}
+
+impl Provider for CachingProvider {
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        if let Some(value) = self.lookup(project, key, profile) {
+            return Ok(Some(value));
+        }
+        let value = self.inner.get(project, key, profile)?;
+        if let Some(value) = &value {
+            self.store(project, key, value, profile);
+        }
+        Ok(value)
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.invalidate(project, [key], profile);
+        self.inner.set(project, key, value, profile)
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.invalidate(project, values.keys().map(String::as_str), profile);
+        self.inner.set_many(project, values, profile)
+    }
+
+    fn allows_set(&self) -> bool {
+        self.inner.allows_set()
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        self.inner.list_keys(project, profile)
+    }
+
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        self.inner.set_at(project, key, profile)
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.inner.record_set_at(project, key, profile, timestamp)
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        self.inner.describe_location(project, key, profile)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.inner.watch_paths()
+    }
+
+    fn is_remote(&self) -> bool {
+        self.inner.is_remote()
+    }
+
+    fn allows_caching(&self) -> bool {
+        self.inner.allows_caching()
+    }
+
+    fn supports_profiles(&self) -> bool {
+        self.inner.supports_profiles()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn load(path: &Path) -> CacheFile {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Writes `entries` to `path`, readable only by the current user.
+fn save(path: &Path, entries: &[CacheEntry]) {
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_vec(&CacheFile {
+            entries: entries.to_vec(),
+        })?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        // `mode` only applies to new files
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(&content)
+    };
+    let _ = write();
+}
@@ -0,0 +1,224 @@
+//! Fallback across several providers.
+//!
+//! A provider specification listing several providers separated by commas,
+//! such as `onepassword://Production,env`, creates a [`ChainProvider`]: reads
+//! try each provider in order and use the first value found, writes go to
+//! the first provider.
+
+use super::Provider;
+use crate::Result;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Splits a comma-separated provider specification into its providers.
+///
+/// Only commas followed by the start of another provider separate members:
+/// a `scheme://` URI or the name of a known provider. Other commas belong to
+/// the member they appear in, so `dotenv://a,b.env,env` is the `.env` file
+/// `a,b.env` followed by `env`.
+///
+/// # Returns
+///
+/// `None` if the specification names a single provider
+pub(crate) fn members(spec: &str) -> Option<Vec<&str>> {
+    let mut members = Vec::new();
+    let mut start = 0;
+    for (index, _) in spec.match_indices(',') {
+        let next = spec[index + 1..].split(',').next().unwrap_or_default();
+        if starts_member(next) {
+            members.push(&spec[start..index]);
+            start = index + 1;
+        }
+    }
+    members.push(&spec[start..]);
+
+    let members: Vec<&str> = members
+        .into_iter()
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+        .collect();
+    (members.len() > 1).then_some(members)
+}
+
+/// Whether `piece`, the text up to the next comma, begins a new member.
+///
+/// Empty pieces do, so that stray commas are ignored as before.
+fn starts_member(piece: &str) -> bool {
+    let piece = piece.trim();
+    if piece.is_empty() {
+        return true;
+    }
+    match piece.split_once(':') {
+        Some((scheme, rest)) if rest.starts_with("//") => {
+            scheme
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        Some((scheme, _)) => super::is_known_scheme(scheme),
+        None => super::is_known_scheme(piece),
+    }
+}
+
+/// A provider that falls back through an ordered list of providers.
+///
+/// Reads return the first value found. A provider that fails is skipped, so
+/// a chain such as `onepassword://Production,env` still reads environment
+/// variables where 1Password is unavailable; its error is only returned if
+/// no other provider has the value.
+///
+/// Writes go to the first provider only, so a chain is read-only if its first
+/// provider is.
+pub struct ChainProvider {
+    providers: Vec<Box<dyn Provider>>,
+}
+
+impl ChainProvider {
+    /// Creates a chain trying `providers` in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `providers` is empty.
+    pub fn new(providers: Vec<Box<dyn Provider>>) -> Self {
+        assert!(!providers.is_empty(), "a provider chain needs a provider");
+        Self { providers }
+    }
+
+    fn first(&self) -> &dyn Provider {
+        self.providers[0].as_ref()
+    }
+}
+
+impl Provider for ChainProvider {
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        let mut first_error = None;
+        for provider in &self.providers {
+            match provider.get(project, key, profile) {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.first().set(project, key, value, profile)
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.first().set_many(project, values, profile)
+    }
+
+    fn allows_set(&self) -> bool {
+        self.first().allows_set()
+    }
+
+    /// Returns the first timestamp any provider recorded for the key.
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        for provider in &self.providers {
+            if let Some(timestamp) = provider.set_at(project, key, profile)? {
+                return Ok(Some(timestamp));
+            }
+        }
+        Ok(None)
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.first().record_set_at(project, key, profile, timestamp)
+    }
+
+    /// Lists the keys of every provider that can enumerate them.
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        let mut keys: Option<BTreeSet<String>> = None;
+        let mut first_error = None;
+        for provider in &self.providers {
+            match provider.list_keys(project, profile) {
+                Ok(Some(listed)) => keys.get_or_insert_default().extend(listed),
+                Ok(None) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match (keys, first_error) {
+            (Some(keys), _) => Ok(Some(keys.into_iter().collect())),
+            (None, Some(e)) => Err(e),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Describes the first provider listing `key` among its keys, without
+    /// reading any value.
+    ///
+    /// Providers that list their keys without `key` are skipped. From the
+    /// first provider that cannot list its keys on, the remaining providers
+    /// are described in order, as are all of them if none lists `key`.
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        let mut remaining = self.providers.as_slice();
+        while let Some((provider, rest)) = remaining.split_first() {
+            match provider.list_keys(project, profile) {
+                Ok(Some(keys)) if keys.iter().any(|listed| listed == key) => {
+                    return provider.describe_location(project, key, profile);
+                }
+                Ok(Some(_)) => remaining = rest,
+                Ok(None) | Err(_) => break,
+            }
+        }
+        if remaining.is_empty() {
+            remaining = &self.providers;
+        }
+        remaining
+            .iter()
+            .map(|provider| provider.describe_location(project, key, profile))
+            .collect::<Vec<_>>()
+            .join(", falling back to ")
+    }
+
+    /// Returns the files backing every provider in the chain.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.providers
+            .iter()
+            .flat_map(|provider| provider.watch_paths())
+            .collect()
+    }
+
+    fn resolve_paths_from(&mut self, dir: &Path) {
+        for provider in &mut self.providers {
+            provider.resolve_paths_from(dir);
+        }
+    }
+
+    fn is_remote(&self) -> bool {
+        self.providers.iter().any(|provider| provider.is_remote())
+    }
+
+    fn allows_caching(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|provider| provider.allows_caching())
+    }
+
+    fn supports_profiles(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|provider| provider.supports_profiles())
+    }
+
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+}
@@ -1,11 +1,26 @@
 use super::Provider;
-use crate::{Result, SecretSpecError};
+use crate::remote::fnv1a;
+use crate::{ProviderError, Result, SecretSpecError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use url::Url;
 
+/// How the dotenv provider separates profiles within one file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProfilePrefix {
+    /// Keys are stored as is and every profile reads the same values.
+    #[default]
+    None,
+    /// Keys are prefixed with the upper-cased profile name, so
+    /// `DATABASE_URL` in `production` is stored as
+    /// `PRODUCTION_DATABASE_URL` and in `default` as `DEFAULT_DATABASE_URL`.
+    Upper,
+}
+
 /// Configuration for the dotenv provider.
 ///
 /// This struct holds the configuration for accessing .env files,
@@ -19,6 +34,7 @@ use url::Url;
 ///
 /// let config = DotEnvConfig {
 ///     path: PathBuf::from(".env.production"),
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +44,9 @@ pub struct DotEnvConfig {
     /// Can be either an absolute path (e.g., `/etc/secrets/.env`)
     /// or a relative path (e.g., `.env`, `config/.env.local`).
     pub path: PathBuf,
+    /// How profiles are separated within the file.
+    #[serde(default)]
+    pub profile_prefix: ProfilePrefix,
 }
 
 impl Default for DotEnvConfig {
@@ -38,6 +57,7 @@ impl Default for DotEnvConfig {
     fn default() -> Self {
         Self {
             path: PathBuf::from(".env"),
+            profile_prefix: ProfilePrefix::None,
         }
     }
 }
@@ -56,6 +76,10 @@ impl TryFrom<&Url> for DotEnvConfig {
     /// - `dotenv://.env` - Relative path (authority as filename)
     /// - `dotenv://` - Uses default `.env` in current directory
     ///
+    /// The optional `profile-prefix=upper` parameter stores each profile's
+    /// keys with the profile name as a prefix (e.g.,
+    /// `dotenv://.env?profile-prefix=upper`).
+    ///
     /// # Examples
     ///
     /// ```ignore
@@ -68,10 +92,10 @@ impl TryFrom<&Url> for DotEnvConfig {
     /// ```
     fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
         if url.scheme() != "dotenv" {
-            return Err(SecretSpecError::ProviderOperationFailed(format!(
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                 "Invalid scheme '{}' for dotenv provider",
                 url.scheme()
-            )));
+            ))));
         }
 
         // For dotenv URLs:
@@ -97,12 +121,58 @@ impl TryFrom<&Url> for DotEnvConfig {
             ".env".to_string()
         };
 
+        let mut profile_prefix = ProfilePrefix::None;
+        for (name, value) in url.query_pairs() {
+            match (name.as_ref(), value.as_ref()) {
+                ("profile-prefix", "none") => profile_prefix = ProfilePrefix::None,
+                ("profile-prefix", "upper") => profile_prefix = ProfilePrefix::Upper,
+                ("profile-prefix", other) => {
+                    return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                        "Invalid dotenv profile-prefix '{}'. Expected 'none' or 'upper'",
+                        other
+                    ))));
+                }
+                _ => {}
+            }
+        }
+
         Ok(Self {
             path: PathBuf::from(path),
+            profile_prefix,
         })
     }
 }
 
+impl DotEnvConfig {
+    /// Returns the prefix of `profile`'s keys in the file.
+    ///
+    /// Characters that can't appear in a variable name, such as `-`, are
+    /// replaced with `_`.
+    fn prefix(&self, profile: &str) -> String {
+        match self.profile_prefix {
+            ProfilePrefix::None => String::new(),
+            ProfilePrefix::Upper => {
+                let name: String = profile
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() {
+                            c.to_ascii_uppercase()
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+                format!("{}_", name)
+            }
+        }
+    }
+
+    /// Returns the name `key` is stored under in `profile`.
+    fn var_name(&self, key: &str, profile: &str) -> String {
+        format!("{}{}", self.prefix(profile), key)
+    }
+}
+
 /// Provider for managing secrets in .env files.
 ///
 /// The DotEnvProvider implements the Provider trait to enable reading
@@ -120,9 +190,10 @@ impl TryFrom<&Url> for DotEnvConfig {
 ///
 /// # Note
 ///
-/// This provider ignores the project and profile parameters as .env files
-/// typically don't have built-in namespacing. All secrets are stored
-/// flat in the file.
+/// This provider ignores the project parameter as .env files typically
+/// don't have built-in namespacing. Unless a
+/// [`profile_prefix`](DotEnvConfig::profile_prefix) is configured, it
+/// ignores the profile too and all secrets are stored flat in the file.
 pub struct DotEnvProvider {
     /// Configuration containing the path to the .env file
     config: DotEnvConfig,
@@ -134,7 +205,7 @@ crate::register_provider! {
     name: "dotenv",
     description: "Traditional .env files",
     schemes: ["dotenv"],
-    examples: ["dotenv://.env", "dotenv://.env.production"],
+    examples: ["dotenv://.env", "dotenv://.env.production", "dotenv://.env?profile-prefix=upper"],
 }
 
 impl DotEnvProvider {
@@ -206,6 +277,7 @@ impl DotEnvProvider {
                     description: Some(format!("{} secret", key)),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
         }
@@ -214,6 +286,158 @@ impl DotEnvProvider {
     }
 }
 
+impl DotEnvProvider {
+    /// Reads all variables from the .env file, which may not exist yet.
+    ///
+    /// Uses dotenvy for parsing to ensure compatibility with standard .env
+    /// file formats, quoted values, multiline strings and escape sequences.
+    fn read_vars(&self) -> Result<HashMap<String, String>> {
+        let mut vars = HashMap::new();
+        if !self.config.path.exists() {
+            return Ok(vars);
+        }
+
+        #[cfg(test)]
+        tests::FILE_READS.with(|reads| reads.set(reads.get() + 1));
+        for item in dotenvy::from_path_iter(&self.config.path)? {
+            let (k, v) = item?;
+            vars.insert(k, v);
+        }
+        Ok(vars)
+    }
+
+    /// Writes `vars` to the .env file, using serde-envfile for proper escaping.
+    ///
+    /// The content is written to a temporary file in the same directory,
+    /// which then replaces the .env file, so a crash never leaves it
+    /// half-written. If the .env file is a symlink, its target is replaced
+    /// and the link kept. An existing file keeps its permissions; a new one
+    /// is only readable by the current user.
+    fn write_vars(&self, vars: &HashMap<String, String>) -> Result<()> {
+        let content = serde_envfile::to_string(vars).map_err(|e| {
+            SecretSpecError::Provider(ProviderError::Other(format!(
+                "Failed to serialize .env file: {}",
+                e
+            )))
+        })?;
+
+        #[cfg(test)]
+        tests::FILE_WRITES.with(|writes| writes.set(writes.get() + 1));
+        let path = &self.target_path();
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let mut temp = tempfile::Builder::new()
+            .prefix(".secretspec.dotenv.")
+            .tempfile_in(dir)?;
+        temp.write_all(content.as_bytes())?;
+        temp.as_file().sync_all()?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(temp.path(), metadata.permissions())?;
+        }
+        temp.persist(path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Returns the path of `<path>.set_at` next to the .env file, which
+    /// records when values were set, as `KEY=<unix seconds>` lines.
+    fn set_at_path(&self) -> PathBuf {
+        let mut path = self.config.path.clone().into_os_string();
+        path.push(".set_at");
+        PathBuf::from(path)
+    }
+
+    /// Reads the recorded timestamps, keyed by variable name. The file may
+    /// not exist yet; entries that aren't numbers are skipped.
+    fn read_set_at(&self) -> Result<HashMap<String, u64>> {
+        let path = self.set_at_path();
+        let mut timestamps = HashMap::new();
+        if !path.exists() {
+            return Ok(timestamps);
+        }
+        for item in dotenvy::from_path_iter(&path)? {
+            let (key, value) = item?;
+            if let Ok(timestamp) = value.parse() {
+                timestamps.insert(key, timestamp);
+            }
+        }
+        Ok(timestamps)
+    }
+
+    /// Returns the file that writes replace: the target of the .env file
+    /// if it is a symlink, otherwise the file itself.
+    ///
+    /// The path is absolute when its directory exists, so that relative and
+    /// absolute paths to the same file agree, even before it is created.
+    fn target_path(&self) -> PathBuf {
+        let path = &self.config.path;
+        if let Ok(target) = fs::canonicalize(path) {
+            return target;
+        }
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        match (fs::canonicalize(dir), path.file_name()) {
+            (Ok(dir), Some(name)) => dir.join(name),
+            _ => path.clone(),
+        }
+    }
+
+    /// Takes an exclusive lock on a file in the secretspec cache directory,
+    /// `~/.cache/secretspec/locks/` on Linux, named after the .env file,
+    /// waiting for other writers to finish. The lock is released when the
+    /// returned file is dropped.
+    ///
+    /// A separate lock file is used because writes replace the .env file,
+    /// and a lock on the replaced file would no longer exclude anyone.
+    /// Keeping it out of the project directory means it never ends up
+    /// committed. Without a cache directory, `<path>.lock` next to the .env
+    /// file is used instead.
+    fn lock(&self) -> Result<fs::File> {
+        let target = self.target_path();
+        let lock_path = match directories::ProjectDirs::from("", "", "secretspec") {
+            Some(dirs) => {
+                let dir = dirs.cache_dir().join("locks");
+                fs::create_dir_all(&dir)?;
+                dir.join(format!("{:016x}.lock", fnv1a(&target.to_string_lossy())))
+            }
+            None => {
+                let mut lock_path = target.into_os_string();
+                lock_path.push(".lock");
+                PathBuf::from(lock_path)
+            }
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)?;
+        file.lock()?;
+        Ok(file)
+    }
+}
+
+/// Returns the 1-based number of the line assigning `key`, as `KEY=` or
+/// `export KEY=`.
+///
+/// When a key is assigned several times the last assignment wins, as when
+/// the file is read.
+fn assignment_line(contents: &str, key: &str) -> Option<usize> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim_start();
+            let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|(index, _)| index + 1)
+        .last()
+}
+
 impl Provider for DotEnvProvider {
     fn name(&self) -> &'static str {
         Self::PROVIDER_NAME
@@ -222,14 +446,14 @@ impl Provider for DotEnvProvider {
     /// Retrieves a secret value from the .env file.
     ///
     /// Reads the .env file and returns the value for the specified key.
-    /// The project and profile parameters are ignored as .env files
-    /// don't support namespacing.
+    /// The project parameter is ignored as .env files don't support
+    /// namespacing.
     ///
     /// # Arguments
     ///
     /// * `_project` - Ignored, .env files don't support project namespacing
     /// * `key` - The environment variable name to look up
-    /// * `_profile` - Ignored, .env files don't support profile namespacing
+    /// * `profile` - Selects the key's prefix if a profile prefix is configured
     ///
     /// # Returns
     ///
@@ -242,20 +466,24 @@ impl Provider for DotEnvProvider {
     /// Uses the dotenvy crate for parsing to ensure compatibility with
     /// standard .env file formats and proper handling of quoted values,
     /// multiline strings, and escape sequences.
-    fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
-        if !self.config.path.exists() {
-            return Ok(None);
-        }
+    fn get(&self, _project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        Ok(self
+            .read_vars()?
+            .remove(&self.config.var_name(key, profile)))
+    }
 
-        // Use dotenvy for reading to ensure compatibility
-        let mut vars = HashMap::new();
-        let env_vars = dotenvy::from_path_iter(&self.config.path)?;
-        for item in env_vars {
-            let (k, v) = item?;
-            vars.insert(k, v);
+    /// Points at the line assigning `key`, as `path:line`, or at the file
+    /// if the key is not in it.
+    fn describe_location(&self, _project: &str, key: &str, profile: &str) -> String {
+        let path = self.config.path.display();
+        let key = self.config.var_name(key, profile);
+        match std::fs::read_to_string(&self.config.path)
+            .ok()
+            .and_then(|contents| assignment_line(&contents, &key))
+        {
+            Some(line) => format!("{}:{}", path, line),
+            None => format!("{} (no {} entry)", path, key),
         }
-
-        Ok(vars.get(key).cloned())
     }
 
     /// Sets a secret value in the .env file.
@@ -265,10 +493,10 @@ impl Provider for DotEnvProvider {
     ///
     /// # Arguments
     ///
-    /// * `_project` - Ignored, .env files don't support project namespacing
+    /// * `project` - Ignored, .env files don't support project namespacing
     /// * `key` - The environment variable name to set
     /// * `value` - The value to store
-    /// * `_profile` - Ignored, .env files don't support profile namespacing
+    /// * `profile` - Selects the key's prefix if a profile prefix is configured
     ///
     /// # Returns
     ///
@@ -280,29 +508,90 @@ impl Provider for DotEnvProvider {
     /// 1. Loads existing variables using dotenvy to preserve them
     /// 2. Updates or adds the new key-value pair
     /// 3. Serializes back using serde-envfile for proper escaping
-    fn set(&self, _project: &str, key: &str, value: &str, _profile: &str) -> Result<()> {
-        // Load existing vars using dotenvy
-        let mut vars = HashMap::new();
-        if self.config.path.exists() {
-            let env_vars = dotenvy::from_path_iter(&self.config.path)?;
-            for item in env_vars {
-                let (k, v) = item?;
-                vars.insert(k, v);
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.set_many(
+            project,
+            &HashMap::from([(key.to_string(), value.to_string())]),
+            profile,
+        )
+    }
+
+    /// Sets several values with a single read and write of the .env file.
+    ///
+    /// The file is locked from the read until the write completes, so
+    /// concurrent writers, including other processes, never lose each
+    /// other's changes.
+    fn set_many(
+        &self,
+        _project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut vars = self.read_vars()?;
+        vars.extend(
+            values
+                .iter()
+                .map(|(k, v)| (self.config.var_name(k, profile), v.clone())),
+        );
+        self.write_vars(&vars)
+    }
+
+    /// Lists all keys defined in the .env file.
+    ///
+    /// The project parameter is ignored as .env files don't support
+    /// namespacing. With a profile prefix, only the profile's keys are
+    /// listed, without the prefix. A missing file has no keys.
+    fn list_keys(&self, _project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        if !self.config.path.exists() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let prefix = self.config.prefix(profile);
+        let mut keys = Vec::new();
+        for item in dotenvy::from_path_iter(&self.config.path)? {
+            let (key, _value) = item?;
+            if let Some(key) = key.strip_prefix(&prefix) {
+                keys.push(key.to_string());
             }
         }
 
-        // Update the value
-        vars.insert(key.to_string(), value.to_string());
+        Ok(Some(keys))
+    }
 
-        // Save back to file using serde-envfile for proper escaping
-        let content = serde_envfile::to_string(&vars).map_err(|e| {
-            SecretSpecError::ProviderOperationFailed(format!(
-                "Failed to serialize .env file: {}",
-                e
-            ))
-        })?;
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.config.path.clone()]
+    }
+
+    fn resolve_paths_from(&mut self, dir: &Path) {
+        self.config.path = dir.join(&self.config.path);
+    }
+
+    /// Reads the timestamp from `<path>.set_at` next to the .env file.
+    fn set_at(&self, _project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        Ok(self
+            .read_set_at()?
+            .remove(&self.config.var_name(key, profile)))
+    }
 
-        fs::write(&self.config.path, content)?;
+    /// Records the timestamp in `<path>.set_at` next to the .env file,
+    /// under the same lock as writes to the .env file.
+    fn record_set_at(
+        &self,
+        _project: &str,
+        key: &str,
+        profile: &str,
+        timestamp: u64,
+    ) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut timestamps = self.read_set_at()?;
+        timestamps.insert(self.config.var_name(key, profile), timestamp);
+        let mut lines: Vec<String> = timestamps
+            .iter()
+            .map(|(key, timestamp)| format!("{}={}\n", key, timestamp))
+            .collect();
+        lines.sort();
+        fs::write(self.set_at_path(), lines.concat())?;
         Ok(())
     }
 }
@@ -310,6 +599,24 @@ impl Provider for DotEnvProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Number of times this thread parsed a .env file
+        pub(super) static FILE_READS: Cell<usize> = const { Cell::new(0) };
+        /// Number of times this thread wrote a .env file
+        pub(super) static FILE_WRITES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[test]
+    fn test_assignment_line() {
+        let contents =
+            "# comment\nAPI_KEY=first\n  export DB_URL = \"x\"\nAPI_KEY_OLD=y\nAPI_KEY=second\n";
+        assert_eq!(assignment_line(contents, "API_KEY"), Some(5));
+        assert_eq!(assignment_line(contents, "DB_URL"), Some(3));
+        assert_eq!(assignment_line(contents, "API"), None);
+        assert_eq!(assignment_line(contents, "MISSING"), None);
+    }
 
     #[test]
     fn test_dotenv_url_parsing() {
@@ -357,6 +664,7 @@ mod tests {
 
         let provider = DotEnvProvider::new(DotEnvConfig {
             path: env_file.clone(),
+            ..Default::default()
         });
 
         let secrets = provider.reflect().unwrap();
@@ -373,13 +681,220 @@ mod tests {
         assert!(api_key_config.default.is_none());
     }
 
+    #[test]
+    fn test_list_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(
+            &env_file,
+            "API_KEY=test123\nDATABASE_URL=postgres://localhost\n",
+        )
+        .unwrap();
+
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path: env_file,
+            ..Default::default()
+        });
+
+        let mut keys = provider.list_keys("project", "default").unwrap().unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["API_KEY", "DATABASE_URL"]);
+    }
+
+    #[test]
+    fn test_set_at_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path: env_file.clone(),
+            profile_prefix: ProfilePrefix::Upper,
+        });
+
+        assert_eq!(
+            provider.set_at("project", "API_KEY", "default").unwrap(),
+            None
+        );
+        provider
+            .set("project", "API_KEY", "abc", "default")
+            .unwrap();
+        provider
+            .record_set_at("project", "API_KEY", "default", 1_700_000_000)
+            .unwrap();
+        provider
+            .record_set_at("project", "API_KEY", "production", 1_600_000_000)
+            .unwrap();
+
+        assert_eq!(
+            provider.set_at("project", "API_KEY", "default").unwrap(),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".env.set_at")).unwrap(),
+            "DEFAULT_API_KEY=1700000000\nPRODUCTION_API_KEY=1600000000\n"
+        );
+        // The timestamps stay out of the .env file
+        assert_eq!(
+            provider.list_keys("project", "default").unwrap(),
+            Some(vec!["API_KEY".to_string()])
+        );
+    }
+
     #[test]
     fn test_reflect_nonexistent_file() {
         let provider = DotEnvProvider::new(DotEnvConfig {
             path: PathBuf::from("/tmp/nonexistent/.env"),
+            ..Default::default()
         });
 
         let secrets = provider.reflect().unwrap();
         assert!(secrets.is_empty());
     }
+
+    #[test]
+    fn test_set_many_reads_and_writes_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "EXISTING=kept\n").unwrap();
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path: path.clone(),
+            ..Default::default()
+        });
+
+        let values: HashMap<String, String> = (0..50)
+            .map(|i| (format!("SECRET_{}", i), format!("value {}", i)))
+            .collect();
+        FILE_READS.with(|reads| reads.set(0));
+        FILE_WRITES.with(|writes| writes.set(0));
+        provider.set_many("project", &values, "default").unwrap();
+        assert_eq!(FILE_READS.with(Cell::get), 1);
+        assert_eq!(FILE_WRITES.with(Cell::get), 1);
+
+        let stored = provider.read_vars().unwrap();
+        assert_eq!(stored.len(), 51);
+        assert_eq!(stored["EXISTING"], "kept");
+        assert_eq!(stored["SECRET_42"], "value 42");
+    }
+
+    #[test]
+    fn test_concurrent_sets_keep_every_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let provider = DotEnvProvider::new(DotEnvConfig {
+                        path,
+                        ..Default::default()
+                    });
+                    for i in 0..10 {
+                        provider
+                            .set(
+                                "project",
+                                &format!("WRITER_{}_KEY_{}", writer, i),
+                                &format!("value {}", i),
+                                "default",
+                            )
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path,
+            ..Default::default()
+        });
+        let stored = provider.read_vars().unwrap();
+        assert_eq!(stored.len(), 80);
+        assert_eq!(stored["WRITER_7_KEY_9"], "value 9");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_set_writes_through_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared.env");
+        fs::write(&shared, "DATABASE_URL=postgres://localhost\n").unwrap();
+        let link = dir.path().join(".env");
+        std::os::unix::fs::symlink(&shared, &link).unwrap();
+
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path: link.clone(),
+            ..Default::default()
+        });
+        provider
+            .set("project", "API_KEY", "abc", "default")
+            .unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        let stored = fs::read_to_string(&shared).unwrap();
+        assert!(stored.contains("API_KEY"));
+        assert!(stored.contains("DATABASE_URL"));
+        assert!(!dir.path().join(".env.lock").exists());
+        assert!(!dir.path().join("shared.env.lock").exists());
+    }
+
+    #[test]
+    fn test_profile_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "DATABASE_URL=plain\n").unwrap();
+        let url = Url::parse(&format!("dotenv://{}?profile-prefix=upper", path.display())).unwrap();
+        let config = DotEnvConfig::try_from(&url).unwrap();
+        assert_eq!(config.path, path);
+        assert_eq!(config.profile_prefix, ProfilePrefix::Upper);
+        let provider = DotEnvProvider::new(config);
+
+        provider
+            .set("project", "DATABASE_URL", "postgres://prod", "production")
+            .unwrap();
+        assert_eq!(
+            provider
+                .get("project", "DATABASE_URL", "production")
+                .unwrap()
+                .as_deref(),
+            Some("postgres://prod")
+        );
+        assert_eq!(
+            provider.read_vars().unwrap()["PRODUCTION_DATABASE_URL"],
+            "postgres://prod"
+        );
+        assert_eq!(
+            provider.get("project", "DATABASE_URL", "staging").unwrap(),
+            None
+        );
+        assert_eq!(
+            provider.list_keys("project", "production").unwrap(),
+            Some(vec!["DATABASE_URL".to_string()])
+        );
+
+        provider.set("project", "API_KEY", "dev", "my-dev").unwrap();
+        assert_eq!(provider.read_vars().unwrap()["MY_DEV_API_KEY"], "dev");
+
+        // Without a prefix every profile reads the unprefixed key
+        let provider = DotEnvProvider::new(DotEnvConfig {
+            path,
+            ..Default::default()
+        });
+        assert_eq!(
+            provider
+                .get("project", "DATABASE_URL", "production")
+                .unwrap()
+                .as_deref(),
+            Some("plain")
+        );
+
+        let url = Url::parse("dotenv://.env?profile-prefix=lower").unwrap();
+        assert!(DotEnvConfig::try_from(&url).is_err());
+    }
 }
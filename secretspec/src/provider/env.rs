@@ -1,5 +1,5 @@
 use super::Provider;
-use crate::{Result, SecretSpecError};
+use crate::{ProviderError, Result, SecretSpecError};
 use serde::{Deserialize, Serialize};
 use std::env;
 use url::Url;
@@ -7,17 +7,23 @@ use url::Url;
 /// Configuration for the environment variables provider.
 ///
 /// This struct represents the configuration for the read-only environment
-/// variables provider. It contains no fields as the provider reads directly
-/// from the process environment without additional configuration.
+/// variables provider. By default secrets are read from variables of the
+/// same name; a prefix namespaces them, so that several applications can
+/// share one process environment.
 ///
 /// # Example
 ///
 /// ```ignore
 /// # use secretspec::provider::env::EnvConfig;
 /// let config = EnvConfig::default();
+/// let prefixed = EnvConfig { prefix: Some("APP_".to_string()) };
 /// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct EnvConfig {}
+pub struct EnvConfig {
+    /// Prefix prepended to secret names, e.g. `APP_` to read `DATABASE_URL`
+    /// from `APP_DATABASE_URL`
+    pub prefix: Option<String>,
+}
 
 impl TryFrom<&Url> for EnvConfig {
     type Error = SecretSpecError;
@@ -25,30 +31,48 @@ impl TryFrom<&Url> for EnvConfig {
     /// Creates an `EnvConfig` from a URL.
     ///
     /// This method validates that the URL has the correct scheme ("env")
-    /// and returns an `EnvConfig` instance. The environment provider
-    /// doesn't require any additional configuration from the URL.
+    /// and returns an `EnvConfig` instance.
+    ///
+    /// # URL Formats
+    ///
+    /// - `env://` - Reads variables named after the secrets
+    /// - `env://?prefix=APP_` - Reads `APP_<NAME>` for secret `NAME`
     ///
     /// # Example
     ///
     /// ```ignore
     /// # use url::Url;
     /// # use secretspec::provider::env::EnvConfig;
-    /// let url = Url::parse("env://").unwrap();
+    /// let url = Url::parse("env://?prefix=APP_").unwrap();
     /// let config: EnvConfig = (&url).try_into().unwrap();
     /// ```
     fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
         if url.scheme() != "env" {
-            return Err(SecretSpecError::ProviderOperationFailed(format!(
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                 "Invalid scheme '{}' for env provider",
                 url.scheme()
-            )));
+            ))));
         }
 
-        Ok(Self::default())
+        let prefix = url
+            .query_pairs()
+            .find(|(key, _)| key == "prefix")
+            .map(|(_, value)| value.into_owned())
+            .filter(|prefix| !prefix.is_empty());
+
+        Ok(Self { prefix })
     }
 }
 
-impl EnvConfig {}
+impl EnvConfig {
+    /// Returns the name of the environment variable holding secret `key`.
+    fn var_name(&self, key: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}{}", prefix, key),
+            None => key.to_string(),
+        }
+    }
+}
 
 /// A read-only provider that reads secrets from environment variables.
 ///
@@ -74,7 +98,6 @@ impl EnvConfig {}
 /// // Can only read values, not set them
 /// ```
 pub struct EnvProvider {
-    #[allow(dead_code)]
     config: EnvConfig,
 }
 
@@ -84,7 +107,7 @@ crate::register_provider! {
     name: "env",
     description: "Read-only environment variables",
     schemes: ["env"],
-    examples: ["env://"],
+    examples: ["env://", "env://?prefix=APP_"],
 }
 
 impl EnvProvider {
@@ -92,7 +115,7 @@ impl EnvProvider {
     ///
     /// # Arguments
     ///
-    /// * `config` - The configuration for the provider (currently unused)
+    /// * `config` - The configuration for the provider
     ///
     /// # Example
     ///
@@ -114,13 +137,14 @@ impl Provider for EnvProvider {
     /// Retrieves a secret value from environment variables.
     ///
     /// This method reads the value directly from the process environment
-    /// using the provided key. The project and profile parameters are
-    /// ignored as environment variables are global to the process.
+    /// using the provided key, preceded by the configured prefix if any. The
+    /// project and profile parameters are ignored as environment variables
+    /// are global to the process.
     ///
     /// # Arguments
     ///
     /// * `_project` - Project name (ignored)
-    /// * `key` - The environment variable name to read
+    /// * `key` - The secret name, i.e. the variable name without its prefix
     /// * `_profile` - Profile name (ignored)
     ///
     /// # Returns
@@ -139,7 +163,11 @@ impl Provider for EnvProvider {
     /// assert_eq!(value, Some("value123".to_string()));
     /// ```
     fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
-        Ok(env::var(key).ok())
+        Ok(env::var(self.config.var_name(key)).ok())
+    }
+
+    fn describe_location(&self, _project: &str, key: &str, _profile: &str) -> String {
+        format!("environment variable {}", self.config.var_name(key))
     }
 
     /// Attempts to set a secret value (always fails).
@@ -157,8 +185,8 @@ impl Provider for EnvProvider {
     ///
     /// # Returns
     ///
-    /// Always returns `Err(SecretSpecError::ProviderOperationFailed)` with
-    /// an explanatory message about the read-only nature of this provider.
+    /// Always returns `Err(SecretSpecError::Provider(ProviderError::ReadOnly { .. }))`
+    /// naming this provider and the `set` operation.
     ///
     /// # Example
     ///
@@ -171,9 +199,10 @@ impl Provider for EnvProvider {
     fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
         // Environment variables are read-only in this backend
         // Setting environment variables at runtime doesn't persist across processes
-        Err(crate::SecretSpecError::ProviderOperationFailed(
-            "Environment variable provider is read-only. Set variables in your shell or process environment.".to_string()
-        ))
+        Err(SecretSpecError::Provider(ProviderError::ReadOnly {
+            provider: Self::PROVIDER_NAME.to_string(),
+            operation: "set",
+        }))
     }
 
     /// Indicates whether this provider supports setting values.
@@ -188,4 +217,50 @@ impl Provider for EnvProvider {
     fn allows_set(&self) -> bool {
         false
     }
+
+    /// Environment variables are global to the process, so every profile
+    /// reads the same values.
+    fn supports_profiles(&self) -> bool {
+        false
+    }
+
+    /// Values from the environment are never written to disk.
+    fn allows_caching(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(uri: &str) -> EnvProvider {
+        EnvProvider::new(EnvConfig::try_from(&Url::parse(uri).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_prefix_url_parsing() {
+        let parse = |s: &str| EnvConfig::try_from(&Url::parse(s).unwrap()).unwrap();
+        assert_eq!(parse("env://").prefix, None);
+        assert_eq!(parse("env://?prefix=").prefix, None);
+        assert_eq!(parse("env://?prefix=APP_").prefix.as_deref(), Some("APP_"));
+    }
+
+    #[test]
+    fn test_prefix_names_namespaced_variable() {
+        let prefixed = provider("env://?prefix=APP_");
+        assert_eq!(prefixed.config.var_name("DATABASE_URL"), "APP_DATABASE_URL");
+        assert_eq!(
+            prefixed.describe_location("app", "DATABASE_URL", "default"),
+            "environment variable APP_DATABASE_URL"
+        );
+        assert!(
+            prefixed
+                .set("app", "DATABASE_URL", "value", "default")
+                .is_err()
+        );
+
+        let unprefixed = provider("env://");
+        assert_eq!(unprefixed.config.var_name("DATABASE_URL"), "DATABASE_URL");
+    }
 }
@@ -1,17 +1,45 @@
 use super::Provider;
-use crate::{Result, SecretSpecError};
+use crate::{ProviderError, Result, SecretSpecError};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
 use url::Url;
 
+/// How the keyring provider maps secrets to keychain entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyringMode {
+    /// One keychain entry per secret, `secretspec/{project}/{profile}/{key}`.
+    #[default]
+    PerKey,
+    /// One keychain entry per profile, `secretspec/{project}/{profile}`,
+    /// holding all of the profile's secrets as a JSON object.
+    ///
+    /// Reading every secret of a profile then needs a single keychain
+    /// access (and a single prompt on macOS), at the cost of writes
+    /// rewriting the whole entry.
+    Bundle,
+}
+
 /// Configuration for the keyring provider.
 ///
 /// This struct holds configuration options for the keyring provider,
 /// which stores secrets in the system's native keychain service.
-/// Currently, no additional configuration is required as the provider
-/// uses sensible defaults for all platforms.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct KeyringConfig {}
+pub struct KeyringConfig {
+    /// How secrets are mapped to keychain entries.
+    #[serde(default)]
+    pub mode: KeyringMode,
+    /// Whether values are encrypted with a key derived from
+    /// `SECRETSPEC_MASTER_KEY` before being stored.
+    #[serde(default)]
+    pub encrypt: bool,
+}
 
 impl TryFrom<&Url> for KeyringConfig {
     type Error = SecretSpecError;
@@ -19,7 +47,9 @@ impl TryFrom<&Url> for KeyringConfig {
     /// Creates a new KeyringConfig from a URL.
     ///
     /// The URL must have the scheme "keyring" (e.g., "keyring://").
-    /// Currently, no additional parameters are supported in the URL.
+    /// The optional `mode` parameter selects `per-key` (default) or
+    /// `bundle` storage (e.g., "keyring://?mode=bundle"), and `encrypt=1`
+    /// encrypts values with `SECRETSPEC_MASTER_KEY` before storing them.
     ///
     /// # Examples
     ///
@@ -31,18 +61,38 @@ impl TryFrom<&Url> for KeyringConfig {
     /// ```
     fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
         if url.scheme() != "keyring" {
-            return Err(SecretSpecError::ProviderOperationFailed(format!(
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                 "Invalid scheme '{}' for keyring provider",
                 url.scheme()
-            )));
+            ))));
         }
 
-        Ok(Self::default())
+        let mut config = Self::default();
+        for (name, value) in url.query_pairs() {
+            match (name.as_ref(), value.as_ref()) {
+                ("mode", "per-key") => config.mode = KeyringMode::PerKey,
+                ("mode", "bundle") => config.mode = KeyringMode::Bundle,
+                ("mode", other) => {
+                    return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                        "Invalid keyring mode '{}'. Expected 'per-key' or 'bundle'",
+                        other
+                    ))));
+                }
+                ("encrypt", "1" | "true") => config.encrypt = true,
+                ("encrypt", "0" | "false") => config.encrypt = false,
+                ("encrypt", other) => {
+                    return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                        "Invalid keyring encrypt value '{}'. Expected '1' or '0'",
+                        other
+                    ))));
+                }
+                _ => {}
+            }
+        }
+        Ok(config)
     }
 }
 
-impl KeyringConfig {}
-
 /// Provider for storing secrets in the system keychain.
 ///
 /// The KeyringProvider uses the operating system's native secure credential
@@ -52,13 +102,15 @@ impl KeyringConfig {}
 /// - Linux: Secret Service API (via libsecret)
 ///
 /// Secrets are stored with a hierarchical key structure:
-/// `secretspec/{project}/{profile}/{key}`
+/// `secretspec/{project}/{profile}/{key}`, or in bundle mode as one JSON
+/// object per `secretspec/{project}/{profile}`.
 ///
 /// This ensures secrets are properly namespaced by project and profile,
 /// preventing conflicts between different projects or environments.
 pub struct KeyringProvider {
-    #[allow(dead_code)]
     config: KeyringConfig,
+    /// Bundles read so far, by service name, so each profile is read once
+    bundles: Mutex<HashMap<String, BTreeMap<String, String>>>,
 }
 
 crate::register_provider! {
@@ -67,7 +119,7 @@ crate::register_provider! {
     name: "keyring",
     description: "Uses system keychain (Recommended)",
     schemes: ["keyring"],
-    examples: ["keyring://"],
+    examples: ["keyring://", "keyring://?mode=bundle", "keyring://?encrypt=1"],
 }
 
 impl KeyringProvider {
@@ -81,8 +133,244 @@ impl KeyringProvider {
     ///
     /// A new instance of KeyringProvider
     pub fn new(config: KeyringConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            bundles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads an entry from the keychain, decrypting it if encryption is on.
+    fn read_entry(&self, service: &str) -> Result<Option<String>> {
+        let entry = open_entry(service, None)?;
+        let stored = match entry.get_password() {
+            Ok(stored) => stored,
+            Err(keyring::Error::NoEntry) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if !self.config.encrypt {
+            return Ok(Some(stored));
+        }
+        decrypt_value(&master_key()?, service, &stored).map(Some)
+    }
+
+    /// Writes an entry to the keychain, encrypting it if encryption is on.
+    fn write_entry(&self, service: &str, value: &str) -> Result<()> {
+        let sealed;
+        let value = if self.config.encrypt {
+            sealed = encrypt_value(&master_key()?, service, value)?;
+            sealed.as_str()
+        } else {
+            value
+        };
+        let entry = open_entry(service, Some(value))?;
+        entry.set_password(value)?;
+        Ok(())
+    }
+
+    /// Reads the bundle entry for a profile from the keychain.
+    fn read_bundle(&self, service: &str) -> Result<BTreeMap<String, String>> {
+        match self.read_entry(service)? {
+            Some(json) => decode_bundle(service, &json),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the bundle for a profile, reading the keychain only on first use.
+    fn cached_bundle(&self, project: &str, profile: &str) -> Result<BTreeMap<String, String>> {
+        let service = bundle_service(project, profile);
+        let mut bundles = self.bundles.lock().unwrap();
+        if let Some(bundle) = bundles.get(&service) {
+            return Ok(bundle.clone());
+        }
+        let bundle = self.read_bundle(&service)?;
+        bundles.insert(service, bundle.clone());
+        Ok(bundle)
+    }
+}
+
+/// Opens the keychain entry for `service` under the current user.
+///
+/// On Windows, the entry and the `value` about to be stored are first
+/// checked against the Credential Manager's limits.
+fn open_entry(service: &str, value: Option<&str>) -> Result<Entry> {
+    let user = whoami::username();
+    if cfg!(windows) {
+        check_windows_limits(service, &user, value)?;
+    }
+    Ok(Entry::new(service, &user)?)
+}
+
+/// Maximum length of a Windows credential comment (`CRED_MAX_STRING_LENGTH`).
+const WINDOWS_MAX_COMMENT: usize = 256;
+
+/// Maximum size in bytes of a Windows credential blob
+/// (`CRED_MAX_CREDENTIAL_BLOB_SIZE`).
+const WINDOWS_MAX_BLOB: usize = 2560;
+
+/// Room left for the ` (keyring v{version})` suffix of the comment.
+const WINDOWS_COMMENT_SUFFIX: usize = 32;
+
+/// Checks an entry against the limits of the Windows Credential Manager.
+///
+/// The keyring crate stores `{user}@{service}:{user}.{service}` plus its
+/// version as the credential comment, so the service name is limited to
+/// roughly half of [`WINDOWS_MAX_COMMENT`]; values are stored as UTF-16.
+/// Exceeding either limit makes the keyring crate report an attribute
+/// error that names neither the secret nor the limit that was hit.
+fn check_windows_limits(service: &str, user: &str, value: Option<&str>) -> Result<()> {
+    let max_service =
+        WINDOWS_MAX_COMMENT.saturating_sub(2 * user.len() + 2 + WINDOWS_COMMENT_SUFFIX) / 2;
+    if service.len() > max_service {
+        return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+            "Keyring entry '{}' is {} characters long, but the Windows Credential Manager \
+             allows at most {} for user '{}'. Use a shorter project, profile or secret name",
+            service,
+            service.len(),
+            max_service,
+            user
+        ))));
     }
+
+    if let Some(value) = value {
+        let size = value.encode_utf16().count() * 2;
+        if size > WINDOWS_MAX_BLOB {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Value for keyring entry '{}' is {} bytes as UTF-16, but the Windows \
+                 Credential Manager allows at most {}",
+                service, size, WINDOWS_MAX_BLOB
+            ))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the keychain service name recording when a secret was set.
+fn set_at_service(project: &str, profile: &str, key: &str) -> String {
+    format!("secretspec/{}/{}/{}.set_at", project, profile, key)
+}
+
+/// Returns the keychain service name holding a profile's bundle.
+fn bundle_service(project: &str, profile: &str) -> String {
+    format!("secretspec/{}/{}", project, profile)
+}
+
+/// Environment variable holding the base64-encoded AES-256 key used by
+/// `encrypt=1`.
+const MASTER_KEY_VAR: &str = "SECRETSPEC_MASTER_KEY";
+
+/// Length in bytes of the master key.
+const MASTER_KEY_LEN: usize = 32;
+
+/// Prefix marking an encrypted entry and its format version.
+const ENCRYPTED_PREFIX: &str = "secretspec-aes-gcm:v1:";
+
+/// Length in bytes of an AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+/// Reads the master key for `encrypt=1` from the environment.
+fn master_key() -> Result<[u8; MASTER_KEY_LEN]> {
+    parse_master_key(std::env::var(MASTER_KEY_VAR).ok().as_deref())
+}
+
+/// Parses the value of `SECRETSPEC_MASTER_KEY`, `None` if it is not set.
+///
+/// The key must be 32 random bytes, base64 encoded, such as the output of
+/// `openssl rand -base64 32`. It is used as the AES-256 key directly, so
+/// passphrases are rejected rather than stretched into a guessable key.
+fn parse_master_key(encoded: Option<&str>) -> Result<[u8; MASTER_KEY_LEN]> {
+    let error = |reason: &str| {
+        SecretSpecError::Provider(ProviderError::Other(format!(
+            "The keyring provider is configured with encrypt=1, but {} {}",
+            MASTER_KEY_VAR, reason
+        )))
+    };
+    let encoded = match encoded {
+        Some(key) if !key.is_empty() => key,
+        _ => return Err(error("is not set")),
+    };
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|key| <[u8; MASTER_KEY_LEN]>::try_from(key).ok())
+        .ok_or_else(|| {
+            error(&format!(
+                "is not a base64-encoded {}-byte key; generate one with `openssl rand -base64 {}`",
+                MASTER_KEY_LEN, MASTER_KEY_LEN
+            ))
+        })
+}
+
+/// Encrypts a value for storage as `{prefix}{base64(nonce || ciphertext)}`.
+///
+/// The keychain service name is authenticated along with the value, so an
+/// encrypted entry copied to another secret's entry fails to decrypt.
+fn encrypt_value(
+    master_key: &[u8; MASTER_KEY_LEN],
+    service: &str,
+    plaintext: &str,
+) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: plaintext.as_bytes(),
+        aad: service.as_bytes(),
+    };
+    let ciphertext = Aes256Gcm::new(master_key.into())
+        .encrypt(&nonce, payload)
+        .map_err(|_| {
+            SecretSpecError::Provider(ProviderError::Other(
+                "Failed to encrypt keyring value".to_string(),
+            ))
+        })?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(sealed)))
+}
+
+/// Decrypts a value written by [`encrypt_value`].
+///
+/// Fails for entries stored without encryption, for entries encrypted with a
+/// different master key and for entries encrypted for another service name.
+fn decrypt_value(master_key: &[u8; MASTER_KEY_LEN], service: &str, stored: &str) -> Result<String> {
+    let error = |reason: &str| {
+        SecretSpecError::Provider(ProviderError::Other(format!(
+            "Keyring entry '{}' {}",
+            service, reason
+        )))
+    };
+    let encoded = stored
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .ok_or_else(|| error("is not encrypted; set it again to store it with encrypt=1"))?;
+    let sealed = STANDARD
+        .decode(encoded)
+        .map_err(|_| error("is not a valid encrypted value"))?;
+    if sealed.len() < NONCE_LEN {
+        return Err(error("is not a valid encrypted value"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let payload = Payload {
+        msg: ciphertext,
+        aad: service.as_bytes(),
+    };
+    let plaintext = Aes256Gcm::new(master_key.into())
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| {
+            error(&format!(
+                "could not be decrypted; check that {} is the key it was stored with",
+                MASTER_KEY_VAR
+            ))
+        })?;
+    String::from_utf8(plaintext).map_err(|_| error("does not decrypt to valid UTF-8"))
+}
+
+/// Parses a bundle entry, a JSON object of secret names to values.
+fn decode_bundle(service: &str, json: &str) -> Result<BTreeMap<String, String>> {
+    serde_json::from_str(json).map_err(|e| {
+        SecretSpecError::Provider(ProviderError::Other(format!(
+            "Keyring entry '{}' is not a valid secretspec bundle: {}",
+            service, e
+        )))
+    })
 }
 
 impl Provider for KeyringProvider {
@@ -90,6 +378,11 @@ impl Provider for KeyringProvider {
         Self::PROVIDER_NAME
     }
 
+    /// Encrypted entries must not end up in the plain-text cache.
+    fn allows_caching(&self) -> bool {
+        !self.config.encrypt
+    }
+
     /// Retrieves a secret from the system keychain.
     ///
     /// The secret is looked up using a hierarchical key structure:
@@ -109,14 +402,36 @@ impl Provider for KeyringProvider {
     /// * `Ok(None)` - If the secret doesn't exist
     /// * `Err` - If there was an error accessing the keychain
     fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        if self.config.mode == KeyringMode::Bundle {
+            return Ok(self.cached_bundle(project, profile)?.remove(key));
+        }
+
         let service = format!("secretspec/{}/{}/{}", project, profile, key);
+        self.read_entry(&service)
+    }
 
-        let entry = Entry::new(&service, &whoami::username())?;
-        match entry.get_password() {
-            Ok(password) => Ok(Some(password)),
-            Err(keyring::Error::NoEntry) => Ok(None),
-            Err(e) => Err(e.into()),
+    /// Names the keychain entry holding the secret; in bundle mode, the
+    /// entry holding the whole profile.
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        let mut location = match self.config.mode {
+            KeyringMode::PerKey => format!(
+                "keyring entry 'secretspec/{}/{}/{}' for user '{}'",
+                project,
+                profile,
+                key,
+                whoami::username()
+            ),
+            KeyringMode::Bundle => format!(
+                "field '{}' of keyring entry '{}' for user '{}'",
+                key,
+                bundle_service(project, profile),
+                whoami::username()
+            ),
+        };
+        if self.config.encrypt {
+            location.push_str(", encrypted");
         }
+        location
     }
 
     /// Stores a secret in the system keychain.
@@ -126,6 +441,8 @@ impl Provider for KeyringProvider {
     ///
     /// The current system username is used as the account identifier.
     /// If a secret already exists with the same key, it will be overwritten.
+    /// In bundle mode, the profile's entry is re-read before being updated so
+    /// concurrent writes to other secrets are not lost.
     ///
     /// # Arguments
     ///
@@ -139,10 +456,247 @@ impl Provider for KeyringProvider {
     /// * `Ok(())` - If the secret was stored successfully
     /// * `Err` - If there was an error accessing the keychain
     fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        if self.config.mode == KeyringMode::Bundle {
+            return self.set_many(
+                project,
+                &HashMap::from([(key.to_string(), value.to_string())]),
+                profile,
+            );
+        }
+
         let service = format!("secretspec/{}/{}/{}", project, profile, key);
+        self.write_entry(&service, value)
+    }
 
-        let entry = Entry::new(&service, &whoami::username())?;
-        entry.set_password(value)?;
+    /// Stores several values; in bundle mode the profile's entry is
+    /// rewritten once.
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        if self.config.mode != KeyringMode::Bundle {
+            let mut keys: Vec<&String> = values.keys().collect();
+            keys.sort();
+            for key in keys {
+                self.set(project, key, &values[key], profile)?;
+            }
+            return Ok(());
+        }
+
+        let service = bundle_service(project, profile);
+        let mut bundle = self.read_bundle(&service)?;
+        bundle.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let json = serde_json::to_string(&bundle)?;
+        self.write_entry(&service, &json)?;
+        self.bundles.lock().unwrap().insert(service, bundle);
         Ok(())
     }
+
+    /// Reads the timestamp from the entry
+    /// `secretspec/{project}/{profile}/{key}.set_at`, in either mode.
+    ///
+    /// Timestamps are not secret, so they are never encrypted.
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        let entry = open_entry(&set_at_service(project, profile, key), None)?;
+        match entry.get_password() {
+            Ok(stored) => Ok(stored.parse().ok()),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Records the timestamp in the entry
+    /// `secretspec/{project}/{profile}/{key}.set_at`.
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        let timestamp = timestamp.to_string();
+        let entry = open_entry(&set_at_service(project, profile, key), Some(&timestamp))?;
+        entry.set_password(&timestamp)?;
+        Ok(())
+    }
+
+    /// Lists the secrets in a profile's bundle.
+    ///
+    /// Only bundle mode can enumerate its secrets; in per-key mode the
+    /// keychain offers no way to list entries by prefix.
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        if self.config.mode == KeyringMode::Bundle {
+            return Ok(Some(
+                self.cached_bundle(project, profile)?.into_keys().collect(),
+            ));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyring_mode_parsing() {
+        let url = Url::parse("keyring://").unwrap();
+        let config: KeyringConfig = (&url).try_into().unwrap();
+        assert_eq!(config.mode, KeyringMode::PerKey);
+
+        let url = Url::parse("keyring://?mode=bundle").unwrap();
+        let config: KeyringConfig = (&url).try_into().unwrap();
+        assert_eq!(config.mode, KeyringMode::Bundle);
+
+        let url = Url::parse("keyring://?mode=per-key").unwrap();
+        let config: KeyringConfig = (&url).try_into().unwrap();
+        assert_eq!(config.mode, KeyringMode::PerKey);
+
+        let url = Url::parse("keyring://?mode=single").unwrap();
+        assert!(KeyringConfig::try_from(&url).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_parsing() {
+        let url = Url::parse("keyring://").unwrap();
+        assert!(!KeyringConfig::try_from(&url).unwrap().encrypt);
+
+        let url = Url::parse("keyring://?mode=bundle&encrypt=1").unwrap();
+        let config = KeyringConfig::try_from(&url).unwrap();
+        assert_eq!(config.mode, KeyringMode::Bundle);
+        assert!(config.encrypt);
+
+        let url = Url::parse("keyring://?encrypt=yes").unwrap();
+        assert!(KeyringConfig::try_from(&url).is_err());
+    }
+
+    #[test]
+    fn test_encryption_round_trip() {
+        let key = [7u8; MASTER_KEY_LEN];
+        let service = "secretspec/app/default/API_KEY";
+        let value = "sk-\"quoted\"\nline é";
+
+        let sealed = encrypt_value(&key, service, value).unwrap();
+        assert!(sealed.starts_with(ENCRYPTED_PREFIX));
+        assert!(!sealed.contains("sk-"));
+        assert_eq!(decrypt_value(&key, service, &sealed).unwrap(), value);
+
+        // A fresh nonce is used for every value
+        assert_ne!(encrypt_value(&key, service, value).unwrap(), sealed);
+
+        let decrypt_error =
+            |key: &[u8; MASTER_KEY_LEN], service: &str, stored: &str| match decrypt_value(
+                key, service, stored,
+            ) {
+                Err(SecretSpecError::Provider(ProviderError::Other(msg))) => msg,
+                other => panic!("Expected decryption error, got {:?}", other),
+            };
+        assert!(
+            decrypt_error(&[8u8; MASTER_KEY_LEN], service, &sealed)
+                .contains("SECRETSPEC_MASTER_KEY")
+        );
+        assert!(decrypt_error(&key, service, value).contains("is not encrypted"));
+
+        // A value copied into another secret's entry is rejected
+        assert!(
+            decrypt_error(&key, "secretspec/app/default/OTHER_KEY", &sealed)
+                .contains("could not be decrypted")
+        );
+
+        // Tampering with the ciphertext is detected
+        let mut bytes = STANDARD
+            .decode(sealed.strip_prefix(ENCRYPTED_PREFIX).unwrap())
+            .unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let tampered = format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(bytes));
+        assert!(decrypt_error(&key, service, &tampered).contains("could not be decrypted"));
+        assert!(
+            decrypt_error(&key, service, &format!("{}AAAA", ENCRYPTED_PREFIX))
+                .contains("not a valid encrypted value")
+        );
+    }
+
+    #[test]
+    fn test_encrypt_requires_master_key() {
+        let url = Url::parse("keyring://?encrypt=1").unwrap();
+        let provider = KeyringProvider::new(KeyringConfig::try_from(&url).unwrap());
+        assert!(!provider.allows_caching());
+
+        let parse_error = |key: Option<&str>| match parse_master_key(key) {
+            Err(SecretSpecError::Provider(ProviderError::Other(msg))) => msg,
+            other => panic!("Expected master key error, got {:?}", other),
+        };
+        assert!(parse_error(None).contains("SECRETSPEC_MASTER_KEY is not set"));
+        assert!(parse_error(Some("")).contains("SECRETSPEC_MASTER_KEY is not set"));
+
+        // Passphrases and short keys are rejected
+        for key in ["correct horse battery staple", "c2hvcnQ="] {
+            assert!(parse_error(Some(key)).contains("is not a base64-encoded 32-byte key"));
+        }
+
+        let encoded = STANDARD.encode([7u8; MASTER_KEY_LEN]);
+        assert_eq!(
+            parse_master_key(Some(&format!("{}\n", encoded))).unwrap(),
+            [7u8; MASTER_KEY_LEN]
+        );
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = BTreeMap::from([
+            ("API_KEY".to_string(), "sk-\"quoted\"\nline".to_string()),
+            (
+                "DATABASE_URL".to_string(),
+                "postgres://localhost".to_string(),
+            ),
+        ]);
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(
+            decode_bundle("secretspec/app/default", &json).unwrap(),
+            bundle
+        );
+
+        match decode_bundle("secretspec/app/default", "not json") {
+            Err(SecretSpecError::Provider(ProviderError::Other(msg))) => {
+                assert!(msg.contains("secretspec/app/default"));
+            }
+            other => panic!("Expected invalid bundle error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_windows_limits() {
+        assert!(check_windows_limits("secretspec/app/default/API_KEY", "alice", Some("v")).is_ok());
+
+        let service = format!("secretspec/app/default/{}", "K".repeat(100));
+        match check_windows_limits(&service, "alice", None) {
+            Err(SecretSpecError::Provider(ProviderError::Other(msg))) => {
+                assert!(msg.contains(&service));
+                assert!(msg.contains("at most 106"));
+            }
+            other => panic!("Expected entry name error, got {:?}", other),
+        }
+
+        // 1280 UTF-16 code units fit, one more does not
+        let value = "é".repeat(WINDOWS_MAX_BLOB / 2);
+        assert!(check_windows_limits("secretspec/app/default/KEY", "alice", Some(&value)).is_ok());
+        let value = format!("{}x", value);
+        assert!(check_windows_limits("secretspec/app/default/KEY", "alice", Some(&value)).is_err());
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_windows_rejects_long_key_name() {
+        let provider = KeyringProvider::new(KeyringConfig::default());
+        let key = "K".repeat(300);
+        match provider.set("secretspec_test", &key, "value", "default") {
+            Err(SecretSpecError::Provider(ProviderError::Other(msg))) => {
+                assert!(msg.contains("Windows Credential Manager"));
+            }
+            other => panic!("Expected entry name error, got {:?}", other),
+        }
+        match provider.get("secretspec_test", &key, "default") {
+            Err(SecretSpecError::Provider(ProviderError::Other(msg))) => {
+                assert!(msg.contains("Windows Credential Manager"));
+            }
+            other => panic!("Expected entry name error, got {:?}", other),
+        }
+    }
 }
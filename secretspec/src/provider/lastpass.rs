@@ -1,5 +1,6 @@
 use crate::provider::Provider;
-use crate::{Result, SecretSpecError};
+use crate::provider::retry::is_transient_network_error;
+use crate::{ProviderError, Result, SecretSpecError};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::process::{Command, Stdio};
@@ -79,10 +80,10 @@ impl TryFrom<&Url> for LastPassConfig {
     /// ```
     fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
         if url.scheme() != "lastpass" {
-            return Err(SecretSpecError::ProviderOperationFailed(format!(
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                 "Invalid scheme '{}' for lastpass provider",
                 url.scheme()
-            )));
+            ))));
         }
 
         let mut config = Self::default();
@@ -175,9 +176,9 @@ impl LastPassProvider {
         let output = match cmd.output() {
             Ok(output) => output,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::BackendUnavailable(
                     "LastPass CLI (lpass) is not installed.\n\nTo install it:\n  - macOS: brew install lastpass-cli\n  - Linux: Check your package manager (apt install lastpass-cli, yum install lastpass-cli, etc.)\n  - NixOS: nix-env -iA nixpkgs.lastpass-cli\n\nAfter installation, run 'lpass login <your-email>' to authenticate.".to_string(),
-                ));
+                )));
             }
             Err(e) => return Err(e.into()),
         };
@@ -187,17 +188,27 @@ impl LastPassProvider {
             if error_msg.contains("Could not find decryption key")
                 || error_msg.contains("Not logged in")
             {
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
                     "LastPass authentication required. Please run 'lpass login' first.".to_string(),
+                )));
+            }
+            if is_transient_network_error(&error_msg) {
+                return Err(SecretSpecError::Provider(
+                    ProviderError::BackendUnavailable(error_msg.to_string()),
                 ));
             }
-            return Err(SecretSpecError::ProviderOperationFailed(
+            if error_msg.contains("Could not find specified account") {
+                return Err(SecretSpecError::Provider(ProviderError::NotFound(
+                    error_msg.to_string(),
+                )));
+            }
+            return Err(SecretSpecError::Provider(ProviderError::Other(
                 error_msg.to_string(),
-            ));
+            )));
         }
 
         String::from_utf8(output.stdout)
-            .map_err(|e| SecretSpecError::ProviderOperationFailed(e.to_string()))
+            .map_err(|e| SecretSpecError::Provider(ProviderError::Other(e.to_string())))
     }
 
     /// Formats the item name for storage in LastPass.
@@ -243,10 +254,10 @@ impl LastPassProvider {
     fn check_if_logged_in(&self) -> Result<()> {
         // Check if we're logged in first
         if !self.check_login_status()? {
-            return Err(SecretSpecError::ProviderOperationFailed(
+            return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
                 "LastPass authentication required. Please run 'lpass login <your-email>' first."
                     .to_string(),
-            ));
+            )));
         }
         Ok(())
     }
@@ -262,12 +273,7 @@ impl LastPassProvider {
     fn check_login_status(&self) -> Result<bool> {
         match self.execute_lpass_command(&["status"]) {
             Ok(output) => Ok(!output.contains("Not logged in")),
-            Err(SecretSpecError::ProviderOperationFailed(msg))
-                if msg.contains("Not logged in")
-                    || msg.contains("LastPass authentication required") =>
-            {
-                Ok(false)
-            }
+            Err(SecretSpecError::Provider(ProviderError::AuthFailed(_))) => Ok(false),
             Err(e) => Err(e),
         }
     }
@@ -278,6 +284,10 @@ impl Provider for LastPassProvider {
         Self::PROVIDER_NAME
     }
 
+    fn is_remote(&self) -> bool {
+        true
+    }
+
     /// Retrieves a secret from LastPass.
     ///
     /// Fetches the value of a secret stored in LastPass at the path
@@ -314,15 +324,18 @@ impl Provider for LastPassProvider {
                     Ok(Some(password.to_string()))
                 }
             }
-            Err(SecretSpecError::ProviderOperationFailed(msg))
-                if msg.contains("Could not find specified account") =>
-            {
-                Ok(None)
-            }
+            Err(SecretSpecError::Provider(ProviderError::NotFound(_))) => Ok(None),
             Err(e) => Err(e),
         }
     }
 
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        format!(
+            "LastPass item '{}'",
+            self.format_item_name(project, key, profile)
+        )
+    }
+
     /// Stores a secret in LastPass.
     ///
     /// Creates or updates a secret in LastPass at the path
@@ -384,9 +397,9 @@ impl Provider for LastPassProvider {
             let output = child.wait_with_output()?;
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::Other(
                     error_msg.to_string(),
-                ));
+                )));
             }
         } else {
             // Create new item using lpass set
@@ -415,9 +428,9 @@ impl Provider for LastPassProvider {
             let output = child.wait_with_output()?;
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::Other(
                     error_msg.to_string(),
-                ));
+                )));
             }
         }
 
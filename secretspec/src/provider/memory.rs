@@ -0,0 +1,245 @@
+//! In-memory provider for tests.
+//!
+//! [`MemoryProvider`] keeps secrets in a map instead of a file or keychain, so
+//! tests of `validate`, `run` or `import` are fast and deterministic. It is
+//! compiled into the crate's own tests and, for downstream crates, behind the
+//! `testing` feature.
+//!
+//! Providers are created from strings such as `memory://fixture` every time
+//! [`Secrets`](crate::Secrets) needs one, so each name refers to a store
+//! shared by all providers created with it for the rest of the process:
+//!
+//! ```ignore
+//! use secretspec::{MemoryProvider, Secrets};
+//!
+//! let store = MemoryProvider::with_secrets("fixture", "my-app", "default", [("API_KEY", "abc")]);
+//! let mut spec = Secrets::load().unwrap();
+//! spec.set_provider("memory://fixture");
+//! spec.validate().unwrap();
+//! assert_eq!(store.get_calls(), 1);
+//! ```
+
+use super::Provider;
+use crate::{ProviderError, Result, SecretSpecError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use url::Url;
+
+/// Secrets keyed by `(project, profile, key)`.
+type SecretMap = HashMap<(String, String, String), String>;
+
+/// Configuration for the in-memory provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// Name of the shared store, taken from the host of `memory://<name>`
+    pub name: String,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+        }
+    }
+}
+
+impl TryFrom<&Url> for MemoryConfig {
+    type Error = SecretSpecError;
+
+    /// Creates a `MemoryConfig` from a URL of the form `memory://[name]`.
+    fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
+        if url.scheme() != "memory" {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Invalid scheme '{}' for memory provider",
+                url.scheme()
+            ))));
+        }
+
+        Ok(match url.host_str() {
+            Some(name) if !name.is_empty() => Self {
+                name: name.to_string(),
+            },
+            _ => Self::default(),
+        })
+    }
+}
+
+/// A named store and the number of operations performed on it.
+#[derive(Default)]
+struct Store {
+    secrets: Mutex<SecretMap>,
+    /// When each secret was set, in seconds since the Unix epoch
+    set_at: Mutex<HashMap<(String, String, String), u64>>,
+    gets: AtomicUsize,
+    sets: AtomicUsize,
+}
+
+/// Returns the store registered under `name`, creating it if needed.
+fn store(name: &str) -> Arc<Store> {
+    static STORES: OnceLock<Mutex<HashMap<String, Arc<Store>>>> = OnceLock::new();
+    STORES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(name.to_string())
+        .or_default()
+        .clone()
+}
+
+/// A provider that keeps secrets in memory.
+///
+/// Every provider created for the same store name shares its secrets and
+/// call counters, which makes it possible to inspect what
+/// [`Secrets`](crate::Secrets) did through the provider it created itself.
+pub struct MemoryProvider {
+    name: String,
+    store: Arc<Store>,
+}
+
+crate::register_provider! {
+    struct: MemoryProvider,
+    config: MemoryConfig,
+    name: "memory",
+    description: "In-memory store for tests",
+    schemes: ["memory"],
+    examples: ["memory://", "memory://fixture"],
+}
+
+impl MemoryProvider {
+    /// Creates a provider for the store named in `config`.
+    pub fn new(config: MemoryConfig) -> Self {
+        Self {
+            store: store(&config.name),
+            name: config.name,
+        }
+    }
+
+    /// Creates a provider for the store `name`, adding `secrets` to it for
+    /// `project` and `profile`.
+    ///
+    /// Use `memory://<name>` as the provider of [`Secrets`](crate::Secrets)
+    /// to read them.
+    pub fn with_secrets<I, K, V>(name: &str, project: &str, profile: &str, secrets: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let provider = Self::new(MemoryConfig {
+            name: name.to_string(),
+        });
+        for (key, value) in secrets {
+            provider.insert(project, profile, key, value);
+        }
+        provider
+    }
+
+    /// Stores a secret without counting it as a `set` call.
+    pub fn insert(
+        &self,
+        project: &str,
+        profile: &str,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.store.secrets.lock().unwrap().insert(
+            (project.to_string(), profile.to_string(), key.into()),
+            value.into(),
+        );
+    }
+
+    /// Records that a secret was set at `timestamp`, in seconds since the
+    /// Unix epoch, e.g. to simulate a value that is due for rotation.
+    pub fn insert_set_at(&self, project: &str, profile: &str, key: &str, timestamp: u64) {
+        self.store.set_at.lock().unwrap().insert(
+            (project.to_string(), profile.to_string(), key.to_string()),
+            timestamp,
+        );
+    }
+
+    /// Returns a copy of every stored secret, keyed by
+    /// `(project, profile, key)`.
+    pub fn secrets(&self) -> HashMap<(String, String, String), String> {
+        self.store.secrets.lock().unwrap().clone()
+    }
+
+    /// Returns how many times a secret was read from the store.
+    pub fn get_calls(&self) -> usize {
+        self.store.gets.load(Ordering::SeqCst)
+    }
+
+    /// Returns how many secrets were written to the store through the
+    /// provider.
+    pub fn set_calls(&self) -> usize {
+        self.store.sets.load(Ordering::SeqCst)
+    }
+
+    /// Removes every secret and resets the call counters.
+    pub fn clear(&self) {
+        self.store.secrets.lock().unwrap().clear();
+        self.store.set_at.lock().unwrap().clear();
+        self.store.gets.store(0, Ordering::SeqCst);
+        self.store.sets.store(0, Ordering::SeqCst);
+    }
+}
+
+impl Provider for MemoryProvider {
+    fn name(&self) -> &'static str {
+        Self::PROVIDER_NAME
+    }
+
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        self.store.gets.fetch_add(1, Ordering::SeqCst);
+        Ok(self
+            .store
+            .secrets
+            .lock()
+            .unwrap()
+            .get(&(project.to_string(), profile.to_string(), key.to_string()))
+            .cloned())
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.store.sets.fetch_add(1, Ordering::SeqCst);
+        self.insert(project, profile, key, value);
+        Ok(())
+    }
+
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        Ok(self
+            .store
+            .set_at
+            .lock()
+            .unwrap()
+            .get(&(project.to_string(), profile.to_string(), key.to_string()))
+            .copied())
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.insert_set_at(project, profile, key, timestamp);
+        Ok(())
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        format!(
+            "memory store '{}', entry {}/{}/{}",
+            self.name, project, profile, key
+        )
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        let mut keys: Vec<String> = self
+            .store
+            .secrets
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(p, prof, _)| p == project && prof == profile)
+            .map(|(_, _, key)| key.clone())
+            .collect();
+        keys.sort();
+        Ok(Some(keys))
+    }
+}
@@ -0,0 +1,135 @@
+//! Remembering secrets a provider doesn't have.
+//!
+//! A lookup that finds nothing costs as much as one that finds a value, and
+//! `check` reads every secret again after prompting for the missing ones.
+//! [`MissCachingProvider`] records misses in a [`KnownMisses`] set shared by
+//! every provider a [`Secrets`](crate::Secrets) creates, so a key is only
+//! looked up once until a value is written.
+
+use super::Provider;
+use crate::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// A shared set of keys known to be missing, as (provider, profile, key).
+///
+/// Cloning a `KnownMisses` yields a handle to the same set.
+#[derive(Debug, Clone, Default)]
+pub struct KnownMisses {
+    keys: Arc<Mutex<HashSet<(String, String, String)>>>,
+}
+
+impl KnownMisses {
+    fn contains(&self, provider: &str, profile: &str, key: &str) -> bool {
+        self.keys.lock().unwrap().contains(&(
+            provider.to_string(),
+            profile.to_string(),
+            key.to_string(),
+        ))
+    }
+
+    fn insert(&self, provider: &str, profile: &str, key: &str) {
+        self.keys.lock().unwrap().insert((
+            provider.to_string(),
+            profile.to_string(),
+            key.to_string(),
+        ));
+    }
+
+    /// Forgets every miss, since a write may have filled any of them.
+    fn clear(&self) {
+        self.keys.lock().unwrap().clear();
+    }
+}
+
+/// A provider decorator that answers known misses without a lookup.
+///
+/// A `get` returning no value is recorded under the provider
+/// specification; later lookups of the same key and profile return `None`
+/// right away. Any `set` or `set_many` clears the whole set.
+pub struct MissCachingProvider {
+    inner: Box<dyn Provider>,
+    provider_spec: String,
+    misses: KnownMisses,
+}
+
+impl MissCachingProvider {
+    /// Wraps `inner`, created from `provider_spec`, recording its misses in
+    /// `misses`.
+    pub fn new(inner: Box<dyn Provider>, provider_spec: &str, misses: KnownMisses) -> Self {
+        Self {
+            inner,
+            provider_spec: provider_spec.to_string(),
+            misses,
+        }
+    }
+}
+
+impl Provider for MissCachingProvider {
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        if self.misses.contains(&self.provider_spec, profile, key) {
+            return Ok(None);
+        }
+        let value = self.inner.get(project, key, profile)?;
+        if value.is_none() {
+            self.misses.insert(&self.provider_spec, profile, key);
+        }
+        Ok(value)
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.misses.clear();
+        self.inner.set(project, key, value, profile)
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.misses.clear();
+        self.inner.set_many(project, values, profile)
+    }
+
+    fn allows_set(&self) -> bool {
+        self.inner.allows_set()
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        self.inner.list_keys(project, profile)
+    }
+
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        self.inner.set_at(project, key, profile)
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.inner.record_set_at(project, key, profile, timestamp)
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        self.inner.describe_location(project, key, profile)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.inner.watch_paths()
+    }
+
+    fn is_remote(&self) -> bool {
+        self.inner.is_remote()
+    }
+
+    fn allows_caching(&self) -> bool {
+        self.inner.allows_caching()
+    }
+
+    fn supports_profiles(&self) -> bool {
+        self.inner.supports_profiles()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
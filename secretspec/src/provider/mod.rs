@@ -17,9 +17,11 @@
 //!
 //! - [`KeyringProvider`]: System keyring integration (default)
 //! - [`DotEnvProvider`]: `.env` file support
+//! - [`AgeProvider`]: age-encrypted files
 //! - [`EnvProvider`]: Environment variables (read-only)
 //! - [`OnePasswordProvider`]: OnePassword integration
 //! - [`LastPassProvider`]: LastPass integration
+//! - [`VaultProvider`]: HashiCorp Vault, and OpenBao with the same API
 //!
 //! ## URI-Based Configuration
 //!
@@ -50,15 +52,30 @@
 //! }
 //! ```
 
-use crate::{Result, SecretSpecError};
+use crate::{ProviderError, Result, SecretSpecError};
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 use url::Url;
 
+#[cfg(feature = "age")]
+pub mod age;
+pub mod cache;
+pub mod chain;
 pub mod dotenv;
 pub mod env;
 pub mod keyring;
 pub mod lastpass;
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+pub mod misses;
 pub mod onepassword;
+pub mod retry;
+pub mod sops;
+pub mod timing;
+#[cfg(feature = "vault")]
+pub mod vault;
 #[macro_use]
 pub mod macros;
 
@@ -69,7 +86,7 @@ pub(crate) mod tests;
 ///
 /// Contains metadata used for displaying available providers to users,
 /// including the provider's name, description, and example URIs.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ProviderInfo {
     /// The canonical name of the provider (e.g., "keyring", "1password").
     pub name: &'static str,
@@ -133,6 +150,150 @@ pub fn providers() -> Vec<ProviderInfo> {
         .collect()
 }
 
+/// Providers that are only compiled in with a Cargo feature, with the
+/// feature enabling each. Their names are also their schemes.
+///
+/// Providers with large dependencies are opt-in; this list lets a build
+/// without them describe them and explain how to enable them instead of
+/// reporting an unknown provider. The information matches their
+/// registrations.
+const FEATURE_GATED_PROVIDERS: &[(ProviderInfo, &str)] = &[
+    (
+        ProviderInfo {
+            name: "age",
+            description: "age-encrypted file, safe to commit",
+            examples: &[
+                "age://secrets.age",
+                "age://secrets.age?identity=/path/to/key.txt",
+            ],
+        },
+        "age",
+    ),
+    (
+        ProviderInfo {
+            name: "vault",
+            description: "HashiCorp Vault KV secrets engine",
+            examples: &["vault://", "vault://vault.example.com:8200/secret"],
+        },
+        "vault",
+    ),
+    (
+        ProviderInfo {
+            name: "openbao",
+            description: "OpenBao KV secrets engine",
+            examples: &["openbao://", "openbao://bao.example.com:8200/secret"],
+        },
+        "vault",
+    ),
+];
+
+/// Returns the feature-gated providers missing from this build.
+///
+/// # Returns
+///
+/// The information and required Cargo feature of every provider that this
+/// build was compiled without.
+pub fn disabled_providers() -> Vec<(ProviderInfo, &'static str)> {
+    FEATURE_GATED_PROVIDERS
+        .iter()
+        .filter(|(info, _)| {
+            !PROVIDER_REGISTRY
+                .iter()
+                .any(|reg| reg.schemes.contains(&info.name))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Whether `scheme` names a provider, including providers registered at
+/// runtime and those left out of this build by a Cargo feature.
+pub(crate) fn is_known_scheme(scheme: &str) -> bool {
+    scheme == "1password"
+        || PROVIDER_REGISTRY
+            .iter()
+            .any(|reg| reg.schemes.contains(&scheme))
+        || FEATURE_GATED_PROVIDERS
+            .iter()
+            .any(|(gated, _)| gated.name == scheme)
+        || runtime_factory(scheme).is_some()
+}
+
+/// A factory creating a provider from its URI, as passed to
+/// [`register_provider`].
+type ProviderFactory = Arc<dyn Fn(&Url) -> Result<Box<dyn Provider>> + Send + Sync>;
+
+/// Providers registered at runtime with [`register_provider`], by scheme.
+fn runtime_registry() -> &'static Mutex<HashMap<String, ProviderFactory>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, ProviderFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Returns the factory registered at runtime for `scheme`, if any.
+fn runtime_factory(scheme: &str) -> Option<ProviderFactory> {
+    runtime_registry().lock().unwrap().get(scheme).cloned()
+}
+
+/// Registers a provider for `scheme` at runtime.
+///
+/// Built-in providers are collected at compile time, so crates embedding
+/// secretspec use this to add their own backends. Once registered, URIs
+/// such as `scheme://...` resolve through `factory` everywhere a provider
+/// specification is accepted, including `secretspec.toml` and the
+/// `SECRETSPEC_PROVIDER` variable.
+///
+/// The registry is global and guarded by a mutex, so registration is safe
+/// from any thread; the factory itself runs without the lock held and must
+/// be `Send + Sync`. Registering a scheme again replaces its factory.
+///
+/// # Arguments
+///
+/// * `scheme` - The URI scheme the provider handles, e.g. `"foo"`
+/// * `factory` - Creates the provider from its parsed URI
+///
+/// # Errors
+///
+/// Returns an error if `scheme` belongs to a built-in provider; built-in
+/// schemes can't be overridden.
+///
+/// # Example
+///
+/// ```ignore
+/// use secretspec::{Provider, register_provider};
+///
+/// register_provider("foo", |url| Ok(Box::new(FooProvider::new(url)?)))?;
+/// let provider = Box::<dyn Provider>::try_from("foo://bucket")?;
+/// ```
+pub fn register_provider<F>(scheme: &str, factory: F) -> Result<()>
+where
+    F: Fn(&Url) -> Result<Box<dyn Provider>> + Send + Sync + 'static,
+{
+    if PROVIDER_REGISTRY
+        .iter()
+        .any(|reg| reg.schemes.contains(&scheme))
+    {
+        return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+            "Provider scheme '{}' is built in and can't be overridden",
+            scheme
+        ))));
+    }
+    runtime_registry()
+        .lock()
+        .unwrap()
+        .insert(scheme.to_string(), Arc::new(factory));
+    Ok(())
+}
+
+/// Builds the error for a scheme that no registered provider handles.
+fn unknown_scheme_error(scheme: &str) -> SecretSpecError {
+    match disabled_providers()
+        .into_iter()
+        .find(|(gated, _)| gated.name == scheme)
+    {
+        Some((_, feature)) => SecretSpecError::ProviderNotEnabled(scheme.to_string(), feature),
+        None => SecretSpecError::ProviderNotFound(scheme.to_string()),
+    }
+}
+
 /// Trait defining the interface for secret storage providers.
 ///
 /// All secret storage backends must implement this trait to integrate with SecretSpec.
@@ -205,6 +366,73 @@ pub trait Provider: Send + Sync {
     /// ```
     fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()>;
 
+    /// Stores several secret values in the provider at once.
+    ///
+    /// The default implementation calls [`set`](Provider::set) for each
+    /// value in key order, so a failure can leave earlier values stored.
+    /// Providers that keep a profile's secrets in a single file or entry
+    /// override this to write it once, storing either every value or none.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project namespace for the secrets
+    /// * `values` - The secret values to store, keyed by name
+    /// * `profile` - The profile context (e.g., "default", "production")
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any value cannot be stored
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        let mut keys: Vec<&String> = values.keys().collect();
+        keys.sort();
+        for key in keys {
+            self.set(project, key, &values[key], profile)?;
+        }
+        Ok(())
+    }
+
+    /// Returns when a secret was last set, in seconds since the Unix epoch.
+    ///
+    /// `check` compares this against the secret's `rotate_after_days`.
+    /// Providers that can't keep metadata next to their values keep the
+    /// default implementation, which returns `Ok(None)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project namespace for the secret
+    /// * `key` - The secret key
+    /// * `profile` - The profile context (e.g., "default", "production")
+    fn set_at(&self, _project: &str, _key: &str, _profile: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Records when a secret was set, in seconds since the Unix epoch.
+    ///
+    /// Called after a secret with `rotate_after_days` is written. The
+    /// default implementation discards the timestamp, so secrets in
+    /// providers without metadata are never reported as due for rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project namespace for the secret
+    /// * `key` - The secret key
+    /// * `profile` - The profile context (e.g., "default", "production")
+    /// * `timestamp` - When the value was written
+    fn record_set_at(
+        &self,
+        _project: &str,
+        _key: &str,
+        _profile: &str,
+        _timestamp: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Returns whether this provider supports setting values.
     ///
     /// By default, providers are assumed to support writing. Read-only providers
@@ -228,6 +456,117 @@ pub trait Provider: Send + Sync {
         true
     }
 
+    /// Lists the keys stored for a project and profile.
+    ///
+    /// This is used to detect secrets that exist in the provider but are not
+    /// declared in the specification. Providers that cannot enumerate their
+    /// contents keep the default implementation, which returns `Ok(None)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project namespace to list
+    /// * `profile` - The profile context (e.g., "default", "production")
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(keys))` with every key the provider holds
+    /// - `Ok(None)` if the provider does not support enumeration
+    /// - `Err` if there was an error accessing the provider
+    fn list_keys(&self, _project: &str, _profile: &str) -> Result<Option<Vec<String>>> {
+        Ok(None)
+    }
+
+    /// Describes where the value of a secret is stored, without its value.
+    ///
+    /// `secretspec get NAME --where` prints this to help find a secret in
+    /// the backend, such as the file and line of a `.env` entry or the
+    /// keychain entry holding it. The description is meant for people, not
+    /// for parsing. The default implementation names the provider, project,
+    /// profile and key.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The project namespace
+    /// * `key` - The secret key
+    /// * `profile` - The profile context (e.g., "default", "production")
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        format!(
+            "{} provider, project '{}', profile '{}', key '{}'",
+            self.name(),
+            project,
+            profile,
+            key
+        )
+    }
+
+    /// Returns the files backing this provider.
+    ///
+    /// `secretspec run --watch` watches these paths and restarts the command
+    /// when one of them changes. Providers that are not backed by a local
+    /// file keep the default implementation, which returns no paths.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Resolves relative file paths against `dir` instead of the current
+    /// directory.
+    ///
+    /// Called for a project read through a secret's `source`, whose
+    /// provider paths are relative to its own `secretspec.toml`. Providers
+    /// that are not backed by local files keep the default implementation,
+    /// which does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory containing the project's `secretspec.toml`
+    fn resolve_paths_from(&mut self, _dir: &Path) {}
+
+    /// Returns whether this provider talks to a remote service.
+    ///
+    /// Remote providers are wrapped in a
+    /// [`RetryingProvider`](retry::RetryingProvider) so that transient
+    /// network failures are retried. Local providers keep the default
+    /// implementation, which returns `false`.
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    /// Returns whether values read from this provider may be written to the
+    /// on-disk cache.
+    ///
+    /// [`CachingProvider`](cache::CachingProvider) stores values in plain
+    /// text, so providers that keep values out of files on purpose, such as
+    /// the environment or encrypted keychain entries, return `false`. Other
+    /// providers keep the default implementation, which returns `true`.
+    fn allows_caching(&self) -> bool {
+        true
+    }
+
+    /// Returns whether this provider keeps a separate set of secrets per
+    /// profile.
+    ///
+    /// Providers that ignore the profile argument, reading the same value
+    /// for every profile, return `false` so that selecting a non-default
+    /// profile can warn instead of silently sharing values.
+    fn supports_profiles(&self) -> bool {
+        true
+    }
+
+    /// Checks that the provider can be used, e.g. that its backend is
+    /// reachable and signed in.
+    ///
+    /// `secretspec doctor` runs this for every provider in use. The default
+    /// implementation reads a secret that is not expected to exist, which
+    /// fails the same way a real read would when the backend is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reading from the provider reports
+    fn health_check(&self) -> Result<()> {
+        self.get("secretspec", "SECRETSPEC_HEALTH_CHECK", "default")
+            .map(|_| ())
+    }
+
     /// Returns the name of this provider.
     ///
     /// This should match the name registered with the provider macro.
@@ -250,6 +589,8 @@ impl TryFrom<String> for Box<dyn Provider> {
     ///
     /// - **1password**: Will error suggesting to use `onepassword` instead
     /// - **Bare provider names**: Automatically converted to `provider://`
+    /// - **Comma-separated providers**: Combined into a
+    ///   [`ChainProvider`](chain::ChainProvider) that tries them in order
     ///
     /// # Examples
     ///
@@ -274,6 +615,15 @@ impl TryFrom<&str> for Box<dyn Provider> {
     type Error = SecretSpecError;
 
     fn try_from(s: &str) -> Result<Self> {
+        // Several comma-separated providers form a fallback chain
+        if let Some(members) = chain::members(s) {
+            let providers = members
+                .into_iter()
+                .map(Self::try_from)
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(Box::new(chain::ChainProvider::new(providers)));
+        }
+
         // Parse the scheme from the input string
         let (scheme, rest) = if let Some(pos) = s.find(':') {
             let scheme = &s[..pos];
@@ -286,25 +636,26 @@ impl TryFrom<&str> for Box<dyn Provider> {
 
         // Validate scheme first
         if scheme == "1password" {
-            return Err(SecretSpecError::ProviderOperationFailed(
+            return Err(SecretSpecError::Provider(ProviderError::Other(
                 "Invalid scheme '1password'. Use 'onepassword' instead (e.g., onepassword://vault/path)".to_string()
-            ));
+            )));
         }
 
         // Check if the scheme is registered
         let is_valid_scheme = PROVIDER_REGISTRY
             .iter()
-            .any(|reg| reg.schemes.contains(&scheme));
+            .any(|reg| reg.schemes.contains(&scheme))
+            || runtime_factory(scheme).is_some();
 
         if !is_valid_scheme {
             // Check if it's a known provider name to give a better error
             if PROVIDER_REGISTRY.iter().any(|reg| reg.info.name == scheme) {
-                return Err(SecretSpecError::ProviderOperationFailed(format!(
+                return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                     "Provider '{}' exists but URI parsing failed",
                     scheme
-                )));
+                ))));
             } else {
-                return Err(SecretSpecError::ProviderNotFound(scheme.to_string()));
+                return Err(unknown_scheme_error(scheme));
             }
         }
 
@@ -321,10 +672,10 @@ impl TryFrom<&str> for Box<dyn Provider> {
         };
 
         let proper_url = Url::parse(&url_string).map_err(|e| {
-            SecretSpecError::ProviderOperationFailed(format!(
+            SecretSpecError::Provider(ProviderError::Other(format!(
                 "Invalid provider specification '{}': {}",
                 s, e
-            ))
+            )))
         })?;
 
         Self::try_from(&proper_url)
@@ -338,12 +689,16 @@ impl TryFrom<&Url> for Box<dyn Provider> {
         let scheme = url.scheme();
 
         // Find the provider registration for this scheme
-        let registration = PROVIDER_REGISTRY
+        if let Some(registration) = PROVIDER_REGISTRY
             .iter()
             .find(|reg| reg.schemes.contains(&scheme))
-            .ok_or_else(|| SecretSpecError::ProviderNotFound(scheme.to_string()))?;
+        {
+            // Use the factory function to create the provider
+            return (registration.factory)(url);
+        }
 
-        // Use the factory function to create the provider
-        (registration.factory)(url)
+        // Fall back to providers registered at runtime
+        let factory = runtime_factory(scheme).ok_or_else(|| unknown_scheme_error(scheme))?;
+        factory(url)
     }
 }
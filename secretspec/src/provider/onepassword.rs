@@ -1,5 +1,6 @@
 use crate::provider::Provider;
-use crate::{Result, SecretSpecError};
+use crate::provider::retry::is_transient_network_error;
+use crate::{ProviderError, Result, SecretSpecError};
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 use url::Url;
@@ -128,41 +129,41 @@ impl TryFrom<&Url> for OnePasswordConfig {
 
         match scheme {
             "1password" => {
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::Other(
                     "Invalid scheme '1password'. Use 'onepassword' instead (e.g., onepassword://vault/path)".to_string()
-                ));
+                )));
             }
             "onepassword" | "onepassword+token" => {}
             _ => {
-                return Err(SecretSpecError::ProviderOperationFailed(format!(
+                return Err(SecretSpecError::Provider(ProviderError::Other(format!(
                     "Invalid scheme '{}' for OnePassword provider",
                     scheme
-                )));
+                ))));
             }
         }
 
         let mut config = Self::default();
 
         // Parse URL components for account@vault format, ignoring dummy localhost
-        if let Some(host) = url.host_str() {
-            if host != "localhost" {
-                // Check if we have username (account) information
-                if !url.username().is_empty() {
-                    // Handle user:token format for service account tokens
-                    if scheme == "onepassword+token" {
-                        if let Some(password) = url.password() {
-                            config.service_account_token = Some(password.to_string());
-                        } else {
-                            config.service_account_token = Some(url.username().to_string());
-                        }
+        if let Some(host) = url.host_str()
+            && host != "localhost"
+        {
+            // Check if we have username (account) information
+            if !url.username().is_empty() {
+                // Handle user:token format for service account tokens
+                if scheme == "onepassword+token" {
+                    if let Some(password) = url.password() {
+                        config.service_account_token = Some(password.to_string());
                     } else {
-                        config.account = Some(url.username().to_string());
+                        config.service_account_token = Some(url.username().to_string());
                     }
-                    config.default_vault = Some(host.to_string());
                 } else {
-                    // No username, so the host is the vault
-                    config.default_vault = Some(host.to_string());
+                    config.account = Some(url.username().to_string());
                 }
+                config.default_vault = Some(host.to_string());
+            } else {
+                // No username, so the host is the vault
+                config.default_vault = Some(host.to_string());
             }
         }
 
@@ -236,6 +237,25 @@ impl OnePasswordProvider {
         Self { config }
     }
 
+    /// Builds an `op` command with `args`, authenticated with the configured
+    /// service account token and targeting the configured account.
+    fn op_command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new("op");
+
+        // Set service account token if provided
+        if let Some(token) = &self.config.service_account_token {
+            cmd.env("OP_SERVICE_ACCOUNT_TOKEN", token);
+        }
+
+        // Add account if specified
+        if let Some(account) = &self.config.account {
+            cmd.arg("--account").arg(account);
+        }
+
+        cmd.args(args);
+        cmd
+    }
+
     /// Executes a OnePassword CLI command with proper error handling.
     ///
     /// This method handles:
@@ -259,26 +279,12 @@ impl OnePasswordProvider {
     /// - Authentication required
     /// - Command execution failures
     fn execute_op_command(&self, args: &[&str]) -> Result<String> {
-        let mut cmd = Command::new("op");
-
-        // Set service account token if provided
-        if let Some(token) = &self.config.service_account_token {
-            cmd.env("OP_SERVICE_ACCOUNT_TOKEN", token);
-        }
-
-        // Add account if specified
-        if let Some(account) = &self.config.account {
-            cmd.arg("--account").arg(account);
-        }
-
-        cmd.args(args);
-
-        let output = match cmd.output() {
+        let output = match self.op_command(args).output() {
             Ok(output) => output,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::BackendUnavailable(
                     "OnePassword CLI (op) is not installed.\n\nTo install it:\n  - macOS: brew install 1password-cli\n  - Linux: Download from https://1password.com/downloads/command-line/\n  - Windows: Download from https://1password.com/downloads/command-line/\n  - NixOS: nix-env -iA nixpkgs.onepassword\n\nAfter installation, run 'eval $(op signin)' to authenticate.".to_string(),
-                ));
+                )));
             }
             Err(e) => return Err(e.into()),
         };
@@ -286,18 +292,33 @@ impl OnePasswordProvider {
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
             if error_msg.contains("not currently signed in") {
-                return Err(SecretSpecError::ProviderOperationFailed(
+                return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
                     "OnePassword authentication required. Please run 'eval $(op signin)' first."
                         .to_string(),
+                )));
+            }
+            if error_msg.contains("no account found") {
+                return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
+                    error_msg.to_string(),
+                )));
+            }
+            if is_transient_network_error(&error_msg) {
+                return Err(SecretSpecError::Provider(
+                    ProviderError::BackendUnavailable(error_msg.to_string()),
                 ));
             }
-            return Err(SecretSpecError::ProviderOperationFailed(
+            if error_msg.contains("isn't an item") {
+                return Err(SecretSpecError::Provider(ProviderError::NotFound(
+                    error_msg.to_string(),
+                )));
+            }
+            return Err(SecretSpecError::Provider(ProviderError::Other(
                 error_msg.to_string(),
-            ));
+            )));
         }
 
         String::from_utf8(output.stdout)
-            .map_err(|e| SecretSpecError::ProviderOperationFailed(e.to_string()))
+            .map_err(|e| SecretSpecError::Provider(ProviderError::Other(e.to_string())))
     }
 
     /// Checks if the user is authenticated with OnePassword.
@@ -313,11 +334,7 @@ impl OnePasswordProvider {
     fn whoami(&self) -> Result<bool> {
         match self.execute_op_command(&["whoami"]) {
             Ok(_) => Ok(true),
-            Err(SecretSpecError::ProviderOperationFailed(msg))
-                if msg.contains("not currently signed in") || msg.contains("no account found") =>
-            {
-                Ok(false)
-            }
+            Err(SecretSpecError::Provider(ProviderError::AuthFailed(_))) => Ok(false),
             Err(e) => Err(e),
         }
     }
@@ -418,6 +435,10 @@ impl Provider for OnePasswordProvider {
         Self::PROVIDER_NAME
     }
 
+    fn is_remote(&self) -> bool {
+        true
+    }
+
     /// Retrieves a secret from OnePassword.
     ///
     /// Searches for an item with the title formatted according to the folder_prefix
@@ -444,10 +465,10 @@ impl Provider for OnePasswordProvider {
     fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
         // Check authentication status first
         if !self.whoami()? {
-            return Err(SecretSpecError::ProviderOperationFailed(
+            return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
                 "OnePassword authentication required. Please run 'eval $(op signin)' first."
                     .to_string(),
-            ));
+            )));
         }
 
         let vault = self.get_vault_name(profile);
@@ -478,13 +499,23 @@ impl Provider for OnePasswordProvider {
 
                 Ok(None)
             }
-            Err(SecretSpecError::ProviderOperationFailed(msg)) if msg.contains("isn't an item") => {
-                Ok(None)
-            }
+            Err(SecretSpecError::Provider(ProviderError::NotFound(_))) => Ok(None),
             Err(e) => Err(e),
         }
     }
 
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        let mut location = format!(
+            "1Password item '{}' in vault '{}'",
+            self.format_item_name(project, key, profile),
+            self.get_vault_name(profile)
+        );
+        if let Some(account) = &self.config.account {
+            location.push_str(&format!(" of account '{}'", account));
+        }
+        location
+    }
+
     /// Stores or updates a secret in OnePassword.
     ///
     /// If an item with the same title exists, it updates the "value" field.
@@ -510,10 +541,10 @@ impl Provider for OnePasswordProvider {
     fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
         // Check authentication status first
         if !self.whoami()? {
-            return Err(SecretSpecError::ProviderOperationFailed(
+            return Err(SecretSpecError::Provider(ProviderError::AuthFailed(
                 "OnePassword authentication required. Please run 'eval $(op signin)' first."
                     .to_string(),
-            ));
+            )));
         }
 
         let vault = self.get_vault_name(profile);
@@ -551,9 +582,9 @@ impl Provider for OnePasswordProvider {
                 &vault,
                 "--template",
                 temp_file.path().to_str().ok_or_else(|| {
-                    SecretSpecError::ProviderOperationFailed(
+                    SecretSpecError::Provider(ProviderError::Other(
                         "Invalid UTF-8 in temporary file path".to_string(),
-                    )
+                    ))
                 })?,
             ];
 
@@ -572,3 +603,40 @@ impl Default for OnePasswordProvider {
         Self::new(OnePasswordConfig::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(uri: &str) -> OnePasswordConfig {
+        OnePasswordConfig::try_from(&Url::parse(uri).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_account_url_parsing() {
+        let config = parse("onepassword://work@Private");
+        assert_eq!(config.account.as_deref(), Some("work"));
+        assert_eq!(config.default_vault.as_deref(), Some("Private"));
+
+        let config = parse("onepassword://Private");
+        assert_eq!(config.account, None);
+        assert_eq!(config.default_vault.as_deref(), Some("Private"));
+    }
+
+    #[test]
+    fn test_account_is_passed_to_op() {
+        let args = |uri: &str| -> Vec<String> {
+            OnePasswordProvider::new(parse(uri))
+                .op_command(&["item", "get", "API_KEY"])
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(
+            args("onepassword://work@Private"),
+            ["--account", "work", "item", "get", "API_KEY"]
+        );
+        assert_eq!(args("onepassword://Private"), ["item", "get", "API_KEY"]);
+    }
+}
@@ -0,0 +1,163 @@
+//! Retry support for network-backed providers.
+//!
+//! [`RetryingProvider`] wraps another provider and retries operations that
+//! fail with [`ProviderError::BackendUnavailable`], waiting with exponential
+//! backoff between attempts. [`Secrets`](crate::Secrets) wraps providers that
+//! report [`Provider::is_remote`] automatically.
+
+use super::Provider;
+use crate::config::GlobalDefaults;
+use crate::{ProviderError, Result, SecretSpecError};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// Retry settings for network-backed providers.
+///
+/// Configured through `retry_attempts` and `retry_backoff_ms` in the
+/// `[defaults]` section of the global configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Total number of attempts, including the first one
+    pub attempts: u32,
+    /// Delay before the first retry; doubled for every further retry
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+impl From<&GlobalDefaults> for RetryConfig {
+    fn from(defaults: &GlobalDefaults) -> Self {
+        let fallback = Self::default();
+        Self {
+            attempts: defaults.retry_attempts.unwrap_or(fallback.attempts),
+            initial_backoff: defaults
+                .retry_backoff_ms
+                .map(Duration::from_millis)
+                .unwrap_or(fallback.initial_backoff),
+        }
+    }
+}
+
+/// A provider decorator that retries transient failures.
+///
+/// Only errors classified as [`ProviderError::BackendUnavailable`] are
+/// retried; everything else, including authentication failures, is returned
+/// immediately.
+pub struct RetryingProvider {
+    inner: Box<dyn Provider>,
+    config: RetryConfig,
+}
+
+impl RetryingProvider {
+    /// Wraps `inner` so that its operations are retried according to `config`.
+    pub fn new(inner: Box<dyn Provider>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Runs `operation`, retrying it while it fails with a retryable error.
+    fn retry<T>(&self, operation: impl Fn() -> Result<T>) -> Result<T> {
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Err(SecretSpecError::Provider(ProviderError::BackendUnavailable(_)))
+                    if attempt < self.config.attempts =>
+                {
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Provider for RetryingProvider {
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        self.retry(|| self.inner.get(project, key, profile))
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.retry(|| self.inner.set(project, key, value, profile))
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.retry(|| self.inner.set_many(project, values, profile))
+    }
+
+    fn allows_set(&self) -> bool {
+        self.inner.allows_set()
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        self.retry(|| self.inner.list_keys(project, profile))
+    }
+
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        self.retry(|| self.inner.set_at(project, key, profile))
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.retry(|| self.inner.record_set_at(project, key, profile, timestamp))
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        self.inner.describe_location(project, key, profile)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.inner.watch_paths()
+    }
+
+    fn is_remote(&self) -> bool {
+        self.inner.is_remote()
+    }
+
+    fn allows_caching(&self) -> bool {
+        self.inner.allows_caching()
+    }
+
+    fn supports_profiles(&self) -> bool {
+        self.inner.supports_profiles()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// Checks whether a CLI error message describes a transient network failure.
+///
+/// Used by CLI-based providers to report such failures as
+/// [`ProviderError::BackendUnavailable`] so they are retried.
+pub(crate) fn is_transient_network_error(message: &str) -> bool {
+    const PATTERNS: &[&str] = &[
+        "connection refused",
+        "connection reset",
+        "timed out",
+        "timeout",
+        "network is unreachable",
+        "no such host",
+        "temporary failure",
+        "service unavailable",
+        "could not connect",
+    ];
+
+    let message = message.to_lowercase();
+    PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
@@ -0,0 +1,322 @@
+use super::Provider;
+use crate::{ProviderError, Result, SecretSpecError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use url::Url;
+
+/// Configuration for the SOPS provider.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::path::PathBuf;
+/// use secretspec::provider::sops::SopsConfig;
+///
+/// let config = SopsConfig {
+///     path: PathBuf::from("secrets.enc.yaml"),
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SopsConfig {
+    /// Path to the SOPS-encrypted file, in any format `sops` supports.
+    pub path: PathBuf,
+}
+
+impl Default for SopsConfig {
+    /// Creates a default configuration with path set to `secrets.enc.yaml`.
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("secrets.enc.yaml"),
+        }
+    }
+}
+
+impl TryFrom<&Url> for SopsConfig {
+    type Error = SecretSpecError;
+
+    /// Creates a SopsConfig from a URL.
+    ///
+    /// # URL Formats
+    ///
+    /// - `sops:///absolute/path/secrets.enc.yaml` - Absolute path
+    /// - `sops://secrets.enc.yaml` - Relative path (authority as filename)
+    /// - `sops://` - Uses `secrets.enc.yaml` in the current directory
+    fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
+        if url.scheme() != "sops" {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Invalid scheme '{}' for sops provider",
+                url.scheme()
+            ))));
+        }
+
+        let path = if url.path() != "" && url.path() != "/" {
+            if let Some(host) = url.host_str() {
+                format!("{}{}", host, url.path())
+            } else {
+                url.path().to_string()
+            }
+        } else if let Some(host) = url.host_str() {
+            host.to_string()
+        } else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// Provider for secrets stored in a [SOPS](https://github.com/getsops/sops)
+/// encrypted file.
+///
+/// Values are nested by profile, then by secret name:
+///
+/// ```yaml
+/// default:
+///   DATABASE_URL: postgres://localhost/app
+/// production:
+///   DATABASE_URL: postgres://db.internal/app
+/// ```
+///
+/// The provider shells out to the `sops` binary, which must be installed and
+/// able to decrypt the file with one of its configured keys (age, PGP or a
+/// cloud KMS). Reads decrypt the whole file; writes use `sops --set`, which
+/// re-encrypts the file in place, so the file must already exist.
+///
+/// # Note
+///
+/// The project parameter is ignored as the file belongs to a single project.
+pub struct SopsProvider {
+    /// Configuration containing the path to the encrypted file
+    config: SopsConfig,
+}
+
+crate::register_provider! {
+    struct: SopsProvider,
+    config: SopsConfig,
+    name: "sops",
+    description: "SOPS-encrypted YAML, JSON or dotenv file",
+    schemes: ["sops"],
+    examples: ["sops://secrets.enc.yaml", "sops:///etc/app/secrets.enc.json"],
+}
+
+impl SopsProvider {
+    /// Creates a new SopsProvider with the given configuration.
+    pub fn new(config: SopsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Runs `sops` with `args` followed by the file path and returns stdout.
+    ///
+    /// # Errors
+    ///
+    /// - `BackendUnavailable` if `sops` is not installed
+    /// - `AuthFailed` if none of the file's keys is available
+    /// - `Other` for any other failure
+    fn run(&self, args: &[&str]) -> Result<String> {
+        let output = match Command::new("sops")
+            .args(args)
+            .arg(&self.config.path)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(ProviderError::BackendUnavailable(
+                    "SOPS (sops) is not installed.\n\nTo install it:\n  - macOS: brew install sops\n  - Linux: download a release from https://github.com/getsops/sops/releases\n  - NixOS: nix-env -iA nixpkgs.sops".to_string(),
+                )
+                .into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if !output.status.success() {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            if error_msg.contains("Failed to get the data key")
+                || error_msg.contains("Error getting data key")
+            {
+                return Err(ProviderError::AuthFailed(format!(
+                    "Could not decrypt {}: none of its keys is available.\n\n{}",
+                    self.config.path.display(),
+                    error_msg.trim()
+                ))
+                .into());
+            }
+            return Err(ProviderError::Other(format!(
+                "{}: {}",
+                self.config.path.display(),
+                error_msg.trim()
+            ))
+            .into());
+        }
+
+        String::from_utf8(output.stdout).map_err(|e| ProviderError::Other(e.to_string()).into())
+    }
+
+    /// Decrypts the file into a JSON document.
+    fn decrypt(&self) -> Result<Value> {
+        let plaintext = self.run(&["--decrypt", "--output-type", "json"])?;
+        serde_json::from_str(&plaintext).map_err(|e| {
+            ProviderError::Other(format!("{}: {}", self.config.path.display(), e)).into()
+        })
+    }
+}
+
+/// Returns the value stored under `profile` and `key` in a decrypted file.
+///
+/// Numbers and booleans are returned as text and `null` as a missing value.
+fn lookup(document: &Value, profile: &str, key: &str) -> Result<Option<String>> {
+    match document.get(profile).and_then(|values| values.get(key)) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(value @ (Value::Bool(_) | Value::Number(_))) => Ok(Some(value.to_string())),
+        Some(_) => Err(ProviderError::Other(format!(
+            "{}.{} is not a string, number or boolean",
+            profile, key
+        ))
+        .into()),
+    }
+}
+
+/// Builds the `sops --set` argument assigning `value` to `profile` and `key`.
+fn set_expression(profile: &str, key: &str, value: &str) -> String {
+    format!(
+        "[{}][{}] {}",
+        Value::from(profile),
+        Value::from(key),
+        Value::from(value)
+    )
+}
+
+impl Provider for SopsProvider {
+    fn name(&self) -> &'static str {
+        Self::PROVIDER_NAME
+    }
+
+    /// Retrieves a secret by decrypting the whole file.
+    fn get(&self, _project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        if !self.config.path.exists() {
+            return Ok(None);
+        }
+        lookup(&self.decrypt()?, profile, key)
+    }
+
+    /// Names the value as `sops --extract` addresses it.
+    fn describe_location(&self, _project: &str, key: &str, profile: &str) -> String {
+        format!(
+            "{}, key [{}][{}]",
+            self.config.path.display(),
+            Value::from(profile),
+            Value::from(key)
+        )
+    }
+
+    /// Sets a secret with `sops --set`, which decrypts the file, updates the
+    /// value and re-encrypts it with the file's keys.
+    fn set(&self, _project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        if !self.config.path.exists() {
+            return Err(ProviderError::Other(format!(
+                "{} does not exist. Create it with 'sops {}' first",
+                self.config.path.display(),
+                self.config.path.display()
+            ))
+            .into());
+        }
+        self.run(&["--set", &set_expression(profile, key, value)])?;
+        Ok(())
+    }
+
+    /// Lists the keys stored for `profile`.
+    fn list_keys(&self, _project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        if !self.config.path.exists() {
+            return Ok(Some(Vec::new()));
+        }
+        let document = self.decrypt()?;
+        Ok(Some(
+            document
+                .get(profile)
+                .and_then(Value::as_object)
+                .map(|values| values.keys().cloned().collect())
+                .unwrap_or_default(),
+        ))
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.config.path.clone()]
+    }
+
+    fn resolve_paths_from(&mut self, dir: &Path) {
+        self.config.path = dir.join(&self.config.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sops_url_parsing() {
+        let parse = |s: &str| SopsConfig::try_from(&Url::parse(s).unwrap()).unwrap();
+        assert_eq!(parse("sops://").path, PathBuf::from("secrets.enc.yaml"));
+        assert_eq!(
+            parse("sops://secrets.enc.json").path,
+            PathBuf::from("secrets.enc.json")
+        );
+        assert_eq!(
+            parse("sops:///etc/app/secrets.yaml").path,
+            PathBuf::from("/etc/app/secrets.yaml")
+        );
+    }
+
+    #[test]
+    fn test_lookup_by_profile() {
+        let document = serde_json::json!({
+            "default": { "API_KEY": "abc", "PORT": 8080, "DEBUG": true, "UNSET": null },
+            "production": { "API_KEY": "xyz", "NESTED": { "a": 1 } },
+        });
+        assert_eq!(
+            lookup(&document, "default", "API_KEY").unwrap().as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            lookup(&document, "production", "API_KEY")
+                .unwrap()
+                .as_deref(),
+            Some("xyz")
+        );
+        assert_eq!(
+            lookup(&document, "default", "PORT").unwrap().as_deref(),
+            Some("8080")
+        );
+        assert_eq!(
+            lookup(&document, "default", "DEBUG").unwrap().as_deref(),
+            Some("true")
+        );
+        assert_eq!(lookup(&document, "default", "UNSET").unwrap(), None);
+        assert_eq!(lookup(&document, "staging", "API_KEY").unwrap(), None);
+        assert!(lookup(&document, "production", "NESTED").is_err());
+    }
+
+    #[test]
+    fn test_set_expression_escapes_value() {
+        assert_eq!(
+            set_expression("default", "API_KEY", "a \"quoted\"\nvalue"),
+            r#"["default"]["API_KEY"] "a \"quoted\"\nvalue""#
+        );
+    }
+
+    #[test]
+    fn test_missing_file_has_no_secrets() {
+        let provider = SopsProvider::new(SopsConfig {
+            path: PathBuf::from("/nonexistent/secrets.enc.yaml"),
+        });
+        assert_eq!(provider.get("app", "API_KEY", "default").unwrap(), None);
+        assert_eq!(
+            provider.list_keys("app", "default").unwrap(),
+            Some(Vec::new())
+        );
+        assert!(provider.set("app", "API_KEY", "value", "default").is_err());
+    }
+}
@@ -1,5 +1,6 @@
-use crate::Result;
 use crate::provider::Provider;
+use crate::provider::retry::{RetryConfig, RetryingProvider, is_transient_network_error};
+use crate::{ProviderError, Result};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::{Arc, Mutex};
@@ -132,6 +133,80 @@ fn test_unknown_provider() {
     }
 }
 
+#[test]
+fn test_register_custom_provider() {
+    use crate::provider::register_provider;
+
+    assert!(matches!(
+        Box::<dyn Provider>::try_from("foo://bucket"),
+        Err(crate::SecretSpecError::ProviderNotFound(_))
+    ));
+
+    let hosts = Arc::new(Mutex::new(Vec::new()));
+    let seen = hosts.clone();
+    register_provider("foo", move |url| {
+        seen.lock()
+            .unwrap()
+            .push(url.host_str().unwrap_or_default().to_string());
+        Ok(Box::new(MockProvider::new()))
+    })
+    .unwrap();
+
+    let provider = Box::<dyn Provider>::try_from("foo://bucket").unwrap();
+    assert_eq!(provider.name(), "mock");
+    provider.set("project", "KEY", "value", "default").unwrap();
+    assert_eq!(
+        provider
+            .get("project", "KEY", "default")
+            .unwrap()
+            .as_deref(),
+        Some("value")
+    );
+    // Bare names and chains resolve the custom scheme too
+    Box::<dyn Provider>::try_from("foo").unwrap();
+    Box::<dyn Provider>::try_from("env,foo://other").unwrap();
+    assert_eq!(*hosts.lock().unwrap(), ["bucket", "", "other"]);
+
+    // Built-in schemes can't be replaced
+    assert!(register_provider("dotenv", |_| Ok(Box::new(MockProvider::new()))).is_err());
+    assert_eq!(
+        Box::<dyn Provider>::try_from("dotenv").unwrap().name(),
+        "dotenv"
+    );
+}
+
+#[test]
+#[cfg(not(feature = "age"))]
+fn test_disabled_provider_names_feature() {
+    for spec in ["age", "age://secrets.age"] {
+        match Box::<dyn Provider>::try_from(spec) {
+            Err(crate::SecretSpecError::ProviderNotEnabled(scheme, feature)) => {
+                assert_eq!(scheme, "age");
+                assert_eq!(feature, "age");
+            }
+            _ => panic!("Expected ProviderNotEnabled error for {}", spec),
+        }
+    }
+    assert!(
+        super::disabled_providers()
+            .iter()
+            .any(|(info, feature)| info.name == "age" && *feature == "age")
+    );
+}
+
+#[test]
+fn test_feature_gated_providers_match_their_registrations() {
+    for (gated, _) in super::FEATURE_GATED_PROVIDERS {
+        if let Some(registered) = super::providers()
+            .into_iter()
+            .find(|info| info.name == gated.name)
+        {
+            assert_eq!(registered.description, gated.description);
+            assert_eq!(registered.examples, gated.examples);
+        }
+    }
+}
+
 #[test]
 fn test_dotenv_shorthand_from_docs() {
     // Test the example from line 187 of registry.rs
@@ -235,7 +310,7 @@ mod integration_tests {
             }
             _ => {
                 let provider = Box::<dyn Provider>::try_from(provider_name)
-                    .expect(&format!("{} provider should exist", provider_name));
+                    .unwrap_or_else(|_| panic!("{} provider should exist", provider_name));
                 (provider, None)
             }
         }
@@ -266,18 +341,22 @@ mod integration_tests {
             // Provider claims to support set, so it should work
             provider
                 .set(&project_name, "TEST_PASSWORD", &test_value, "default")
-                .expect(&format!(
-                    "[{}] Provider claims to support set but failed",
-                    provider_name
-                ));
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "[{}] Provider claims to support set but failed",
+                        provider_name
+                    )
+                });
 
             // Verify we can retrieve it
             let retrieved = provider
                 .get(&project_name, "TEST_PASSWORD", "default")
-                .expect(&format!(
-                    "[{}] Should not error when getting after set",
-                    provider_name
-                ));
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "[{}] Should not error when getting after set",
+                        provider_name
+                    )
+                });
 
             match retrieved {
                 Some(value) => {
@@ -378,15 +457,15 @@ mod integration_tests {
 
         // Verify isolation between profiles
         for i in 0..profiles.len() {
-            for j in 0..profiles.len() {
+            for (j, profile) in profiles.iter().enumerate() {
                 let result = provider
-                    .get(&project_name, test_key, profiles[j])
+                    .get(&project_name, test_key, profile)
                     .expect("Should not error");
 
                 if i == j {
                     assert!(result.is_some(), "Should find value in same profile");
                 } else {
-                    let expected_value = format!("key_for_{}", profiles[j]);
+                    let expected_value = format!("key_for_{}", profile);
                     assert_eq!(
                         result,
                         Some(expected_value),
@@ -397,3 +476,447 @@ mod integration_tests {
         }
     }
 }
+
+/// Provider that fails with a given error a fixed number of times before succeeding
+struct FlakyProvider {
+    failures_left: Mutex<u32>,
+    calls: Arc<Mutex<u32>>,
+    error: fn() -> crate::SecretSpecError,
+}
+
+impl Provider for FlakyProvider {
+    fn get(&self, _project: &str, _key: &str, _profile: &str) -> Result<Option<String>> {
+        *self.calls.lock().unwrap() += 1;
+        let mut failures_left = self.failures_left.lock().unwrap();
+        if *failures_left > 0 {
+            *failures_left -= 1;
+            return Err((self.error)());
+        }
+        Ok(Some("value".to_string()))
+    }
+
+    fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "flaky"
+    }
+}
+
+fn flaky_retrying_provider(
+    failures: u32,
+    attempts: u32,
+    error: fn() -> crate::SecretSpecError,
+) -> (RetryingProvider, Arc<Mutex<u32>>) {
+    let calls = Arc::new(Mutex::new(0));
+    let inner = FlakyProvider {
+        failures_left: Mutex::new(failures),
+        calls: calls.clone(),
+        error,
+    };
+    let config = RetryConfig {
+        attempts,
+        initial_backoff: std::time::Duration::from_millis(1),
+    };
+    (RetryingProvider::new(Box::new(inner), config), calls)
+}
+
+fn unavailable() -> crate::SecretSpecError {
+    ProviderError::BackendUnavailable("connection reset".to_string()).into()
+}
+
+#[test]
+fn test_retrying_provider_recovers_from_transient_errors() {
+    let (provider, calls) = flaky_retrying_provider(2, 3, unavailable);
+    assert_eq!(
+        provider.get("project", "KEY", "default").unwrap(),
+        Some("value".to_string())
+    );
+    assert_eq!(*calls.lock().unwrap(), 3);
+}
+
+#[test]
+fn test_retrying_provider_gives_up_after_max_attempts() {
+    let (provider, calls) = flaky_retrying_provider(5, 3, unavailable);
+    assert!(matches!(
+        provider.get("project", "KEY", "default"),
+        Err(crate::SecretSpecError::Provider(
+            ProviderError::BackendUnavailable(_)
+        ))
+    ));
+    assert_eq!(*calls.lock().unwrap(), 3);
+}
+
+#[test]
+fn test_retrying_provider_does_not_retry_other_errors() {
+    let (provider, calls) = flaky_retrying_provider(1, 3, || {
+        ProviderError::AuthFailed("sign in first".to_string()).into()
+    });
+    assert!(provider.get("project", "KEY", "default").is_err());
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_retry_config_from_global_defaults() {
+    let defaults = crate::GlobalDefaults {
+        retry_attempts: Some(5),
+        ..Default::default()
+    };
+    let config = RetryConfig::from(&defaults);
+    assert_eq!(config.attempts, 5);
+    assert_eq!(
+        config.initial_backoff,
+        RetryConfig::default().initial_backoff
+    );
+}
+
+#[test]
+fn test_only_remote_providers_are_remote() {
+    for spec in ["keyring", "dotenv", "env", "sops"] {
+        assert!(!Box::<dyn Provider>::try_from(spec).unwrap().is_remote());
+    }
+    for spec in ["onepassword", "lastpass"] {
+        assert!(Box::<dyn Provider>::try_from(spec).unwrap().is_remote());
+    }
+}
+
+#[test]
+fn test_only_env_ignores_profiles() {
+    for spec in ["keyring", "dotenv", "onepassword", "lastpass"] {
+        assert!(
+            Box::<dyn Provider>::try_from(spec)
+                .unwrap()
+                .supports_profiles()
+        );
+    }
+    let env = Box::<dyn Provider>::try_from("env").unwrap();
+    assert!(!env.supports_profiles());
+
+    // Decorators report the wrapped provider's capabilities
+    let retrying = RetryingProvider::new(env, RetryConfig::default());
+    assert!(!retrying.supports_profiles());
+}
+
+#[test]
+fn test_transient_network_error_classification() {
+    assert!(is_transient_network_error(
+        "dial tcp: lookup my.1password.com: no such host"
+    ));
+    assert!(is_transient_network_error("Error: Connection timed out"));
+    assert!(!is_transient_network_error("isn't an item in the vault"));
+}
+
+#[test]
+fn test_timing_provider_reports_slowest_first() {
+    use super::timing::{TimingProvider, Timings};
+
+    struct SlowProvider;
+
+    impl Provider for SlowProvider {
+        fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
+            if key == "SLOW" {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            Ok(None)
+        }
+
+        fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+    }
+
+    let timings = Timings::default();
+    let provider = TimingProvider::new(Box::new(SlowProvider), timings.clone());
+    provider.get("project", "FAST", "default").unwrap();
+    provider.get("project", "SLOW", "default").unwrap();
+    // Providers that cannot enumerate keys are not recorded
+    provider.list_keys("project", "default").unwrap();
+
+    let report = timings.report().unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "Provider timings:");
+    assert!(lines[1].trim_start().starts_with("SLOW"));
+    assert!(lines[2].trim_start().starts_with("FAST"));
+    assert!(lines[3].trim_start().starts_with("total"));
+
+    // Reporting drains the log
+    assert!(timings.report().is_none());
+}
+
+#[test]
+fn test_caching_provider_serves_values_from_disk() {
+    use super::cache::CachingProvider;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct CountingProvider {
+        gets: Arc<AtomicUsize>,
+    }
+
+    impl Provider for CountingProvider {
+        fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
+            self.gets.fetch_add(1, Ordering::SeqCst);
+            Ok((key != "MISSING").then(|| format!("{}-value", key)))
+        }
+
+        fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+    }
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("cache").join("provider-cache.json");
+    let gets = Arc::new(AtomicUsize::new(0));
+    let caching = |ttl| {
+        CachingProvider::new(
+            Box::new(CountingProvider { gets: gets.clone() }),
+            "counting://",
+            Duration::from_secs(ttl),
+            path.clone(),
+        )
+    };
+
+    let provider = caching(300);
+    assert_eq!(
+        provider.get("project", "API_KEY", "default").unwrap(),
+        Some("API_KEY-value".to_string())
+    );
+    provider.get("project", "API_KEY", "default").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    // Missing values are not cached
+    provider.get("project", "MISSING", "default").unwrap();
+    provider.get("project", "MISSING", "default").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 3);
+
+    // New entries are written once the provider is dropped, readable only
+    // by the current user even if the file already existed
+    assert!(!path.exists());
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "{}").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    }
+    drop(provider);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // A later invocation reads the cache file
+    let provider = caching(300);
+    provider.get("project", "API_KEY", "default").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 3);
+    // Other profiles are cached separately
+    provider.get("project", "API_KEY", "production").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 4);
+
+    // Writing a secret invalidates its entry
+    provider
+        .set("project", "API_KEY", "new", "default")
+        .unwrap();
+    provider.get("project", "API_KEY", "default").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 5);
+
+    // Entries expire after the TTL
+    let provider = caching(0);
+    provider.get("project", "OTHER", "default").unwrap();
+    provider.get("project", "OTHER", "default").unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 7);
+}
+
+#[test]
+fn test_chain_members_split_on_top_level_commas() {
+    use crate::provider::chain::members;
+
+    assert_eq!(members("keyring"), None);
+    assert_eq!(members("env,"), None);
+    assert_eq!(
+        members("onepassword://Production, env"),
+        Some(vec!["onepassword://Production", "env"])
+    );
+    assert_eq!(
+        members("dotenv:.env.local,keyring"),
+        Some(vec!["dotenv:.env.local", "keyring"])
+    );
+
+    // Commas that don't start another provider belong to the URI
+    assert_eq!(members("dotenv://a,b.env"), None);
+    assert_eq!(
+        members("dotenv://a,b.env,env"),
+        Some(vec!["dotenv://a,b.env", "env"])
+    );
+    assert_eq!(
+        members("onepassword://vault/Production?tags=a,b,custom://x"),
+        Some(vec![
+            "onepassword://vault/Production?tags=a,b",
+            "custom://x"
+        ])
+    );
+}
+
+#[test]
+fn test_chain_reads_first_value_and_writes_first_provider() {
+    use crate::MemoryProvider;
+
+    let primary = MemoryProvider::with_secrets(
+        "test_chain_primary",
+        "project",
+        "default",
+        [("SHARED", "primary")],
+    );
+    let fallback = MemoryProvider::with_secrets(
+        "test_chain_fallback",
+        "project",
+        "default",
+        [("SHARED", "fallback"), ("CI_ONLY", "from-ci")],
+    );
+    let chain =
+        Box::<dyn Provider>::try_from("memory://test_chain_primary, memory://test_chain_fallback")
+            .unwrap();
+    assert_eq!(chain.name(), "chain");
+
+    let get = |key: &str| chain.get("project", key, "default").unwrap();
+    assert_eq!(get("SHARED").as_deref(), Some("primary"));
+    assert_eq!(get("CI_ONLY").as_deref(), Some("from-ci"));
+    assert_eq!(get("MISSING"), None);
+    assert_eq!(
+        chain.list_keys("project", "default").unwrap(),
+        Some(vec!["CI_ONLY".to_string(), "SHARED".to_string()])
+    );
+
+    assert!(chain.allows_set());
+    chain.set("project", "NEW", "value", "default").unwrap();
+    assert_eq!(primary.set_calls(), 1);
+    assert_eq!(fallback.set_calls(), 0);
+}
+
+#[test]
+fn test_chain_skips_failing_providers() {
+    use crate::MemoryProvider;
+    use crate::provider::chain::ChainProvider;
+
+    let failing = || -> Box<dyn Provider> {
+        Box::new(FlakyProvider {
+            failures_left: Mutex::new(u32::MAX),
+            calls: Arc::new(Mutex::new(0)),
+            error: || ProviderError::AuthFailed("sign in first".to_string()).into(),
+        })
+    };
+    MemoryProvider::with_secrets(
+        "test_chain_skips_failing_providers",
+        "project",
+        "default",
+        [("API_KEY", "from-env")],
+    );
+    let fallback =
+        || Box::<dyn Provider>::try_from("memory://test_chain_skips_failing_providers").unwrap();
+
+    // A later provider's value wins over an earlier provider's error
+    let chain = ChainProvider::new(vec![failing(), fallback()]);
+    assert_eq!(
+        chain
+            .get("project", "API_KEY", "default")
+            .unwrap()
+            .as_deref(),
+        Some("from-env")
+    );
+    // Without a value the error is reported instead of a missing secret
+    assert!(matches!(
+        chain.get("project", "MISSING", "default"),
+        Err(crate::SecretSpecError::Provider(ProviderError::AuthFailed(
+            _
+        )))
+    ));
+
+    // Writes only go to the first provider, so a read-only one makes the chain read-only
+    let chain =
+        Box::<dyn Provider>::try_from("env,memory://test_chain_skips_failing_providers").unwrap();
+    assert!(!chain.allows_set());
+    assert!(!chain.supports_profiles());
+}
+
+#[test]
+fn test_chain_resolves_paths_of_every_provider() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("shared.env"), "API_KEY=from-dir\n").unwrap();
+
+    let mut chain = Box::<dyn Provider>::try_from("env,dotenv://shared.env").unwrap();
+    chain.resolve_paths_from(dir.path());
+    assert_eq!(
+        chain
+            .get("project", "API_KEY", "default")
+            .unwrap()
+            .as_deref(),
+        Some("from-dir")
+    );
+    assert_eq!(chain.watch_paths(), vec![dir.path().join("shared.env")]);
+}
+
+#[test]
+fn test_chain_describes_and_watches_without_reading_values() {
+    use super::chain::ChainProvider;
+
+    /// A provider that cannot list its keys and must not be read
+    struct Unlisted;
+
+    impl Provider for Unlisted {
+        fn get(&self, _project: &str, _key: &str, _profile: &str) -> Result<Option<String>> {
+            panic!("describe_location must not read values");
+        }
+
+        fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn describe_location(&self, _project: &str, key: &str, _profile: &str) -> String {
+            format!("unlisted {}", key)
+        }
+
+        fn name(&self) -> &'static str {
+            "unlisted"
+        }
+    }
+
+    let dir = TempDir::new().unwrap();
+    let local = dir.path().join(".env.local");
+    let shared = dir.path().join(".env");
+    std::fs::write(&local, "LOCAL_ONLY=local\n").unwrap();
+    std::fs::write(&shared, "API_KEY=shared\n").unwrap();
+    let dotenv = |path: &std::path::Path| {
+        Box::<dyn Provider>::try_from(format!("dotenv://{}", path.display()).as_str()).unwrap()
+    };
+
+    let chain = ChainProvider::new(vec![dotenv(&local), dotenv(&shared), Box::new(Unlisted)]);
+    assert_eq!(chain.watch_paths(), vec![local.clone(), shared.clone()]);
+    // The first provider listing the key holds it
+    assert_eq!(
+        chain.describe_location("project", "API_KEY", "default"),
+        format!("{}:1", shared.display())
+    );
+    // Past the providers that don't list it, the rest are described
+    assert_eq!(
+        chain.describe_location("project", "OTHER", "default"),
+        "unlisted OTHER"
+    );
+
+    let chain = ChainProvider::new(vec![Box::new(Unlisted), dotenv(&shared)]);
+    assert_eq!(
+        chain.describe_location("project", "API_KEY", "default"),
+        format!("unlisted API_KEY, falling back to {}:1", shared.display())
+    );
+}
@@ -0,0 +1,168 @@
+//! Latency measurement for provider operations.
+//!
+//! [`TimingProvider`] wraps another provider and records how long each
+//! lookup takes in a shared [`Timings`] log. [`Secrets`](crate::Secrets)
+//! installs it when timing is enabled, e.g. with `--timing`.
+
+use super::Provider;
+use crate::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Label recorded for `list_keys` calls, which are not tied to a secret.
+const LIST_KEYS_LABEL: &str = "(list keys)";
+
+/// A shared log of measured provider operations.
+///
+/// Cloning a `Timings` yields a handle to the same log.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    entries: Arc<Mutex<Vec<(String, Duration)>>>,
+}
+
+impl Timings {
+    /// Records that the operation labelled `label` took `elapsed`.
+    fn record(&self, label: &str, elapsed: Duration) {
+        self.entries
+            .lock()
+            .unwrap()
+            .push((label.to_string(), elapsed));
+    }
+
+    /// Removes and returns all recorded entries, slowest first.
+    pub fn take(&self) -> Vec<(String, Duration)> {
+        let mut entries = std::mem::take(&mut *self.entries.lock().unwrap());
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        entries
+    }
+
+    /// Removes all recorded entries and formats them as a report, slowest
+    /// first and followed by the total.
+    ///
+    /// # Returns
+    ///
+    /// The report, or `None` if nothing was recorded
+    pub fn report(&self) -> Option<String> {
+        let entries = self.take();
+        if entries.is_empty() {
+            return None;
+        }
+
+        let width = entries
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or(0)
+            .max("total".len());
+        let format_ms = |d: Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+
+        let mut report = String::from("Provider timings:\n");
+        for (label, elapsed) in &entries {
+            report.push_str(&format!(
+                "  {:<width$}  {:>10}\n",
+                label,
+                format_ms(*elapsed)
+            ));
+        }
+        let total: Duration = entries.iter().map(|(_, elapsed)| *elapsed).sum();
+        report.push_str(&format!(
+            "  {:<width$}  {:>10}\n",
+            "total",
+            format_ms(total)
+        ));
+        Some(report)
+    }
+}
+
+/// A provider decorator that measures how long lookups take.
+///
+/// Every `get` is recorded under the secret's name and every `list_keys`
+/// that enumerates keys under `(list keys)`; writes are passed through
+/// unmeasured.
+pub struct TimingProvider {
+    inner: Box<dyn Provider>,
+    timings: Timings,
+}
+
+impl TimingProvider {
+    /// Wraps `inner` so that its lookups are recorded in `timings`.
+    pub fn new(inner: Box<dyn Provider>, timings: Timings) -> Self {
+        Self { inner, timings }
+    }
+
+    /// Runs `operation`, recording its duration under `label`.
+    fn measure<T>(&self, label: &str, operation: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = operation();
+        self.timings.record(label, start.elapsed());
+        result
+    }
+}
+
+impl Provider for TimingProvider {
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        self.measure(key, || self.inner.get(project, key, profile))
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.inner.set(project, key, value, profile)
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.inner.set_many(project, values, profile)
+    }
+
+    fn allows_set(&self) -> bool {
+        self.inner.allows_set()
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        let start = Instant::now();
+        let result = self.inner.list_keys(project, profile);
+        // Providers that cannot enumerate keys return immediately; don't
+        // clutter the report with them
+        if !matches!(result, Ok(None)) {
+            self.timings.record(LIST_KEYS_LABEL, start.elapsed());
+        }
+        result
+    }
+
+    fn set_at(&self, project: &str, key: &str, profile: &str) -> Result<Option<u64>> {
+        self.inner.set_at(project, key, profile)
+    }
+
+    fn record_set_at(&self, project: &str, key: &str, profile: &str, timestamp: u64) -> Result<()> {
+        self.inner.record_set_at(project, key, profile, timestamp)
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        self.inner.describe_location(project, key, profile)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        self.inner.watch_paths()
+    }
+
+    fn is_remote(&self) -> bool {
+        self.inner.is_remote()
+    }
+
+    fn allows_caching(&self) -> bool {
+        self.inner.allows_caching()
+    }
+
+    fn supports_profiles(&self) -> bool {
+        self.inner.supports_profiles()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
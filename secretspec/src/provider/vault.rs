@@ -0,0 +1,529 @@
+//! HashiCorp Vault and OpenBao providers.
+//!
+//! OpenBao is the open-source fork of Vault with the same HTTP API, so both
+//! schemes share one implementation: `vault://` reads `VAULT_*` environment
+//! variables and `openbao://` reads `BAO_*` ones, falling back to `VAULT_*`.
+
+use super::Provider;
+use crate::{ProviderError, Result, SecretSpecError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use url::Url;
+
+/// Which server a [`VaultConfig`] talks to.
+///
+/// Both speak the same API; the flavor only selects the environment
+/// variables the address, token and namespace are read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VaultFlavor {
+    /// HashiCorp Vault (`vault://`)
+    #[default]
+    Vault,
+    /// OpenBao (`openbao://`)
+    OpenBao,
+}
+
+impl VaultFlavor {
+    /// Returns the environment variables to read `setting` from, in order
+    /// of precedence, e.g. `BAO_TOKEN` then `VAULT_TOKEN` for OpenBao.
+    fn env_vars(self, setting: &str) -> Vec<String> {
+        match self {
+            VaultFlavor::Vault => vec![format!("VAULT_{}", setting)],
+            VaultFlavor::OpenBao => vec![format!("BAO_{}", setting), format!("VAULT_{}", setting)],
+        }
+    }
+
+    /// Returns the first of `env_vars(setting)` that `lookup` finds.
+    fn setting(self, setting: &str, lookup: impl Fn(&str) -> Option<String>) -> Option<String> {
+        self.env_vars(setting)
+            .iter()
+            .find_map(|name| lookup(name).filter(|value| !value.is_empty()))
+    }
+}
+
+/// Configuration for the Vault and OpenBao providers.
+///
+/// # Examples
+///
+/// ```ignore
+/// use secretspec::provider::vault::{VaultConfig, VaultFlavor};
+///
+/// let config = VaultConfig {
+///     address: Some("https://vault.example.com:8200".to_string()),
+///     mount: "secret".to_string(),
+///     flavor: VaultFlavor::Vault,
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Server address; `VAULT_ADDR` (or `BAO_ADDR`) is used when `None`
+    pub address: Option<String>,
+    /// Mount path of the KV version 2 secrets engine
+    pub mount: String,
+    /// Whether this is a Vault or an OpenBao server
+    pub flavor: VaultFlavor,
+}
+
+impl Default for VaultConfig {
+    /// Creates a configuration for the `secret` mount of the server in
+    /// `VAULT_ADDR`.
+    fn default() -> Self {
+        Self {
+            address: None,
+            mount: "secret".to_string(),
+            flavor: VaultFlavor::Vault,
+        }
+    }
+}
+
+impl TryFrom<&Url> for VaultConfig {
+    type Error = SecretSpecError;
+
+    /// Creates a VaultConfig from a URL.
+    ///
+    /// # URL Formats
+    ///
+    /// - `vault://` - Server from `VAULT_ADDR`, `secret` mount
+    /// - `vault://vault.example.com:8200/kv` - HTTPS server and mount
+    /// - `vault://localhost:8200/secret?tls=false` - Plain HTTP, e.g. a dev server
+    /// - `openbao://...` - The same formats, reading `BAO_*` variables first
+    fn try_from(url: &Url) -> std::result::Result<Self, Self::Error> {
+        let flavor = match url.scheme() {
+            "vault" => VaultFlavor::Vault,
+            "openbao" => VaultFlavor::OpenBao,
+            scheme => {
+                return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                    "Invalid scheme '{}' for vault provider",
+                    scheme
+                ))));
+            }
+        };
+
+        let tls = !url
+            .query_pairs()
+            .any(|(key, value)| key == "tls" && value == "false");
+        let address = url.host_str().filter(|host| !host.is_empty()).map(|host| {
+            let scheme = if tls { "https" } else { "http" };
+            match url.port() {
+                Some(port) => format!("{}://{}:{}", scheme, host, port),
+                None => format!("{}://{}", scheme, host),
+            }
+        });
+
+        let mount = url.path().trim_matches('/');
+        Ok(Self {
+            address,
+            mount: if mount.is_empty() {
+                Self::default().mount
+            } else {
+                mount.to_string()
+            },
+            flavor,
+        })
+    }
+}
+
+/// Provider for secrets stored in the KV version 2 engine of a HashiCorp
+/// Vault server.
+///
+/// Each profile of a project is one KV secret at
+/// `<mount>/secretspec/<project>/<profile>`, whose keys are the secret names.
+/// Writes read the current version and write it back with check-and-set, so
+/// a concurrent change is reported instead of being overwritten.
+///
+/// The token is read from `VAULT_TOKEN` and, for Vault Enterprise, the
+/// namespace from `VAULT_NAMESPACE`.
+pub struct VaultProvider {
+    config: VaultConfig,
+}
+
+crate::register_provider! {
+    struct: VaultProvider,
+    config: VaultConfig,
+    name: "vault",
+    description: "HashiCorp Vault KV secrets engine",
+    schemes: ["vault"],
+    examples: ["vault://", "vault://vault.example.com:8200/secret"],
+}
+
+/// Provider for secrets stored in the KV version 2 engine of an OpenBao
+/// server.
+///
+/// Behaves like [`VaultProvider`], but reads `BAO_ADDR`, `BAO_TOKEN` and
+/// `BAO_NAMESPACE` before their `VAULT_*` equivalents.
+pub struct OpenBaoProvider(VaultProvider);
+
+crate::register_provider! {
+    struct: OpenBaoProvider,
+    config: VaultConfig,
+    name: "openbao",
+    description: "OpenBao KV secrets engine",
+    schemes: ["openbao"],
+    examples: ["openbao://", "openbao://bao.example.com:8200/secret"],
+}
+
+impl OpenBaoProvider {
+    /// Creates a new OpenBaoProvider with the given configuration.
+    pub fn new(config: VaultConfig) -> Self {
+        Self(VaultProvider::new(VaultConfig {
+            flavor: VaultFlavor::OpenBao,
+            ..config
+        }))
+    }
+}
+
+/// A KV secret as read from the server.
+struct KvSecret {
+    data: Map<String, Value>,
+    /// Current version, 0 if the secret was never written
+    version: u64,
+}
+
+impl VaultProvider {
+    /// Creates a new VaultProvider with the given configuration.
+    pub fn new(config: VaultConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the environment variable holding `setting` for error
+    /// messages, e.g. `VAULT_TOKEN` or `BAO_TOKEN`.
+    fn env_name(&self, setting: &str) -> String {
+        self.config.flavor.env_vars(setting).remove(0)
+    }
+
+    fn setting(&self, setting: &str) -> Option<String> {
+        self.config
+            .flavor
+            .setting(setting, |name| std::env::var(name).ok())
+    }
+
+    /// Returns the API URL of the KV secret holding `project` and `profile`.
+    fn secret_url(&self, project: &str, profile: &str) -> Result<String> {
+        let address = match &self.config.address {
+            Some(address) => address.clone(),
+            None => self.setting("ADDR").ok_or_else(|| {
+                ProviderError::BackendUnavailable(format!(
+                    "No server address configured. Set {} or use e.g. {}://host:8200/{}",
+                    self.env_name("ADDR"),
+                    self.name(),
+                    self.config.mount
+                ))
+            })?,
+        };
+        let invalid =
+            || ProviderError::BackendUnavailable(format!("Invalid server address '{}'", address));
+        let mut url = Url::parse(&address).map_err(|_| invalid())?;
+        // Project and profile are single segments, so characters such as
+        // `/`, `?` or `#` in them are percent-encoded instead of changing
+        // which secret is addressed
+        url.path_segments_mut()
+            .map_err(|_| invalid())?
+            .pop_if_empty()
+            .push("v1")
+            .extend(self.config.mount.split('/'))
+            .extend(["data", "secretspec", project, profile]);
+        Ok(url.into())
+    }
+
+    fn request(&self, method: &str, url: &str) -> Result<ureq::Request> {
+        let token = self.setting("TOKEN").ok_or_else(|| {
+            ProviderError::AuthFailed(format!(
+                "No token found. Set {} to a token that can access the '{}' mount",
+                self.env_name("TOKEN"),
+                self.config.mount
+            ))
+        })?;
+        let mut request = ureq::request(method, url).set("X-Vault-Token", &token);
+        if let Some(namespace) = self.setting("NAMESPACE") {
+            request = request.set("X-Vault-Namespace", &namespace);
+        }
+        Ok(request)
+    }
+
+    /// Converts a failed request into a provider error.
+    fn error(&self, error: ureq::Error) -> SecretSpecError {
+        match error {
+            ureq::Error::Status(status @ (401 | 403), _) => ProviderError::AuthFailed(format!(
+                "{} denied access ({}). Check that {} holds a valid token with access to the '{}' mount",
+                self.name(),
+                status,
+                self.env_name("TOKEN"),
+                self.config.mount
+            ))
+            .into(),
+            ureq::Error::Status(status, response) => ProviderError::Other(format!(
+                "{} returned {}: {}",
+                self.name(),
+                status,
+                response.into_string().unwrap_or_default().trim()
+            ))
+            .into(),
+            ureq::Error::Transport(transport) => {
+                ProviderError::Other(format!("Could not connect to {}: {}", self.name(), transport))
+                    .into()
+            }
+        }
+    }
+
+    /// Reads the KV secret holding the values of `project` and `profile`.
+    fn read(&self, project: &str, profile: &str) -> Result<KvSecret> {
+        let url = self.secret_url(project, profile)?;
+        let body = match self.request("GET", &url)?.call() {
+            Ok(response) => response.into_string()?,
+            // Deleted secrets are reported as 404 with their metadata, which
+            // still holds the version a write has to check against
+            Err(ureq::Error::Status(404, response)) => response.into_string().unwrap_or_default(),
+            Err(e) => return Err(self.error(e)),
+        };
+        Ok(parse_secret(&body))
+    }
+
+    /// Writes `values` into the KV secret of `project` and `profile`,
+    /// keeping the values already stored there.
+    fn write(&self, project: &str, values: &HashMap<String, String>, profile: &str) -> Result<()> {
+        let KvSecret { mut data, version } = self.read(project, profile)?;
+        for (key, value) in values {
+            data.insert(key.clone(), Value::from(value.as_str()));
+        }
+        let body = serde_json::json!({ "options": { "cas": version }, "data": data });
+
+        let url = self.secret_url(project, profile)?;
+        match self
+            .request("POST", &url)?
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+        {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(400, response)) => {
+                let message = response.into_string().unwrap_or_default();
+                if message.contains("check-and-set") {
+                    Err(ProviderError::Other(format!(
+                        "secretspec/{}/{} was changed by someone else while writing; try again",
+                        project, profile
+                    ))
+                    .into())
+                } else {
+                    Err(ProviderError::Other(format!(
+                        "{} returned 400: {}",
+                        self.name(),
+                        message.trim()
+                    ))
+                    .into())
+                }
+            }
+            Err(e) => Err(self.error(e)),
+        }
+    }
+}
+
+/// Parses the body of a KV version 2 read, treating anything unexpected as
+/// an empty secret.
+fn parse_secret(body: &str) -> KvSecret {
+    let document: Value = serde_json::from_str(body).unwrap_or_default();
+    let data = document
+        .pointer("/data/data")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let version = document
+        .pointer("/data/metadata/version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    KvSecret { data, version }
+}
+
+impl Provider for VaultProvider {
+    fn name(&self) -> &'static str {
+        match self.config.flavor {
+            VaultFlavor::Vault => Self::PROVIDER_NAME,
+            VaultFlavor::OpenBao => OpenBaoProvider::PROVIDER_NAME,
+        }
+    }
+
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        Ok(self
+            .read(project, profile)?
+            .data
+            .get(key)
+            .and_then(|value| match value {
+                Value::String(value) => Some(value.clone()),
+                Value::Null => None,
+                value => Some(value.to_string()),
+            }))
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.write(
+            project,
+            &HashMap::from([(key.to_string(), value.to_string())]),
+            profile,
+        )
+    }
+
+    /// Writes all values as a single new version of the profile's secret.
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.write(project, values, profile)
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        let mut keys: Vec<String> = self.read(project, profile)?.data.keys().cloned().collect();
+        keys.sort();
+        Ok(Some(keys))
+    }
+
+    /// Names the KV secret as `vault kv get` expects it, and the server.
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        let server = match &self.config.address {
+            Some(address) => address.clone(),
+            None => self
+                .setting("ADDR")
+                .unwrap_or_else(|| format!("the server in {}", self.env_name("ADDR"))),
+        };
+        format!(
+            "key '{}' of secret 'secretspec/{}/{}' in the '{}' mount on {}",
+            key, project, profile, self.config.mount, server
+        )
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+}
+
+impl Provider for OpenBaoProvider {
+    fn name(&self) -> &'static str {
+        Self::PROVIDER_NAME
+    }
+
+    fn get(&self, project: &str, key: &str, profile: &str) -> Result<Option<String>> {
+        self.0.get(project, key, profile)
+    }
+
+    fn set(&self, project: &str, key: &str, value: &str, profile: &str) -> Result<()> {
+        self.0.set(project, key, value, profile)
+    }
+
+    fn set_many(
+        &self,
+        project: &str,
+        values: &HashMap<String, String>,
+        profile: &str,
+    ) -> Result<()> {
+        self.0.set_many(project, values, profile)
+    }
+
+    fn list_keys(&self, project: &str, profile: &str) -> Result<Option<Vec<String>>> {
+        self.0.list_keys(project, profile)
+    }
+
+    fn describe_location(&self, project: &str, key: &str, profile: &str) -> String {
+        self.0.describe_location(project, key, profile)
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_url_parsing() {
+        let parse = |s: &str| VaultConfig::try_from(&Url::parse(s).unwrap()).unwrap();
+
+        let config = parse("vault://");
+        assert_eq!(config.address, None);
+        assert_eq!(config.mount, "secret");
+        assert_eq!(config.flavor, VaultFlavor::Vault);
+
+        let config = parse("vault://vault.example.com:8200/kv/team");
+        assert_eq!(
+            config.address.as_deref(),
+            Some("https://vault.example.com:8200")
+        );
+        assert_eq!(config.mount, "kv/team");
+
+        let config = parse("openbao://localhost:8200/secret?tls=false");
+        assert_eq!(config.address.as_deref(), Some("http://localhost:8200"));
+        assert_eq!(config.flavor, VaultFlavor::OpenBao);
+    }
+
+    #[test]
+    fn test_secret_url_encodes_project_and_profile() {
+        let provider = |uri: &str| {
+            VaultProvider::new(VaultConfig::try_from(&Url::parse(uri).unwrap()).unwrap())
+        };
+
+        assert_eq!(
+            provider("vault://vault.example.com:8200/kv/team")
+                .secret_url("app", "default")
+                .unwrap(),
+            "https://vault.example.com:8200/v1/kv/team/data/secretspec/app/default"
+        );
+        assert_eq!(
+            provider("vault://vault.example.com/secret")
+                .secret_url("my app/../other?x#y", "dev%2F")
+                .unwrap(),
+            "https://vault.example.com/v1/secret/data/secretspec/my%20app%2F..%2Fother%3Fx%23y/dev%252F"
+        );
+    }
+
+    #[test]
+    fn test_openbao_scheme_resolves_to_openbao_provider() {
+        let provider = Box::<dyn Provider>::try_from("openbao://bao.example.com/secret").unwrap();
+        assert_eq!(provider.name(), "openbao");
+        assert!(provider.is_remote());
+
+        let provider = Box::<dyn Provider>::try_from("vault://vault.example.com/secret").unwrap();
+        assert_eq!(provider.name(), "vault");
+
+        let names: Vec<&str> = crate::provider::providers()
+            .iter()
+            .map(|info| info.name)
+            .collect();
+        assert!(names.contains(&"vault") && names.contains(&"openbao"));
+    }
+
+    #[test]
+    fn test_openbao_reads_bao_variables_first() {
+        let env = HashMap::from([("BAO_TOKEN", "bao"), ("VAULT_TOKEN", "vault")]);
+        let lookup = |name: &str| env.get(name).map(|value| value.to_string());
+        assert_eq!(
+            VaultFlavor::OpenBao.setting("TOKEN", lookup).as_deref(),
+            Some("bao")
+        );
+        assert_eq!(
+            VaultFlavor::Vault.setting("TOKEN", lookup).as_deref(),
+            Some("vault")
+        );
+
+        let env = HashMap::from([("VAULT_ADDR", "http://127.0.0.1:8200")]);
+        let lookup = |name: &str| env.get(name).map(|value| value.to_string());
+        assert_eq!(
+            VaultFlavor::OpenBao.setting("ADDR", lookup).as_deref(),
+            Some("http://127.0.0.1:8200")
+        );
+        assert_eq!(VaultFlavor::OpenBao.setting("NAMESPACE", lookup), None);
+    }
+
+    #[test]
+    fn test_parse_kv_secret() {
+        let secret =
+            parse_secret(r#"{"data": {"data": {"API_KEY": "abc"}, "metadata": {"version": 3}}}"#);
+        assert_eq!(secret.data["API_KEY"], "abc");
+        assert_eq!(secret.version, 3);
+
+        // A 404 for a path that was never written has no body to speak of
+        let secret = parse_secret(r#"{"errors": []}"#);
+        assert!(secret.data.is_empty());
+        assert_eq!(secret.version, 0);
+    }
+}
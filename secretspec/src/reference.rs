@@ -0,0 +1,153 @@
+//! References to other secrets inside default values
+//!
+//! A default may embed the value of another secret of the same profile with
+//! `${secret:NAME}`:
+//!
+//! ```toml
+//! [profiles.default]
+//! BASE_URL = { description = "Public URL", required = false, default = "http://localhost:3000" }
+//! CALLBACK_URL = { description = "OAuth callback", required = false, default = "${secret:BASE_URL}/callback" }
+//! ```
+//!
+//! Referenced secrets are resolved first, so defaults may build on each other
+//! as long as the references do not form a cycle.
+
+use crate::error::{Result, SecretSpecError};
+use std::collections::{HashMap, HashSet};
+
+const OPEN: &str = "${secret:";
+const CLOSE: char = '}';
+
+/// Splits `template` at its first reference into the text before it, the
+/// referenced name and the text after it.
+fn split_reference(template: &str) -> Option<(&str, &str, &str)> {
+    let start = template.find(OPEN)?;
+    let rest = &template[start + OPEN.len()..];
+    let end = rest.find(CLOSE)?;
+    Some((&template[..start], &rest[..end], &rest[end + 1..]))
+}
+
+/// Returns the names referenced by `template`, in order of appearance.
+pub(crate) fn references(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some((_, name, after)) = split_reference(rest) {
+        names.push(name);
+        rest = after;
+    }
+    names
+}
+
+/// Replaces every reference in `template` with the value in `values`.
+///
+/// # Returns
+///
+/// `None` if a referenced secret has no value
+pub(crate) fn substitute(template: &str, values: &HashMap<String, String>) -> Option<String> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some((before, name, after)) = split_reference(rest) {
+        result.push_str(before);
+        result.push_str(values.get(name)?);
+        rest = after;
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+/// Orders `names` and every secret they reference so that each secret comes
+/// after the secrets its default refers to.
+///
+/// `references` maps a secret to the names referenced by its default. Names
+/// without references keep their relative order.
+///
+/// # Errors
+///
+/// Returns `CircularDependency` if defaults reference each other in a cycle
+pub(crate) fn resolution_order(
+    names: &[String],
+    references: &HashMap<String, Vec<String>>,
+) -> Result<Vec<String>> {
+    fn visit(
+        name: &str,
+        references: &HashMap<String, Vec<String>>,
+        path: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|visiting| visiting == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(SecretSpecError::CircularDependency(format!(
+                "secret defaults reference each other: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        path.push(name.to_string());
+        for referenced in references.get(name).into_iter().flatten() {
+            visit(referenced, references, path, done, order)?;
+        }
+        path.pop();
+
+        done.insert(name.to_string());
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::with_capacity(names.len());
+    let mut done = HashSet::new();
+    for name in names {
+        visit(name, references, &mut Vec::new(), &mut done, &mut order)?;
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_references_and_substitute() {
+        let template = "${secret:SCHEME}://${secret:HOST}/callback?x=${other}";
+        assert_eq!(references(template), vec!["SCHEME", "HOST"]);
+
+        let mut values = HashMap::new();
+        values.insert("SCHEME".to_string(), "https".to_string());
+        assert_eq!(substitute(template, &values), None);
+
+        values.insert("HOST".to_string(), "example.com".to_string());
+        assert_eq!(
+            substitute(template, &values).as_deref(),
+            Some("https://example.com/callback?x=${other}")
+        );
+        assert_eq!(substitute("plain", &values).as_deref(), Some("plain"));
+        assert_eq!(references("${secret:UNCLOSED"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_resolution_order_puts_references_first() {
+        let mut references = HashMap::new();
+        references.insert("A_URL".to_string(), names(&["Z_HOST"]));
+        let order = resolution_order(&names(&["A_URL", "B", "Z_HOST"]), &references).unwrap();
+        assert_eq!(order, names(&["Z_HOST", "A_URL", "B"]));
+    }
+
+    #[test]
+    fn test_resolution_order_detects_cycles() {
+        let mut references = HashMap::new();
+        references.insert("A".to_string(), names(&["B"]));
+        references.insert("B".to_string(), names(&["A"]));
+        match resolution_order(&names(&["A", "B"]), &references) {
+            Err(SecretSpecError::CircularDependency(msg)) => assert!(msg.contains("A -> B -> A")),
+            other => panic!("Expected CircularDependency, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Remote `extends` entries
+//!
+//! Besides relative paths, `extends` accepts remote configurations:
+//!
+//! - `git+https://github.com/org/shared-secrets.git#v1.0` - a git repository at
+//!   an optional branch, tag or commit, with `secretspec.toml` at its root
+//! - `https://example.com/shared/secretspec.toml` - a configuration file
+//!
+//! Only `https://` URLs are accepted. Remote configurations are fetched into
+//! `~/.cache/secretspec/extends/<hash>`: git repositories pinned to a ref
+//! once, everything else on every load. If fetching fails, the previously
+//! cached copy is used. Fetching requires the `remote-extends` feature.
+
+use crate::config::ParseError;
+use std::path::PathBuf;
+
+/// A remote configuration referenced from `extends`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RemoteSpec {
+    /// A git repository with an optional ref (branch, tag or commit)
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+    /// A configuration file served over HTTPS
+    Http { url: String },
+}
+
+impl RemoteSpec {
+    /// Parses an `extends` entry, returning `None` for local paths.
+    pub(crate) fn parse(entry: &str) -> Option<Self> {
+        if let Some(rest) = entry.strip_prefix("git+") {
+            let (url, reference) = match rest.split_once('#') {
+                Some((url, reference)) if !reference.is_empty() => {
+                    (url, Some(reference.to_string()))
+                }
+                Some((url, _)) => (url, None),
+                None => (rest, None),
+            };
+            Some(RemoteSpec::Git {
+                url: url.to_string(),
+                reference,
+            })
+        } else if entry.starts_with("https://") || entry.starts_with("http://") {
+            Some(RemoteSpec::Http {
+                url: entry.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the key identifying this configuration, used for circular
+    /// dependency detection and to name the cache directory.
+    pub(crate) fn key(&self) -> String {
+        match self {
+            RemoteSpec::Git { url, reference } => {
+                format!("git+{}#{}", url, reference.as_deref().unwrap_or("HEAD"))
+            }
+            RemoteSpec::Http { url } => url.clone(),
+        }
+    }
+
+    /// Rejects URLs other than `https://`, which could be tampered with in
+    /// transit or run transports such as `ext::`, and git refs starting with
+    /// `-`, which `git` would parse as an option such as `--upload-pack` and
+    /// could run commands with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::Remote`] naming the offending value
+    pub(crate) fn validate(&self) -> Result<(), ParseError> {
+        let url = match self {
+            RemoteSpec::Git { url, .. } | RemoteSpec::Http { url } => url,
+        };
+        if !url.starts_with("https://") {
+            return Err(ParseError::Remote(format!(
+                "Invalid remote extends '{}': only https:// URLs are supported",
+                url
+            )));
+        }
+        if let RemoteSpec::Git { url, reference } = self {
+            for value in std::iter::once(url).chain(reference) {
+                if value.starts_with('-') {
+                    return Err(ParseError::Remote(format!(
+                        "Invalid git extends '{}': URLs and refs cannot start with '-'",
+                        value
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the configuration into the cache.
+    ///
+    /// A git repository pinned to a ref is only fetched if it is not cached
+    /// yet. If fetching fails but a cached copy exists, a warning is pushed
+    /// to `warnings` and the cached copy is used.
+    ///
+    /// # Returns
+    ///
+    /// The path of the cached `secretspec.toml`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching fails and there is no cached copy, or if
+    /// secretspec was built without the `remote-extends` feature.
+    #[cfg(feature = "remote-extends")]
+    pub(crate) fn fetch(&self, warnings: &mut Vec<String>) -> Result<PathBuf, ParseError> {
+        let dir = cache_dir(&self.key())?;
+        std::fs::create_dir_all(&dir)?;
+        let config_path = dir.join("secretspec.toml");
+
+        let pinned = matches!(
+            self,
+            RemoteSpec::Git {
+                reference: Some(_),
+                ..
+            }
+        );
+        if pinned && config_path.exists() {
+            return Ok(config_path);
+        }
+
+        let result = match self {
+            RemoteSpec::Git { url, reference } => fetch_git(url, reference.as_deref(), &dir),
+            RemoteSpec::Http { url } => fetch_http(url, &config_path),
+        };
+
+        match result {
+            Ok(()) if config_path.exists() => Ok(config_path),
+            Ok(()) => Err(ParseError::Remote(format!(
+                "{} does not contain a secretspec.toml",
+                self.key()
+            ))),
+            Err(e) if config_path.exists() => {
+                warnings.push(format!(
+                    "Failed to fetch {}, using cached copy: {}",
+                    self.key(),
+                    e
+                ));
+                Ok(config_path)
+            }
+            Err(e) => Err(ParseError::Remote(format!(
+                "Failed to fetch {}: {}",
+                self.key(),
+                e
+            ))),
+        }
+    }
+
+    #[cfg(not(feature = "remote-extends"))]
+    pub(crate) fn fetch(&self, _warnings: &mut Vec<String>) -> Result<PathBuf, ParseError> {
+        Err(ParseError::Remote(format!(
+            "Cannot extend {}: secretspec was built without the `remote-extends` feature",
+            self.key()
+        )))
+    }
+}
+
+/// Returns the cache directory for a remote configuration.
+#[cfg(feature = "remote-extends")]
+fn cache_dir(key: &str) -> Result<PathBuf, ParseError> {
+    use directories::ProjectDirs;
+    let dirs = ProjectDirs::from("", "", "secretspec")
+        .ok_or_else(|| ParseError::Remote("Could not determine the cache directory".to_string()))?;
+    Ok(dirs
+        .cache_dir()
+        .join("extends")
+        .join(format!("{:016x}", fnv1a(key))))
+}
+
+/// Hashes `input` with 64-bit FNV-1a, which is stable across builds unlike
+/// the standard library's hasher.
+pub(crate) fn fnv1a(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Fetches `reference` (or the default branch) of a git repository into `dir`.
+#[cfg(feature = "remote-extends")]
+fn fetch_git(url: &str, reference: Option<&str>, dir: &std::path::Path) -> Result<(), String> {
+    use std::process::Command;
+
+    let git = |args: &[&str]| -> Result<(), String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => "git is not installed".to_string(),
+                _ => e.to_string(),
+            })?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    };
+
+    if !dir.join(".git").exists() {
+        git(&["init", "-q"])?;
+    }
+    git(&[
+        "fetch",
+        "-q",
+        "--depth",
+        "1",
+        "--",
+        url,
+        reference.unwrap_or("HEAD"),
+    ])?;
+    git(&["checkout", "-q", "--force", "FETCH_HEAD"])
+}
+
+/// Downloads a configuration file to `path`.
+#[cfg(feature = "remote-extends")]
+fn fetch_http(url: &str, path: &std::path::Path) -> Result<(), String> {
+    let content = ureq::get(url)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
@@ -0,0 +1,404 @@
+//! Rendering of the `check` listing
+
+use crate::validation::ValidatedSecrets;
+use colored::{ColoredString, Colorize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Output formats of `secretspec check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum CheckFormat {
+    /// A status symbol, name and description per secret, colored on
+    /// terminals
+    #[default]
+    Pretty,
+    /// Status, name, description and note in aligned columns
+    Table,
+    /// Tab-separated status, name, description and note, never colored,
+    /// for logs and pipes
+    Plain,
+}
+
+/// The status of a secret in the `check` listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckStatus {
+    /// The provider has a value
+    Found,
+    /// The provider has a value set longer ago than `rotate_after_days`
+    DueForRotation,
+    /// The default is used
+    Default,
+    /// The provider has no value, but the default would apply
+    NotStored,
+    /// The value doesn't satisfy the secret's constraints
+    Invalid,
+    /// A required secret has no value
+    Missing,
+    /// A recommended secret has no value
+    Recommended,
+    /// An optional secret has no value
+    Optional,
+    /// The provider holds a key the specification doesn't declare
+    Undeclared,
+}
+
+impl CheckStatus {
+    /// The status as a single word, for the table and plain formats
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Found => "found",
+            CheckStatus::DueForRotation => "rotate",
+            CheckStatus::Default => "default",
+            CheckStatus::NotStored => "not-stored",
+            CheckStatus::Invalid => "invalid",
+            CheckStatus::Missing => "missing",
+            CheckStatus::Recommended => "recommended",
+            CheckStatus::Optional => "optional",
+            CheckStatus::Undeclared => "undeclared",
+        }
+    }
+
+    /// The symbol shown in front of the secret in the pretty format
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Found => "✓",
+            CheckStatus::Default | CheckStatus::NotStored | CheckStatus::Optional => "○",
+            CheckStatus::Invalid | CheckStatus::Missing => "✗",
+            CheckStatus::DueForRotation | CheckStatus::Recommended | CheckStatus::Undeclared => "⚠",
+        }
+    }
+
+    fn paint(self, text: &str) -> ColoredString {
+        match self {
+            CheckStatus::Found => text.green(),
+            CheckStatus::DueForRotation
+            | CheckStatus::Default
+            | CheckStatus::NotStored
+            | CheckStatus::Recommended
+            | CheckStatus::Undeclared => text.yellow(),
+            CheckStatus::Invalid | CheckStatus::Missing => text.red(),
+            CheckStatus::Optional => text.blue(),
+        }
+    }
+}
+
+/// A secret in the `check` listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckRow {
+    pub status: CheckStatus,
+    pub name: String,
+    /// The description, `None` for undeclared keys
+    pub description: Option<String>,
+    /// Why the secret has its status, e.g. `required`
+    pub note: Option<String>,
+}
+
+/// The counts shown below the `check` listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct CheckSummary {
+    pub found: usize,
+    pub missing: usize,
+    pub invalid: usize,
+    /// Unset recommended secrets
+    pub recommended: usize,
+    /// Secrets set longer ago than their `rotate_after_days`
+    pub due_for_rotation: usize,
+    /// Unset optional secrets left out with `--hide-optional`
+    pub hidden_optional: usize,
+}
+
+/// Applies `color` to `text` unless `format` is plain
+fn paint(text: &str, format: CheckFormat, color: fn(&str) -> ColoredString) -> String {
+    match format {
+        CheckFormat::Plain => text.to_string(),
+        CheckFormat::Pretty | CheckFormat::Table => color(text).to_string(),
+    }
+}
+
+/// Formats the line introducing the listing, and the project description
+pub(crate) fn format_header(
+    project: &str,
+    description: Option<&str>,
+    provider: &str,
+    profile: &str,
+    format: CheckFormat,
+) -> String {
+    let mut out = format!(
+        "Checking secrets in {} using {} (profile: {})...\n",
+        paint(project, format, |s| s.bold()),
+        paint(provider, format, |s| s.blue()),
+        paint(profile, format, |s| s.cyan())
+    );
+    if let Some(description) = description {
+        let _ = writeln!(out, "{}", paint(description, format, |s| s.dimmed()));
+    }
+    out
+}
+
+/// Formats one line per secret
+pub(crate) fn format_rows(rows: &[CheckRow], format: CheckFormat) -> String {
+    match format {
+        CheckFormat::Pretty => rows.iter().map(pretty_row).collect(),
+        CheckFormat::Table => table(rows),
+        CheckFormat::Plain => rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{}\t{}\t{}\t{}\n",
+                    row.status.label(),
+                    row.name,
+                    row.description.as_deref().unwrap_or_default(),
+                    row.note.as_deref().unwrap_or_default()
+                )
+            })
+            .collect(),
+    }
+}
+
+fn pretty_row(row: &CheckRow) -> String {
+    let mut line = format!("{} {} -", row.status.paint(row.status.symbol()), row.name);
+    if let Some(description) = &row.description {
+        let _ = write!(line, " {}", description);
+    }
+    if let Some(note) = &row.note {
+        let _ = write!(line, " {}", row.status.paint(&format!("({})", note)));
+    }
+    line.push('\n');
+    line
+}
+
+fn table(rows: &[CheckRow]) -> String {
+    const HEADERS: [&str; 4] = ["STATUS", "NAME", "DESCRIPTION", "NOTE"];
+
+    let cells: Vec<[&str; 4]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.status.label(),
+                row.name.as_str(),
+                row.description.as_deref().unwrap_or_default(),
+                row.note.as_deref().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // Cells are padded before the status is colored, so escape codes don't
+    // throw off the alignment; the last column isn't padded
+    let line = |cells: [&str; 4], status: Option<CheckStatus>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column == cells.len() - 1 {
+                    cell.to_string()
+                } else {
+                    format!("{:width$}", cell, width = width)
+                }
+            })
+            .collect();
+        let status = match status {
+            Some(status) => status.paint(&padded[0]).to_string(),
+            None => padded[0].clone(),
+        };
+        format!("{}  {}", status, padded[1..].join("  "))
+            .trim_end()
+            .to_string()
+            + "\n"
+    };
+
+    let mut out = line(HEADERS, None);
+    for (row, cells) in rows.iter().zip(cells) {
+        out.push_str(&line(cells, Some(row.status)));
+    }
+    out
+}
+
+/// Formats the summary line below the listing
+pub(crate) fn format_summary(summary: &CheckSummary, format: CheckFormat) -> String {
+    let mut out = format!(
+        "Summary: {} found, {} missing",
+        paint(&summary.found.to_string(), format, |s| s.green()),
+        paint(&summary.missing.to_string(), format, |s| s.red())
+    );
+    if summary.invalid > 0 {
+        let _ = write!(
+            out,
+            ", {} invalid",
+            paint(&summary.invalid.to_string(), format, |s| s.red())
+        );
+    }
+    if summary.recommended > 0 {
+        let _ = write!(
+            out,
+            ", {} recommended not set",
+            paint(&summary.recommended.to_string(), format, |s| s.yellow())
+        );
+    }
+    if summary.due_for_rotation > 0 {
+        let _ = write!(
+            out,
+            ", {} due for rotation",
+            paint(&summary.due_for_rotation.to_string(), format, |s| s
+                .yellow())
+        );
+    }
+    if summary.hidden_optional > 0 {
+        let _ = write!(
+            out,
+            ", {} optional not shown",
+            paint(&summary.hidden_optional.to_string(), format, |s| s.blue())
+        );
+    }
+    out.push('\n');
+    out
+}
+
+/// Formats the `check --porcelain` listing
+///
+/// Every checked secret gets a `STATUS\tNAME\tPROFILE` line, ordered by
+/// name, where the status is `PRESENT`, `DEFAULT`, `MISSING_REQUIRED` or
+/// `MISSING_OPTIONAL`. Invalid values are `PRESENT`; undeclared keys are
+/// left out. The format never changes with the terminal or verbosity.
+pub(crate) fn format_porcelain(validated: &ValidatedSecrets) -> String {
+    let mut statuses: BTreeMap<&str, &str> = BTreeMap::new();
+    for name in validated.resolved.secrets.keys() {
+        statuses.insert(name, "PRESENT");
+    }
+    for (name, _) in &validated.invalid_values {
+        statuses.insert(name, "PRESENT");
+    }
+    for (name, _) in &validated.with_defaults {
+        statuses.insert(name, "DEFAULT");
+    }
+    for name in validated
+        .missing_optional
+        .iter()
+        .chain(validated.would_default.iter().map(|(name, _)| name))
+    {
+        statuses.insert(name, "MISSING_OPTIONAL");
+    }
+    for name in &validated.missing_required {
+        statuses.insert(name, "MISSING_REQUIRED");
+    }
+
+    statuses
+        .into_iter()
+        .map(|(name, status)| format!("{}\t{}\t{}\n", status, name, validated.resolved.profile))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<CheckRow> {
+        vec![
+            CheckRow {
+                status: CheckStatus::Found,
+                name: "API_KEY".to_string(),
+                description: Some("API key".to_string()),
+                note: None,
+            },
+            CheckRow {
+                status: CheckStatus::Missing,
+                name: "DATABASE_URL".to_string(),
+                description: Some("Database URL".to_string()),
+                note: Some("required".to_string()),
+            },
+            CheckRow {
+                status: CheckStatus::Recommended,
+                name: "SENTRY_DSN".to_string(),
+                description: Some("Sentry DSN".to_string()),
+                note: Some("recommended".to_string()),
+            },
+            CheckRow {
+                status: CheckStatus::Undeclared,
+                name: "EXTRA".to_string(),
+                description: None,
+                note: Some("undeclared".to_string()),
+            },
+        ]
+    }
+
+    /// Removes `ESC [ ... m` color sequences
+    fn strip_colors(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_plain_format_is_tab_separated() {
+        assert_eq!(
+            format_rows(&rows(), CheckFormat::Plain),
+            "found\tAPI_KEY\tAPI key\t\n\
+             missing\tDATABASE_URL\tDatabase URL\trequired\n\
+             recommended\tSENTRY_DSN\tSentry DSN\trecommended\n\
+             undeclared\tEXTRA\t\tundeclared\n"
+        );
+    }
+
+    #[test]
+    fn test_formats_with_colors_forced() {
+        let summary = CheckSummary {
+            found: 1,
+            missing: 1,
+            invalid: 0,
+            recommended: 1,
+            due_for_rotation: 1,
+            hidden_optional: 2,
+        };
+        colored::control::set_override(true);
+        let plain = format_header(
+            "app",
+            Some("An app"),
+            "dotenv",
+            "default",
+            CheckFormat::Plain,
+        ) + &format_rows(&rows(), CheckFormat::Plain)
+            + &format_summary(&summary, CheckFormat::Plain);
+        let pretty = format_rows(&rows(), CheckFormat::Pretty);
+        let table = format_rows(&rows(), CheckFormat::Table);
+        colored::control::unset_override();
+
+        // Plain output never contains escape codes, even when colors are forced
+        assert!(!plain.contains('\x1b'), "{:?}", plain);
+        assert!(plain.ends_with(
+            "Summary: 1 found, 1 missing, 1 recommended not set, 1 due for rotation, 2 optional not shown\n"
+        ));
+
+        assert!(pretty.contains('\x1b'));
+        assert_eq!(
+            strip_colors(&pretty),
+            "✓ API_KEY - API key\n\
+             ✗ DATABASE_URL - Database URL (required)\n\
+             ⚠ SENTRY_DSN - Sentry DSN (recommended)\n\
+             ⚠ EXTRA - (undeclared)\n"
+        );
+
+        // Columns stay aligned around the colored status
+        assert!(table.contains('\x1b'));
+        assert_eq!(
+            strip_colors(&table),
+            "STATUS       NAME          DESCRIPTION   NOTE\n\
+             found        API_KEY       API key\n\
+             missing      DATABASE_URL  Database URL  required\n\
+             recommended  SENTRY_DSN    Sentry DSN    recommended\n\
+             undeclared   EXTRA                       undeclared\n"
+        );
+    }
+}
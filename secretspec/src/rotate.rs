@@ -0,0 +1,59 @@
+//! Generation of random secret values for `secretspec rotate`
+
+use rand::seq::SliceRandom;
+
+const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const HEX: &[u8] = b"0123456789abcdef";
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Characters a generated secret value is drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Charset {
+    /// `A-Z`, `a-z` and `0-9`
+    #[default]
+    Alphanumeric,
+    /// Lowercase hexadecimal digits
+    Hex,
+    /// The standard base64 alphabet, without padding
+    Base64,
+}
+
+impl Charset {
+    fn alphabet(self) -> &'static [u8] {
+        match self {
+            Charset::Alphanumeric => ALPHANUMERIC,
+            Charset::Hex => HEX,
+            Charset::Base64 => BASE64,
+        }
+    }
+}
+
+/// Generates a random value of `length` characters from `charset`
+///
+/// Characters are drawn uniformly using the thread-local CSPRNG, which is
+/// seeded from the operating system.
+pub(crate) fn generate_value(length: usize, charset: Charset) -> String {
+    let alphabet = charset.alphabet();
+    let mut rng = rand::thread_rng();
+    (0..length)
+        .map(|_| *alphabet.choose(&mut rng).expect("alphabet is not empty") as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_values_use_charset() {
+        for charset in [Charset::Alphanumeric, Charset::Hex, Charset::Base64] {
+            let value = generate_value(64, charset);
+            assert_eq!(value.len(), 64);
+            assert!(value.bytes().all(|b| charset.alphabet().contains(&b)));
+        }
+        assert_ne!(
+            generate_value(32, Charset::Alphanumeric),
+            generate_value(32, Charset::Alphanumeric)
+        );
+    }
+}
@@ -1,16 +1,309 @@
 //! Core secrets management functionality
 
-use crate::config::{Config, GlobalConfig, Resolved};
-use crate::error::{Result, SecretSpecError};
+use crate::config::{Config, GlobalConfig, LocalConfig, Profile, Resolved, Secret};
+use crate::error::{ProviderError, Result, SecretSpecError};
+use crate::export::{self, ExportFormat};
 use crate::provider::Provider as ProviderTrait;
-use crate::validation::{ValidatedSecrets, ValidationErrors};
+use crate::provider::cache::CachingProvider;
+use crate::provider::chain::{self, ChainProvider};
+use crate::provider::misses::{KnownMisses, MissCachingProvider};
+use crate::provider::retry::{RetryConfig, RetryingProvider};
+use crate::provider::timing::{TimingProvider, Timings};
+use crate::reference;
+use crate::report::{self, CheckFormat, CheckRow, CheckStatus, CheckSummary};
+use crate::rotate::{self, Charset};
+use crate::transform;
+use crate::validation::{
+    SecretFilter, SecretValue, ValidatedSecrets, ValidationErrors, secret_value, unix_now,
+};
 use colored::Colorize;
-use std::collections::{HashMap, HashSet};
+use notify::{RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::env;
 use std::io::{self, IsTerminal, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for further file events before restarting in watch mode.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often to check whether the child exited while waiting for file events.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default number of secrets fetched at once from remote providers.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Name of the project manifest searched for by [`find_manifest`].
+const MANIFEST_NAME: &str = "secretspec.toml";
+
+/// Seconds in a day, for `rotate_after_days`
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Finds the `secretspec.toml` of the current project.
+///
+/// Like git and cargo, the search starts in the current directory and walks
+/// up through its parents, so secretspec can be used from any subdirectory of
+/// a project. Set `SECRETSPEC_NO_TRAVERSE=1` to only look in the current
+/// directory.
+///
+/// # Errors
+///
+/// Returns `NoManifest` if no `secretspec.toml` is found
+pub fn find_manifest() -> Result<PathBuf> {
+    let traverse = !matches!(
+        env::var("SECRETSPEC_NO_TRAVERSE").as_deref(),
+        Ok("1") | Ok("true")
+    );
+    find_manifest_from(&env::current_dir()?, traverse).ok_or(SecretSpecError::NoManifest)
+}
+
+/// Whether `dir`, a canonical path, is the current directory
+fn is_current_dir(dir: &Path) -> bool {
+    env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .is_ok_and(|cwd| cwd == dir)
+}
+
+/// Returns the first `secretspec.toml` in `start` or, if `traverse` is set,
+/// in one of its ancestors.
+pub(crate) fn find_manifest_from(start: &Path, traverse: bool) -> Option<PathBuf> {
+    let mut dirs = start.ancestors();
+    let candidates: Box<dyn Iterator<Item = &Path>> = if traverse {
+        Box::new(dirs)
+    } else {
+        Box::new(dirs.next().into_iter())
+    };
+    candidates
+        .map(|dir| dir.join(MANIFEST_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Reads a secret value piped to `secretspec set NAME -`
+///
+/// The whole input is the value, so multi-line values such as certificates
+/// are kept intact; only the single trailing newline added by `echo` and
+/// most generators is removed.
+pub(crate) fn read_piped_value(mut reader: impl io::Read) -> io::Result<String> {
+    let mut value = String::new();
+    reader.read_to_string(&mut value)?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
+
+/// Reads the value of a missing secret during `check`
+///
+/// Given the secret's name and profile, returns its value, or `None` if no
+/// one can be asked for it.
+pub(crate) type Prompt<'a> = &'a mut dyn FnMut(&str, &str) -> Result<Option<String>>;
+
+/// Prompts on the terminal for the value of `name` in `profile`, or returns
+/// `None` if stdin is not a terminal
+fn prompt_on_terminal(name: &str, profile: &str) -> Result<Option<String>> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    print!("Enter value for {} (profile: {}): ", name, profile);
+    io::stdout().flush()?;
+    Ok(Some(rpassword::read_password()?))
+}
+
+/// Returns the git work tree containing `path`, found by looking for a
+/// `.git` entry in its directory and their ancestors
+fn git_work_tree(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Fetches `names` from `backend`, returning the values in the same order.
+///
+/// Remote providers are queried from up to `max_concurrency` threads, as
+/// their lookups are independent and network-bound. Local providers are
+/// read sequentially.
+///
+/// # Errors
+///
+/// Returns the error of the first failed lookup, in the order of `names`
+pub(crate) fn fetch_secrets(
+    backend: &dyn ProviderTrait,
+    project: &str,
+    profile: &str,
+    names: &[String],
+    max_concurrency: usize,
+) -> Result<Vec<Option<String>>> {
+    let workers = max_concurrency.min(names.len());
+    if !backend.is_remote() || workers <= 1 {
+        return names
+            .iter()
+            .map(|name| backend.get(project, name, profile))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut fetched: Vec<(usize, Result<Option<String>>)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut fetched = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(name) = names.get(index) else {
+                            return fetched;
+                        };
+                        fetched.push((index, backend.get(project, name, profile)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    fetched.sort_by_key(|(index, _)| *index);
+    fetched.into_iter().map(|(_, value)| value).collect()
+}
+
+/// Reads `values` back from `backend` after they were written.
+///
+/// Catches providers that store values differently than given, e.g. by
+/// truncating or re-quoting them.
+///
+/// # Returns
+///
+/// The names whose stored value is missing or differs, sorted
+pub(crate) fn verify_written(
+    backend: &dyn ProviderTrait,
+    project: &str,
+    profile: &str,
+    values: &HashMap<String, String>,
+    max_concurrency: usize,
+) -> Result<Vec<String>> {
+    let mut names: Vec<String> = values.keys().cloned().collect();
+    names.sort();
+    let stored = fetch_secrets(backend, project, profile, &names, max_concurrency)?;
+    Ok(names
+        .into_iter()
+        .zip(stored)
+        .filter(|(name, stored)| stored.as_ref() != Some(&values[name]))
+        .map(|(name, _)| name)
+        .collect())
+}
+
+/// How much [`Secrets`] prints to the terminal while it works
+///
+/// Prompts, requested values, warnings and errors are printed at every
+/// level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// No progress or status messages, e.g. for scripts that only need the
+    /// exit code
+    Quiet,
+    /// Progress and status messages
+    #[default]
+    Normal,
+    /// Also reports which provider and profile were selected and why
+    Verbose,
+}
+
+/// Where a resolved provider or profile came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionSource {
+    /// Passed explicitly, e.g. with `--provider` or `--profile`
+    Argument,
+    /// Set with `Secrets::set_provider` or `Secrets::set_profile`
+    Builder,
+    /// The `SECRETSPEC_PROVIDER` or `SECRETSPEC_PROFILE` environment variable
+    Environment,
+    /// The `provider` of the selected profile in `secretspec.toml`
+    Profile,
+    /// The defaults in `.secretspec.local.toml`
+    LocalConfig,
+    /// The defaults in the global user configuration
+    GlobalConfig,
+    /// The built-in `default` profile
+    Default,
+}
+
+impl std::fmt::Display for ResolutionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self {
+            ResolutionSource::Argument => "argument",
+            ResolutionSource::Builder => "set programmatically",
+            ResolutionSource::Environment => "environment variable",
+            ResolutionSource::Profile => "profile in secretspec.toml",
+            ResolutionSource::LocalConfig => ".secretspec.local.toml",
+            ResolutionSource::GlobalConfig => "global configuration",
+            ResolutionSource::Default => "built-in default",
+        };
+        f.write_str(source)
+    }
+}
+
+/// An overview of the project, as returned by [`Secrets::summary`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSummary {
+    /// The project name
+    pub name: String,
+    /// The project description, if any
+    pub description: Option<String>,
+    /// The profile names, `default` first and the rest sorted
+    pub profiles: Vec<String>,
+    /// How many distinct secrets the profiles declare
+    pub secret_count: usize,
+    /// The provider and profile that would be used
+    pub resolution: ResolutionInfo,
+}
+
+/// A resolved provider specification or profile name and its source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// The provider specification or profile name
+    pub value: String,
+    /// Where the value came from
+    pub source: ResolutionSource,
+}
+
+/// The provider and profile that `Secrets` would use, as returned by
+/// [`Secrets::resolution`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionInfo {
+    /// The provider specification, or `None` if no provider is configured
+    pub provider: Option<Resolution>,
+    /// The profile name
+    pub profile: Resolution,
+}
+
+/// The outcome of checking one provider in use, as returned by
+/// [`Secrets::provider_health`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderHealth {
+    /// The provider specification
+    pub provider: String,
+    /// The project whose secrets are read from the provider
+    pub project: String,
+    /// `None` if the provider is usable, otherwise the error it reported
+    pub error: Option<String>,
+    /// How long the check took
+    pub elapsed: Duration,
+}
 
 /// The main entry point for the secretspec library
 ///
@@ -31,10 +324,51 @@ pub struct Secrets {
     config: Config,
     /// Optional global user configuration
     global_config: Option<GlobalConfig>,
+    /// Optional per-checkout overrides from `.secretspec.local.toml`
+    local_config: Option<LocalConfig>,
     /// The provider to use (if set via builder)
     provider: Option<String>,
     /// The profile to use (if set via builder)
     profile: Option<String>,
+    /// Restricts which secrets are validated and injected
+    filter: SecretFilter,
+    /// Log of provider latencies, if timing is enabled
+    timings: Option<Timings>,
+    /// Whether defaults fill in secrets missing from the provider
+    resolve_defaults: bool,
+    /// Whether `run` refuses to use default values
+    strict: bool,
+    /// Whether optional secrets with a default skip the provider
+    prefer_defaults: bool,
+    /// The directory `run` starts the command in, if not the current one
+    working_dir: Option<PathBuf>,
+    /// Whether `import` reads back the values it wrote
+    verify_import: bool,
+    /// Whether `check` leaves unset optional secrets out of its listing
+    hide_optional: bool,
+    /// Where `check` writes an audit snapshot, if anywhere
+    audit_out: Option<PathBuf>,
+    /// How `check` renders its listing
+    check_format: CheckFormat,
+    /// Keys the providers are known not to have, until the next write
+    known_misses: KnownMisses,
+    /// How many secrets are fetched at once from remote providers
+    max_concurrency: usize,
+    /// How much is printed to the terminal
+    verbosity: Verbosity,
+    /// Whether the selected provider and profile were already reported in
+    /// verbose mode
+    resolution_reported: AtomicBool,
+    /// The `secretspec.toml` this was loaded from, if any
+    manifest: Option<PathBuf>,
+    /// The unsupported revision `secretspec.toml` was allowed to have, which
+    /// sourced projects may have too
+    allowed_revision: Option<String>,
+    /// The manifests of the projects whose secrets are being read from this
+    /// one through `source`, to detect cycles
+    sourced_by: Vec<PathBuf>,
+    /// Warnings raised while loading `secretspec.toml`
+    warnings: Vec<String>,
 }
 
 impl Secrets {
@@ -60,15 +394,36 @@ impl Secrets {
         Self {
             config,
             global_config,
+            local_config: None,
             provider,
             profile,
+            filter: SecretFilter::default(),
+            timings: None,
+            resolve_defaults: true,
+            strict: false,
+            prefer_defaults: false,
+            working_dir: None,
+            verify_import: false,
+            hide_optional: false,
+            audit_out: None,
+            check_format: CheckFormat::default(),
+            known_misses: KnownMisses::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            verbosity: Verbosity::default(),
+            resolution_reported: AtomicBool::new(false),
+            manifest: None,
+            allowed_revision: None,
+            sourced_by: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
     /// Loads a `Secrets` using default configuration paths
     ///
     /// This method looks for:
-    /// - `secretspec.toml` in the current directory for project configuration
+    /// - `secretspec.toml` in the current directory or its parents for project
+    ///   configuration (see [`find_manifest`])
+    /// - An optional `.secretspec.local.toml` next to it with per-checkout defaults
     /// - User configuration in the system config directory
     ///
     /// # Returns
@@ -92,16 +447,98 @@ impl Secrets {
     /// spec.check().unwrap();
     /// ```
     pub fn load() -> Result<Self> {
-        let project_config = Config::try_from(Path::new("secretspec.toml"))?;
-        let global_config = GlobalConfig::load()?;
+        Self::load_with_global(GlobalConfig::load()?)
+    }
+
+    /// Loads a `Secrets` like [`Secrets::load`], with the given global
+    /// configuration instead of the one in the system config directory
+    ///
+    /// # Arguments
+    ///
+    /// * `global_config` - The global configuration to use, or `None` to
+    ///   ignore the user's global defaults
+    ///
+    /// # Returns
+    ///
+    /// A loaded `Secrets` instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No `secretspec.toml` file is found
+    /// - Configuration files are invalid
+    /// - The project revision is unsupported
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load_with_global(None).unwrap();
+    /// spec.set_provider("env");
+    /// spec.check().unwrap();
+    /// ```
+    pub fn load_with_global(global_config: Option<GlobalConfig>) -> Result<Self> {
+        Self::load_manifest(
+            &find_manifest()?,
+            global_config,
+            crate::config::allowed_revision_from_env().as_deref(),
+        )
+    }
+
+    /// Loads a `Secrets` from the `secretspec.toml` at `manifest`
+    ///
+    /// The configuration is validated up front, so a file without profiles
+    /// is reported as [`SecretSpecError::NoProfiles`] rather than as a
+    /// missing `default` profile later on. A file of revision
+    /// `allowed_revision` is read despite being unsupported, with a warning
+    /// in [`Secrets::warnings`].
+    pub(crate) fn load_manifest(
+        manifest: &Path,
+        global_config: Option<GlobalConfig>,
+        allowed_revision: Option<&str>,
+    ) -> Result<Self> {
+        let (project_config, report) = Config::from_path_with_report(manifest, allowed_revision)?;
+        if project_config.profiles.is_empty() {
+            return Err(SecretSpecError::NoProfiles);
+        }
+        project_config.validate()?;
+        let local_config = LocalConfig::load(manifest.parent().unwrap_or(Path::new(".")))?;
         Ok(Self {
             config: project_config,
             global_config,
+            local_config,
             provider: None,
             profile: None,
+            filter: SecretFilter::default(),
+            timings: None,
+            resolve_defaults: true,
+            strict: false,
+            prefer_defaults: false,
+            working_dir: None,
+            verify_import: false,
+            hide_optional: false,
+            audit_out: None,
+            check_format: CheckFormat::default(),
+            known_misses: KnownMisses::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            verbosity: Verbosity::default(),
+            resolution_reported: AtomicBool::new(false),
+            manifest: manifest.canonicalize().ok(),
+            allowed_revision: allowed_revision.map(str::to_string),
+            sourced_by: Vec::new(),
+            warnings: report.warnings,
         })
     }
 
+    /// Returns the warnings raised while loading `secretspec.toml`, such as
+    /// reading an allowed unsupported revision
+    ///
+    /// Loading doesn't print them, so callers decide how to show them.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     /// Sets the provider to use for secret operations
     ///
     /// This overrides the provider from global configuration.
@@ -144,161 +581,160 @@ impl Secrets {
         self.profile = Some(profile.into());
     }
 
-    /// Get a reference to the project configuration (for testing)
-    #[cfg(test)]
-    pub(crate) fn config(&self) -> &Config {
-        &self.config
+    /// Restricts validation to the secrets accepted by `filter`
+    ///
+    /// Affects `validate`, `check`, `run` and everything built on them.
+    /// Required secrets outside the filter are not reported as missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The secrets to consider
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::{SecretFilter, Secrets};
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_filter(SecretFilter::default().except(["STRIPE_KEY"]));
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_filter(&mut self, filter: SecretFilter) {
+        self.filter = filter;
     }
 
-    /// Get a reference to the global configuration (for testing)
-    #[cfg(test)]
-    pub(crate) fn global_config(&self) -> &Option<GlobalConfig> {
-        &self.global_config
+    /// Enables or disables measuring provider latencies
+    ///
+    /// When enabled, `check` and `run` print how long each secret lookup
+    /// took to stderr, slowest first and followed by the total.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to measure provider operations
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_timing(true);
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_timing(&mut self, enabled: bool) {
+        self.timings = enabled.then(Timings::default);
     }
 
-    /// Resolves the profile to use based on the provided value and configuration
+    /// Enables or disables applying default values during validation
     ///
-    /// Profile resolution order:
-    /// 1. Provided profile argument
-    /// 2. Profile set via set_profile()
-    /// 3. SECRETSPEC_PROFILE environment variable
-    /// 4. Global configuration default profile
-    /// 5. "default" profile
+    /// Defaults are applied unless disabled. When disabled, validation
+    /// reports the raw provider state: secrets missing from the provider are
+    /// listed in `ValidatedSecrets::would_default` instead of being filled in
+    /// with their defaults, which separates what is stored from what is
+    /// inherited from `secretspec.toml`.
     ///
     /// # Arguments
     ///
-    /// * `profile` - Optional profile name to use
+    /// * `enabled` - Whether to apply default values
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// The resolved profile name
-    pub(crate) fn resolve_profile(&self, profile: Option<&str>) -> String {
-        profile
-            .map(|p| p.to_string())
-            .or_else(|| self.profile.clone())
-            .or_else(|| env::var("SECRETSPEC_PROFILE").ok())
-            .or_else(|| {
-                self.global_config
-                    .as_ref()
-                    .and_then(|gc| gc.defaults.profile.clone())
-            })
-            .unwrap_or_else(|| "default".to_string())
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_resolve_defaults(false);
+    /// let status = spec.check_result(None, None).unwrap();
+    /// for (name, default) in &status.would_default {
+    ///     println!("{} is not stored; the default {:?} applies", name, default);
+    /// }
+    /// ```
+    pub fn set_resolve_defaults(&mut self, enabled: bool) {
+        self.resolve_defaults = enabled;
     }
 
-    /// Resolves the configuration for a specific secret
+    /// Makes `run`, `run_watch` and `write_env_file` fail when a secret
+    /// would use its default value
     ///
-    /// This method looks for the secret in the specified profile, falling back
-    /// to the default profile if not found. If the secret exists in both profiles,
-    /// fields are merged with the current profile taking precedence.
+    /// Defaults are a development convenience; in production every secret
+    /// should be provisioned explicitly in the provider. Disabled by default.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the secret
-    /// * `profile` - Optional profile to search in (if None, uses resolved profile)
+    /// * `enabled` - Whether to reject default values
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// The secret configuration if found (may be merged from multiple profiles)
-    pub(crate) fn resolve_secret_config(
-        &self,
-        name: &str,
-        profile: Option<&str>,
-    ) -> Option<crate::config::Secret> {
-        let profile_name = self.resolve_profile(profile);
-
-        let current_secret = self
-            .config
-            .profiles
-            .get(&profile_name)
-            .and_then(|profile_config| profile_config.secrets.get(name));
-
-        let default_secret = if profile_name != "default" {
-            self.config
-                .profiles
-                .get("default")
-                .and_then(|default_profile| default_profile.secrets.get(name))
-        } else {
-            None
-        };
-
-        match (current_secret, default_secret) {
-            (Some(current), Some(default)) => {
-                // Merge: current profile takes precedence
-                Some(crate::config::Secret {
-                    description: current
-                        .description
-                        .clone()
-                        .or_else(|| default.description.clone()),
-                    required: current.required,
-                    default: current.default.clone(),
-                })
-            }
-            (Some(secret), None) | (None, Some(secret)) => Some(secret.clone()),
-            (None, None) => None,
-        }
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_strict(true);
+    /// spec.run(vec!["npm".to_string(), "start".to_string()]).unwrap();
+    /// ```
+    pub fn set_strict(&mut self, enabled: bool) {
+        self.strict = enabled;
     }
 
-    /// Gets the provider instance to use for secret operations
+    /// Uses the defaults of optional secrets without reading the provider
     ///
-    /// Provider resolution order:
-    /// 1. Provided provider argument
-    /// 2. Provider set via builder
-    /// 3. Global configuration default provider
-    /// 4. Error if no provider is configured
+    /// Secrets that are optional and have a default are not looked up, so
+    /// where every secret has a default, `run` and `validate` don't call the
+    /// provider's `get` at all. A value stored in the provider is then
+    /// ignored in favour of the default. Required secrets are always read,
+    /// and defaults that reference a secret without a value fall back to the
+    /// provider. Disabled by default, so stored values override defaults.
     ///
     /// # Arguments
     ///
-    /// * `provider_arg` - Optional provider specification (name or URI)
-    ///
-    /// # Returns
+    /// * `enabled` - Whether to skip the provider for defaulted optional secrets
     ///
-    /// A boxed provider instance
+    /// # Example
     ///
-    /// # Errors
+    /// ```no_run
+    /// use secretspec::Secrets;
     ///
-    /// Returns an error if:
-    /// - No provider is configured
-    /// - The specified provider is not found
-    pub(crate) fn get_provider(
-        &self,
-        provider_arg: Option<String>,
-    ) -> Result<Box<dyn ProviderTrait>> {
-        let provider_spec = provider_arg
-            .or_else(|| env::var("SECRETSPEC_PROVIDER").ok())
-            .or_else(|| self.provider.clone())
-            .or_else(|| {
-                self.global_config
-                    .as_ref()
-                    .and_then(|gc| gc.defaults.provider.clone())
-            })
-            .ok_or(SecretSpecError::NoProviderConfigured)?;
-
-        let provider = Box::<dyn ProviderTrait>::try_from(provider_spec)?;
-
-        Ok(provider)
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_prefer_defaults(true);
+    /// spec.run(vec!["npm".to_string(), "start".to_string()]).unwrap();
+    /// ```
+    pub fn set_prefer_defaults(&mut self, enabled: bool) {
+        self.prefer_defaults = enabled;
     }
 
-    /// Sets a secret value in the provider
+    /// Sets the directory `run` and `run_watch` start the command in
     ///
-    /// If no value is provided, the user will be prompted to enter it securely.
+    /// The specification and relative provider paths such as
+    /// `dotenv://.env` are still resolved from the current directory; only
+    /// the command runs elsewhere. By default the command inherits the
+    /// current directory.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the secret to set
-    /// * `value` - Optional value to set (prompts if None)
-    /// * `provider_arg` - Optional provider to use
-    /// * `profile` - Optional profile to use
+    /// * `dir` - The working directory for the command
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// `Ok(())` if the secret was successfully set
+    /// ```no_run
+    /// use secretspec::Secrets;
     ///
-    /// # Errors
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_working_dir("frontend");
+    /// spec.run(vec!["npm".to_string(), "start".to_string()]).unwrap();
+    /// ```
+    pub fn set_working_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.working_dir = Some(dir.into());
+    }
+
+    /// Makes `import` read back every value it wrote and fail if any differs
     ///
-    /// Returns an error if:
-    /// - The secret is not defined in the specification
-    /// - The provider doesn't support setting values
-    /// - The storage operation fails
+    /// Catches providers that silently change values, such as truncating
+    /// them or mangling quotes, at the cost of a second read per imported
+    /// secret. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to verify imported values
     ///
     /// # Example
     ///
@@ -306,81 +742,1205 @@ impl Secrets {
     /// use secretspec::Secrets;
     ///
     /// let mut spec = Secrets::load().unwrap();
-    /// spec.set("DATABASE_URL", Some("postgres://localhost".to_string())).unwrap();
+    /// spec.set_verify_import(true);
+    /// spec.import("dotenv://.env.production").unwrap();
     /// ```
-    pub fn set(&self, name: &str, value: Option<String>) -> Result<()> {
-        // Check if the secret exists in the spec
-        let profile_name = self.resolve_profile(None);
-        let profile_config = self.config.profiles.get(&profile_name).ok_or_else(|| {
-            SecretSpecError::SecretNotFound(format!(
-                "Profile '{}' is not defined in secretspec.toml. Available profiles: {}",
-                profile_name,
-                self.config
-                    .profiles
-                    .keys()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ))
-        })?;
+    pub fn set_verify_import(&mut self, enabled: bool) {
+        self.verify_import = enabled;
+    }
 
-        // Check if the secret exists in the profile or is inherited from default
-        if self.resolve_secret_config(name, None).is_none() {
-            // Collect available secrets from both current profile and default
-            let mut available_secrets = profile_config.secrets.keys().cloned().collect::<Vec<_>>();
-            if profile_name != "default" {
-                if let Some(default_profile) = self.config.profiles.get("default") {
-                    for key in default_profile.secrets.keys() {
-                        if !available_secrets.contains(key) {
-                            available_secrets.push(key.clone());
-                        }
-                    }
-                }
+    /// Returns whether `secret` takes its default without reading the provider
+    ///
+    /// Only applies when defaults are resolved, since reporting which
+    /// defaults would apply needs the provider's values.
+    fn prefers_default(&self, secret: &crate::config::Secret) -> bool {
+        self.prefer_defaults
+            && self.resolve_defaults
+            && !secret.required
+            && secret.default.is_some()
+    }
+
+    /// Makes `check` leave unset optional secrets out of its listing
+    ///
+    /// Present, defaulted, missing required and missing recommended secrets
+    /// are still listed, and the summary counts the hidden ones. Disabled by
+    /// default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to hide unset optional secrets
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_hide_optional(true);
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_hide_optional(&mut self, enabled: bool) {
+        self.hide_optional = enabled;
+    }
+
+    /// Makes `check` write an audit snapshot of the secrets to `path`
+    ///
+    /// The snapshot is the JSON form of [`AuditSnapshot`](crate::AuditSnapshot):
+    /// the status of every checked secret before any prompting, without
+    /// values. It is written even if required secrets are missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write the snapshot to
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_audit_out("audit.json");
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_audit_out(&mut self, path: impl Into<PathBuf>) {
+        self.audit_out = Some(path.into());
+    }
+
+    /// Sets how `check` renders its listing
+    ///
+    /// [`CheckFormat::Plain`] never emits colors, so its output can be
+    /// written to logs or parsed; the other formats are colored on
+    /// terminals. Defaults to [`CheckFormat::Pretty`].
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The listing format
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::{CheckFormat, Secrets};
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_check_format(CheckFormat::Plain);
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_check_format(&mut self, format: CheckFormat) {
+        self.check_format = format;
+    }
+
+    /// Sets how many secrets are fetched at once from remote providers
+    ///
+    /// Lookups against remote providers such as 1Password are independent
+    /// and dominated by network latency, so they run on up to this many
+    /// threads. Local providers are always read sequentially. Defaults to
+    /// [`DEFAULT_MAX_CONCURRENCY`]; `1` disables concurrent reads.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrency` - The maximum number of concurrent lookups
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_max_concurrency(4);
+    /// spec.validate().unwrap();
+    /// ```
+    pub fn set_max_concurrency(&mut self, max_concurrency: usize) {
+        self.max_concurrency = max_concurrency.max(1);
+    }
+
+    /// Sets how much is printed to the terminal
+    ///
+    /// With [`Verbosity::Quiet`], `check`, `set` and `import` only print
+    /// prompts, warnings and errors; with [`Verbosity::Verbose`], the
+    /// selected provider and profile and where they came from are printed to
+    /// stderr before the provider is first used.
+    ///
+    /// # Arguments
+    ///
+    /// * `verbosity` - The output level
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::{Secrets, Verbosity};
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_verbosity(Verbosity::Quiet);
+    /// spec.check().unwrap();
+    /// ```
+    pub fn set_verbosity(&mut self, verbosity: Verbosity) {
+        self.verbosity = verbosity;
+    }
+
+    /// Returns whether progress and status messages are suppressed
+    fn quiet(&self) -> bool {
+        self.verbosity == Verbosity::Quiet
+    }
+
+    /// Prints the provider latencies measured since the last report to stderr
+    fn print_timings(&self) {
+        if let Some(timings) = &self.timings
+            && let Some(report) = timings.report()
+        {
+            eprint!("\n{}", report);
+        }
+    }
+
+    /// Sets the per-checkout overrides (for testing)
+    #[cfg(test)]
+    pub(crate) fn set_local_config(&mut self, local_config: Option<LocalConfig>) {
+        self.local_config = local_config;
+    }
+
+    /// Get a reference to the project configuration (for testing)
+    #[cfg(test)]
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Get a reference to the global configuration (for testing)
+    #[cfg(test)]
+    pub(crate) fn global_config(&self) -> &Option<GlobalConfig> {
+        &self.global_config
+    }
+
+    /// Resolves the profile to use based on the provided value and configuration
+    ///
+    /// Profile resolution order:
+    /// 1. Provided profile argument
+    /// 2. Profile set via set_profile()
+    /// 3. SECRETSPEC_PROFILE environment variable
+    /// 4. `.secretspec.local.toml` default profile
+    /// 5. Global configuration default profile
+    /// 6. "default" profile
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - Optional profile name to use
+    ///
+    /// # Returns
+    ///
+    /// The resolved profile name
+    pub(crate) fn resolve_profile(&self, profile: Option<&str>) -> String {
+        self.resolve_profile_with_source(profile).value
+    }
+
+    /// Resolves the profile like [`Secrets::resolve_profile`], recording where
+    /// the value came from
+    fn resolve_profile_with_source(&self, profile: Option<&str>) -> Resolution {
+        let local = self
+            .local_config
+            .as_ref()
+            .and_then(|lc| lc.defaults.profile.clone());
+        let global = self
+            .global_config
+            .as_ref()
+            .and_then(|gc| gc.defaults.profile.clone());
+
+        [
+            (profile.map(str::to_string), ResolutionSource::Argument),
+            (self.profile.clone(), ResolutionSource::Builder),
+            (
+                env::var("SECRETSPEC_PROFILE").ok(),
+                ResolutionSource::Environment,
+            ),
+            (local, ResolutionSource::LocalConfig),
+            (global, ResolutionSource::GlobalConfig),
+        ]
+        .into_iter()
+        .find_map(|(value, source)| value.map(|value| Resolution { value, source }))
+        .unwrap_or_else(|| Resolution {
+            value: "default".to_string(),
+            source: ResolutionSource::Default,
+        })
+    }
+
+    /// Resolves the provider specification without creating the provider
+    ///
+    /// Provider resolution order:
+    /// 1. Provided provider argument
+    /// 2. Provider set via set_provider()
+    /// 3. SECRETSPEC_PROVIDER environment variable
+    /// 4. The `provider` of `profile`, or else of `default`, in `secretspec.toml`
+    /// 5. `.secretspec.local.toml` default provider
+    /// 6. Global configuration default provider
+    ///
+    /// # Returns
+    ///
+    /// The provider specification and its source, or `None` if no provider
+    /// is configured
+    fn resolve_provider_spec(
+        &self,
+        provider_arg: Option<String>,
+        profile: &str,
+    ) -> Option<Resolution> {
+        // Profiles inherit the provider of `default` like its secrets
+        let from_profile = [profile, "default"].into_iter().find_map(|name| {
+            self.config
+                .profiles
+                .get(name)
+                .and_then(|p| p.provider.clone())
+        });
+        let local = self
+            .local_config
+            .as_ref()
+            .and_then(|lc| lc.defaults.provider.clone());
+        let global = self
+            .global_config
+            .as_ref()
+            .and_then(|gc| gc.defaults.provider.clone());
+
+        [
+            (provider_arg, ResolutionSource::Argument),
+            (self.provider.clone(), ResolutionSource::Builder),
+            (
+                env::var("SECRETSPEC_PROVIDER").ok(),
+                ResolutionSource::Environment,
+            ),
+            (from_profile, ResolutionSource::Profile),
+            (local, ResolutionSource::LocalConfig),
+            (global, ResolutionSource::GlobalConfig),
+        ]
+        .into_iter()
+        .find_map(|(value, source)| value.map(|value| Resolution { value, source }))
+    }
+
+    /// Reports which provider and profile would be used, without contacting
+    /// the provider
+    ///
+    /// This follows the same precedence as every other operation, so it can
+    /// be used to check the effect of flags, environment variables and
+    /// configuration files before running anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_arg` - Optional provider, as passed to e.g. `check_result`
+    /// * `profile_arg` - Optional profile, as passed to e.g. `check_result`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let info = spec.resolution(None, None);
+    /// println!("profile {} (from {})", info.profile.value, info.profile.source);
+    /// ```
+    pub fn resolution(
+        &self,
+        provider_arg: Option<String>,
+        profile_arg: Option<String>,
+    ) -> ResolutionInfo {
+        let profile = self.resolve_profile_with_source(profile_arg.as_deref());
+        ResolutionInfo {
+            provider: self.resolve_provider_spec(provider_arg, &profile.value),
+            profile,
+        }
+    }
+
+    /// Summarizes the project: its metadata, profiles, number of secrets
+    /// and the provider and profile that would be used
+    ///
+    /// Nothing is read from the provider.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let summary = spec.summary();
+    /// println!("{}: {} secrets", summary.name, summary.secret_count);
+    /// ```
+    pub fn summary(&self) -> ProjectSummary {
+        let mut profiles: Vec<String> = self.config.profiles.keys().cloned().collect();
+        profiles.sort_by(|a, b| (a != "default", a).cmp(&(b != "default", b)));
+        let secrets: HashSet<&String> = self
+            .config
+            .profiles
+            .values()
+            .flat_map(|profile| profile.secrets.keys())
+            .collect();
+
+        ProjectSummary {
+            name: self.config.project.name.clone(),
+            description: self.config.project.description.clone(),
+            profiles,
+            secret_count: secrets.len(),
+            resolution: self.resolution(None, None),
+        }
+    }
+
+    /// Checks every provider the selected profile reads secrets from
+    ///
+    /// These are the members of the project's provider, or the provider
+    /// itself if it is not a chain, and the providers of the projects its
+    /// secrets are read from with `source`. Each distinct provider is
+    /// checked with [`Provider::health_check`](ProviderTrait::health_check)
+    /// on its own thread, so slow backends are waited for only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_arg` - Optional provider to use instead of the configured one
+    /// * `profile_arg` - Optional profile to use instead of the configured one
+    ///
+    /// # Returns
+    ///
+    /// One entry per provider, this project's first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no provider is configured, the profile does not
+    /// exist or the `secretspec.toml` of a sourced project cannot be read.
+    /// A provider failing its check is reported in its entry instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// for health in spec.provider_health(None, None).unwrap() {
+    ///     println!("{}: {}", health.provider, health.error.as_deref().unwrap_or("ok"));
+    /// }
+    /// ```
+    pub fn provider_health(
+        &self,
+        provider_arg: Option<String>,
+        profile_arg: Option<String>,
+    ) -> Result<Vec<ProviderHealth>> {
+        let profile_name = self.resolve_profile(profile_arg.as_deref());
+        let mut targets: Vec<(String, String, Option<PathBuf>)> = Vec::new();
+        let mut add_targets = |project: &Self, provider_spec: String, dir: Option<PathBuf>| {
+            let members = match chain::members(&provider_spec) {
+                Some(members) => members.into_iter().map(str::to_string).collect(),
+                None => vec![provider_spec],
+            };
+            for member in members {
+                let target = (project.config.project.name.clone(), member, dir.clone());
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        };
+
+        let provider_spec = self
+            .resolve_provider_spec(provider_arg, &profile_name)
+            .ok_or(SecretSpecError::NoProviderConfigured)?
+            .value;
+        add_targets(self, provider_spec, None);
+
+        let mut names: Vec<String> = self.declared_names(&profile_name)?.into_iter().collect();
+        names.sort();
+        for name in names {
+            let Some(secret_config) = self.resolve_secret_config(&name, Some(&profile_name)) else {
+                continue;
+            };
+            if secret_config.source.is_none() {
+                continue;
+            }
+            let (manifest, _) = self.source_manifest(&name, &secret_config)?;
+            let other = self.load_source(&manifest)?;
+            let provider_spec = other
+                .resolve_provider_spec(None, &profile_name)
+                .ok_or(SecretSpecError::NoProviderConfigured)?
+                .value;
+            add_targets(
+                &other,
+                provider_spec,
+                manifest.parent().map(Path::to_path_buf),
+            );
+        }
+
+        let check = |provider_spec: &str, dir: Option<&Path>| -> Result<()> {
+            let mut provider = Box::<dyn ProviderTrait>::try_from(provider_spec)?;
+            if let Some(dir) = dir {
+                provider.resolve_paths_from(dir);
+            }
+            provider.health_check()
+        };
+        Ok(thread::scope(|scope| {
+            let handles: Vec<_> = targets
+                .iter()
+                .map(|(project, provider_spec, dir)| {
+                    scope.spawn(move || {
+                        let started = Instant::now();
+                        let result = check(provider_spec, dir.as_deref());
+                        ProviderHealth {
+                            provider: provider_spec.clone(),
+                            project: project.clone(),
+                            error: result.err().map(|e| e.to_string()),
+                            elapsed: started.elapsed(),
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        }))
+    }
+
+    /// Resolves the configuration for a specific secret
+    ///
+    /// This method looks for the secret in the specified profile, falling back
+    /// to the default profile if not found. If the secret exists in both profiles,
+    /// fields are merged with the current profile taking precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret
+    /// * `profile` - Optional profile to search in (if None, uses resolved profile)
+    ///
+    /// # Returns
+    ///
+    /// The secret configuration if found (may be merged from multiple profiles)
+    pub(crate) fn resolve_secret_config(
+        &self,
+        name: &str,
+        profile: Option<&str>,
+    ) -> Option<crate::config::Secret> {
+        let profile_name = self.resolve_profile(profile);
+
+        let current_secret = self
+            .config
+            .profiles
+            .get(&profile_name)
+            .and_then(|profile_config| profile_config.secrets.get(name));
+
+        let default_secret = if profile_name != "default" {
+            self.config
+                .profiles
+                .get("default")
+                .and_then(|default_profile| default_profile.secrets.get(name))
+        } else {
+            None
+        };
+
+        match (current_secret, default_secret) {
+            (Some(current), Some(default)) => {
+                // Merge: current profile takes precedence
+                Some(crate::config::Secret {
+                    description: current
+                        .description
+                        .clone()
+                        .or_else(|| default.description.clone()),
+                    help_url: current
+                        .help_url
+                        .clone()
+                        .or_else(|| default.help_url.clone()),
+                    required: current.required,
+                    recommended: current.recommended,
+                    default: current.default.clone(),
+                    required_if: current.required_if.clone(),
+                    min_length: current.min_length.or(default.min_length),
+                    max_length: current.max_length.or(default.max_length),
+                    allowed: if current.allowed.is_empty() {
+                        default.allowed.clone()
+                    } else {
+                        current.allowed.clone()
+                    },
+                    disallow_empty: current.disallow_empty.or(default.disallow_empty),
+                    transform: if current.transform.is_empty() {
+                        default.transform.clone()
+                    } else {
+                        current.transform.clone()
+                    },
+                    aliases: if current.aliases.is_empty() {
+                        default.aliases.clone()
+                    } else {
+                        current.aliases.clone()
+                    },
+                    group: current.group.clone().or_else(|| default.group.clone()),
+                    source: current.source.clone().or_else(|| default.source.clone()),
+                    rotate_after_days: current.rotate_after_days.or(default.rotate_after_days),
+                })
+            }
+            (Some(secret), None) | (None, Some(secret)) => Some(secret.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the profile whose definition of `name` applies in
+    /// `profile_name`: the profile itself if it declares the secret,
+    /// otherwise the default profile it is inherited from
+    fn secret_config_profile(&self, name: &str, profile_name: &str) -> Option<String> {
+        [profile_name, "default"]
+            .into_iter()
+            .find(|candidate| {
+                self.config
+                    .profiles
+                    .get(*candidate)
+                    .is_some_and(|profile| profile.secrets.contains_key(name))
+            })
+            .map(str::to_string)
+    }
+
+    /// Collects the `${secret:NAME}` references in the defaults of `names`
+    /// and of every secret they reference, transitively
+    ///
+    /// # Returns
+    ///
+    /// A map from each of these secrets to the names its default references
+    ///
+    /// # Errors
+    ///
+    /// Returns `UndeclaredSecret` if a default references an undeclared secret
+    fn default_references(
+        &self,
+        names: &[String],
+        profile_name: &str,
+    ) -> Result<HashMap<String, Vec<String>>> {
+        let mut references = HashMap::new();
+        let mut pending = names.to_vec();
+        while let Some(name) = pending.pop() {
+            if references.contains_key(&name) {
+                continue;
+            }
+            let secret_config = self
+                .resolve_secret_config(&name, Some(profile_name))
+                .ok_or_else(|| self.undeclared_secret(&name, profile_name))?;
+            let referenced: Vec<String> = secret_config
+                .default
+                .as_deref()
+                .map(reference::references)
+                .unwrap_or_default()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            pending.extend(referenced.iter().cloned());
+            references.insert(name, referenced);
+        }
+        Ok(references)
+    }
+
+    /// Gets the provider instance to use for secret operations
+    ///
+    /// Provider resolution order:
+    /// 1. Provided provider argument
+    /// 2. Provider set via builder
+    /// 3. SECRETSPEC_PROVIDER environment variable
+    /// 4. The `provider` of the profile in `secretspec.toml`
+    /// 5. `.secretspec.local.toml` default provider
+    /// 6. Global configuration default provider
+    /// 7. Error if no provider is configured
+    ///
+    /// Remote providers are wrapped in a [`RetryingProvider`] configured from
+    /// the global configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_arg` - Optional provider specification (name or URI)
+    /// * `profile_arg` - Optional profile whose provider applies, instead of
+    ///   the configured one
+    ///
+    /// # Returns
+    ///
+    /// A boxed provider instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No provider is configured
+    /// - The specified provider is not found
+    pub(crate) fn get_provider(
+        &self,
+        provider_arg: Option<String>,
+        profile_arg: Option<&str>,
+    ) -> Result<Box<dyn ProviderTrait>> {
+        let profile = self.resolve_profile_with_source(profile_arg);
+        let resolution = self
+            .resolve_provider_spec(provider_arg, &profile.value)
+            .ok_or(SecretSpecError::NoProviderConfigured)?;
+        if self.verbosity == Verbosity::Verbose
+            && !self.resolution_reported.swap(true, Ordering::Relaxed)
+        {
+            eprintln!(
+                "Provider: {} (from {})\nProfile: {} (from {})",
+                resolution.value, resolution.source, profile.value, profile.source
+            );
+        }
+        let provider_spec = resolution.value;
+
+        // Each provider of a chain is retried and cached on its own, so a
+        // chain never caches values read from the environment
+        let provider = match chain::members(&provider_spec) {
+            Some(members) => Box::new(ChainProvider::new(
+                members
+                    .into_iter()
+                    .map(|member| self.decorated_provider(member))
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            None => self.decorated_provider(&provider_spec)?,
+        };
+
+        Ok(self.with_timing(provider))
+    }
+
+    /// Creates the provider for `provider_spec`, adding retries for remote
+    /// providers, the on-disk cache if enabled and the cache of misses
+    fn decorated_provider(&self, provider_spec: &str) -> Result<Box<dyn ProviderTrait>> {
+        let mut provider = Box::<dyn ProviderTrait>::try_from(provider_spec)?;
+
+        // Relative files are next to secretspec.toml, which may be in a
+        // parent of the current directory or, for a project read through
+        // `source`, in another project
+        if let Some(dir) = self.manifest.as_deref().and_then(Path::parent)
+            && (!self.sourced_by.is_empty() || !is_current_dir(dir))
+        {
+            provider.resolve_paths_from(dir);
+        }
+
+        // Retry transient failures of network-backed providers
+        let provider: Box<dyn ProviderTrait> = if provider.is_remote() {
+            let retry_config = self
+                .global_config
+                .as_ref()
+                .map(|gc| RetryConfig::from(&gc.defaults))
+                .unwrap_or_default();
+            Box::new(RetryingProvider::new(provider, retry_config))
+        } else {
+            provider
+        };
+
+        // Values from the environment and encrypted entries are never
+        // written to disk
+        let provider: Box<dyn ProviderTrait> = match self.cache_ttl() {
+            Some(ttl) if provider.allows_caching() => match CachingProvider::default_path() {
+                Some(path) => Box::new(CachingProvider::new(provider, provider_spec, ttl, path)),
+                None => provider,
+            },
+            _ => provider,
+        };
+
+        // The environment and watched files can change while a command
+        // runs, so their misses are looked up again
+        Ok(
+            if provider.name() == "env" || !provider.watch_paths().is_empty() {
+                provider
+            } else {
+                Box::new(MissCachingProvider::new(
+                    provider,
+                    provider_spec,
+                    self.known_misses.clone(),
+                ))
+            },
+        )
+    }
+
+    /// Returns how long provider values may be cached on disk
+    ///
+    /// `SECRETSPEC_CACHE_TTL` takes precedence over `cache_ttl` in the global
+    /// configuration. Both are in seconds; caching is disabled if neither is
+    /// set or the TTL is 0.
+    fn cache_ttl(&self) -> Option<Duration> {
+        let seconds = match env::var("SECRETSPEC_CACHE_TTL") {
+            Ok(value) => value.trim().parse().ok(),
+            Err(_) => self
+                .global_config
+                .as_ref()
+                .and_then(|gc| gc.defaults.cache_ttl),
+        };
+        seconds
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Wraps `provider` to record its latencies if timing is enabled
+    fn with_timing(&self, provider: Box<dyn ProviderTrait>) -> Box<dyn ProviderTrait> {
+        match &self.timings {
+            Some(timings) => Box::new(TimingProvider::new(provider, timings.clone())),
+            None => provider,
+        }
+    }
+
+    /// Looks up the configuration of a profile that is about to be used
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProfileNotFound` if the profile is not defined, or
+    /// `InvalidProfile` listing the selectable profiles if it is abstract
+    fn selected_profile(&self, profile: &str) -> Result<&Profile> {
+        let Some(profile_config) = self.config.profiles.get(profile) else {
+            let mut available: Vec<String> = self.config.profiles.keys().cloned().collect();
+            available.sort();
+            return Err(SecretSpecError::ProfileNotFound(
+                profile.to_string(),
+                available,
+            ));
+        };
+        if !profile_config.is_abstract {
+            return Ok(profile_config);
+        }
+
+        let mut selectable: Vec<&str> = self
+            .config
+            .profiles
+            .iter()
+            .filter(|(_, p)| !p.is_abstract)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        selectable.sort();
+        Err(SecretSpecError::InvalidProfile(format!(
+            "Profile '{}' is abstract and only provides secrets to other profiles. Select one of: {}",
+            profile,
+            selectable.join(", ")
+        )))
+    }
+
+    /// Ensures `name` is declared in the profile or inherited from `default`
+    ///
+    /// # Returns
+    ///
+    /// The resolved profile name
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProfileNotFound` or `UndeclaredSecret` listing the available
+    /// profiles or secrets
+    fn require_defined(&self, name: &str, profile: Option<&str>) -> Result<String> {
+        // Check if the secret exists in the spec
+        let profile_name = self.resolve_profile(profile);
+        self.selected_profile(&profile_name)?;
+
+        // Check if the secret exists in the profile or is inherited from default
+        if self.resolve_secret_config(name, profile).is_none() {
+            return Err(self.undeclared_secret(name, &profile_name));
+        }
+
+        Ok(profile_name)
+    }
+
+    /// Returns the provider of `profile`, failing with `ReadOnly` for
+    /// `operation` if it does not support setting values
+    fn writable_provider(
+        &self,
+        provider_arg: Option<String>,
+        profile: Option<&str>,
+        operation: &'static str,
+    ) -> Result<Box<dyn ProviderTrait>> {
+        let backend = self.get_provider(provider_arg, profile)?;
+        if !backend.allows_set() {
+            return Err(SecretSpecError::Provider(ProviderError::ReadOnly {
+                provider: backend.name().to_string(),
+                operation,
+            }));
+        }
+        Ok(backend)
+    }
+
+    /// Returns a warning if `profile` is a non-default profile but the
+    /// provider reads the same values for every profile
+    fn profile_warning(backend: &dyn ProviderTrait, profile: &str) -> Option<String> {
+        (profile != "default" && !backend.supports_profiles()).then(|| {
+            format!(
+                "Provider '{}' does not support profiles; profile '{}' uses the same values as every other profile",
+                backend.name(),
+                profile
+            )
+        })
+    }
+
+    /// Returns how many days ago a secret was set, if that is longer ago
+    /// than its `rotate_after_days`
+    ///
+    /// Secrets read from another project through `source` rotate there.
+    /// Providers that can't tell when the secret was set, or fail to, never
+    /// report it as due.
+    fn days_since_due(
+        &self,
+        backend: &dyn ProviderTrait,
+        name: &str,
+        secret_config: &Secret,
+        profile: &str,
+    ) -> Option<u64> {
+        let rotate_after_days = secret_config.rotate_after_days?;
+        if secret_config.source.is_some() {
+            return None;
+        }
+        let set_at = backend
+            .set_at(&self.config.project.name, name, profile)
+            .ok()
+            .flatten()?;
+        let age = unix_now().saturating_sub(set_at);
+        (age > rotate_after_days.saturating_mul(SECONDS_PER_DAY)).then_some(age / SECONDS_PER_DAY)
+    }
+
+    /// Prints the warning from [`Secrets::profile_warning`] to stderr
+    fn warn_if_profiles_unsupported(backend: &dyn ProviderTrait, profile: &str) {
+        if let Some(warning) = Self::profile_warning(backend, profile) {
+            eprintln!("{} {}", "Warning:".yellow(), warning);
+        }
+    }
+
+    /// Records the current time as when the secrets in `names` were set,
+    /// for those with a `rotate_after_days`
+    ///
+    /// The values were already written, so a provider failing to record the
+    /// time only prints a warning.
+    fn record_set_at<'a>(
+        &self,
+        backend: &dyn ProviderTrait,
+        names: impl IntoIterator<Item = &'a str>,
+        profile: &str,
+    ) {
+        let now = unix_now();
+        for name in names {
+            let rotates = self
+                .resolve_secret_config(name, Some(profile))
+                .is_some_and(|secret_config| secret_config.rotate_after_days.is_some());
+            if !rotates {
+                continue;
+            }
+            if let Err(e) = backend.record_set_at(&self.config.project.name, name, profile, now) {
+                eprintln!(
+                    "{} Could not record when '{}' was set: {}",
+                    "Warning:".yellow(),
+                    name,
+                    e
+                );
             }
-            available_secrets.sort();
+        }
+    }
 
-            return Err(SecretSpecError::SecretNotFound(format!(
-                "Secret '{}' is not defined in profile '{}'. Available secrets: {}",
+    /// Sets a secret value in the provider
+    ///
+    /// If no value is provided, the user will be prompted to enter it securely.
+    /// A value of `-` reads the value from stdin instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret to set
+    /// * `value` - Optional value to set (prompts if None, reads stdin if `-`)
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the secret was successfully set
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The secret is not defined in the specification
+    /// - The provider doesn't support setting values
+    /// - The storage operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set("DATABASE_URL", Some("postgres://localhost".to_string())).unwrap();
+    /// ```
+    pub fn set(&self, name: &str, value: Option<String>) -> Result<()> {
+        self.set_in_profiles(name, value, &[self.resolve_profile(None)])
+    }
+
+    /// Sets the same secret value in several profiles
+    ///
+    /// The secret must be defined in every profile, which is checked before
+    /// anything is written. The value is then written to each profile in
+    /// turn; a failed write is reported and the remaining profiles are still
+    /// attempted. If no value is provided, the user is prompted once; a value
+    /// of `-` reads the value from stdin.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret to set
+    /// * `value` - Optional value to set (prompts if None, reads stdin if `-`)
+    /// * `profiles` - The profiles to set the value in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A profile does not exist or does not define the secret
+    /// - The provider doesn't support setting values
+    /// - The storage operation fails for any profile
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let profiles = ["staging".to_string(), "production".to_string()];
+    /// spec.set_in_profiles("API_KEY", Some("sk-123".to_string()), &profiles)
+    ///     .unwrap();
+    /// ```
+    pub fn set_in_profiles(
+        &self,
+        name: &str,
+        value: Option<String>,
+        profiles: &[String],
+    ) -> Result<()> {
+        for profile in profiles {
+            self.require_defined(name, Some(profile))?;
+        }
+
+        // Profiles can declare their own provider
+        let backends = profiles
+            .iter()
+            .map(|profile| self.writable_provider(None, Some(profile), "set"))
+            .collect::<Result<Vec<_>>>()?;
+        for (profile, backend) in profiles.iter().zip(&backends) {
+            Self::warn_if_profiles_unsupported(backend.as_ref(), profile);
+        }
+
+        let value = if value.as_deref() == Some("-") {
+            read_piped_value(io::stdin().lock())?
+        } else if let Some(v) = value {
+            v
+        } else if io::stdin().is_terminal() {
+            print!(
+                "Enter value for {} (profile: {}): ",
                 name,
-                profile_name,
-                available_secrets.join(", ")
-            )));
+                profiles.join(", ")
+            );
+            io::stdout().flush()?;
+            rpassword::read_password()?
+        } else {
+            // Read from stdin when input is piped
+            let mut buffer = String::new();
+            io::stdin().read_line(&mut buffer)?;
+            buffer.trim().to_string()
+        };
+
+        let mut failed = Vec::new();
+        for (profile, backend) in profiles.iter().zip(&backends) {
+            match backend.set(&self.config.project.name, name, &value, profile) {
+                Ok(()) => {
+                    self.record_set_at(backend.as_ref(), [name], profile);
+                    if !self.quiet() {
+                        println!(
+                            "{} Secret '{}' saved to {} (profile: {})",
+                            "✓".green(),
+                            name,
+                            backend.name(),
+                            profile
+                        );
+                    }
+                }
+                // A single profile fails with its own error
+                Err(e) if profiles.len() == 1 => return Err(e),
+                Err(e) => {
+                    eprintln!(
+                        "{} Failed to save secret '{}' to {} (profile: {}): {}",
+                        "✗".red(),
+                        name,
+                        backend.name(),
+                        profile,
+                        e
+                    );
+                    failed.push(profile.as_str());
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Secret '{}' could not be saved in profiles: {}",
+                name,
+                failed.join(", ")
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Sets several secret values in the provider at once
+    ///
+    /// Every name is checked against the specification before anything is
+    /// written. Providers that keep a profile's secrets in one file, such as
+    /// `dotenv`, write it a single time.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values to set, keyed by secret name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A secret is not defined in the specification
+    /// - The provider doesn't support setting values
+    /// - The storage operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    /// use std::collections::HashMap;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let values = HashMap::from([
+    ///     ("DATABASE_URL".to_string(), "postgres://localhost".to_string()),
+    ///     ("REDIS_URL".to_string(), "redis://localhost".to_string()),
+    /// ]);
+    /// spec.set_many(&values).unwrap();
+    /// ```
+    pub fn set_many(&self, values: &HashMap<String, String>) -> Result<()> {
+        let profile_name = self.resolve_profile(None);
+        let mut names: Vec<&String> = values.keys().collect();
+        names.sort();
+        for name in &names {
+            self.require_defined(name, None)?;
+        }
+
+        let backend = self.writable_provider(None, None, "set_many")?;
+        Self::warn_if_profiles_unsupported(backend.as_ref(), &profile_name);
+
+        backend.set_many(&self.config.project.name, values, &profile_name)?;
+        self.record_set_at(
+            backend.as_ref(),
+            names.iter().map(|name| name.as_str()),
+            &profile_name,
+        );
+        if self.quiet() {
+            return Ok(());
+        }
+        for name in names {
+            println!(
+                "{} Secret '{}' saved to {} (profile: {})",
+                "✓".green(),
+                name,
+                backend.name(),
+                profile_name
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Stores default values in the provider so its state matches the
+    /// specification
+    ///
+    /// Intended for the [`ValidatedSecrets::with_defaults`] of a previous
+    /// [`check`](Self::check) or [`check_result`](Self::check_result), e.g.
+    /// to seed a fresh provider.
+    /// Read-only providers are skipped with a warning, and so are defaults
+    /// built from other secrets with `${secret:NAME}`, which would otherwise
+    /// be stored expanded and stop following the secrets they reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `defaults` - The secrets to store and their default values
+    ///
+    /// # Returns
+    ///
+    /// The number of secrets stored, 0 if the provider is read-only
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a secret is not defined in the specification or
+    /// the storage operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let validated = spec.check_result(None, None).unwrap();
+    /// spec.store_defaults(&validated.with_defaults).unwrap();
+    /// ```
+    pub fn store_defaults(&self, defaults: &[(String, String)]) -> Result<usize> {
+        if defaults.is_empty() {
+            return Ok(0);
         }
 
-        let backend = self.get_provider(None)?;
-        let profile_display = self.resolve_profile(None);
-
-        // Check if the provider supports setting values
+        let backend = self.get_provider(None, None)?;
         if !backend.allows_set() {
-            return Err(SecretSpecError::ProviderOperationFailed(format!(
-                "Provider '{}' is read-only and does not support setting values",
+            eprintln!(
+                "{} Provider '{}' is read-only; defaults were not stored",
+                "Warning:".yellow(),
                 backend.name()
-            )));
+            );
+            return Ok(0);
         }
 
-        let value = if let Some(v) = value {
-            v
-        } else if io::stdin().is_terminal() {
-            print!("Enter value for {} (profile: {}): ", name, profile_display);
-            io::stdout().flush()?;
-            rpassword::read_password()?
-        } else {
-            // Read from stdin when input is piped
-            let mut buffer = String::new();
-            io::stdin().read_line(&mut buffer)?;
-            buffer.trim().to_string()
-        };
+        let (derived, values): (Vec<_>, Vec<_>) = defaults
+            .iter()
+            .cloned()
+            .partition(|(name, _)| self.has_derived_default(name));
+        let values: HashMap<String, String> = values.into_iter().collect();
+        if !derived.is_empty() {
+            let names: Vec<&str> = derived.iter().map(|(name, _)| name.as_str()).collect();
+            eprintln!(
+                "{} Not storing the defaults of {}: they reference other secrets",
+                "Warning:".yellow(),
+                names.join(", ")
+            );
+        }
+        if values.is_empty() {
+            return Ok(0);
+        }
+        self.set_many(&values)?;
+        Ok(values.len())
+    }
 
-        backend.set(&self.config.project.name, name, &value, &profile_name)?;
-        println!(
-            "{} Secret '{}' saved to {} (profile: {})",
-            "✓".green(),
-            name,
-            backend.name(),
-            profile_display
-        );
+    /// Returns whether the default of `name` in the current profile is built
+    /// from other secrets with `${secret:NAME}`
+    fn has_derived_default(&self, name: &str) -> bool {
+        self.resolve_secret_config(name, None)
+            .and_then(|secret| secret.default)
+            .is_some_and(|default| !reference::references(&default).is_empty())
+    }
 
-        Ok(())
+    /// Generates a new random value for a secret and stores it in the provider
+    ///
+    /// Unlike [`Secrets::set`], the value is never prompted for or passed in;
+    /// it is drawn from a cryptographically secure random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret to rotate
+    /// * `length` - The number of characters to generate
+    /// * `charset` - The characters the value is drawn from
+    /// * `provider_arg` - Optional provider to use
+    /// * `profile` - Optional profile to use
+    ///
+    /// # Returns
+    ///
+    /// The newly stored value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The secret is not defined in the specification
+    /// - The provider doesn't support setting values
+    /// - The storage operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::{Charset, Secrets};
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// spec.rotate("API_KEY", 32, Charset::Alphanumeric, None, None).unwrap();
+    /// ```
+    pub fn rotate(
+        &self,
+        name: &str,
+        length: usize,
+        charset: Charset,
+        provider_arg: Option<String>,
+        profile: Option<String>,
+    ) -> Result<String> {
+        let profile_name = self.require_defined(name, profile.as_deref())?;
+        let backend = self.writable_provider(provider_arg, Some(&profile_name), "rotate")?;
+
+        let value = rotate::generate_value(length, charset);
+        backend.set(&self.config.project.name, name, &value, &profile_name)?;
+        self.record_set_at(backend.as_ref(), [name], &profile_name);
+        Ok(value)
     }
 
     /// Retrieves and prints a secret value
@@ -392,8 +1952,6 @@ impl Secrets {
     /// # Arguments
     ///
     /// * `name` - The name of the secret to retrieve
-    /// * `provider_arg` - Optional provider to use
-    /// * `profile` - Optional profile to use
     ///
     /// # Returns
     ///
@@ -405,39 +1963,375 @@ impl Secrets {
     /// - The secret is not defined in the specification
     /// - The secret is not found and has no default value
     pub fn get(&self, name: &str) -> Result<()> {
-        let backend = self.get_provider(None)?;
+        match self.get_value(name)? {
+            Some(value) => {
+                println!("{}", value);
+                Ok(())
+            }
+            None => Err(SecretSpecError::SecretNotFound(name.to_string())),
+        }
+    }
+
+    /// Retrieves the value of a secret without printing it
+    ///
+    /// The provider's value is transformed as configured; if the provider has
+    /// no value, the secret's default is used.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the secret has neither a value nor a usable default
+    ///
+    /// # Errors
+    ///
+    /// Returns `UndeclaredSecret` if the secret is not defined in the
+    /// specification, or an error from the provider
+    pub fn get_value(&self, name: &str) -> Result<Option<String>> {
+        Ok(self.get_many(&[name])?.remove(name).flatten())
+    }
+
+    /// Describes where the value of a secret is stored, without its value
+    ///
+    /// The description comes from the provider, e.g. the file and line of a
+    /// `.env` entry or the name of a keychain entry. If the provider has no
+    /// value under the secret's name but has one under one of its aliases,
+    /// the alias's location is described. Provider errors while looking for
+    /// the value are ignored, so the location can be shown even when the
+    /// provider cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UndeclaredSecret` if the secret is not defined in the
+    /// specification, or an error if the provider cannot be created
+    pub fn location(&self, name: &str) -> Result<String> {
         let profile_name = self.resolve_profile(None);
+        self.selected_profile(&profile_name)?;
         let secret_config = self
             .resolve_secret_config(name, None)
-            .ok_or_else(|| SecretSpecError::SecretNotFound(name.to_string()))?;
-        let default = secret_config.default.clone();
+            .ok_or_else(|| self.undeclared_secret(name, &profile_name))?;
 
-        match backend.get(&self.config.project.name, name, &profile_name)? {
-            Some(value) => {
-                println!("{}", value);
-                Ok(())
+        let backend = self.get_provider(None, None)?;
+        let project = &self.config.project.name;
+        let has_value = |key: &str| matches!(backend.get(project, key, &profile_name), Ok(Some(_)));
+        let key = if has_value(name) {
+            name
+        } else {
+            secret_config
+                .aliases
+                .iter()
+                .find(|alias| has_value(alias))
+                .map_or(name, String::as_str)
+        };
+        Ok(backend.describe_location(project, key, &profile_name))
+    }
+
+    /// Retrieves the values of several secrets, keyed by name
+    ///
+    /// Values are resolved as in [`get_value`](Self::get_value), using a
+    /// single provider instance for all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UndeclaredSecret` for the first name not defined in the
+    /// specification, or an error from the provider
+    pub fn get_many<S: AsRef<str>>(&self, names: &[S]) -> Result<BTreeMap<String, Option<String>>> {
+        let profile_name = self.resolve_profile(None);
+        self.selected_profile(&profile_name)?;
+        let configs = names
+            .iter()
+            .map(|name| {
+                let name = name.as_ref();
+                self.resolve_secret_config(name, None)
+                    .map(|config| (name, config))
+                    .ok_or_else(|| self.undeclared_secret(name, &profile_name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let backend = self.get_provider(None, None)?;
+        let mut values = BTreeMap::new();
+        for (name, secret_config) in configs {
+            let stored = self.get_stored(backend.as_ref(), name, &secret_config, &profile_name)?;
+            let value = match stored {
+                Some(value) => Some(transform::apply_all(&secret_config.transform, value)?),
+                None => match secret_config.default {
+                    Some(template) => {
+                        self.resolve_default(backend.as_ref(), name, &template, &profile_name)?
+                    }
+                    None => None,
+                },
+            };
+            values.insert(name.to_string(), value);
+        }
+        Ok(values)
+    }
+
+    /// Returns the names of the secrets declared for the selected profile,
+    /// including those inherited from the default profile, sorted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected profile does not exist
+    pub fn secret_names(&self) -> Result<Vec<String>> {
+        let profile_name = self.resolve_profile(None);
+        let mut names: Vec<String> = self.declared_names(&profile_name)?.into_iter().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns the secrets of `profile` that belong to `group`
+    ///
+    /// Secrets inherited from the default profile are included, with the
+    /// group resolved like any other field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist
+    pub fn secrets_in_group(&self, group: &str, profile: &str) -> Result<HashSet<String>> {
+        let mut names = self.declared_names(profile)?;
+        names.retain(|name| {
+            self.resolve_secret_config(name, Some(profile))
+                .is_some_and(|secret| secret.group.as_deref() == Some(group))
+        });
+        Ok(names)
+    }
+
+    /// Returns the members of the group the filter restricts secrets to, or
+    /// `None` if it doesn't name a group
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroupNotFound` if no secret of the profile is in the group,
+    /// which is most likely a typo
+    fn group_members(&self, profile_name: &str) -> Result<Option<HashSet<String>>> {
+        let Some(group) = self.filter.group_name() else {
+            return Ok(None);
+        };
+        let members = self.secrets_in_group(group, profile_name)?;
+        if members.is_empty() {
+            return Err(SecretSpecError::GroupNotFound(
+                group.to_string(),
+                profile_name.to_string(),
+            ));
+        }
+        Ok(Some(members))
+    }
+
+    /// Returns the secrets declared in `profile_name` and, for other
+    /// profiles, in the default profile
+    fn declared_names(&self, profile_name: &str) -> Result<HashSet<String>> {
+        let profile_config = self.selected_profile(profile_name)?;
+        let mut names: HashSet<String> = profile_config.secrets.keys().cloned().collect();
+        if profile_name != "default"
+            && let Some(default_profile) = self.config.profiles.get("default")
+        {
+            names.extend(default_profile.secrets.keys().cloned());
+        }
+        Ok(names)
+    }
+
+    /// Builds the error for `name` not being declared in `profile_name`,
+    /// listing the secrets that are
+    fn undeclared_secret(&self, name: &str, profile_name: &str) -> SecretSpecError {
+        let mut available: Vec<String> = self
+            .declared_names(profile_name)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        available.sort();
+        SecretSpecError::UndeclaredSecret(name.to_string(), profile_name.to_string(), available)
+    }
+
+    /// Reads the value stored for `name`, falling back to its aliases in order
+    ///
+    /// A secret with a `source` is read from the other project instead.
+    fn get_stored(
+        &self,
+        backend: &dyn ProviderTrait,
+        name: &str,
+        secret_config: &Secret,
+        profile_name: &str,
+    ) -> Result<Option<String>> {
+        if secret_config.source.is_some() {
+            return self.sourced_value(name, secret_config, profile_name);
+        }
+        if let Some(value) = backend.get(&self.config.project.name, name, profile_name)? {
+            return Ok(Some(value));
+        }
+        self.get_alias(backend, &secret_config.aliases, profile_name)
+    }
+
+    /// Reads the value of a secret with a `source` from the other project
+    ///
+    /// The other project's `secretspec.toml` is loaded with the same global
+    /// configuration and `profile_name` selected, and its provider is chosen
+    /// the way it would be in that project's directory. Its value is
+    /// transformed and defaulted as that project declares.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CircularDependency` if the other project reads this one's
+    /// secrets back through its own sources, an error if its
+    /// `secretspec.toml` cannot be read or lacks the profile, or
+    /// `UndeclaredSecret` if it doesn't declare the secret
+    fn sourced_value(
+        &self,
+        name: &str,
+        secret_config: &Secret,
+        profile_name: &str,
+    ) -> Result<Option<String>> {
+        let (manifest, source_name) = self.source_manifest(name, secret_config)?;
+
+        let mut chain = self.sourced_by.clone();
+        chain.extend(self.manifest.clone());
+        if chain.contains(&manifest) {
+            chain.push(manifest);
+            let chain: Vec<String> = chain
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            return Err(SecretSpecError::CircularDependency(format!(
+                "secret '{}' is sourced in a cycle: {}",
+                name,
+                chain.join(" -> ")
+            )));
+        }
+
+        let mut other = self.load_source(&manifest)?;
+        other.sourced_by = chain;
+        other.set_profile(profile_name);
+        other.get_value(source_name)
+    }
+
+    /// Returns the `secretspec.toml` that the `source` of secret `name`
+    /// points to, and the name of the secret to read from it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source is invalid or the file doesn't exist
+    fn source_manifest<'a>(
+        &self,
+        name: &str,
+        secret_config: &'a Secret,
+    ) -> Result<(PathBuf, &'a str)> {
+        let Some((path, source_name)) = secret_config.source_parts() else {
+            return Err(SecretSpecError::InvalidConfig(format!(
+                "Secret '{}' has an invalid source",
+                name
+            )));
+        };
+        let base = self
+            .manifest
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("."));
+        let mut manifest = base.join(path);
+        if manifest.is_dir() {
+            manifest.push(MANIFEST_NAME);
+        }
+        let manifest = manifest.canonicalize().map_err(|e| {
+            SecretSpecError::Io(io::Error::new(
+                e.kind(),
+                format!(
+                    "Cannot read the source of secret '{}' at {}: {}",
+                    name,
+                    manifest.display(),
+                    e
+                ),
+            ))
+        })?;
+        Ok((manifest, source_name))
+    }
+
+    /// Loads the sourced project at `manifest` with this one's global
+    /// configuration and allowed revision
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `manifest` if it doesn't exist, or an error
+    /// if it cannot be loaded
+    fn load_source(&self, manifest: &Path) -> Result<Self> {
+        Self::load_manifest(
+            manifest,
+            self.global_config.clone(),
+            self.allowed_revision.as_deref(),
+        )
+        .map_err(|e| match e {
+            SecretSpecError::NoManifest => SecretSpecError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Sourced manifest {} does not exist", manifest.display()),
+            )),
+            e => e,
+        })
+    }
+
+    /// Reads the first of `aliases` that `backend` has a value for
+    fn get_alias(
+        &self,
+        backend: &dyn ProviderTrait,
+        aliases: &[String],
+        profile_name: &str,
+    ) -> Result<Option<String>> {
+        for alias in aliases {
+            if let Some(value) = backend.get(&self.config.project.name, alias, profile_name)? {
+                return Ok(Some(value));
             }
-            None => {
-                if let Some(default_value) = default {
-                    println!("{}", default_value);
-                    Ok(())
-                } else {
-                    Err(SecretSpecError::SecretNotFound(name.to_string()))
-                }
+        }
+        Ok(None)
+    }
+
+    /// Substitutes the `${secret:NAME}` references in the default of `name`
+    ///
+    /// Referenced secrets are read from `backend`, falling back to their own
+    /// defaults.
+    ///
+    /// # Returns
+    ///
+    /// `None` if a referenced secret has no value
+    ///
+    /// # Errors
+    ///
+    /// Returns `CircularDependency` if defaults reference each other in a
+    /// cycle, or an error from the provider
+    fn resolve_default(
+        &self,
+        backend: &dyn ProviderTrait,
+        name: &str,
+        template: &str,
+        profile_name: &str,
+    ) -> Result<Option<String>> {
+        let references = self.default_references(&[name.to_string()], profile_name)?;
+        let order = reference::resolution_order(&[name.to_string()], &references)?;
+
+        let mut available = HashMap::new();
+        for dependency in order.iter().filter(|dependency| *dependency != name) {
+            let secret_config = self
+                .resolve_secret_config(dependency, Some(profile_name))
+                .expect("Referenced secrets are declared in the config");
+            let value = match self.get_stored(backend, dependency, &secret_config, profile_name)? {
+                Some(value) => Some(transform::apply_all(&secret_config.transform, value)?),
+                None => secret_config
+                    .default
+                    .as_deref()
+                    .and_then(|template| reference::substitute(template, &available)),
+            };
+            if let Some(value) = value {
+                available.insert(dependency.clone(), value);
             }
         }
+        Ok(reference::substitute(template, &available))
     }
 
     /// Ensures all required secrets are present, optionally prompting for missing ones
     ///
-    /// This method validates all secrets and, in interactive mode, prompts the
-    /// user to provide values for any missing required secrets.
+    /// This method validates all secrets and, given a `prompt`, asks for the
+    /// values of any missing required secrets.
     ///
     /// # Arguments
     ///
-    /// * `provider_arg` - Optional provider to use
-    /// * `profile` - Optional profile to use
-    /// * `interactive` - Whether to prompt for missing secrets
+    /// * `backend` - The provider of the selected profile
+    /// * `validated` - The status of every secret if already read, so the
+    ///   provider is only read again after prompting
+    /// * `prompt` - Reads the values of missing secrets, or `None` to fail
+    ///   without prompting
     ///
     /// # Returns
     ///
@@ -450,21 +2344,32 @@ impl Secrets {
     /// - Storage operations fail
     fn ensure_secrets(
         &self,
-        provider_arg: Option<String>,
-        profile: Option<String>,
-        interactive: bool,
+        backend: &dyn ProviderTrait,
+        validated: Option<ValidatedSecrets>,
+        prompt: Option<Prompt>,
     ) -> Result<ValidatedSecrets> {
-        let backend = self.get_provider(provider_arg.clone())?;
-        let profile_display = self.resolve_profile(profile.as_deref());
+        let profile_display = self.resolve_profile(None);
 
         // First validate to see what's missing
-        let validation_result = self.validate()?;
+        let validated = match validated {
+            Some(validated) => validated,
+            None => self.check_with_backend(backend, profile_display.clone())?,
+        };
+        let validation_result = if validated.is_valid() {
+            Ok(validated)
+        } else {
+            Err(Self::validation_errors(validated))
+        };
 
         match validation_result {
             Ok(valid_secrets) => Ok(valid_secrets),
             Err(validation_errors) => {
-                // If we're in interactive mode and have missing required secrets, prompt for them
-                if interactive && !validation_errors.missing_required.is_empty() {
+                // If we can prompt and have missing required secrets, prompt for
+                // them, unless the provider could not store the answers
+                if let Some(prompt) = prompt
+                    && backend.allows_set()
+                    && !validation_errors.missing_required.is_empty()
+                {
                     println!("\nThe following required secrets are missing:");
                     for secret_name in &validation_errors.missing_required {
                         if let Some(secret_config) =
@@ -475,18 +2380,12 @@ impl Secrets {
                                 .as_deref()
                                 .unwrap_or("No description");
                             println!("\n{} - {}", secret_name.bold(), description);
-                            let value = if io::stdin().is_terminal() {
-                                print!(
-                                    "Enter value for {} (profile: {}): ",
-                                    secret_name, profile_display
-                                );
-                                io::stdout().flush()?;
-                                rpassword::read_password()?
-                            } else {
-                                // When stdin is not a terminal, we can't prompt interactively
-                                return Err(SecretSpecError::RequiredSecretMissing(
-                                    validation_errors.missing_required.join(", "),
-                                ));
+                            if let Some(help_url) = &secret_config.help_url {
+                                println!("Get this value at: {}", help_url.cyan());
+                            }
+                            let Some(value) = prompt(secret_name, &profile_display)? else {
+                                // No one can be asked, e.g. stdin is not a terminal
+                                return Err(validation_errors.clone().into_error());
                             };
 
                             backend.set(
@@ -495,30 +2394,34 @@ impl Secrets {
                                 &value,
                                 &profile_display,
                             )?;
-                            println!(
-                                "{} Secret '{}' saved to {} (profile: {})",
-                                "✓".green(),
-                                secret_name,
-                                backend.name(),
-                                profile_display
-                            );
+                            self.record_set_at(backend, [secret_name.as_str()], &profile_display);
+                            if !self.quiet() {
+                                println!(
+                                    "{} Secret '{}' saved to {} (profile: {})",
+                                    "✓".green(),
+                                    secret_name,
+                                    backend.name(),
+                                    profile_display
+                                );
+                            }
                         }
                     }
 
-                    println!("\nAll required secrets have been set.");
+                    if !self.quiet() {
+                        println!("\nAll required secrets have been set.");
+                    }
 
-                    // Re-validate to get the updated results
-                    match self.validate()? {
-                        Ok(valid_secrets) => Ok(valid_secrets),
-                        Err(still_errors) => Err(SecretSpecError::RequiredSecretMissing(
-                            still_errors.missing_required.join(", "),
-                        )),
+                    // Re-validate to get the updated results, without
+                    // repeating the warning about profiles
+                    let validated = self.check_with_backend(backend, profile_display)?;
+                    if validated.is_valid() {
+                        Ok(validated)
+                    } else {
+                        Err(Self::validation_errors(validated).into_error())
                     }
                 } else {
                     // Not interactive or no missing required secrets
-                    Err(SecretSpecError::RequiredSecretMissing(
-                        validation_errors.missing_required.join(", "),
-                    ))
+                    Err(validation_errors.into_error())
                 }
             }
         }
@@ -537,7 +2440,8 @@ impl Secrets {
     ///
     /// # Returns
     ///
-    /// `Ok(())` if all required secrets are present after prompting
+    /// The status of every secret once all required secrets are present,
+    /// after prompting
     ///
     /// # Errors
     ///
@@ -553,55 +2457,67 @@ impl Secrets {
     /// let mut spec = Secrets::load().unwrap();
     /// spec.check().unwrap();
     /// ```
-    pub fn check(&self) -> Result<()> {
-        let provider = self.get_provider(None)?;
-        let profile_display = self.resolve_profile(None);
-
-        println!(
-            "Checking secrets in {} using {} (profile: {})...\n",
-            self.config.project.name.bold(),
-            provider.name().blue(),
-            profile_display.cyan()
-        );
-
-        // First get the initial validation result to display status
-        let initial_validation_result = self.validate()?;
-
-        // We need to handle both success and error cases for display
-        let empty_map = HashMap::new();
-        let (secrets_map, missing_required, missing_optional, with_defaults) =
-            match &initial_validation_result {
-                Ok(valid) => (
-                    &valid.resolved.secrets,
-                    vec![],
-                    valid.missing_optional.clone(),
-                    valid.with_defaults.clone(),
-                ),
-                Err(errors) => (
-                    &empty_map,
-                    errors.missing_required.clone(),
-                    errors.missing_optional.clone(),
-                    errors.with_defaults.clone(),
-                ),
-            };
-
-        // Display status for each secret
-        let profile_name = self.resolve_profile(None);
-        let profile_config = self.config.profiles.get(&profile_name).ok_or_else(|| {
-            SecretSpecError::SecretNotFound(format!("Profile '{}' not found", profile_name))
-        })?;
+    pub fn check(&self) -> Result<ValidatedSecrets> {
+        self.check_with_prompt(&mut prompt_on_terminal)
+    }
 
-        // Collect all secrets to display - from current profile and default profile
-        let mut all_secrets_to_display = Vec::new();
+    /// Checks like [`check`](Self::check), reading missing secrets with
+    /// `prompt` instead of from the terminal
+    pub(crate) fn check_with_prompt(&self, prompt: Prompt) -> Result<ValidatedSecrets> {
+        let provider = self.get_provider(None, None)?;
+        let profile_display = self.resolve_profile(None);
+        let format = self.check_format;
 
-        // Add secrets from the current profile
-        for (name, config) in &profile_config.secrets {
-            all_secrets_to_display.push((name.clone(), config.clone()));
+        if !self.quiet() {
+            println!(
+                "{}",
+                report::format_header(
+                    &self.config.project.name,
+                    self.config.project.description.as_deref(),
+                    provider.name(),
+                    &profile_display,
+                    format,
+                )
+            );
+            Self::warn_if_profiles_unsupported(provider.as_ref(), &profile_display);
         }
 
-        // If not the default profile, also add secrets from default profile
-        if profile_name != "default" {
-            if let Some(default_profile) = self.config.profiles.get("default") {
+        // First get the status of every secret to display it
+        let validated = self.check_with_backend(provider.as_ref(), profile_display.clone())?;
+        self.write_audit_snapshot(&validated)?;
+        let ValidatedSecrets {
+            resolved,
+            missing_required,
+            missing_optional,
+            missing_recommended,
+            with_defaults,
+            would_default,
+            missing_conditional,
+            invalid_values,
+            extra,
+            resolved_from,
+            due_for_rotation,
+            ..
+        } = &validated;
+        let secrets_map = &resolved.secrets;
+
+        if !self.quiet() {
+            // Display status for each secret
+            let profile_name = self.resolve_profile(None);
+            let profile_config = self.selected_profile(&profile_name)?;
+
+            // Collect all secrets to display - from current profile and default profile
+            let mut all_secrets_to_display = Vec::new();
+
+            // Add secrets from the current profile
+            for (name, config) in &profile_config.secrets {
+                all_secrets_to_display.push((name.clone(), config.clone()));
+            }
+
+            // If not the default profile, also add secrets from default profile
+            if profile_name != "default"
+                && let Some(default_profile) = self.config.profiles.get("default")
+            {
                 for (name, config) in &default_profile.secrets {
                     // Only add if not already in current profile
                     if !profile_config.secrets.contains_key(name) {
@@ -609,60 +2525,157 @@ impl Secrets {
                     }
                 }
             }
-        }
-
-        // Sort by name for consistent display
-        all_secrets_to_display.sort_by(|a, b| a.0.cmp(&b.0));
 
-        for (name, config) in all_secrets_to_display {
-            if secrets_map.contains_key(&name) {
-                if with_defaults.iter().any(|(n, _)| n == &name) {
-                    println!(
-                        "{} {} - {} {}",
-                        "○".yellow(),
-                        name,
-                        config.description.as_deref().unwrap_or("No description"),
-                        "(has default)".yellow()
-                    );
-                } else {
-                    println!(
-                        "{} {} - {}",
-                        "✓".green(),
-                        name,
-                        config.description.as_deref().unwrap_or("No description")
-                    );
-                }
-            } else if missing_required.contains(&name) {
-                println!(
-                    "{} {} - {} {}",
-                    "✗".red(),
-                    name,
-                    config.description.as_deref().unwrap_or("No description"),
-                    "(required)".red()
-                );
-            } else if missing_optional.contains(&name) {
-                println!(
-                    "{} {} - {} {}",
-                    "○".blue(),
+            // Sort by name for consistent display
+            let group = self.group_members(&profile_name)?;
+            all_secrets_to_display.retain(|(name, _)| {
+                self.filter.matches(name) && group.as_ref().is_none_or(|group| group.contains(name))
+            });
+            all_secrets_to_display.sort_by(|a, b| a.0.cmp(&b.0));
+
+            let mut rows = Vec::new();
+            for (name, config) in all_secrets_to_display {
+                let (status, note) =
+                    if let Some((_, reason)) = invalid_values.iter().find(|(n, _)| n == &name) {
+                        (CheckStatus::Invalid, Some(reason.clone()))
+                    } else if secrets_map.contains_key(&name) {
+                        if with_defaults.iter().any(|(n, _)| n == &name) {
+                            (CheckStatus::Default, Some("has default".to_string()))
+                        } else if let Some((_, days)) =
+                            due_for_rotation.iter().find(|(n, _)| n == &name)
+                        {
+                            (
+                                CheckStatus::DueForRotation,
+                                Some(format!(
+                                    "set {} days ago, rotate every {} days",
+                                    days,
+                                    self.resolve_secret_config(&name, Some(&profile_name))
+                                        .and_then(|config| config.rotate_after_days)
+                                        .unwrap_or_default()
+                                )),
+                            )
+                        } else {
+                            (CheckStatus::Found, None)
+                        }
+                    } else if would_default.iter().any(|(n, _)| n == &name) {
+                        (
+                            CheckStatus::NotStored,
+                            Some("not stored, has default".to_string()),
+                        )
+                    } else if let Some((_, trigger)) =
+                        missing_conditional.iter().find(|(n, _)| n == &name)
+                    {
+                        (
+                            CheckStatus::Missing,
+                            Some(format!("required because {} is set", trigger)),
+                        )
+                    } else if missing_required.contains(&name) {
+                        (CheckStatus::Missing, Some("required".to_string()))
+                    } else if missing_recommended.contains(&name) {
+                        (CheckStatus::Recommended, Some("recommended".to_string()))
+                    } else if missing_optional.contains(&name) && !self.hide_optional {
+                        (CheckStatus::Optional, Some("optional".to_string()))
+                    } else {
+                        continue;
+                    };
+                // Say where inherited definitions come from, which explains
+                // why a secret is required in one profile but not another
+                let note = match resolved_from.get(&name) {
+                    Some(source)
+                        if self.verbosity == Verbosity::Verbose && *source != profile_name =>
+                    {
+                        let inherited = format!("inherited from {}", source);
+                        Some(match note {
+                            Some(note) => format!("{}, {}", note, inherited),
+                            None => inherited,
+                        })
+                    }
+                    _ => note,
+                };
+                rows.push(CheckRow {
+                    status,
                     name,
-                    config.description.as_deref().unwrap_or("No description"),
-                    "(optional)".blue()
-                );
+                    description: Some(
+                        config
+                            .description
+                            .unwrap_or_else(|| "No description".to_string()),
+                    ),
+                    note,
+                });
             }
-        }
 
-        let found_count = secrets_map.len() - with_defaults.len();
-        let missing_count = missing_required.len();
+            // Warn about keys in the provider that the spec doesn't declare
+            rows.extend(extra.iter().map(|name| CheckRow {
+                status: CheckStatus::Undeclared,
+                name: name.clone(),
+                description: None,
+                note: Some("undeclared".to_string()),
+            }));
+            print!("{}", report::format_rows(&rows, format));
+
+            let summary = CheckSummary {
+                found: secrets_map.len() - with_defaults.len(),
+                missing: missing_required.len(),
+                invalid: invalid_values.len(),
+                recommended: missing_recommended.len(),
+                due_for_rotation: due_for_rotation.len(),
+                hidden_optional: if self.hide_optional {
+                    missing_optional.len() - missing_recommended.len()
+                } else {
+                    0
+                },
+            };
+            print!("\n{}", report::format_summary(&summary, format));
+        }
+        self.print_timings();
 
-        println!(
-            "\nSummary: {} found, {} missing",
-            found_count.to_string().green(),
-            missing_count.to_string().red()
-        );
+        // Now ensure all secrets are present (will prompt if needed),
+        // reading the provider again only after prompting
+        self.ensure_secrets(provider.as_ref(), Some(validated), Some(prompt))
+    }
 
-        // Now ensure all secrets are present (will prompt if needed)
-        self.ensure_secrets(None, None, true)?;
+    /// Checks the status of all secrets and prints it in a stable format
+    /// for scripts
+    ///
+    /// Prints one `STATUS\tNAME\tPROFILE` line per secret, ordered by name,
+    /// where the status is `PRESENT`, `DEFAULT`, `MISSING_REQUIRED` or
+    /// `MISSING_OPTIONAL`. Unlike [`check`](Self::check), nothing else is
+    /// printed to stdout, nothing is colored and missing secrets are never
+    /// prompted for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error after printing the listing if required secrets are
+    /// missing or values are invalid, or an error if the provider cannot be
+    /// read
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// spec.check_porcelain().unwrap();
+    /// ```
+    pub fn check_porcelain(&self) -> Result<()> {
+        let validated = self.check_result(None, None)?;
+        self.write_audit_snapshot(&validated)?;
+        print!("{}", report::format_porcelain(&validated));
+        self.print_timings();
+        if validated.is_valid() {
+            Ok(())
+        } else {
+            Err(Self::validation_errors(validated).into_error())
+        }
+    }
 
+    /// Writes the audit snapshot of `validated` to the file set with
+    /// [`set_audit_out`](Self::set_audit_out), if any
+    fn write_audit_snapshot(&self, validated: &ValidatedSecrets) -> Result<()> {
+        if let Some(path) = &self.audit_out {
+            let snapshot = validated.audit_snapshot(&self.config.project.name);
+            std::fs::write(path, serde_json::to_string_pretty(&snapshot)? + "\n")?;
+        }
         Ok(())
     }
 
@@ -685,6 +2698,8 @@ impl Secrets {
     /// - The source provider cannot be initialized
     /// - The target provider cannot be initialized
     /// - Storage operations fail
+    /// - Verification is enabled with `set_verify_import` and a value reads
+    ///   back differently from the target
     ///
     /// # Example
     ///
@@ -696,27 +2711,25 @@ impl Secrets {
     /// ```
     pub fn import(&self, from_provider: &str) -> Result<()> {
         // Get the "to" provider from global config (default)
-        let to_provider = self.get_provider(None)?;
+        let to_provider = self.get_provider(None, None)?;
 
         // Resolve profile (checks env var, then global config, then defaults to "default")
         let profile_display = self.resolve_profile(None);
+        let profile_config = self.selected_profile(&profile_display)?;
 
         // Create the "from" provider
         let from_provider_instance = Box::<dyn ProviderTrait>::try_from(from_provider.to_string())?;
 
-        println!(
-            "Importing secrets from {} to {} (profile: {})...\n",
-            from_provider.blue(),
-            to_provider.name().blue(),
-            profile_display.cyan()
-        );
-
-        // Get the profile configuration
-        let profile_config = self.config.profiles.get(&profile_display).ok_or_else(|| {
-            SecretSpecError::SecretNotFound(format!("Profile '{}' not found", profile_display))
-        })?;
+        if !self.quiet() {
+            println!(
+                "Importing secrets from {} to {} (profile: {})...\n",
+                from_provider.blue(),
+                to_provider.name().blue(),
+                profile_display.cyan()
+            );
+        }
 
-        let mut imported = 0;
+        let mut to_import = HashMap::new();
         let mut already_exists = 0;
         let mut not_found = 0;
 
@@ -728,30 +2741,20 @@ impl Secrets {
                     // Secret exists in "from" provider, check if it exists in "to" provider
                     match to_provider.get(&self.config.project.name, name, &profile_display)? {
                         Some(_) => {
-                            println!(
-                                "{} {} - {} {}",
-                                "○".yellow(),
-                                name,
-                                config.description.as_deref().unwrap_or("No description"),
-                                "(already exists in target)".yellow()
-                            );
+                            if !self.quiet() {
+                                println!(
+                                    "{} {} - {} {}",
+                                    "○".yellow(),
+                                    name,
+                                    config.description.as_deref().unwrap_or("No description"),
+                                    "(already exists in target)".yellow()
+                                );
+                            }
                             already_exists += 1;
                         }
                         None => {
                             // Secret doesn't exist in "to" provider, import it
-                            to_provider.set(
-                                &self.config.project.name,
-                                name,
-                                &value,
-                                &profile_display,
-                            )?;
-                            println!(
-                                "{} {} - {}",
-                                "✓".green(),
-                                name,
-                                config.description.as_deref().unwrap_or("No description")
-                            );
-                            imported += 1;
+                            to_import.insert(name.clone(), value);
                         }
                     }
                 }
@@ -760,23 +2763,27 @@ impl Secrets {
                     // Check if it exists in the "to" provider
                     match to_provider.get(&self.config.project.name, name, &profile_display)? {
                         Some(_) => {
-                            println!(
-                                "{} {} - {} {}",
-                                "○".blue(),
-                                name,
-                                config.description.as_deref().unwrap_or("No description"),
-                                "(already in target, not in source)".blue()
-                            );
+                            if !self.quiet() {
+                                println!(
+                                    "{} {} - {} {}",
+                                    "○".blue(),
+                                    name,
+                                    config.description.as_deref().unwrap_or("No description"),
+                                    "(already in target, not in source)".blue()
+                                );
+                            }
                             already_exists += 1;
                         }
                         None => {
-                            println!(
-                                "{} {} - {} {}",
-                                "✗".red(),
-                                name,
-                                config.description.as_deref().unwrap_or("No description"),
-                                "(not found in source)".red()
-                            );
+                            if !self.quiet() {
+                                println!(
+                                    "{} {} - {} {}",
+                                    "✗".red(),
+                                    name,
+                                    config.description.as_deref().unwrap_or("No description"),
+                                    "(not found in source)".red()
+                                );
+                            }
                             not_found += 1;
                         }
                     }
@@ -784,24 +2791,89 @@ impl Secrets {
             }
         }
 
-        println!(
+        // Write everything at once, then report what was imported
+        if !to_import.is_empty() {
+            to_provider.set_many(&self.config.project.name, &to_import, &profile_display)?;
+            self.record_set_at(
+                to_provider.as_ref(),
+                to_import.keys().map(String::as_str),
+                &profile_display,
+            );
+        }
+        let mismatched = if self.verify_import {
+            verify_written(
+                to_provider.as_ref(),
+                &self.config.project.name,
+                &profile_display,
+                &to_import,
+                self.max_concurrency,
+            )?
+        } else {
+            Vec::new()
+        };
+        for name in &mismatched {
+            eprintln!(
+                "{} {} - {}",
+                "✗".red(),
+                name,
+                "(reads back differently from the target)".red()
+            );
+        }
+        let result = if mismatched.is_empty() {
+            Ok(())
+        } else {
+            Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "{} did not store the imported value of: {}",
+                to_provider.name(),
+                mismatched.join(", ")
+            ))))
+        };
+        if self.quiet() {
+            return result;
+        }
+        let mut imported: Vec<&String> = to_import
+            .keys()
+            .filter(|name| !mismatched.contains(name))
+            .collect();
+        imported.sort();
+        for name in &imported {
+            println!(
+                "{} {} - {}",
+                "✓".green(),
+                name,
+                profile_config.secrets[*name]
+                    .description
+                    .as_deref()
+                    .unwrap_or("No description")
+            );
+        }
+
+        print!(
             "\nSummary: {} imported, {} already exists, {} not found in source",
-            imported.to_string().green(),
+            imported.len().to_string().green(),
             already_exists.to_string().yellow(),
             not_found.to_string().red()
         );
+        if mismatched.is_empty() {
+            println!();
+        } else {
+            println!(
+                ", {} failed verification",
+                mismatched.len().to_string().red()
+            );
+        }
 
-        if imported > 0 {
+        if !imported.is_empty() {
             println!(
                 "\n{} Successfully imported {} secrets from {} to {}",
                 "✓".green(),
-                imported,
+                imported.len(),
                 from_provider,
                 to_provider.name()
             );
         }
 
-        Ok(())
+        result
     }
 
     /// Validates all secrets in the specification
@@ -818,6 +2890,7 @@ impl Secrets {
     /// Returns an error if:
     /// - The provider cannot be initialized
     /// - The specified profile doesn't exist
+    /// - The filter set with `set_filter` names an undeclared secret
     /// - Storage operations fail
     ///
     /// # Example
@@ -832,79 +2905,468 @@ impl Secrets {
     /// }
     /// ```
     pub fn validate(&self) -> Result<std::result::Result<ValidatedSecrets, ValidationErrors>> {
-        let backend = self.get_provider(None)?;
-        let mut secrets = HashMap::new();
-        let mut missing_required = Vec::new();
-        let mut missing_optional = Vec::new();
-        let mut with_defaults = Vec::new();
-
+        let backend = self.get_provider(None, None)?;
         let profile_name = self.resolve_profile(None);
-        let profile_config = self.config.profiles.get(&profile_name).ok_or_else(|| {
-            SecretSpecError::SecretNotFound(format!("Profile '{}' not found", profile_name))
-        })?;
+        Self::warn_if_profiles_unsupported(backend.as_ref(), &profile_name);
+        let validated = self.check_with_backend(backend.as_ref(), profile_name)?;
+        if validated.is_valid() {
+            Ok(Ok(validated))
+        } else {
+            Ok(Err(Self::validation_errors(validated)))
+        }
+    }
 
-        // Collect all secrets to check - from current profile and default profile
-        let mut all_secrets = HashSet::new();
+    /// Collects what makes `validated` fail validation
+    fn validation_errors(validated: ValidatedSecrets) -> ValidationErrors {
+        let mut errors = ValidationErrors::new(
+            validated.missing_required,
+            validated.missing_optional,
+            validated.with_defaults,
+            validated.missing_conditional,
+            validated.extra,
+            validated.resolved.provider,
+            validated.resolved.profile,
+        );
+        errors.invalid_values = validated.invalid_values;
+        errors
+    }
 
-        // Add secrets from the current profile
-        for name in profile_config.secrets.keys() {
-            all_secrets.insert(name.clone());
-        }
+    /// Checks the status of all secrets without printing or prompting
+    ///
+    /// Unlike [`validate`](Self::validate), missing required secrets are not
+    /// an error: they are reported in `missing_required` alongside everything
+    /// that was resolved, and [`ValidatedSecrets::is_valid`] tells whether
+    /// the specification is satisfied. This is the library counterpart of
+    /// the interactive [`check`](Self::check).
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Optional provider to use instead of the configured one
+    /// * `profile` - Optional profile to use instead of the configured one
+    ///
+    /// # Returns
+    ///
+    /// A `ValidatedSecrets` with the status of every secret
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The provider cannot be initialized
+    /// - The specified profile doesn't exist
+    /// - The filter set with `set_filter` names an undeclared secret
+    /// - Storage operations fail
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let status = spec.check_result(None, Some("production".to_string())).unwrap();
+    /// if !status.is_valid() {
+    ///     eprintln!("Missing: {}", status.missing_required.join(", "));
+    /// }
+    /// ```
+    pub fn check_result(
+        &self,
+        provider: Option<String>,
+        profile: Option<String>,
+    ) -> Result<ValidatedSecrets> {
+        let backend = self.get_provider(provider, profile.as_deref())?;
+        self.check_with_backend(backend.as_ref(), self.resolve_profile(profile.as_deref()))
+    }
 
-        // If not the default profile, also add secrets from default profile
-        if profile_name != "default" {
-            if let Some(default_profile) = self.config.profiles.get("default") {
-                for name in default_profile.secrets.keys() {
-                    all_secrets.insert(name.clone());
-                }
-            }
+    /// Checks the status of all secrets of `profile_name` in `backend`, for
+    /// callers that already created the provider
+    fn check_with_backend(
+        &self,
+        backend: &dyn ProviderTrait,
+        profile_name: String,
+    ) -> Result<ValidatedSecrets> {
+        let mut secrets = HashMap::new();
+        let mut missing_required = Vec::new();
+        let mut missing_optional = Vec::new();
+        let mut missing_recommended = Vec::new();
+        let mut with_defaults = Vec::new();
+        let mut would_default = Vec::new();
+        let mut invalid_values = Vec::new();
+        let mut due_for_rotation = Vec::new();
+
+        let mut all_secrets = self.declared_names(&profile_name)?;
+
+        // A filter naming an undeclared secret is most likely a typo
+        if let Some(unknown) = self
+            .filter
+            .names()
+            .find(|name| !all_secrets.contains(*name))
+        {
+            return Err(self.undeclared_secret(unknown, &profile_name));
         }
 
-        // Now check all secrets
-        for name in all_secrets {
+        let group = self.group_members(&profile_name)?;
+
+        // Collect keys the provider holds that aren't declared in the spec,
+        // either as a secret or as one of its aliases. Undeclared keys belong
+        // to no group.
+        let aliases: HashSet<String> = all_secrets
+            .iter()
+            .filter_map(|name| self.resolve_secret_config(name, Some(&profile_name)))
+            .flat_map(|secret_config| secret_config.aliases)
+            .collect();
+        let mut extra: Vec<String> = backend
+            .list_keys(&self.config.project.name, &profile_name)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|key| {
+                !all_secrets.contains(key)
+                    && !aliases.contains(key)
+                    && self.filter.matches(key)
+                    && group.is_none()
+            })
+            .collect();
+        extra.sort();
+
+        all_secrets.retain(|name| {
+            self.filter.matches(name) && group.as_ref().is_none_or(|group| group.contains(name))
+        });
+        let resolved_from: HashMap<String, String> = all_secrets
+            .iter()
+            .filter_map(|name| {
+                self.secret_config_profile(name, &profile_name)
+                    .map(|source| (name.clone(), source))
+            })
+            .collect();
+
+        // Secrets referenced by defaults are fetched even if filtered out
+        let mut names: Vec<String> = all_secrets.iter().cloned().collect();
+        names.sort();
+        let references = self.default_references(&names, &profile_name)?;
+        let order = reference::resolution_order(&names, &references)?;
+
+        // Fetch in name order so the missing lists are stable
+        let mut fetch_names: Vec<String> = references
+            .into_keys()
+            .filter(|name| {
+                !self
+                    .resolve_secret_config(name, Some(&profile_name))
+                    .is_some_and(|secret_config| {
+                        self.prefers_default(&secret_config) || secret_config.source.is_some()
+                    })
+            })
+            .collect();
+        fetch_names.sort();
+        let values = fetch_secrets(
+            backend,
+            &self.config.project.name,
+            &profile_name,
+            &fetch_names,
+            self.max_concurrency,
+        )?;
+        let mut fetched: HashMap<String, Option<String>> =
+            fetch_names.into_iter().zip(values).collect();
+
+        // Now check all secrets, referenced ones first, deferring
+        // conditionally required ones until every other secret has been
+        // resolved. `available` also holds defaults that are not applied, so
+        // references resolve the same way either way.
+        let mut available = HashMap::new();
+        let mut conditional = Vec::new();
+        for name in order {
             let secret_config = self
-                .resolve_secret_config(&name, None)
+                .resolve_secret_config(&name, Some(&profile_name))
                 .expect("Secret should exist in config since we're iterating over it");
             let required = secret_config.required;
-            let default = secret_config.default.clone();
+            // A default referencing a secret without a value is unavailable
+            let default = secret_config
+                .default
+                .as_deref()
+                .and_then(|template| reference::substitute(template, &available));
+            let value = if self.prefers_default(&secret_config) && default.is_some() {
+                None
+            } else if secret_config.source.is_some() {
+                self.sourced_value(&name, &secret_config, &profile_name)?
+            } else {
+                // A secret skipped for its default is read after all if the
+                // default turned out unavailable
+                let stored = match fetched.remove(&name) {
+                    Some(value) => value,
+                    None => backend.get(&self.config.project.name, &name, &profile_name)?,
+                };
+                // Values still stored under a former name are read from its aliases
+                match stored {
+                    Some(value) => Some(value),
+                    None => self.get_alias(backend, &secret_config.aliases, &profile_name)?,
+                }
+            };
 
-            match backend.get(&self.config.project.name, &name, &profile_name)? {
-                Some(value) => {
-                    secrets.insert(name.clone(), value);
+            if !all_secrets.contains(&name) {
+                let value = value
+                    .and_then(|value| transform::apply_all(&secret_config.transform, value).ok())
+                    .or(default);
+                if let Some(value) = value {
+                    available.insert(name, value);
                 }
+                continue;
+            }
+
+            match value {
+                Some(value) => match transform::apply_all(&secret_config.transform, value) {
+                    Ok(value) => {
+                        if let Some(reason) = secret_config.check_value(&value) {
+                            invalid_values.push((name.clone(), reason));
+                        }
+                        if let Some(days) =
+                            self.days_since_due(backend, &name, &secret_config, &profile_name)
+                        {
+                            due_for_rotation.push((name.clone(), days));
+                        }
+                        available.insert(name.clone(), value.clone());
+                        secrets.insert(name.clone(), secret_value(value));
+                    }
+                    Err(e) => invalid_values.push((name.clone(), e.to_string())),
+                },
                 None => {
                     if let Some(default_value) = default {
-                        secrets.insert(name.clone(), default_value.clone());
-                        with_defaults.push((name.clone(), default_value));
+                        if let Some(reason) = secret_config.check_value(&default_value) {
+                            invalid_values.push((name.clone(), reason));
+                        }
+                        available.insert(name.clone(), default_value.clone());
+                        if self.resolve_defaults {
+                            secrets.insert(name.clone(), secret_value(default_value.clone()));
+                            with_defaults.push((name.clone(), default_value));
+                        } else {
+                            would_default.push((name.clone(), default_value));
+                        }
+                    } else if let Some(trigger) = secret_config.required_if {
+                        conditional.push((name.clone(), trigger));
                     } else if required {
                         missing_required.push(name.clone());
                     } else {
+                        if secret_config.recommended {
+                            missing_recommended.push(name.clone());
+                        }
                         missing_optional.push(name.clone());
                     }
                 }
             }
         }
 
-        // Check if there are any missing required secrets
-        if !missing_required.is_empty() {
-            Ok(Err(ValidationErrors::new(
-                missing_required,
-                missing_optional,
-                with_defaults,
-                backend.name().to_string(),
-                profile_name.to_string(),
-            )))
-        } else {
-            Ok(Ok(ValidatedSecrets {
-                resolved: Resolved::new(
-                    secrets,
-                    backend.name().to_string(),
-                    profile_name.to_string(),
-                ),
-                missing_optional,
-                with_defaults,
-            }))
+        // A conditionally required secret is only missing if its trigger resolved
+        let mut missing_conditional = Vec::new();
+        for (name, trigger) in conditional {
+            if secrets.contains_key(&trigger) {
+                missing_required.push(name.clone());
+                missing_conditional.push((name, trigger));
+            } else {
+                missing_optional.push(name);
+            }
+        }
+
+        // Optional values are loaded so they can trigger conditionally
+        // required secrets, then dropped if only required secrets are wanted
+        if self.filter.is_required_only() {
+            let required: HashSet<String> = secrets
+                .keys()
+                .filter(|name| {
+                    let config = self
+                        .resolve_secret_config(name, Some(&profile_name))
+                        .expect("Resolved secrets are declared in the config");
+                    config.required
+                        || config
+                            .required_if
+                            .is_some_and(|trigger| secrets.contains_key(&trigger))
+                })
+                .cloned()
+                .collect();
+            secrets.retain(|name, _| required.contains(name));
+            with_defaults.retain(|(name, _)| required.contains(name));
+            due_for_rotation.retain(|(name, _)| required.contains(name));
+            would_default.clear();
+            missing_optional.clear();
+            missing_recommended.clear();
+        }
+
+        // Secrets are resolved in dependency order; report them by name so
+        // the output is the same on every run
+        missing_required.sort();
+        missing_optional.sort();
+        missing_recommended.sort();
+        with_defaults.sort();
+        would_default.sort();
+        missing_conditional.sort();
+        invalid_values.sort();
+        due_for_rotation.sort();
+
+        Ok(ValidatedSecrets {
+            resolved: Resolved::new(secrets, backend.name().to_string(), profile_name),
+            missing_required,
+            missing_optional,
+            missing_recommended,
+            with_defaults,
+            would_default,
+            missing_conditional,
+            invalid_values,
+            extra,
+            resolved_from,
+            due_for_rotation,
+        })
+    }
+
+    /// Formats all resolved secrets for consumption by other tools
+    ///
+    /// This validates that all required secrets are present and renders them,
+    /// sorted by name, in the requested format. Nothing is printed; callers
+    /// decide where the output goes.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The output format, e.g. [`ExportFormat::Direnv`] for `.envrc`
+    ///
+    /// # Returns
+    ///
+    /// The formatted secrets, one per line
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Required secrets are missing
+    /// - The provider cannot be read
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::{ExportFormat, Secrets};
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// print!("{}", spec.to_env_string(ExportFormat::Direnv).unwrap());
+    /// ```
+    pub fn to_env_string(&self, format: ExportFormat) -> Result<String> {
+        let backend = self.get_provider(None, None)?;
+        let validation_result = self.ensure_secrets(backend.as_ref(), None, None)?;
+        Ok(export::format_secrets(
+            &validation_result.resolved.secrets,
+            format,
+        ))
+    }
+
+    /// Formats the declared secrets as a dotenv template, such as a `.env.example`
+    ///
+    /// Every secret of the selected profile that passes the filter is listed
+    /// with its description as a comment and its default, or a placeholder
+    /// derived from the description, as the value. The template is built from
+    /// the specification alone; the provider is never read.
+    ///
+    /// # Returns
+    ///
+    /// The template, sorted by secret name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist or the filter's group
+    /// has no secrets in it
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// std::fs::write(".env.example", spec.to_template_string().unwrap()).unwrap();
+    /// ```
+    pub fn to_template_string(&self) -> Result<String> {
+        let profile_name = self.resolve_profile(None);
+        let group = self.group_members(&profile_name)?;
+        let secrets: BTreeMap<String, Secret> = self
+            .declared_names(&profile_name)?
+            .into_iter()
+            .filter(|name| {
+                self.filter.matches(name) && group.as_ref().is_none_or(|group| group.contains(name))
+            })
+            .filter_map(|name| {
+                let secret = self.resolve_secret_config(&name, Some(&profile_name))?;
+                Some((name, secret))
+            })
+            .filter(|(_, secret)| {
+                !self.filter.is_required_only() || secret.required || secret.required_if.is_some()
+            })
+            .collect();
+        Ok(export::format_template(&secrets))
+    }
+
+    /// Writes the validated secrets to an env file for docker-compose
+    ///
+    /// The file is written in [`ExportFormat::Compose`], created with `0600`
+    /// permissions and replaced if it exists. A confirmation is printed to
+    /// stderr unless [`Verbosity::Quiet`] is set, followed by a warning when
+    /// the file is inside a git repository, since it should not be committed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write, e.g. the `env_file:` of a compose service
+    ///
+    /// # Returns
+    ///
+    /// The number of secrets written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Required secrets are missing
+    /// - A secret would use its default value and strict mode is enabled
+    /// - The provider cannot be read
+    /// - The file cannot be written
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// spec.write_env_file(std::path::Path::new(".env.compose")).unwrap();
+    /// ```
+    pub fn write_env_file(&self, path: &Path) -> Result<usize> {
+        let backend = self.get_provider(None, None)?;
+        Self::warn_if_profiles_unsupported(backend.as_ref(), &self.resolve_profile(None));
+
+        let validation_result = self.secrets_for_run()?;
+        let secrets = &validation_result.resolved.secrets;
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        // `mode` only applies to new files
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(export::format_secrets(secrets, ExportFormat::Compose).as_bytes())?;
+
+        if !self.quiet() {
+            eprintln!(
+                "{} Wrote {} secrets to {}",
+                "✓".green(),
+                secrets.len(),
+                path.display()
+            );
+        }
+        // Warn last, so nothing else is written once a reader such as
+        // `grep -q` has seen the warning and closed the pipe
+        if let Some(repository) = git_work_tree(path) {
+            eprintln!(
+                "{} {} is inside the git repository at {}; add it to .gitignore so it is not committed",
+                "Warning:".yellow(),
+                path.display(),
+                repository.display()
+            );
         }
+
+        Ok(secrets.len())
     }
 
     /// Runs a command with secrets injected as environment variables
@@ -927,7 +3389,9 @@ impl Secrets {
     ///
     /// Returns an error if:
     /// - No command is specified
+    /// - The working directory doesn't exist or isn't a directory
     /// - Required secrets are missing
+    /// - A secret would use its default value and strict mode is enabled
     /// - The command cannot be executed
     ///
     /// # Example
@@ -945,18 +3409,261 @@ impl Secrets {
                 "No command specified. Usage: secretspec run -- <command> [args...]",
             )));
         }
+        self.check_working_dir()?;
+
+        let backend = self.get_provider(None, None)?;
+        Self::warn_if_profiles_unsupported(backend.as_ref(), &self.resolve_profile(None));
 
         // Ensure all secrets are available (will error out if missing)
-        let validation_result = self.ensure_secrets(None, None, false)?;
+        let validation_result = self.secrets_for_run()?;
+
+        let status = self
+            .command_with_secrets(&command, validation_result.resolved.secrets)
+            .status()?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    /// Runs a command with secrets injected, restarting it when secrets change
+    ///
+    /// Like [`run`](Self::run), but watches the file backing the provider and,
+    /// whenever it changes, re-validates the secrets, kills the running child
+    /// and spawns it again with the new values. Bursts of writes are debounced
+    /// so that saving a file only triggers a single restart.
+    ///
+    /// If re-validation fails after a change, the error is reported and the
+    /// current child keeps running until the next change. If the child exits
+    /// on its own, secretspec keeps watching and starts it again on the next
+    /// change.
+    ///
+    /// Only file-backed providers (such as `dotenv`) can be watched.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command and arguments to run
+    ///
+    /// # Returns
+    ///
+    /// This method only returns when watching fails or on error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No command is specified
+    /// - The working directory doesn't exist or isn't a directory
+    /// - The provider is not backed by a file
+    /// - Required secrets are missing on startup
+    /// - A secret would use its default value and strict mode is enabled
+    /// - The command cannot be executed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let mut spec = Secrets::load().unwrap();
+    /// spec.set_provider("dotenv://.env");
+    /// spec.run_watch(vec!["npm".to_string(), "start".to_string()]).unwrap();
+    /// ```
+    pub fn run_watch(&self, command: Vec<String>) -> Result<()> {
+        if command.is_empty() {
+            return Err(SecretSpecError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No command specified. Usage: secretspec run --watch -- <command> [args...]",
+            )));
+        }
+        self.check_working_dir()?;
+
+        let backend = self.get_provider(None, None)?;
+        Self::warn_if_profiles_unsupported(backend.as_ref(), &self.resolve_profile(None));
+        let watch_paths = backend.watch_paths();
+        if watch_paths.is_empty() {
+            return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                "Provider '{}' does not support --watch; only file-backed providers such as dotenv can be watched",
+                backend.name()
+            ))));
+        }
+        let watched = watch_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let watch_error = |e: notify::Error| {
+            SecretSpecError::Provider(ProviderError::Other(format!(
+                "Failed to watch {}: {}",
+                watched, e
+            )))
+        };
+
+        // Watch the parent directories rather than the files themselves:
+        // editors often save by replacing the file, which would drop a file
+        // watch.
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+        let mut watch_dirs = HashSet::new();
+        for watch_path in &watch_paths {
+            let watch_dir = match watch_path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => Path::new(".").to_path_buf(),
+            };
+            if watch_dirs.insert(watch_dir.clone()) {
+                watcher
+                    .watch(&watch_dir, RecursiveMode::NonRecursive)
+                    .map_err(watch_error)?;
+            }
+        }
 
-        let mut env_vars = env::vars().collect::<HashMap<_, _>>();
-        env_vars.extend(validation_result.resolved.secrets);
+        let validation_result = self.secrets_for_run()?;
+        let mut child = Some(
+            self.command_with_secrets(&command, validation_result.resolved.secrets)
+                .spawn()?,
+        );
+
+        loop {
+            match rx.recv_timeout(WATCH_POLL_INTERVAL) {
+                Ok(event) => {
+                    let event = event.map_err(watch_error)?;
+                    if event.kind.is_access() {
+                        continue;
+                    }
+                    let Some(watch_path) = watch_paths.iter().find(|watch_path| {
+                        event
+                            .paths
+                            .iter()
+                            .any(|path| path.file_name() == watch_path.file_name())
+                    }) else {
+                        continue;
+                    };
+
+                    // Debounce: wait until no further events arrive
+                    while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+                    let secrets = match self.validate()? {
+                        Ok(valid_secrets) => {
+                            if let Err(e) = self.reject_defaults(&valid_secrets.with_defaults) {
+                                eprintln!(
+                                    "{} {} changed but secrets are invalid, not restarting: {}",
+                                    "✗".red(),
+                                    watch_path.display(),
+                                    e
+                                );
+                                continue;
+                            }
+                            valid_secrets.resolved.secrets
+                        }
+                        Err(errors) => {
+                            eprintln!(
+                                "{} {} changed but secrets are invalid, not restarting: {}",
+                                "✗".red(),
+                                watch_path.display(),
+                                errors
+                            );
+                            continue;
+                        }
+                    };
+                    self.print_timings();
+
+                    eprintln!(
+                        "{} {} changed, restarting {}",
+                        "↻".blue(),
+                        watch_path.display(),
+                        command[0]
+                    );
+                    if let Some(mut running) = child.take() {
+                        // The child may already have exited; that is fine
+                        let _ = running.kill();
+                        running.wait()?;
+                    }
+                    child = Some(self.command_with_secrets(&command, secrets).spawn()?);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Some(running) = child.as_mut()
+                        && let Some(status) = running.try_wait()?
+                    {
+                        eprintln!(
+                            "{} {} exited with {}, waiting for changes to {}",
+                            "○".yellow(),
+                            command[0],
+                            status,
+                            watched
+                        );
+                        child = None;
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(SecretSpecError::Provider(ProviderError::Other(format!(
+                        "Stopped watching {}",
+                        watched
+                    ))));
+                }
+            }
+        }
+    }
+
+    /// Validates the secrets to inject into a command or env file, rejecting
+    /// defaults in strict mode.
+    fn secrets_for_run(&self) -> Result<ValidatedSecrets> {
+        let backend = self.get_provider(None, None)?;
+        let validated = self.ensure_secrets(backend.as_ref(), None, None)?;
+        self.print_timings();
+        self.reject_defaults(&validated.with_defaults)?;
+        Self::warn_if_recommended_missing(&validated.missing_recommended);
+        Ok(validated)
+    }
+
+    /// Prints a warning to stderr naming the recommended secrets that have
+    /// no value
+    fn warn_if_recommended_missing(missing_recommended: &[String]) {
+        if !missing_recommended.is_empty() {
+            eprintln!(
+                "{} recommended secrets not set: {}",
+                "Warning:".yellow(),
+                missing_recommended.join(", ")
+            );
+        }
+    }
+
+    /// Returns `DefaultsNotAllowed` in strict mode if any secret uses its
+    /// default value.
+    fn reject_defaults(&self, with_defaults: &[(String, String)]) -> Result<()> {
+        if !self.strict || with_defaults.is_empty() {
+            return Ok(());
+        }
+        let mut names: Vec<String> = with_defaults.iter().map(|(name, _)| name.clone()).collect();
+        names.sort();
+        Err(SecretSpecError::DefaultsNotAllowed(names))
+    }
 
+    /// Builds the command to run with the resolved secrets added to the
+    /// current environment.
+    fn command_with_secrets(
+        &self,
+        command: &[String],
+        secrets: HashMap<String, SecretValue>,
+    ) -> Command {
         let mut cmd = Command::new(&command[0]);
         cmd.args(&command[1..]);
-        cmd.envs(&env_vars);
+        cmd.envs(env::vars());
+        cmd.envs(&secrets);
+        if let Some(dir) = &self.working_dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
 
-        let status = cmd.status()?;
-        std::process::exit(status.code().unwrap_or(1));
+    /// Returns an error unless the working directory set with
+    /// [`set_working_dir`](Self::set_working_dir), if any, is a directory
+    fn check_working_dir(&self) -> Result<()> {
+        let Some(dir) = &self.working_dir else {
+            return Ok(());
+        };
+        let problem = match std::fs::metadata(dir) {
+            Ok(metadata) if metadata.is_dir() => return Ok(()),
+            Ok(_) => "is not a directory",
+            Err(_) => "does not exist",
+        };
+        Err(SecretSpecError::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Working directory {} {}", dir.display(), problem),
+        )))
     }
 }
@@ -1,12 +1,13 @@
 use crate::config::{
-    Config, GlobalConfig, GlobalDefaults, ParseError, Profile, Project, Resolved, Secret,
+    Config, GlobalConfig, GlobalDefaults, LocalConfig, ParseError, Profile, Project, Resolved,
+    Secret,
 };
-use crate::error::{Result, SecretSpecError};
+use crate::error::{ProviderError, Result, SecretSpecError};
 use crate::secrets::Secrets;
-use crate::validation::{ValidatedSecrets, ValidationErrors};
+use crate::validation::{SecretFilter, ValidatedSecrets, ValidationErrors};
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 use tempfile::TempDir;
 
@@ -22,7 +23,7 @@ fn parse_spec_from_str(content: &str, _base_path: Option<&Path>) -> Result<Confi
         ));
     }
 
-    config.validate().map_err(|e| SecretSpecError::from(e))?;
+    config.validate().map_err(SecretSpecError::from)?;
 
     Ok(config)
 }
@@ -35,9 +36,11 @@ fn test_new_with_project_config() {
         project: Project {
             name: "test-project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: HashMap::new(),
+        secrets: HashMap::new(),
     };
 
     let spec = Secrets::new(config, None, None, None);
@@ -96,9 +99,11 @@ fn test_new_with_default_overrides() {
         project: Project {
             name: "test-project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: HashMap::new(),
+        secrets: HashMap::new(),
     };
 
     // Create a global config with specific defaults
@@ -106,6 +111,7 @@ fn test_new_with_default_overrides() {
         defaults: GlobalDefaults {
             provider: Some("dotenv".to_string()),
             profile: Some("production".to_string()),
+            ..Default::default()
         },
     };
 
@@ -221,8 +227,16 @@ fn test_validation_result_structure() {
     // Test ValidatedSecrets structure
     let valid_result = ValidatedSecrets {
         resolved: Resolved::new(HashMap::new(), "keyring".to_string(), "default".to_string()),
+        missing_required: Vec::new(),
         missing_optional: vec!["optional_secret".to_string()],
+        missing_recommended: Vec::new(),
         with_defaults: Vec::new(),
+        would_default: Vec::new(),
+        missing_conditional: Vec::new(),
+        invalid_values: Vec::new(),
+        extra: Vec::new(),
+        resolved_from: HashMap::new(),
+        due_for_rotation: Vec::new(),
     };
     assert_eq!(valid_result.missing_optional.len(), 1);
     assert_eq!(valid_result.with_defaults.len(), 0);
@@ -232,6 +246,8 @@ fn test_validation_result_structure() {
         vec!["required_secret".to_string()],
         vec!["optional_secret".to_string()],
         vec![],
+        vec![],
+        vec![],
         "keyring".to_string(),
         "default".to_string(),
     );
@@ -245,15 +261,18 @@ fn test_secretspec_new() {
         project: Project {
             name: "test".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: HashMap::new(),
+        secrets: HashMap::new(),
     };
 
     let global_config = GlobalConfig {
         defaults: GlobalDefaults {
             provider: Some("keyring".to_string()),
             profile: Some("dev".to_string()),
+            ..Default::default()
         },
     };
 
@@ -275,6 +294,7 @@ fn test_resolve_profile() {
         defaults: GlobalDefaults {
             provider: Some("keyring".to_string()),
             profile: Some("development".to_string()),
+            ..Default::default()
         },
     };
 
@@ -283,9 +303,11 @@ fn test_resolve_profile() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: HashMap::new(),
+            secrets: HashMap::new(),
         },
         Some(global_config),
         None,
@@ -304,9 +326,11 @@ fn test_resolve_profile() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: HashMap::new(),
+            secrets: HashMap::new(),
         },
         None,
         None,
@@ -324,6 +348,7 @@ fn test_resolve_secret_config() {
             description: Some("API Key".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         },
     );
     default_secrets.insert(
@@ -332,6 +357,7 @@ fn test_resolve_secret_config() {
             description: Some("Database URL".to_string()),
             required: false,
             default: Some("sqlite:///default.db".to_string()),
+            ..Default::default()
         },
     );
 
@@ -342,6 +368,7 @@ fn test_resolve_secret_config() {
             description: Some("Dev API Key".to_string()),
             required: false,
             default: Some("dev-key".to_string()),
+            ..Default::default()
         },
     );
 
@@ -350,12 +377,14 @@ fn test_resolve_secret_config() {
         "default".to_string(),
         Profile {
             secrets: default_secrets,
+            ..Default::default()
         },
     );
     profiles.insert(
         "development".to_string(),
         Profile {
             secrets: dev_secrets,
+            ..Default::default()
         },
     );
 
@@ -364,9 +393,11 @@ fn test_resolve_secret_config() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         },
         None,
         None,
@@ -404,9 +435,11 @@ fn test_get_provider_error_cases() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: HashMap::new(),
+            secrets: HashMap::new(),
         },
         None,
         None,
@@ -414,7 +447,7 @@ fn test_get_provider_error_cases() {
     );
 
     // Test with no provider configured
-    let result = spec.get_provider(None);
+    let result = spec.get_provider(None, None);
     assert!(matches!(result, Err(SecretSpecError::NoProviderConfigured)));
 }
 
@@ -424,6 +457,7 @@ fn test_get_provider_with_global_config() {
         defaults: GlobalDefaults {
             provider: Some("keyring".to_string()),
             profile: None,
+            ..Default::default()
         },
     };
 
@@ -432,9 +466,11 @@ fn test_get_provider_with_global_config() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: HashMap::new(),
+            secrets: HashMap::new(),
         },
         Some(global_config),
         None,
@@ -442,7 +478,7 @@ fn test_get_provider_with_global_config() {
     );
 
     // Should not error with global config
-    let result = spec.get_provider(None);
+    let result = spec.get_provider(None, None);
     assert!(result.is_ok());
 }
 
@@ -461,6 +497,79 @@ fn test_project_config_from_path_error_handling() {
     assert!(matches!(result, Err(SecretSpecError::NoManifest)));
 }
 
+#[test]
+fn test_load_without_profiles_is_a_clear_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest = temp_dir.path().join("secretspec.toml");
+    fs::write(
+        &manifest,
+        "[project]\nname = \"test\"\nrevision = \"1.0\"\n",
+    )
+    .unwrap();
+
+    let error = match Secrets::load_manifest(&manifest, None, None) {
+        Err(error) => error,
+        Ok(_) => panic!("Expected a config without profiles to be rejected"),
+    };
+    assert!(matches!(error, SecretSpecError::NoProfiles));
+    let message = error.to_string();
+    assert!(message.contains("defines no profiles"), "{}", message);
+    assert!(message.contains("[profiles.default]"), "{}", message);
+
+    // Shared secrets alone create the default profile
+    fs::write(
+        &manifest,
+        "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[secrets]\nAPI_KEY = { description = \"API key\" }\n",
+    )
+    .unwrap();
+    assert!(Secrets::load_manifest(&manifest, None, None).is_ok());
+}
+
+#[test]
+fn test_load_accepts_profiles_that_only_set_a_provider() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_load_accepts_profiles_that_only_set_a_provider",
+        "test",
+        "production",
+        [("API_KEY", "production-key")],
+    );
+    let temp_dir = TempDir::new().unwrap();
+    let manifest = temp_dir.path().join("secretspec.toml");
+    fs::write(
+        &manifest,
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.production]
+provider = "memory://test_load_accepts_profiles_that_only_set_a_provider"
+"#,
+    )
+    .unwrap();
+
+    let mut spec = Secrets::load_manifest(&manifest, None, None).unwrap();
+    spec.set_profile("production");
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        validated.resolved.secrets["API_KEY"].as_str(),
+        "production-key"
+    );
+
+    // `default` itself still needs a secret
+    fs::write(
+        &manifest,
+        "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nprovider = \"env\"\n",
+    )
+    .unwrap();
+    assert!(Secrets::load_manifest(&manifest, None, None).is_err());
+}
+
 #[test]
 fn test_parse_spec_from_str() {
     let valid_toml = r#"
@@ -483,6 +592,71 @@ API_KEY = { description = "API Key", required = true }
     assert!(matches!(result, Err(SecretSpecError::Toml(_))));
 }
 
+#[test]
+fn test_merge_report_tracks_provenance() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path();
+    fs::create_dir_all(base_path.join("common")).unwrap();
+    fs::create_dir_all(base_path.join("base")).unwrap();
+
+    fs::write(
+        base_path.join("common/secretspec.toml"),
+        r#"
+[project]
+name = "common"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database connection string", required = true }
+REDIS_URL = { description = "Redis connection URL", required = false }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        base_path.join("base/secretspec.toml"),
+        r#"
+[project]
+name = "base"
+revision = "1.0"
+extends = ["../common"]
+
+[profiles.default]
+DATABASE_URL = { description = "Database connection string", required = false }
+"#,
+    )
+    .unwrap();
+
+    let base_file = base_path.join("base/secretspec.toml");
+    let common_file = base_path.join("base/../common/secretspec.toml");
+    let report = Config::merge_report(&base_file, None).unwrap();
+    let default = &report.profiles["default"];
+
+    // Overridden: the base definition wins over the one in ../common
+    assert_eq!(
+        default["DATABASE_URL"].source,
+        base_file.display().to_string()
+    );
+    assert_eq!(
+        default["DATABASE_URL"].overridden,
+        vec![common_file.display().to_string()]
+    );
+
+    // Inherited: only ../common defines it
+    assert_eq!(
+        default["REDIS_URL"].source,
+        common_file.display().to_string()
+    );
+    assert!(default["REDIS_URL"].overridden.is_empty());
+
+    let text = report.to_string();
+    assert!(text.contains("[profiles.default]"));
+    assert!(text.contains(&format!(
+        "DATABASE_URL  {} (overrides {})",
+        base_file.display(),
+        common_file.display()
+    )));
+}
+
 #[test]
 fn test_extends_with_real_world_example() {
     // Test a real-world scenario with multiple extends and profile overrides
@@ -1073,7 +1247,7 @@ revision = "1.0"
 
 [profiles.default]
 SECRET_A = { description = "Original description A", required = true }
-SECRET_B = { description = "Original description B", required = true, default = "original-b" }
+SECRET_B = { description = "Original description B", required = true }
 SECRET_C = { description = "Original description C", required = false }
 SECRET_D = { description = "Original description D", required = false, default = "original-d" }
 "#;
@@ -1089,7 +1263,7 @@ extends = ["../base"]
 [profiles.default]
 # Override just description
 SECRET_A = { description = "New description A", required = true }
-# Override just required flag
+# Override the required flag, which a default needs
 SECRET_B = { description = "Original description B", required = false, default = "original-b" }
 # Override just default value
 SECRET_C = { description = "Original description C", required = false, default = "new-c" }
@@ -1110,7 +1284,7 @@ SECRET_E = { description = "New secret E", required = true }
     assert!(secret_a.required);
     assert_eq!(secret_a.default, None);
 
-    // Verify SECRET_B: only required flag changed
+    // Verify SECRET_B: required flag changed and default added
     let secret_b = default_profile.secrets.get("SECRET_B").unwrap();
     assert_eq!(
         secret_b.description,
@@ -1169,6 +1343,86 @@ API_KEY = { description = "API key for external service", required = true }
     }
 }
 
+#[test]
+fn test_remote_extends_parsing() {
+    use crate::remote::RemoteSpec;
+
+    assert_eq!(
+        RemoteSpec::parse("git+https://github.com/org/shared.git#v1.0"),
+        Some(RemoteSpec::Git {
+            url: "https://github.com/org/shared.git".to_string(),
+            reference: Some("v1.0".to_string()),
+        })
+    );
+    let without_ref = RemoteSpec::parse("git+https://github.com/org/shared.git").unwrap();
+    assert_eq!(
+        without_ref.key(),
+        "git+https://github.com/org/shared.git#HEAD"
+    );
+    assert_eq!(
+        RemoteSpec::parse("https://example.com/secretspec.toml"),
+        Some(RemoteSpec::Http {
+            url: "https://example.com/secretspec.toml".to_string(),
+        })
+    );
+
+    // Local paths are not remote
+    assert_eq!(RemoteSpec::parse("../shared"), None);
+    assert_eq!(RemoteSpec::parse("./https"), None);
+
+    // Values git would read as options are rejected
+    for entry in [
+        "git+--upload-pack=touch /tmp/pwned",
+        "git+https://github.com/org/shared.git#--upload-pack=touch /tmp/pwned",
+    ] {
+        assert!(matches!(
+            RemoteSpec::parse(entry).unwrap().validate(),
+            Err(ParseError::Remote(_))
+        ));
+    }
+    assert!(without_ref.validate().is_ok());
+
+    // Only HTTPS is accepted
+    for entry in [
+        "http://example.com/secretspec.toml",
+        "git+http://github.com/org/shared.git#v1.0",
+        "git+ssh://git@github.com/org/shared.git",
+        "git+ext::sh -c touch% /tmp/pwned",
+    ] {
+        match RemoteSpec::parse(entry).unwrap().validate() {
+            Err(ParseError::Remote(msg)) => assert!(msg.contains("only https://")),
+            other => panic!("Expected {} to be rejected, got {:?}", entry, other),
+        }
+    }
+    assert!(
+        RemoteSpec::parse("https://example.com/secretspec.toml")
+            .unwrap()
+            .validate()
+            .is_ok()
+    );
+}
+
+#[cfg(not(feature = "remote-extends"))]
+#[test]
+fn test_remote_extends_requires_feature() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = r#"
+[project]
+name = "test_project"
+revision = "1.0"
+extends = ["git+https://example.com/shared.git#main"]
+
+[profiles.default]
+API_KEY = { description = "API key", required = true }
+"#;
+    fs::write(temp_dir.path().join("secretspec.toml"), config).unwrap();
+
+    match Config::try_from(temp_dir.path().join("secretspec.toml").as_path()) {
+        Err(ParseError::Remote(msg)) => assert!(msg.contains("remote-extends")),
+        other => panic!("Expected remote error, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_extends_with_invalid_inputs() {
     let temp_dir = TempDir::new().unwrap();
@@ -1277,12 +1531,67 @@ NEW_SECRET = { description = "New secret", required = true }
     }
 }
 
+#[test]
+fn test_allowed_revision_is_read_anyway() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path();
+    fs::create_dir_all(base_path.join("shared")).unwrap();
+    fs::write(
+        base_path.join("shared/secretspec.toml"),
+        "[project]\nname = \"shared\"\nrevision = \"2.0\"\n\n[profiles.default]\nSHARED_SECRET = { description = \"Shared secret\" }\n",
+    )
+    .unwrap();
+    let manifest = base_path.join("secretspec.toml");
+    fs::write(
+        &manifest,
+        "[project]\nname = \"app\"\nrevision = \"2.0\"\nextends = [\"./shared\"]\n\n[profiles.default]\nAPP_SECRET = { description = \"App secret\" }\n",
+    )
+    .unwrap();
+
+    // Without the allowance the revision is still a hard error
+    match Config::from_path_allowing_revision(&manifest, None) {
+        Err(ParseError::UnsupportedRevision(rev)) => assert_eq!(rev, "2.0"),
+        _ => panic!("Expected UnsupportedRevision error"),
+    }
+    assert!(matches!(
+        Config::from_path_allowing_revision(&manifest, Some("3.0")),
+        Err(ParseError::UnsupportedRevision(_))
+    ));
+
+    // The allowance also covers the configurations it extends
+    let config = Config::from_path_allowing_revision(&manifest, Some("2.0")).unwrap();
+    let secrets = &config.profiles["default"].secrets;
+    assert!(secrets.contains_key("APP_SECRET"));
+    assert!(secrets.contains_key("SHARED_SECRET"));
+
+    // Each file read anyway is reported to the caller instead of printed
+    let (_, report) = Config::from_path_with_report(&manifest, Some("2.0")).unwrap();
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].starts_with(&manifest.display().to_string()));
+    assert!(report.warnings[1].contains("shared"));
+    assert!(
+        report
+            .warnings
+            .iter()
+            .all(|warning| warning.contains("has revision '2.0'"))
+    );
+    let spec = Secrets::load_manifest(&manifest, None, Some("2.0")).unwrap();
+    assert_eq!(spec.warnings(), report.warnings.as_slice());
+
+    // Parsing a string never reads an allowance from the environment
+    assert!(matches!(
+        fs::read_to_string(&manifest).unwrap().parse::<Config>(),
+        Err(ParseError::UnsupportedRevision(_))
+    ));
+}
+
 #[test]
 fn test_set_with_undefined_secret() {
     let project_config = Config {
         project: Project {
             name: "test_project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1294,34 +1603,44 @@ fn test_set_with_undefined_secret() {
                     description: Some("A defined secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
+                },
+            );
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
                 },
             );
-            profiles.insert("default".to_string(), Profile { secrets });
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     let global_config = GlobalConfig {
         defaults: GlobalDefaults {
             provider: Some("env".to_string()),
             profile: None,
+            ..Default::default()
         },
     };
 
     let spec = Secrets::new(project_config, Some(global_config), None, None);
 
     // Test setting an undefined secret - env provider is read-only,
-    // but we should get the SecretNotFound error before the provider error
+    // but we should get the UndeclaredSecret error before the provider error
     let result = spec.set("UNDEFINED_SECRET", Some("test_value".to_string()));
 
     assert!(result.is_err());
     match result {
-        Err(SecretSpecError::SecretNotFound(msg)) => {
+        Err(error @ SecretSpecError::UndeclaredSecret(..)) => {
+            let msg = error.to_string();
             assert!(msg.contains("UNDEFINED_SECRET"));
             assert!(msg.contains("not defined in profile"));
             assert!(msg.contains("DEFINED_SECRET"));
         }
-        _ => panic!("Expected SecretNotFound error"),
+        _ => panic!("Expected UndeclaredSecret error"),
     }
 }
 
@@ -1339,6 +1658,7 @@ fn test_set_with_defined_secret() {
         project: Project {
             name: "test_project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1350,17 +1670,26 @@ fn test_set_with_defined_secret() {
                     description: Some("A defined secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
+                },
+            );
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
                 },
             );
-            profiles.insert("default".to_string(), Profile { secrets });
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     let global_config = GlobalConfig {
         defaults: GlobalDefaults {
             provider: Some("dotenv".to_string()),
             profile: None,
+            ..Default::default()
         },
     };
 
@@ -1382,6 +1711,7 @@ fn test_set_with_readonly_provider() {
         project: Project {
             name: "test_project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1393,17 +1723,26 @@ fn test_set_with_readonly_provider() {
                     description: Some("A defined secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
+                },
+            );
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
                 },
             );
-            profiles.insert("default".to_string(), Profile { secrets });
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     let global_config = GlobalConfig {
         defaults: GlobalDefaults {
             provider: Some("env".to_string()),
             profile: None,
+            ..Default::default()
         },
     };
 
@@ -1414,10 +1753,14 @@ fn test_set_with_readonly_provider() {
 
     assert!(result.is_err());
     match result {
-        Err(SecretSpecError::ProviderOperationFailed(msg)) => {
-            assert!(msg.contains("read-only"));
+        Err(SecretSpecError::Provider(ProviderError::ReadOnly {
+            provider,
+            operation,
+        })) => {
+            assert_eq!(provider, "env");
+            assert_eq!(operation, "set");
         }
-        _ => panic!("Expected ProviderOperationFailed error for read-only provider"),
+        _ => panic!("Expected ProviderError::ReadOnly for read-only provider"),
     }
 }
 
@@ -1432,6 +1775,7 @@ fn test_import_between_dotenv_files() {
         project: Project {
             name: "test_import_project".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1445,6 +1789,7 @@ fn test_import_between_dotenv_files() {
                     description: Some("First test secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             secrets.insert(
@@ -1453,6 +1798,7 @@ fn test_import_between_dotenv_files() {
                     description: Some("Second test secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             secrets.insert(
@@ -1461,6 +1807,7 @@ fn test_import_between_dotenv_files() {
                     description: Some("Third test secret".to_string()),
                     required: false,
                     default: Some("default_value".to_string()),
+                    ..Default::default()
                 },
             );
             secrets.insert(
@@ -1469,12 +1816,20 @@ fn test_import_between_dotenv_files() {
                     description: Some("Fourth test secret (not in source)".to_string()),
                     required: false,
                     default: None,
+                    ..Default::default()
                 },
             );
 
-            profiles.insert("default".to_string(), Profile { secrets });
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
+                },
+            );
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     // Create source .env file
@@ -1494,6 +1849,7 @@ fn test_import_between_dotenv_files() {
         defaults: GlobalDefaults {
             provider: Some(format!("dotenv://{}", target_env_path.display())),
             profile: Some("default".to_string()),
+            ..Default::default()
         },
     };
 
@@ -1532,11 +1888,11 @@ fn test_import_between_dotenv_files() {
 
     // SECRET_THREE and SECRET_FOUR should not be in the file
     assert!(
-        vars.get("SECRET_THREE").is_none(),
+        !vars.contains_key("SECRET_THREE"),
         "SECRET_THREE should not be imported (not in source)"
     );
     assert!(
-        vars.get("SECRET_FOUR").is_none(),
+        !vars.contains_key("SECRET_FOUR"),
         "SECRET_FOUR should not be imported (not in source)"
     );
 }
@@ -1551,6 +1907,7 @@ fn test_import_edge_cases() {
         project: Project {
             name: "test_edge_cases".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1563,6 +1920,7 @@ fn test_import_edge_cases() {
                     description: Some("Secret with empty value".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             secrets.insert(
@@ -1571,6 +1929,7 @@ fn test_import_edge_cases() {
                     description: Some("Secret with special characters".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             secrets.insert(
@@ -1579,12 +1938,20 @@ fn test_import_edge_cases() {
                     description: Some("Secret with multiline value".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
 
-            profiles.insert("default".to_string(), Profile { secrets });
+            profiles.insert(
+                "default".to_string(),
+                Profile {
+                    secrets,
+                    ..Default::default()
+                },
+            );
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     // Create source .env file with edge case values
@@ -1604,6 +1971,7 @@ fn test_import_edge_cases() {
         defaults: GlobalDefaults {
             provider: Some(format!("dotenv://{}", target_env_path.display())),
             profile: Some("default".to_string()),
+            ..Default::default()
         },
     };
 
@@ -1664,12 +2032,12 @@ name = "test-no-merge"
 revision = "1.0"
 
 [profiles.default]
-DATABASE_URL = { description = "Default database connection", required = true, default = "postgres://localhost/default" }
+DATABASE_URL = { description = "Default database connection", required = false, default = "postgres://localhost/default" }
 API_KEY = { description = "API key for services", required = true }
 CACHE_TTL = { description = "Cache time to live", required = false, default = "3600" }
 
 [profiles.development]
-DATABASE_URL = { description = "Dev database connection", required = true, default = "postgres://localhost/dev" }
+DATABASE_URL = { description = "Dev database connection", required = false, default = "postgres://localhost/dev" }
 API_KEY = { description = "Dev API key", required = true }
 # Note: CACHE_TTL is NOT defined in development profile
 "#;
@@ -1683,6 +2051,7 @@ API_KEY = { description = "Dev API key", required = true }
         defaults: GlobalDefaults {
             provider: Some("env".to_string()),
             profile: None,
+            ..Default::default()
         },
     };
 
@@ -1694,7 +2063,7 @@ API_KEY = { description = "Dev API key", required = true }
     let secret_config = spec
         .resolve_secret_config("DATABASE_URL", Some("default"))
         .expect("DATABASE_URL should exist in default");
-    assert!(secret_config.required);
+    assert!(!secret_config.required);
     assert_eq!(
         secret_config.default,
         Some("postgres://localhost/default".to_string())
@@ -1704,7 +2073,7 @@ API_KEY = { description = "Dev API key", required = true }
     let secret_config = spec
         .resolve_secret_config("DATABASE_URL", Some("development"))
         .expect("DATABASE_URL should exist in development");
-    assert!(secret_config.required);
+    assert!(!secret_config.required);
     assert_eq!(
         secret_config.default,
         Some("postgres://localhost/dev".to_string())
@@ -1766,6 +2135,92 @@ API_KEY = { description = "Dev API key", required = true }
     );
 }
 
+#[test]
+fn test_resolved_from_names_the_defining_profile() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_resolved_from_names_the_defining_profile",
+        "test",
+        "production",
+        [("API_KEY", "prod-key"), ("SENTRY_DSN", "dsn")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+SENTRY_DSN = { description = "Sentry DSN", required = false }
+
+[profiles.production]
+API_KEY = { description = "Production API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_resolved_from_names_the_defining_profile".to_string()),
+        Some("production".to_string()),
+    );
+
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved_from["API_KEY"], "production");
+    assert_eq!(validated.resolved_from["SENTRY_DSN"], "default");
+}
+
+#[test]
+fn test_audit_snapshot_leaves_out_values() {
+    use crate::{AuditStatus, MemoryProvider};
+
+    MemoryProvider::with_secrets(
+        "test_audit_snapshot_leaves_out_values",
+        "test",
+        "default",
+        [("API_KEY", "stored-api-key")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+LOG_LEVEL = { description = "Log level", default = "default-log-level" }
+DATABASE_URL = { description = "Database URL" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_audit_snapshot_leaves_out_values".to_string()),
+        None,
+    );
+
+    let snapshot = spec
+        .check_result(None, None)
+        .unwrap()
+        .audit_snapshot("test");
+    assert_eq!(snapshot.project, "test");
+    assert_eq!(snapshot.profile, "default");
+    assert_eq!(snapshot.provider, "memory");
+    assert_eq!(snapshot.secrets["API_KEY"], AuditStatus::Present);
+    assert_eq!(snapshot.secrets["LOG_LEVEL"], AuditStatus::Defaulted);
+    assert_eq!(snapshot.secrets["DATABASE_URL"], AuditStatus::Missing);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert!(json.contains(r#""LOG_LEVEL":"defaulted""#), "{}", json);
+    assert!(!json.contains("stored-api-key"), "{}", json);
+    assert!(!json.contains("default-log-level"), "{}", json);
+}
+
 #[test]
 fn test_import_with_profiles() {
     let temp_dir = TempDir::new().unwrap();
@@ -1776,6 +2231,7 @@ fn test_import_with_profiles() {
         project: Project {
             name: "test_profiles".to_string(),
             revision: "1.0".to_string(),
+            description: None,
             extends: None,
         },
         profiles: {
@@ -1789,6 +2245,7 @@ fn test_import_with_profiles() {
                     description: Some("Development secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             dev_secrets.insert(
@@ -1797,12 +2254,14 @@ fn test_import_with_profiles() {
                     description: Some("Shared secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             profiles.insert(
                 "development".to_string(),
                 Profile {
                     secrets: dev_secrets,
+                    ..Default::default()
                 },
             );
 
@@ -1814,6 +2273,7 @@ fn test_import_with_profiles() {
                     description: Some("Production secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             prod_secrets.insert(
@@ -1822,17 +2282,20 @@ fn test_import_with_profiles() {
                     description: Some("Shared secret".to_string()),
                     required: true,
                     default: None,
+                    ..Default::default()
                 },
             );
             profiles.insert(
                 "production".to_string(),
                 Profile {
                     secrets: prod_secrets,
+                    ..Default::default()
                 },
             );
 
             profiles
         },
+        secrets: HashMap::new(),
     };
 
     // Create source .env file with all secrets
@@ -1852,6 +2315,7 @@ fn test_import_with_profiles() {
         defaults: GlobalDefaults {
             provider: Some(format!("dotenv://{}", target_env_path.display())),
             profile: Some("development".to_string()), // Use development profile
+            ..Default::default()
         },
     };
 
@@ -1885,7 +2349,7 @@ fn test_import_with_profiles() {
         "Shared secret should be imported for development profile"
     );
     assert!(
-        vars.get("PROD_SECRET").is_none(),
+        !vars.contains_key("PROD_SECRET"),
         "Production secret should not be imported when using development profile"
     );
 }
@@ -1901,14 +2365,17 @@ fn test_run_with_empty_command() {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles: HashMap::new(),
+            secrets: HashMap::new(),
         },
         Some(GlobalConfig {
             defaults: GlobalDefaults {
                 provider: Some(format!("dotenv://{}", env_file.display())),
                 profile: None,
+                ..Default::default()
             },
         }),
         None,
@@ -1927,6 +2394,40 @@ fn test_run_with_empty_command() {
     }
 }
 
+#[test]
+fn test_run_rejects_a_missing_working_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file");
+    fs::write(&file, "").unwrap();
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test_run_rejects_a_missing_working_dir"
+revision = "1.0"
+
+[profiles.default]
+KEY = { description = "Key", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(config, None, Some("memory://working_dir".into()), None);
+
+    for (dir, problem) in [
+        (temp_dir.path().join("missing"), "does not exist"),
+        (file, "is not a directory"),
+    ] {
+        spec.set_working_dir(&dir);
+        match spec.run(vec!["pwd".to_string()]) {
+            Err(SecretSpecError::Io(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+                assert!(e.to_string().contains(problem), "{}", e);
+            }
+            _ => panic!("Expected IO InvalidInput error"),
+        }
+    }
+}
+
 #[test]
 fn test_run_with_missing_required_secrets() {
     let temp_dir = TempDir::new().unwrap();
@@ -1941,25 +2442,35 @@ fn test_run_with_missing_required_secrets() {
             description: Some("A required secret".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         },
     );
 
     let mut profiles = HashMap::new();
-    profiles.insert("default".to_string(), Profile { secrets });
+    profiles.insert(
+        "default".to_string(),
+        Profile {
+            secrets,
+            ..Default::default()
+        },
+    );
 
     let spec = Secrets::new(
         Config {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         },
         Some(GlobalConfig {
             defaults: GlobalDefaults {
                 provider: Some(format!("dotenv://{}", env_file.display())),
                 profile: None,
+                ..Default::default()
             },
         }),
         None,
@@ -1990,25 +2501,35 @@ fn test_get_existing_secret() {
             description: Some("Test secret".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         },
     );
 
     let mut profiles = HashMap::new();
-    profiles.insert("default".to_string(), Profile { secrets });
+    profiles.insert(
+        "default".to_string(),
+        Profile {
+            secrets,
+            ..Default::default()
+        },
+    );
 
     let spec = Secrets::new(
         Config {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         },
         Some(GlobalConfig {
             defaults: GlobalDefaults {
                 provider: Some(format!("dotenv://{}", env_file.display())),
                 profile: None,
+                ..Default::default()
             },
         }),
         None,
@@ -2033,25 +2554,35 @@ fn test_get_secret_with_default() {
             description: Some("Secret with default value".to_string()),
             required: false,
             default: Some("default_value".to_string()),
+            ..Default::default()
         },
     );
 
     let mut profiles = HashMap::new();
-    profiles.insert("default".to_string(), Profile { secrets });
+    profiles.insert(
+        "default".to_string(),
+        Profile {
+            secrets,
+            ..Default::default()
+        },
+    );
 
     let spec = Secrets::new(
         Config {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         },
         Some(GlobalConfig {
             defaults: GlobalDefaults {
                 provider: Some(format!("dotenv://{}", env_file.display())),
                 profile: None,
+                ..Default::default()
             },
         }),
         None,
@@ -2075,25 +2606,35 @@ fn test_get_nonexistent_secret() {
             description: Some("Existing secret".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         },
     );
 
     let mut profiles = HashMap::new();
-    profiles.insert("default".to_string(), Profile { secrets });
+    profiles.insert(
+        "default".to_string(),
+        Profile {
+            secrets,
+            ..Default::default()
+        },
+    );
 
     let spec = Secrets::new(
         Config {
             project: Project {
                 name: "test".to_string(),
                 revision: "1.0".to_string(),
+                description: None,
                 extends: None,
             },
             profiles,
+            secrets: HashMap::new(),
         },
         Some(GlobalConfig {
             defaults: GlobalDefaults {
                 provider: Some(format!("dotenv://{}", env_file.display())),
                 profile: None,
+                ..Default::default()
             },
         }),
         None,
@@ -2104,9 +2645,3289 @@ fn test_get_nonexistent_secret() {
     assert!(result.is_err());
 
     match result {
-        Err(SecretSpecError::SecretNotFound(msg)) => {
-            assert!(msg.contains("NONEXISTENT_SECRET"));
+        Err(SecretSpecError::UndeclaredSecret(name, ..)) => {
+            assert_eq!(name, "NONEXISTENT_SECRET");
         }
-        _ => panic!("Expected SecretNotFound error"),
+        _ => panic!("Expected UndeclaredSecret error"),
     }
 }
+
+/// Loads a specification from `toml` that reads its secrets from `env_file`
+fn dotenv_spec(toml: &str, env_file: &Path) -> Secrets {
+    Secrets::new(
+        parse_spec_from_str(toml, None).unwrap(),
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    )
+}
+
+fn required_if_spec(env_file: &Path) -> Secrets {
+    dotenv_spec(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+OAUTH_CLIENT_ID = { description = "OAuth client ID", required = false }
+OAUTH_CLIENT_SECRET = { description = "OAuth client secret", required_if = "OAUTH_CLIENT_ID" }
+"#,
+        env_file,
+    )
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_validated_values_are_zeroizing() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "DATABASE_URL=postgres://localhost\n").unwrap();
+
+    let spec = dotenv_spec(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database" }
+LOG_LEVEL = { description = "Log level", default = "info" }
+"#,
+        &env_file,
+    );
+    let validated = spec.validate().unwrap().unwrap();
+    let secrets: HashMap<String, crate::Zeroizing<String>> = validated.resolved.secrets;
+    assert_eq!(secrets["DATABASE_URL"].as_str(), "postgres://localhost");
+    assert_eq!(secrets["LOG_LEVEL"].as_str(), "info");
+}
+
+#[test]
+fn test_required_if_triggered() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "OAUTH_CLIENT_ID=client\n").unwrap();
+
+    let spec = required_if_spec(&env_file);
+    let errors = match spec.validate().unwrap() {
+        Err(errors) => errors,
+        Ok(_) => panic!("Expected OAUTH_CLIENT_SECRET to be required"),
+    };
+
+    assert_eq!(errors.missing_required, vec!["OAUTH_CLIENT_SECRET"]);
+    assert_eq!(
+        errors.missing_conditional,
+        vec![(
+            "OAUTH_CLIENT_SECRET".to_string(),
+            "OAUTH_CLIENT_ID".to_string()
+        )]
+    );
+    assert!(
+        errors
+            .to_string()
+            .contains("OAUTH_CLIENT_SECRET (required because OAUTH_CLIENT_ID is set)")
+    );
+}
+
+#[test]
+fn test_required_if_not_triggered() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "").unwrap();
+
+    let spec = required_if_spec(&env_file);
+    let validated = match spec.validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+
+    assert!(
+        validated
+            .missing_optional
+            .contains(&"OAUTH_CLIENT_SECRET".to_string())
+    );
+}
+
+#[test]
+fn test_required_if_cannot_reference_itself() {
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+TOKEN = { description = "Token", required_if = "TOKEN" }
+"#,
+        None,
+    );
+
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("required_if cannot reference itself")
+    );
+}
+
+#[test]
+fn test_required_if_must_reference_a_declared_secret() {
+    let parse = |profiles: &str| {
+        parse_spec_from_str(
+            &format!(
+                "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n{}",
+                profiles
+            ),
+            None,
+        )
+    };
+
+    let error = parse(
+        r#"
+[profiles.default]
+OAUTH_CLIENT_SECRET = { description = "OAuth client secret", required_if = "OAUTH_CLIENT_ID" }
+"#,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("required_if references undeclared secret 'OAUTH_CLIENT_ID'"),
+        "{}",
+        error
+    );
+
+    // A trigger inherited from the default profile is declared
+    parse(
+        r#"
+[profiles.default]
+OAUTH_CLIENT_ID = { description = "OAuth client ID", required = false }
+
+[profiles.production]
+OAUTH_CLIENT_SECRET = { description = "OAuth client secret", required_if = "OAUTH_CLIENT_ID" }
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_validate_reports_undeclared_provider_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        "OAUTH_CLIENT_ID=client\nOAUTH_CLEINT_SECRET=typo\n",
+    )
+    .unwrap();
+
+    let spec = required_if_spec(&env_file);
+    let errors = match spec.validate().unwrap() {
+        Err(errors) => errors,
+        Ok(_) => panic!("Expected OAUTH_CLIENT_SECRET to be missing"),
+    };
+
+    assert_eq!(errors.extra, vec!["OAUTH_CLEINT_SECRET"]);
+}
+
+#[test]
+fn test_validate_extra_empty_without_enumeration() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+SECRETSPEC_TEST_OPTIONAL = { description = "Optional secret", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, Some("env".to_string()), None);
+
+    let validated = match spec.validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+    assert!(validated.extra.is_empty());
+}
+
+#[test]
+fn test_run_watch_requires_file_backed_provider() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+SECRETSPEC_TEST_OPTIONAL = { description = "Optional secret", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, Some("env".to_string()), None);
+
+    match spec.run_watch(vec!["true".to_string()]) {
+        Err(SecretSpecError::Provider(ProviderError::Other(msg))) => {
+            assert!(msg.contains("does not support --watch"));
+        }
+        other => panic!("Expected ProviderError::Other, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_dotenv_watch_paths() {
+    let provider = Box::<dyn crate::provider::Provider>::try_from("dotenv://.env.local").unwrap();
+    assert_eq!(provider.watch_paths(), vec![PathBuf::from(".env.local")]);
+
+    let provider = Box::<dyn crate::provider::Provider>::try_from("env://").unwrap();
+    assert!(provider.watch_paths().is_empty());
+}
+
+fn filter_spec(env_file: &Path) -> Secrets {
+    dotenv_spec(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database", required = true }
+STRIPE_KEY = { description = "Stripe", required = true }
+"#,
+        env_file,
+    )
+}
+
+#[test]
+fn test_filter_only_ignores_other_required_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "DATABASE_URL=postgres://localhost\n").unwrap();
+
+    let mut spec = filter_spec(&env_file);
+    assert!(spec.validate().unwrap().is_err());
+
+    spec.set_filter(SecretFilter::default().only(["DATABASE_URL"]));
+    let validated = match spec.validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+    assert_eq!(
+        validated.resolved.secrets.keys().collect::<Vec<_>>(),
+        vec!["DATABASE_URL"]
+    );
+}
+
+#[test]
+fn test_filter_except_excludes_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "DATABASE_URL=postgres://localhost\n").unwrap();
+
+    let mut spec = filter_spec(&env_file);
+    spec.set_filter(SecretFilter::default().except(["STRIPE_KEY"]));
+    let validated = match spec.validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+    assert!(!validated.resolved.secrets.contains_key("STRIPE_KEY"));
+}
+
+#[test]
+fn test_filter_rejects_undeclared_secret() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "").unwrap();
+
+    let mut spec = filter_spec(&env_file);
+    spec.set_filter(SecretFilter::default().only(["DATABSE_URL"]));
+    match spec.validate() {
+        Err(SecretSpecError::UndeclaredSecret(name, ..)) => assert_eq!(name, "DATABSE_URL"),
+        Err(e) => panic!("Unexpected error: {}", e),
+        Ok(_) => panic!("Expected an error for an undeclared secret"),
+    }
+}
+
+#[test]
+fn test_shared_secrets_inherited_by_every_profile() {
+    let config: Config = r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[secrets]
+SENTRY_DSN = { description = "Sentry DSN" }
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+
+[profiles.default]
+LOG_LEVEL = { description = "Log level", required = false, default = "debug" }
+
+[profiles.production]
+SENTRY_DSN = { description = "Production Sentry DSN", required = false }
+"#
+    .parse()
+    .unwrap();
+    config.validate().unwrap();
+    let spec = Secrets::new(config, None, None, None);
+
+    // Visible in every profile
+    for profile in ["default", "production"] {
+        assert!(
+            spec.resolve_secret_config("SENTRY_DSN", Some(profile))
+                .is_some()
+        );
+    }
+
+    // Overridable per profile
+    let sentry = spec
+        .resolve_secret_config("SENTRY_DSN", Some("production"))
+        .unwrap();
+    assert!(!sentry.required);
+    assert!(
+        spec.resolve_secret_config("SENTRY_DSN", Some("default"))
+            .unwrap()
+            .required
+    );
+
+    // The shared definition takes precedence over the default profile's
+    let log_level = |profile| {
+        spec.resolve_secret_config("LOG_LEVEL", Some(profile))
+            .unwrap()
+            .default
+    };
+    assert_eq!(log_level("default").as_deref(), Some("debug"));
+    assert_eq!(log_level("production").as_deref(), Some("info"));
+}
+
+#[test]
+fn test_shared_secrets_create_default_profile() {
+    let config: Config = r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[secrets]
+API_KEY = { description = "API key" }
+"#
+    .parse()
+    .unwrap();
+    config.validate().unwrap();
+    assert!(config.profiles["default"].secrets.contains_key("API_KEY"));
+}
+
+#[test]
+fn test_spec_diff() {
+    let before = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key", required = false, default = "dev-key" }
+OLD_SECRET = { description = "Old" }
+UNCHANGED = { description = "Unchanged" }
+"#,
+        None,
+    )
+    .unwrap();
+    let after = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key", required = true }
+UNCHANGED = { description = "Unchanged" }
+
+[profiles.production]
+NEW_SECRET = { description = "New" }
+"#,
+        None,
+    )
+    .unwrap();
+
+    let diff = before.diff(&after);
+    assert_eq!(
+        diff.added["production"].keys().collect::<Vec<_>>(),
+        vec!["NEW_SECRET"]
+    );
+    assert_eq!(
+        diff.removed["default"].keys().collect::<Vec<_>>(),
+        vec!["OLD_SECRET"]
+    );
+    assert_eq!(diff.changed.len(), 1);
+
+    let change = &diff.changed["default"]["API_KEY"];
+    assert!(!change.before.required);
+    assert_eq!(change.before.default.as_deref(), Some("dev-key"));
+    assert!(change.after.required);
+    assert_eq!(change.after.default, None);
+    assert_eq!(
+        change.to_string(),
+        "required false -> true; default \"dev-key\" -> (none)"
+    );
+
+    assert_eq!(
+        diff.to_string(),
+        "[profiles.default]\n- OLD_SECRET\n~ API_KEY: required false -> true; default \"dev-key\" -> (none)\n\n[profiles.production]\n+ NEW_SECRET\n"
+    );
+    assert!(before.diff(&before).is_empty());
+}
+
+#[test]
+fn test_inconsistent_definitions_across_profiles() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL", min_length = 10 }
+API_KEY = { description = "API key", transform = "trim" }
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+
+[profiles.production]
+DATABASE_URL = { description = "Databse URL" }
+API_KEY = { description = "API key", transform = "trim", required = false }
+LOG_LEVEL = { description = "Log level", required = false, default = "warn" }
+"#,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.inconsistencies(),
+        vec![
+            "DATABASE_URL has a different description across profiles: default \"Database URL\", production \"Databse URL\"",
+            "DATABASE_URL has a different min_length across profiles: default 10, production (none)",
+        ]
+    );
+
+    let consistent = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.production]
+API_KEY = { description = "API key", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    assert!(consistent.inconsistencies().is_empty());
+}
+
+#[test]
+fn test_spec_to_markdown() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "web_api"
+revision = "1.0"
+
+[profiles.production]
+DATABASE_URL = { description = "Production database" }
+
+[profiles.default]
+DATABASE_URL = { description = "Postgres *primary* | replica" }
+REDIS_URL = { description = "Redis", required = false, default = "redis://localhost:6379" }
+SMTP_HOST = { description = "SMTP host", required = false }
+SMTP_PASSWORD = { description = "SMTP password", required_if = "SMTP_HOST", help_url = "https://example.com/smtp" }
+"#,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.to_markdown(),
+        "# web\\_api secrets
+
+## Profile `default`
+
+| Name | Description | Required | Default | Help |
+|------|-------------|----------|---------|------|
+| `DATABASE_URL` | Postgres \\*primary\\* \\| replica | ✓ |  |  |
+| `REDIS_URL` | Redis | ✗ | `redis://localhost:6379` |  |
+| `SMTP_HOST` | SMTP host | ✗ |  |  |
+| `SMTP_PASSWORD` | SMTP password | ✓ if `SMTP_HOST` is set |  | <https://example.com/smtp> |
+
+## Profile `production`
+
+| Name | Description | Required | Default | Help |
+|------|-------------|----------|---------|------|
+| `DATABASE_URL` | Production database | ✓ |  |  |
+"
+    );
+}
+
+#[test]
+fn test_local_config_load() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // A missing local config is not an error
+    assert!(LocalConfig::load(temp_dir.path()).unwrap().is_none());
+
+    let local_path = temp_dir.path().join(LocalConfig::FILE_NAME);
+    fs::write(
+        &local_path,
+        "[defaults]\nprovider = \"dotenv://.env.local\"\nprofile = \"development\"\n",
+    )
+    .unwrap();
+    let local = LocalConfig::load(temp_dir.path()).unwrap().unwrap();
+    assert_eq!(
+        local.defaults.provider.as_deref(),
+        Some("dotenv://.env.local")
+    );
+    assert_eq!(local.defaults.profile.as_deref(), Some("development"));
+
+    // Secret definitions belong in secretspec.toml
+    fs::write(
+        &local_path,
+        "[profiles.default]\nAPI_KEY = { description = \"API key\" }\n",
+    )
+    .unwrap();
+    assert!(LocalConfig::load(temp_dir.path()).is_err());
+}
+
+#[test]
+fn test_local_config_precedence() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(LocalConfig::FILE_NAME),
+        "[defaults]\nprovider = \"dotenv://.env.local\"\nprofile = \"development\"\n",
+    )
+    .unwrap();
+    let local = LocalConfig::load(temp_dir.path()).unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let global = GlobalConfig {
+        defaults: GlobalDefaults {
+            provider: Some("env".to_string()),
+            profile: Some("staging".to_string()),
+            ..Default::default()
+        },
+    };
+
+    // The local file overrides the global configuration
+    let mut spec = Secrets::new(config, Some(global), None, None);
+    spec.set_local_config(local);
+    assert_eq!(spec.get_provider(None, None).unwrap().name(), "dotenv");
+    assert_eq!(spec.resolve_profile(None), "development");
+
+    // Explicit selections override the local file
+    spec.set_provider("env");
+    spec.set_profile("production");
+    assert_eq!(spec.get_provider(None, None).unwrap().name(), "env");
+    assert_eq!(spec.resolve_profile(None), "production");
+}
+
+#[test]
+fn test_check_result_reports_missing_without_failing() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "DATABASE_URL=postgres://localhost\n").unwrap();
+
+    let spec = filter_spec(&env_file);
+    let status = spec.check_result(None, None).unwrap();
+    assert!(!status.is_valid());
+    assert_eq!(status.missing_required, vec!["STRIPE_KEY"]);
+    assert_eq!(
+        status
+            .resolved
+            .secrets
+            .get("DATABASE_URL")
+            .map(|value| value.as_str()),
+        Some("postgres://localhost")
+    );
+
+    // An explicit provider overrides the configured one
+    let other_env = temp_dir.path().join(".env.other");
+    fs::write(&other_env, "DATABASE_URL=a\nSTRIPE_KEY=b\n").unwrap();
+    let status = spec
+        .check_result(Some(format!("dotenv://{}", other_env.display())), None)
+        .unwrap();
+    assert!(status.is_valid());
+    assert_eq!(status.resolved.secrets.len(), 2);
+}
+
+fn length_spec(env_file: &Path) -> Secrets {
+    dotenv_spec(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_PASSWORD = { description = "Database password", min_length = 12 }
+API_TOKEN = { description = "API token", min_length = 32, max_length = 32 }
+"#,
+        env_file,
+    )
+}
+
+#[test]
+fn test_length_constraints_too_short() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        format!("DATABASE_PASSWORD=secret\nAPI_TOKEN={}\n", "a".repeat(32)),
+    )
+    .unwrap();
+
+    let errors = match length_spec(&env_file).validate().unwrap() {
+        Err(errors) => errors,
+        Ok(_) => panic!("Expected DATABASE_PASSWORD to be too short"),
+    };
+    assert!(errors.missing_required.is_empty());
+    assert_eq!(
+        errors.invalid_values,
+        vec![(
+            "DATABASE_PASSWORD".to_string(),
+            "is 6 chars, minimum 12".to_string()
+        )]
+    );
+    assert_eq!(
+        errors.to_string(),
+        "Invalid secret values: DATABASE_PASSWORD is 6 chars, minimum 12"
+    );
+    assert!(matches!(
+        errors.into_error(),
+        SecretSpecError::ValidationFailed(_)
+    ));
+}
+
+#[test]
+fn test_disallow_empty_rejects_empty_values() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_disallow_empty_rejects_empty_values",
+        "test",
+        "default",
+        [("CI_TOKEN", ""), ("OPTIONAL_EMPTY", "")],
+    );
+    let spec_with = |flag: &str| {
+        let config = parse_spec_from_str(
+            &format!(
+                r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+CI_TOKEN = {{ description = "CI token"{flag} }}
+OPTIONAL_EMPTY = {{ description = "Allowed to be empty", required = false }}
+"#
+            ),
+            None,
+        )
+        .unwrap();
+        Secrets::new(
+            config,
+            None,
+            Some("memory://test_disallow_empty_rejects_empty_values".to_string()),
+            None,
+        )
+    };
+
+    // Without the flag an empty value counts as present
+    let validated = spec_with("").validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["CI_TOKEN"].as_str(), "");
+
+    let errors = match spec_with(", disallow_empty = true").validate().unwrap() {
+        Err(errors) => errors,
+        Ok(_) => panic!("Expected CI_TOKEN to be rejected as empty"),
+    };
+    assert!(errors.missing_required.is_empty());
+    assert_eq!(
+        errors.invalid_values,
+        vec![("CI_TOKEN".to_string(), "is empty".to_string())]
+    );
+
+    // A profile can turn the flag off again for a secret it inherits it for
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+CI_TOKEN = { description = "CI token", disallow_empty = true }
+
+[profiles.development]
+CI_TOKEN = { description = "CI token", disallow_empty = false }
+
+[profiles.staging]
+CI_TOKEN = { description = "CI token" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, None, None);
+    let disallow_empty = |profile: &str| {
+        spec.resolve_secret_config("CI_TOKEN", Some(profile))
+            .unwrap()
+            .disallow_empty
+    };
+    assert_eq!(disallow_empty("development"), Some(false));
+    assert_eq!(disallow_empty("staging"), Some(true));
+}
+
+#[test]
+fn test_length_constraints_too_long() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        format!(
+            "DATABASE_PASSWORD=correct-horse-battery\nAPI_TOKEN={}\n",
+            "a".repeat(40)
+        ),
+    )
+    .unwrap();
+
+    let status = length_spec(&env_file).check_result(None, None).unwrap();
+    assert!(!status.is_valid());
+    assert_eq!(
+        status.invalid_values,
+        vec![(
+            "API_TOKEN".to_string(),
+            "is 40 chars, maximum 32".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_length_constraints_within_bounds() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        format!(
+            "DATABASE_PASSWORD=correct-horse-battery\nAPI_TOKEN={}\n",
+            "é".repeat(32)
+        ),
+    )
+    .unwrap();
+
+    let validated = match length_spec(&env_file).validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+    assert!(validated.invalid_values.is_empty());
+}
+
+#[test]
+fn test_min_length_greater_than_max_length_rejected() {
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+TOKEN = { description = "Token", min_length = 16, max_length = 8 }
+"#,
+        None,
+    );
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("min_length (16) cannot be greater than max_length (8)")
+    );
+}
+
+#[test]
+fn test_help_url_inherited_and_validated() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+STRIPE_KEY = { description = "Stripe key", help_url = "https://dashboard.stripe.com/apikeys" }
+
+[profiles.development]
+STRIPE_KEY = { description = "Stripe test key", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, None, None);
+
+    let secret_config = spec
+        .resolve_secret_config("STRIPE_KEY", Some("development"))
+        .unwrap();
+    assert_eq!(
+        secret_config.help_url.as_deref(),
+        Some("https://dashboard.stripe.com/apikeys")
+    );
+
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+STRIPE_KEY = { description = "Stripe key", help_url = "see the wiki" }
+"#,
+        None,
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("help_url 'see the wiki' is not a valid URL")
+    );
+}
+
+#[test]
+fn test_rotate_stores_generated_value() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "STRIPE_KEY=old\n").unwrap();
+
+    let spec = filter_spec(&env_file);
+    let value = spec
+        .rotate("STRIPE_KEY", 24, crate::Charset::Hex, None, None)
+        .unwrap();
+    assert_eq!(value.len(), 24);
+    assert!(value.chars().all(|c| c.is_ascii_hexdigit()));
+
+    let stored = fs::read_to_string(&env_file).unwrap();
+    assert!(stored.contains(&value));
+    assert!(!stored.contains("old"));
+
+    match spec.rotate("UNDECLARED", 24, crate::Charset::Hex, None, None) {
+        Err(SecretSpecError::UndeclaredSecret(name, ..)) => assert_eq!(name, "UNDECLARED"),
+        other => panic!("Expected UndeclaredSecret, got {:?}", other),
+    }
+    match spec.rotate(
+        "STRIPE_KEY",
+        24,
+        crate::Charset::Hex,
+        Some("env".to_string()),
+        None,
+    ) {
+        Err(SecretSpecError::Provider(ProviderError::ReadOnly { operation, .. })) => {
+            assert_eq!(operation, "rotate")
+        }
+        other => panic!("Expected ReadOnly, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_check_result_without_resolving_defaults() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "STORED=value\n").unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+STORED = { description = "Stored", required = false, default = "fallback" }
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    let resolved = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        resolved.resolved.secrets.get("LOG_LEVEL").unwrap().as_str(),
+        "info"
+    );
+    assert_eq!(
+        resolved.with_defaults,
+        vec![("LOG_LEVEL".to_string(), "info".to_string())]
+    );
+    assert!(resolved.would_default.is_empty());
+
+    spec.set_resolve_defaults(false);
+    let raw = spec.check_result(None, None).unwrap();
+    assert!(raw.is_valid());
+    assert_eq!(
+        raw.resolved.secrets.get("STORED").unwrap().as_str(),
+        "value"
+    );
+    assert!(!raw.resolved.secrets.contains_key("LOG_LEVEL"));
+    assert!(raw.with_defaults.is_empty());
+    assert!(raw.missing_optional.is_empty());
+    assert_eq!(
+        raw.would_default,
+        vec![("LOG_LEVEL".to_string(), "info".to_string())]
+    );
+}
+
+#[test]
+fn test_resolution_reports_sources() {
+    use crate::{Resolution, ResolutionSource};
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let global_config = GlobalConfig {
+        defaults: GlobalDefaults {
+            provider: Some("keyring".to_string()),
+            profile: Some("development".to_string()),
+            ..Default::default()
+        },
+    };
+    let mut spec = Secrets::new(config, Some(global_config), None, None);
+
+    let info = spec.resolution(None, None);
+    assert_eq!(
+        info.provider,
+        Some(Resolution {
+            value: "keyring".to_string(),
+            source: ResolutionSource::GlobalConfig,
+        })
+    );
+    assert_eq!(info.profile.value, "development");
+    assert_eq!(info.profile.source, ResolutionSource::GlobalConfig);
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(LocalConfig::FILE_NAME),
+        "[defaults]\nprovider = \"dotenv\"\n",
+    )
+    .unwrap();
+    spec.set_local_config(LocalConfig::load(temp_dir.path()).unwrap());
+    spec.set_profile("staging");
+    let info = spec.resolution(None, None);
+    assert_eq!(info.provider.unwrap().source, ResolutionSource::LocalConfig);
+    assert_eq!(info.profile.value, "staging");
+    assert_eq!(info.profile.source, ResolutionSource::Builder);
+
+    let info = spec.resolution(Some("env".to_string()), Some("production".to_string()));
+    assert_eq!(info.provider.unwrap().source, ResolutionSource::Argument);
+    assert_eq!(info.profile.source, ResolutionSource::Argument);
+}
+
+#[test]
+fn test_required_only_excludes_optional_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        "DATABASE_URL=postgres://localhost\nSENTRY_DSN=https://sentry\n",
+    )
+    .unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database", required = true }
+SENTRY_DSN = { description = "Sentry", required = false }
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    let all = spec.check_result(None, None).unwrap();
+    assert_eq!(all.resolved.secrets.len(), 3);
+
+    spec.set_filter(SecretFilter::default().required_only(true));
+    let required = spec.check_result(None, None).unwrap();
+    assert!(required.is_valid());
+    assert_eq!(
+        required.resolved.secrets.keys().collect::<Vec<_>>(),
+        vec!["DATABASE_URL"]
+    );
+    assert!(required.with_defaults.is_empty());
+    assert_eq!(
+        spec.to_env_string(crate::ExportFormat::Dotenv).unwrap(),
+        "DATABASE_URL=\"postgres://localhost\"\n"
+    );
+
+    // A conditionally required secret is kept once its trigger is set,
+    // while the optional trigger itself is left out
+    fs::write(
+        &env_file,
+        "OAUTH_CLIENT_ID=client\nOAUTH_CLIENT_SECRET=secret\n",
+    )
+    .unwrap();
+    let mut spec = required_if_spec(&env_file);
+    spec.set_filter(SecretFilter::default().required_only(true));
+    let resolved = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        resolved.resolved.secrets.keys().collect::<Vec<_>>(),
+        vec!["OAUTH_CLIENT_SECRET"]
+    );
+}
+
+#[test]
+fn test_abstract_profile_cannot_be_selected() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "DATABASE_URL=postgres://prod\n").unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+abstract = true
+DATABASE_URL = { description = "Database" }
+
+[profiles.production]
+API_KEY = { description = "API key", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    assert!(config.profiles["default"].is_abstract);
+    let spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    match spec.check_result(None, Some("default".to_string())) {
+        Err(SecretSpecError::InvalidProfile(msg)) => {
+            assert!(msg.contains("'default' is abstract"));
+            assert!(msg.contains("production"));
+        }
+        other => panic!("Expected InvalidProfile, got {:?}", other.map(|_| ())),
+    }
+    assert!(matches!(
+        spec.rotate(
+            "DATABASE_URL",
+            16,
+            crate::Charset::Hex,
+            None,
+            Some("default".to_string())
+        ),
+        Err(SecretSpecError::InvalidProfile(_))
+    ));
+
+    // Concrete profiles still inherit the abstract profile's secrets
+    let result = spec
+        .check_result(None, Some("production".to_string()))
+        .unwrap();
+    assert!(result.is_valid());
+    assert_eq!(
+        result
+            .resolved
+            .secrets
+            .get("DATABASE_URL")
+            .unwrap()
+            .as_str(),
+        "postgres://prod"
+    );
+}
+
+#[test]
+fn test_all_profiles_abstract_rejected() {
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+abstract = true
+API_KEY = { description = "API key" }
+"#,
+        None,
+    );
+    match result {
+        Err(SecretSpecError::InvalidConfig(msg)) => assert!(msg.contains("must not be abstract")),
+        other => panic!("Expected a validation error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_spec_document_preserves_comments() {
+    use crate::document::SpecDocument;
+
+    let content = r#"# Secrets for the billing service
+[project]
+name = "billing"
+revision = "1.0"
+
+[profiles.default]
+# Rotated quarterly, ask #payments for access
+STRIPE_KEY = { description = "Stripe API key" }   # live key
+
+[profiles.development]
+STRIPE_KEY = { description = "Stripe test key", required = false, default = "sk_test" }
+"#;
+    let mut document = SpecDocument::parse(content).unwrap();
+    document
+        .set_secret(
+            "default",
+            "DATABASE_URL",
+            &Secret {
+                description: Some("Database".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    document
+        .set_secret(
+            "default",
+            "STRIPE_KEY",
+            &Secret {
+                description: Some("Stripe secret key".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let output = document.to_string();
+
+    assert!(output.starts_with("# Secrets for the billing service\n"));
+    assert!(output.contains(
+        "# Rotated quarterly, ask #payments for access\nSTRIPE_KEY = { description = \"Stripe secret key\", required = true }   # live key\n"
+    ));
+    assert!(output.contains(
+        "DATABASE_URL = { description = \"Database\", required = true }\n\n[profiles.development]"
+    ));
+
+    let config: Config = toml::from_str(&output).unwrap();
+    assert_eq!(config.profiles["default"].secrets.len(), 2);
+    assert_eq!(
+        config.profiles["development"].secrets["STRIPE_KEY"].default,
+        Some("sk_test".to_string())
+    );
+}
+
+#[test]
+fn test_find_manifest_walks_up() {
+    use crate::secrets::find_manifest_from;
+
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    let nested = project.join("src").join("bin");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(project.join("secretspec.toml"), "").unwrap();
+
+    assert_eq!(
+        find_manifest_from(&nested, true),
+        Some(project.join("secretspec.toml"))
+    );
+    assert_eq!(find_manifest_from(&nested, false), None);
+    assert_eq!(
+        find_manifest_from(&project, false),
+        Some(project.join("secretspec.toml"))
+    );
+
+    // The closest manifest wins
+    fs::write(nested.join("secretspec.toml"), "").unwrap();
+    assert_eq!(
+        find_manifest_from(&nested, true),
+        Some(nested.join("secretspec.toml"))
+    );
+}
+
+/// Provider that tracks how many lookups run at the same time. Each lookup
+/// holds off until `wait_for` lookups have been in flight together, so a
+/// concurrent fetch is observed regardless of scheduling
+struct SlowRemoteProvider {
+    remote: bool,
+    wait_for: usize,
+    in_flight: std::sync::atomic::AtomicUsize,
+    peak: std::sync::atomic::AtomicUsize,
+}
+
+impl SlowRemoteProvider {
+    fn new(remote: bool) -> Self {
+        Self::waiting_for(remote, 1)
+    }
+
+    fn waiting_for(remote: bool, wait_for: usize) -> Self {
+        Self {
+            remote,
+            wait_for,
+            in_flight: Default::default(),
+            peak: Default::default(),
+        }
+    }
+}
+
+impl crate::provider::Provider for SlowRemoteProvider {
+    fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
+        use std::sync::atomic::Ordering;
+
+        let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(running, Ordering::SeqCst);
+        // The deadline only keeps a broken fetch from hanging the test; the
+        // peak assertions report it
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while self.peak.load(Ordering::SeqCst) < self.wait_for
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::yield_now();
+        }
+        // Give lookups that should not overlap a chance to do so
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        if key == "BROKEN" {
+            return Err(SecretSpecError::Provider(
+                ProviderError::BackendUnavailable("offline".to_string()),
+            ));
+        }
+        Ok(key.ends_with('0').then(|| format!("value of {}", key)))
+    }
+
+    fn set(&self, _project: &str, _key: &str, _value: &str, _profile: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    fn name(&self) -> &'static str {
+        "slow"
+    }
+}
+
+#[test]
+fn test_fetch_secrets_concurrently() {
+    use crate::secrets::fetch_secrets;
+    use std::sync::atomic::Ordering;
+
+    let names: Vec<String> = (0..32).map(|i| format!("SECRET_{}", i)).collect();
+    let expected: Vec<Option<String>> = names
+        .iter()
+        .map(|name| name.ends_with('0').then(|| format!("value of {}", name)))
+        .collect();
+
+    let sequential = SlowRemoteProvider::new(true);
+    let values = fetch_secrets(&sequential, "project", "default", &names, 1).unwrap();
+    assert_eq!(values, expected);
+    assert_eq!(sequential.peak.load(Ordering::SeqCst), 1);
+
+    // Every lookup waits until 8 are in flight, which only happens if the
+    // fetch runs all of its workers at once
+    let concurrent = SlowRemoteProvider::waiting_for(true, 8);
+    let values = fetch_secrets(&concurrent, "project", "default", &names, 8).unwrap();
+    // Results keep the order of the names regardless of completion order
+    assert_eq!(values, expected);
+    assert_eq!(concurrent.peak.load(Ordering::SeqCst), 8);
+
+    // Local providers are read one secret at a time
+    let local = SlowRemoteProvider::new(false);
+    fetch_secrets(&local, "project", "default", &names[..4], 8).unwrap();
+    assert_eq!(local.peak.load(Ordering::SeqCst), 1);
+
+    // A failed lookup fails the whole fetch
+    let mut with_broken = names.clone();
+    with_broken.insert(5, "BROKEN".to_string());
+    let result = fetch_secrets(
+        &SlowRemoteProvider::new(true),
+        "project",
+        "default",
+        &with_broken,
+        8,
+    );
+    assert!(matches!(
+        result,
+        Err(SecretSpecError::Provider(
+            ProviderError::BackendUnavailable(_)
+        ))
+    ));
+}
+
+#[test]
+fn test_unknown_profile_is_profile_not_found() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "").unwrap();
+    let spec = filter_spec(&env_file);
+
+    let assert_profile_not_found = |result: Result<()>| match result {
+        Err(SecretSpecError::ProfileNotFound(name, available)) => {
+            assert_eq!(name, "staging");
+            assert_eq!(available, vec!["default".to_string()]);
+        }
+        other => panic!("Expected ProfileNotFound, got {:?}", other),
+    };
+
+    assert_profile_not_found(
+        spec.check_result(None, Some("staging".to_string()))
+            .map(|_| ()),
+    );
+    assert_profile_not_found(
+        spec.rotate(
+            "STRIPE_KEY",
+            16,
+            crate::Charset::Hex,
+            None,
+            Some("staging".to_string()),
+        )
+        .map(|_| ()),
+    );
+
+    let mut spec = filter_spec(&env_file);
+    spec.set_profile("staging");
+    assert_profile_not_found(spec.get("STRIPE_KEY"));
+    assert_profile_not_found(spec.set("STRIPE_KEY", Some("value".to_string())));
+    assert_profile_not_found(spec.validate().map(|_| ()));
+    assert_profile_not_found(spec.import(&format!("dotenv://{}", env_file.display())));
+
+    let message = spec.get("STRIPE_KEY").unwrap_err().to_string();
+    assert_eq!(
+        message,
+        "Profile 'staging' is not defined in secretspec.toml. Available profiles: default"
+    );
+}
+
+#[test]
+fn test_transforms_applied_to_provider_values() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(
+        &env_file,
+        "TOKEN=\"  abc\\n\"\nCERT=\" c2VjcmV0\\n\"\nBROKEN=\"not base64!\"\n",
+    )
+    .unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+TOKEN = { description = "Token", transform = "trim" }
+CERT = { description = "Certificate", transform = "trim+base64-decode" }
+BROKEN = { description = "Broken", transform = "base64-decode" }
+PLAIN = { description = "Plain", required = false, default = " kept ", transform = "trim" }
+"#,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        config.profiles["default"].secrets["CERT"].transform,
+        vec![crate::Transform::Trim, crate::Transform::Base64Decode]
+    );
+    let spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    let result = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        result.resolved.secrets.get("TOKEN").unwrap().as_str(),
+        "abc"
+    );
+    assert_eq!(
+        result.resolved.secrets.get("CERT").unwrap().as_str(),
+        "secret"
+    );
+    // Defaults are used as written
+    assert_eq!(
+        result.resolved.secrets.get("PLAIN").unwrap().as_str(),
+        " kept "
+    );
+    assert!(!result.resolved.secrets.contains_key("BROKEN"));
+    assert_eq!(result.invalid_values.len(), 1);
+    assert_eq!(result.invalid_values[0].0, "BROKEN");
+    assert!(
+        result.invalid_values[0]
+            .1
+            .starts_with("base64-decode failed")
+    );
+    assert!(!result.is_valid());
+
+    let unknown = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+TOKEN = { description = "Token", transform = "trim+rot13" }
+"#,
+        None,
+    );
+    match unknown {
+        Err(SecretSpecError::Toml(e)) => {
+            assert!(e.to_string().contains("unknown transform 'rot13'"))
+        }
+        other => panic!("Expected a TOML error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_default_references_other_secret() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "BASE_URL=https://example.com\n").unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+BASE_URL = { description = "Public URL", required = false, default = "http://localhost" }
+CALLBACK_URL = { description = "OAuth callback", required = false, default = "${secret:BASE_URL}/callback" }
+WEBHOOK_URL = { description = "Webhook", required = false, default = "${secret:CALLBACK_URL}/hook" }
+MISSING_REF = { description = "Unresolvable", required = false, default = "${secret:OPTIONAL}" }
+OPTIONAL = { description = "Optional", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    let result = spec.check_result(None, None).unwrap();
+    let secrets = &result.resolved.secrets;
+    assert_eq!(
+        secrets["CALLBACK_URL"].as_str(),
+        "https://example.com/callback"
+    );
+    assert_eq!(
+        secrets["WEBHOOK_URL"].as_str(),
+        "https://example.com/callback/hook"
+    );
+    // A reference to a secret without a value leaves the default unavailable
+    assert!(!secrets.contains_key("MISSING_REF"));
+    assert!(result.missing_optional.contains(&"MISSING_REF".to_string()));
+
+    // Referenced secrets are resolved even when filtered out
+    let mut spec = spec;
+    spec.set_filter(SecretFilter::default().only(["CALLBACK_URL"]));
+    let result = spec.check_result(None, None).unwrap();
+    assert_eq!(result.resolved.secrets.len(), 1);
+    assert_eq!(
+        result.resolved.secrets["CALLBACK_URL"].as_str(),
+        "https://example.com/callback"
+    );
+}
+
+#[test]
+fn test_cyclic_default_references() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+A = { description = "A", required = false, default = "${secret:B}" }
+B = { description = "B", required = false, default = "x-${secret:A}" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, Some("env".to_string()), None);
+
+    match spec.check_result(None, None) {
+        Err(SecretSpecError::CircularDependency(msg)) => assert!(msg.contains("A -> B -> A")),
+        Err(e) => panic!("Expected CircularDependency error, got {:?}", e),
+        Ok(_) => panic!("Expected CircularDependency error"),
+    }
+}
+
+#[test]
+fn test_store_defaults_seeds_provider() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "API_KEY=stored\n").unwrap();
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+LOG_FILTER = { description = "Log filter", required = false, default = "app=${secret:LOG_LEVEL}" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config.clone(),
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        validated.with_defaults,
+        vec![
+            ("LOG_FILTER".to_string(), "app=info".to_string()),
+            ("LOG_LEVEL".to_string(), "info".to_string())
+        ]
+    );
+    // Defaults referencing other secrets are derived, not stored
+    assert_eq!(spec.store_defaults(&validated.with_defaults).unwrap(), 1);
+
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        validated.with_defaults,
+        vec![("LOG_FILTER".to_string(), "app=info".to_string())]
+    );
+    assert_eq!(validated.resolved.secrets["LOG_LEVEL"].as_str(), "info");
+    let stored = fs::read_to_string(&env_file).unwrap();
+    assert!(stored.contains("LOG_LEVEL"));
+    assert!(!stored.contains("LOG_FILTER"));
+
+    // Read-only providers are skipped
+    let spec = Secrets::new(config, None, Some("env".to_string()), None);
+    let defaults = vec![("LOG_LEVEL".to_string(), "info".to_string())];
+    assert_eq!(spec.store_defaults(&defaults).unwrap(), 0);
+}
+
+#[test]
+fn test_memory_provider_counts_access() {
+    use crate::MemoryProvider;
+
+    let store = MemoryProvider::with_secrets(
+        "test_memory_provider_counts_access",
+        "test",
+        "default",
+        [("API_KEY", "abc"), ("UNDECLARED", "x")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+DATABASE_URL = { description = "Database URL", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_memory_provider_counts_access".to_string()),
+        None,
+    );
+
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["API_KEY"].as_str(), "abc");
+    assert_eq!(validated.missing_optional, vec!["DATABASE_URL".to_string()]);
+    assert_eq!(validated.extra, vec!["UNDECLARED".to_string()]);
+    // One read per declared secret
+    assert_eq!(store.get_calls(), 2);
+
+    spec.set("DATABASE_URL", Some("postgres://localhost".to_string()))
+        .unwrap();
+    assert_eq!(store.set_calls(), 1);
+    assert_eq!(
+        store.secrets()[&(
+            "test".to_string(),
+            "default".to_string(),
+            "DATABASE_URL".to_string()
+        )],
+        "postgres://localhost"
+    );
+}
+
+#[test]
+fn test_strict_mode_rejects_defaults() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_strict_mode_rejects_defaults",
+        "test",
+        "default",
+        [("API_KEY", "abc")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+PORT = { description = "Port", required = false, default = "8080" }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_strict_mode_rejects_defaults".to_string()),
+        None,
+    );
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join("compose.env");
+
+    // Without strict mode the default is used
+    assert_eq!(spec.write_env_file(&env_file).unwrap(), 2);
+
+    spec.set_strict(true);
+    match spec.write_env_file(&env_file) {
+        Err(SecretSpecError::DefaultsNotAllowed(names)) => assert_eq!(names, vec!["PORT"]),
+        other => panic!("Expected DefaultsNotAllowed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_required_with_default_is_rejected() {
+    let parse = |secret: &str| {
+        format!(
+            "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nPORT = {}\n",
+            secret
+        )
+        .parse::<Config>()
+    };
+
+    let error = parse(r#"{ description = "Port", required = true, default = "8080" }"#)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("secret 'PORT'"), "{}", error);
+    assert!(error.contains("required = true conflicts with default"));
+
+    // A default without `required` makes the secret optional
+    let config = parse(r#"{ description = "Port", default = "8080" }"#).unwrap();
+    assert!(!config.profiles["default"].secrets["PORT"].required);
+
+    // Conditionally required secrets may still have a default
+    parse(r#"{ description = "Port", required = true, required_if = "HOST", default = "8080" }"#)
+        .unwrap();
+
+    // Secrets built in code are held to the same rule
+    let secret = Secret {
+        description: Some("Port".to_string()),
+        required: true,
+        default: Some("8080".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        secret
+            .validate()
+            .unwrap_err()
+            .contains("required = true conflicts with default")
+    );
+}
+
+#[test]
+fn test_severity_accepts_bools_and_names() {
+    use crate::Severity;
+
+    let parse = |required: &str| {
+        format!(
+            "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nSENTRY_DSN = {{ description = \"Sentry DSN\", required = {} }}\n",
+            required
+        )
+        .parse::<Config>()
+        .map(|config| config.profiles["default"].secrets["SENTRY_DSN"].clone())
+    };
+
+    // The boolean form keeps its meaning
+    let secret = parse("true").unwrap();
+    assert!(secret.required && !secret.recommended);
+    assert_eq!(secret.severity(), Severity::Required);
+    let secret = parse("false").unwrap();
+    assert!(!secret.required && !secret.recommended);
+    assert_eq!(secret.severity(), Severity::Optional);
+
+    assert_eq!(
+        parse(r#""required""#).unwrap().severity(),
+        Severity::Required
+    );
+    assert_eq!(
+        parse(r#""optional""#).unwrap().severity(),
+        Severity::Optional
+    );
+    for recommended in [r#""recommended""#, r#""warn""#] {
+        let secret = parse(recommended).unwrap();
+        assert!(!secret.required && secret.recommended);
+        assert_eq!(secret.severity(), Severity::Recommended);
+    }
+
+    let error = parse(r#""sometimes""#).unwrap_err().to_string();
+    assert!(error.contains("invalid severity 'sometimes'"), "{}", error);
+
+    // Serializing writes the boolean form where there is one
+    let config = "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nA = { description = \"A\", required = \"required\" }\nB = { description = \"B\", required = \"warn\" }\nC = { description = \"C\", required = \"optional\" }\n"
+    .parse::<Config>()
+    .unwrap();
+    let written = toml::to_string(&config).unwrap();
+    assert!(written.contains("required = true"), "{}", written);
+    assert!(
+        written.contains("required = \"recommended\""),
+        "{}",
+        written
+    );
+    assert!(written.contains("required = false"), "{}", written);
+    assert_eq!(
+        written.parse::<Config>().unwrap().profiles["default"].secrets,
+        config.profiles["default"].secrets
+    );
+}
+
+#[test]
+fn test_missing_recommended_secret_is_not_an_error() {
+    use crate::MemoryProvider;
+
+    let spec = |name: &str| {
+        let config = parse_spec_from_str(
+            r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL", required = true }
+SENTRY_DSN = { description = "Sentry DSN", required = "recommended" }
+LOG_LEVEL = { description = "Log level", required = false }
+"#,
+            None,
+        )
+        .unwrap();
+        Secrets::new(config, None, Some(format!("memory://{}", name)), None)
+    };
+
+    MemoryProvider::with_secrets(
+        "test_missing_recommended_secret_is_not_an_error",
+        "test",
+        "default",
+        [("DATABASE_URL", "postgres://localhost")],
+    );
+    let validated = spec("test_missing_recommended_secret_is_not_an_error")
+        .validate()
+        .unwrap()
+        .unwrap();
+    assert!(validated.is_valid());
+    assert_eq!(validated.missing_recommended, vec!["SENTRY_DSN"]);
+    assert_eq!(validated.missing_optional, vec!["LOG_LEVEL", "SENTRY_DSN"]);
+
+    // A recommended secret with a value is not reported
+    MemoryProvider::with_secrets(
+        "test_missing_recommended_secret_is_set",
+        "test",
+        "default",
+        [
+            ("DATABASE_URL", "postgres://localhost"),
+            ("SENTRY_DSN", "dsn"),
+        ],
+    );
+    let validated = spec("test_missing_recommended_secret_is_set")
+        .validate()
+        .unwrap()
+        .unwrap();
+    assert!(validated.missing_recommended.is_empty());
+    assert_eq!(validated.missing_optional, vec!["LOG_LEVEL"]);
+
+    // Required secrets are still enforced
+    MemoryProvider::with_secrets(
+        "test_missing_recommended_secret_and_required",
+        "test",
+        "default",
+        [("SENTRY_DSN", "dsn")],
+    );
+    match spec("test_missing_recommended_secret_and_required")
+        .validate()
+        .unwrap()
+    {
+        Err(errors) => assert_eq!(errors.missing_required, vec!["DATABASE_URL"]),
+        Ok(_) => panic!("Expected DATABASE_URL to be required"),
+    }
+}
+
+#[test]
+fn test_severity_changes_are_diffed() {
+    let parse = |required: &str| {
+        format!(
+            "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nSENTRY_DSN = {{ description = \"Sentry DSN\", required = {} }}\n",
+            required
+        )
+        .parse::<Config>()
+        .unwrap()
+    };
+
+    let diff = parse("false").diff(&parse(r#""recommended""#));
+    assert_eq!(
+        diff.changed["default"]["SENTRY_DSN"].to_string(),
+        "required false -> \"recommended\""
+    );
+    assert!(parse("true").diff(&parse(r#""required""#)).is_empty());
+}
+
+#[test]
+fn test_get_many_resolves_values_and_defaults() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_get_many_resolves_values_and_defaults",
+        "test",
+        "default",
+        [("DB_HOST", "db.internal")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DB_HOST = { description = "Database host" }
+DB_PORT = { description = "Database port", required = false, default = "5432" }
+DB_PASSWORD = { description = "Database password" }
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_get_many_resolves_values_and_defaults".to_string()),
+        None,
+    );
+
+    assert_eq!(
+        spec.secret_names().unwrap(),
+        vec!["API_KEY", "DB_HOST", "DB_PASSWORD", "DB_PORT"]
+    );
+    assert_eq!(
+        spec.get_value("DB_HOST").unwrap().as_deref(),
+        Some("db.internal")
+    );
+    assert_eq!(spec.get_value("DB_PASSWORD").unwrap(), None);
+
+    let values = spec
+        .get_many(&["DB_HOST", "DB_PORT", "DB_PASSWORD"])
+        .unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values["DB_HOST"].as_deref(), Some("db.internal"));
+    assert_eq!(values["DB_PORT"].as_deref(), Some("5432"));
+    assert_eq!(values["DB_PASSWORD"], None);
+
+    assert!(matches!(
+        spec.get_many(&["DB_HOST", "UNDECLARED"]),
+        Err(SecretSpecError::UndeclaredSecret(name, ..)) if name == "UNDECLARED"
+    ));
+}
+
+#[test]
+fn test_aliases_resolve_secrets_stored_under_former_names() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_aliases_resolve_secrets_stored_under_former_names",
+        "test",
+        "default",
+        [
+            ("DB_URL", "postgres://old"),
+            ("LEGACY_TOKEN", "legacy"),
+            ("API_TOKEN", "current"),
+        ],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL", aliases = ["DATABASE", "DB_URL"] }
+API_TOKEN = { description = "API token", aliases = ["LEGACY_TOKEN"] }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_aliases_resolve_secrets_stored_under_former_names".to_string()),
+        None,
+    );
+
+    let validated = spec.validate().unwrap().unwrap();
+    let secrets = &validated.resolved.secrets;
+    // Only an alias is set, so its value is exposed under the canonical name
+    assert_eq!(secrets["DATABASE_URL"].as_str(), "postgres://old");
+    assert!(!secrets.contains_key("DB_URL"));
+    // The canonical name wins over an alias
+    assert_eq!(secrets["API_TOKEN"].as_str(), "current");
+    // Keys stored under an alias are not reported as undeclared
+    assert!(validated.extra.is_empty());
+
+    assert_eq!(
+        spec.get_value("DATABASE_URL").unwrap().as_deref(),
+        Some("postgres://old")
+    );
+    assert_eq!(
+        spec.get_value("API_TOKEN").unwrap().as_deref(),
+        Some("current")
+    );
+}
+
+#[test]
+fn test_invalid_alias_is_rejected() {
+    let secret = Secret {
+        description: Some("Database URL".to_string()),
+        aliases: vec!["not a name".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(secret.validate().unwrap_err(), "invalid alias 'not a name'");
+}
+
+#[test]
+fn test_project_description_and_summary() {
+    let config: Config = r#"
+[project]
+name = "billing"
+revision = "1.0"
+description = "Billing API"
+
+[secrets]
+SHARED = { description = "Shared secret" }
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.production]
+DATABASE_URL = { description = "Database URL" }
+
+[profiles.ci]
+API_KEY = { description = "CI API key" }
+"#
+    .parse()
+    .unwrap();
+    assert_eq!(config.project.description.as_deref(), Some("Billing API"));
+
+    let spec = Secrets::new(config, None, Some("memory://".to_string()), None);
+    let summary = spec.summary();
+    assert_eq!(summary.name, "billing");
+    assert_eq!(summary.description.as_deref(), Some("Billing API"));
+    assert_eq!(summary.profiles, vec!["default", "ci", "production"]);
+    assert_eq!(summary.secret_count, 3);
+    assert_eq!(
+        summary.resolution.provider.map(|provider| provider.value),
+        Some("memory://".to_string())
+    );
+}
+
+#[test]
+fn test_project_without_description_round_trips() {
+    let project = Project {
+        name: "app".to_string(),
+        revision: "1.0".to_string(),
+        description: None,
+        extends: None,
+    };
+    let serialized = toml::to_string(&project).unwrap();
+    assert!(!serialized.contains("description"));
+    let parsed: Project = toml::from_str(&serialized).unwrap();
+    assert_eq!(parsed.description, None);
+}
+
+#[test]
+fn test_provider_health_reports_each_provider() {
+    let temp_dir = TempDir::new().unwrap();
+    // The .env "file" is a directory, so reading it fails
+    let broken = temp_dir.path().join(".env");
+    fs::create_dir_all(&broken).unwrap();
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test-project"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, None, None);
+
+    let checks = spec.provider_health(Some("env".to_string()), None).unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].provider, "env");
+    assert_eq!(checks[0].project, "test-project");
+    assert_eq!(checks[0].error, None);
+
+    let provider = format!("dotenv://{}", broken.display());
+    let checks = spec.provider_health(Some(provider.clone()), None).unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].provider, provider);
+    assert!(checks[0].error.is_some());
+}
+
+#[test]
+fn test_set_in_profiles_writes_every_profile() {
+    use crate::MemoryProvider;
+
+    let store = MemoryProvider::with_secrets(
+        "test_set_in_profiles_writes_every_profile",
+        "test",
+        "default",
+        Vec::<(String, String)>::new(),
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.development]
+API_KEY = { description = "Development API key" }
+
+[profiles.production]
+AUDIT_TOKEN = { description = "Audit token" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_set_in_profiles_writes_every_profile".to_string()),
+        None,
+    );
+    let profiles = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    spec.set_in_profiles(
+        "API_KEY",
+        Some("shared".to_string()),
+        &profiles(&["development", "production"]),
+    )
+    .unwrap();
+    let stored = store.secrets();
+    for profile in ["development", "production"] {
+        let key = (
+            "test".to_string(),
+            profile.to_string(),
+            "API_KEY".to_string(),
+        );
+        assert_eq!(stored.get(&key).map(String::as_str), Some("shared"));
+    }
+
+    // Every profile is checked before anything is written
+    assert!(matches!(
+        spec.set_in_profiles(
+            "AUDIT_TOKEN",
+            Some("token".to_string()),
+            &profiles(&["production", "development"]),
+        ),
+        Err(SecretSpecError::UndeclaredSecret(_, profile, _)) if profile == "development"
+    ));
+    assert!(
+        !store
+            .secrets()
+            .keys()
+            .any(|(_, _, key)| key == "AUDIT_TOKEN")
+    );
+}
+
+#[test]
+fn test_group_filter_validates_only_grouped_secrets() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_group_filter_validates_only_grouped_secrets",
+        "test",
+        "production",
+        [("DATABASE_URL", "postgres://prod"), ("STRAY_KEY", "x")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL", group = "database" }
+DATABASE_POOL = { description = "Pool size", required = false, default = "5", group = "database" }
+API_KEY = { description = "API key", group = "api" }
+
+[profiles.production]
+DATABASE_URL = { description = "Production database URL" }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_group_filter_validates_only_grouped_secrets".to_string()),
+        Some("production".to_string()),
+    );
+
+    // The production override inherits its group from the default profile
+    let mut database = spec.secrets_in_group("database", "production").unwrap();
+    let mut names: Vec<_> = database.drain().collect();
+    names.sort();
+    assert_eq!(names, vec!["DATABASE_POOL", "DATABASE_URL"]);
+
+    // API_KEY is missing, but outside the group
+    assert!(spec.validate().unwrap().is_err());
+    spec.set_filter(SecretFilter::default().group("database"));
+    let validated = match spec.validate().unwrap() {
+        Ok(validated) => validated,
+        Err(errors) => panic!("Unexpected validation errors: {}", errors),
+    };
+    let mut resolved: Vec<_> = validated.resolved.secrets.keys().collect();
+    resolved.sort();
+    assert_eq!(resolved, vec!["DATABASE_POOL", "DATABASE_URL"]);
+    assert!(validated.extra.is_empty());
+
+    spec.set_filter(SecretFilter::default().group("api"));
+    match spec.validate().unwrap() {
+        Err(errors) => assert_eq!(errors.missing_required, vec!["API_KEY"]),
+        Ok(_) => panic!("Expected API_KEY to be missing"),
+    }
+
+    spec.set_filter(SecretFilter::default().group("databse"));
+    match spec.validate() {
+        Err(SecretSpecError::GroupNotFound(group, profile)) => {
+            assert_eq!(
+                (group.as_str(), profile.as_str()),
+                ("databse", "production")
+            )
+        }
+        Err(e) => panic!("Unexpected error: {}", e),
+        Ok(_) => panic!("Expected an error for an unknown group"),
+    }
+}
+
+#[test]
+fn test_validation_lists_are_sorted_and_stable() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_validation_lists_are_sorted_and_stable",
+        "test",
+        "default",
+        Vec::<(String, String)>::new(),
+    );
+    // Defaults referencing other secrets make the resolution order differ
+    // from the name order
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+ZETA = { description = "Zeta" }
+ALPHA = { description = "Alpha" }
+MIDDLE = { description = "Middle" }
+Z_URL = { description = "Z URL", required = false, default = "http://localhost" }
+A_CALLBACK = { description = "A callback", required = false, default = "${secret:Z_URL}/callback" }
+Y_OPTIONAL = { description = "Y optional", required = false }
+B_OPTIONAL = { description = "B optional", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_validation_lists_are_sorted_and_stable".to_string()),
+        None,
+    );
+
+    let lists = || {
+        let errors = match spec.validate().unwrap() {
+            Err(errors) => errors,
+            Ok(_) => panic!("Expected missing required secrets"),
+        };
+        (
+            errors.missing_required,
+            errors.missing_optional,
+            errors.with_defaults,
+        )
+    };
+    let first = lists();
+    assert_eq!(first.0, vec!["ALPHA", "MIDDLE", "ZETA"]);
+    assert_eq!(first.1, vec!["B_OPTIONAL", "Y_OPTIONAL"]);
+    assert_eq!(
+        first.2,
+        vec![
+            (
+                "A_CALLBACK".to_string(),
+                "http://localhost/callback".to_string()
+            ),
+            ("Z_URL".to_string(), "http://localhost".to_string()),
+        ]
+    );
+    for _ in 0..5 {
+        assert_eq!(lists(), first);
+    }
+}
+
+#[test]
+fn test_template_never_reads_the_provider() {
+    use crate::MemoryProvider;
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database connection URL" }
+LOG_LEVEL = { description = "Log level", default = "info" }
+SENTRY_DSN = { description = "Sentry DSN", required = false }
+
+[profiles.production]
+DATABASE_URL = { description = "Production database URL" }
+"#,
+        None,
+    )
+    .unwrap();
+    let store = MemoryProvider::with_secrets(
+        "test_template_never_reads_the_provider",
+        "test",
+        "production",
+        [("DATABASE_URL", "postgres://prod")],
+    );
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_template_never_reads_the_provider".to_string()),
+        Some("production".to_string()),
+    );
+
+    assert_eq!(
+        spec.to_template_string().unwrap(),
+        "# Production database URL\nDATABASE_URL=<production-database-url>\n\n\
+         # Log level (optional)\nLOG_LEVEL=\"info\"\n\n\
+         # Sentry DSN (optional)\nSENTRY_DSN=<sentry-dsn>\n"
+    );
+
+    spec.set_filter(SecretFilter::default().required_only(true));
+    assert_eq!(
+        spec.to_template_string().unwrap(),
+        "# Production database URL\nDATABASE_URL=<production-database-url>\n"
+    );
+    assert_eq!(store.get_calls(), 0);
+
+    // Works even where the provider could not be created
+    let spec = Secrets::new(
+        parse_spec_from_str(
+            "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nTOKEN = { description = \"API token\" }\n",
+            None,
+        )
+        .unwrap(),
+        None,
+        Some("unknown-provider://".to_string()),
+        None,
+    );
+    assert_eq!(
+        spec.to_template_string().unwrap(),
+        "# API token\nTOKEN=<api-token>\n"
+    );
+}
+
+#[test]
+fn test_allowed_values() {
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+LOG_LEVEL = { description = "Log level", allowed = ["debug", "info", "warn", "error"] }
+LOG_FORMAT = { description = "Log format", default = "json", allowed = ["json", "text"] }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some(format!("dotenv://{}", env_file.display())),
+        None,
+    );
+
+    fs::write(&env_file, "LOG_LEVEL=warn\n").unwrap();
+    match spec.validate().unwrap() {
+        Ok(validated) => assert!(validated.invalid_values.is_empty()),
+        Err(errors) => panic!("Expected valid secrets, got {}", errors),
+    }
+
+    fs::write(&env_file, "LOG_LEVEL=verbose\nLOG_FORMAT=xml\n").unwrap();
+    let errors = match spec.validate().unwrap() {
+        Err(errors) => errors,
+        Ok(_) => panic!("Expected LOG_LEVEL and LOG_FORMAT to be rejected"),
+    };
+    assert_eq!(
+        errors.invalid_values,
+        vec![
+            (
+                "LOG_FORMAT".to_string(),
+                "is not one of: json, text".to_string()
+            ),
+            (
+                "LOG_LEVEL".to_string(),
+                "is not one of: debug, info, warn, error".to_string()
+            ),
+        ]
+    );
+    assert!(!errors.to_string().contains("verbose"));
+}
+
+#[test]
+fn test_default_not_in_allowed_values_rejected() {
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+LOG_LEVEL = { description = "Log level", default = "trace", allowed = ["debug", "info"] }
+"#,
+        None,
+    );
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("default 'trace' is not one of the allowed values: debug, info")
+    );
+}
+
+#[test]
+fn test_read_piped_value() {
+    use crate::secrets::read_piped_value;
+
+    assert_eq!(read_piped_value("sk-123\n".as_bytes()).unwrap(), "sk-123");
+    assert_eq!(read_piped_value("sk-123\r\n".as_bytes()).unwrap(), "sk-123");
+    assert_eq!(read_piped_value("sk-123".as_bytes()).unwrap(), "sk-123");
+    // Only one trailing newline is removed, and inner lines are kept
+    assert_eq!(
+        read_piped_value("-----BEGIN-----\nabc\n-----END-----\n\n".as_bytes()).unwrap(),
+        "-----BEGIN-----\nabc\n-----END-----\n"
+    );
+    assert_eq!(
+        read_piped_value("  padded  \n".as_bytes()).unwrap(),
+        "  padded  "
+    );
+    assert_eq!(read_piped_value("".as_bytes()).unwrap(), "");
+}
+
+#[test]
+fn test_location_describes_where_values_are_stored() {
+    use crate::MemoryProvider;
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+DATABASE_URL = { description = "Database URL", aliases = ["DB_URL"] }
+"#,
+        None,
+    )
+    .unwrap();
+    MemoryProvider::with_secrets(
+        "test_location_describes_where_values_are_stored",
+        "test",
+        "default",
+        [("API_KEY", "sk-secret"), ("DB_URL", "postgres://secret")],
+    );
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_location_describes_where_values_are_stored".to_string()),
+        None,
+    );
+
+    let api_key = spec.location("API_KEY").unwrap();
+    assert_eq!(
+        api_key,
+        "memory store 'test_location_describes_where_values_are_stored', entry test/default/API_KEY"
+    );
+    // A value stored under an alias is located there
+    assert!(
+        spec.location("DATABASE_URL")
+            .unwrap()
+            .ends_with("entry test/default/DB_URL")
+    );
+    assert!(matches!(
+        spec.location("UNDECLARED"),
+        Err(SecretSpecError::UndeclaredSecret(..))
+    ));
+
+    // Chains point at the provider holding the value
+    let temp_dir = TempDir::new().unwrap();
+    let env_file = temp_dir.path().join(".env");
+    fs::write(&env_file, "OTHER=1\nAPI_KEY=from-dotenv\n").unwrap();
+    let config = parse_spec_from_str(
+        "[project]\nname = \"test\"\nrevision = \"1.0\"\n\n[profiles.default]\nAPI_KEY = { description = \"API key\" }\n",
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some(format!(
+            "memory://empty_location_store,dotenv://{}",
+            env_file.display()
+        )),
+        None,
+    );
+    let location = spec.location("API_KEY").unwrap();
+    assert_eq!(location, format!("{}:2", env_file.display()));
+    assert!(!location.contains("from-dotenv"));
+}
+
+#[test]
+fn test_prefer_defaults_skips_the_provider() {
+    use crate::MemoryProvider;
+
+    let spec_toml = r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+LOG_LEVEL = { description = "Log level", required = false, default = "info" }
+PORT = { description = "Port", required = false, default = "8080" }
+"#;
+    let store = MemoryProvider::with_secrets(
+        "test_prefer_defaults_skips_the_provider",
+        "test",
+        "default",
+        [("LOG_LEVEL", "debug")],
+    );
+    let mut spec = Secrets::new(
+        parse_spec_from_str(spec_toml, None).unwrap(),
+        None,
+        Some("memory://test_prefer_defaults_skips_the_provider".to_string()),
+        None,
+    );
+
+    // By default, a stored value overrides the default
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["LOG_LEVEL"].as_str(), "debug");
+    assert_eq!(store.get_calls(), 2);
+
+    spec.set_prefer_defaults(true);
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["LOG_LEVEL"].as_str(), "info");
+    assert_eq!(validated.resolved.secrets["PORT"].as_str(), "8080");
+    assert_eq!(
+        validated.with_defaults,
+        vec![
+            ("LOG_LEVEL".to_string(), "info".to_string()),
+            ("PORT".to_string(), "8080".to_string()),
+        ]
+    );
+    assert_eq!(store.get_calls(), 2);
+}
+
+#[test]
+fn test_prefer_defaults_still_reads_required_and_unresolvable() {
+    use crate::MemoryProvider;
+
+    let spec_toml = r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+BASE_URL = { description = "Base URL", required = false }
+CALLBACK_URL = { description = "OAuth callback", required = false, default = "${secret:BASE_URL}/callback" }
+"#;
+    let store = MemoryProvider::with_secrets(
+        "test_prefer_defaults_still_reads_required_and_unresolvable",
+        "test",
+        "default",
+        [
+            ("API_KEY", "abc"),
+            ("CALLBACK_URL", "https://stored/callback"),
+        ],
+    );
+    let mut spec = Secrets::new(
+        parse_spec_from_str(spec_toml, None).unwrap(),
+        None,
+        Some("memory://test_prefer_defaults_still_reads_required_and_unresolvable".to_string()),
+        None,
+    );
+    spec.set_prefer_defaults(true);
+
+    // BASE_URL has no value, so CALLBACK_URL's default is unavailable and
+    // the stored value is read instead
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["API_KEY"].as_str(), "abc");
+    assert_eq!(
+        validated.resolved.secrets["CALLBACK_URL"].as_str(),
+        "https://stored/callback"
+    );
+    assert!(validated.with_defaults.is_empty());
+    assert_eq!(store.get_calls(), 3);
+}
+
+#[test]
+fn test_check_looks_up_a_missing_secret_once() {
+    use crate::{MemoryProvider, Verbosity};
+
+    let store = MemoryProvider::with_secrets(
+        "test_check_looks_up_a_missing_secret_once",
+        "test",
+        "default",
+        [("API_KEY", "abc")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+SENTRY_DSN = { description = "Sentry DSN", required = false }
+"#,
+        None,
+    )
+    .unwrap();
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_check_looks_up_a_missing_secret_once".to_string()),
+        None,
+    );
+    spec.set_verbosity(Verbosity::Quiet);
+
+    // The second pass only reads API_KEY again
+    spec.check().unwrap();
+    spec.validate().unwrap().unwrap();
+    assert_eq!(store.get_calls(), 3);
+
+    // Writing a value forgets the misses
+    spec.set("SENTRY_DSN", Some("dsn".to_string())).unwrap();
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(validated.resolved.secrets["SENTRY_DSN"].as_str(), "dsn");
+}
+
+#[test]
+fn test_check_reads_the_provider_again_only_after_prompting() {
+    use crate::{MemoryProvider, Verbosity};
+
+    let store = MemoryProvider::with_secrets(
+        "test_check_reads_the_provider_again_only_after_prompting",
+        "test",
+        "default",
+        [("UNRELATED", "value")],
+    );
+    let spec_with = |name: &str| {
+        let config = parse_spec_from_str(
+            &format!(
+                r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+{} = {{ description = "Database URL" }}
+"#,
+                name
+            ),
+            None,
+        )
+        .unwrap();
+        let mut spec = Secrets::new(
+            config,
+            None,
+            Some("memory://test_check_reads_the_provider_again_only_after_prompting".to_string()),
+            None,
+        );
+        spec.set_verbosity(Verbosity::Quiet);
+        spec
+    };
+
+    // The secret is read once for the listing and once after prompting
+    let spec = spec_with("DATABASE_URL");
+    let mut prompted = Vec::new();
+    spec.check_with_prompt(&mut |name, profile| {
+        prompted.push(format!("{}/{}", name, profile));
+        Ok(Some("postgres://localhost".to_string()))
+    })
+    .unwrap();
+    assert_eq!(prompted, ["DATABASE_URL/default"]);
+    assert_eq!(store.get_calls(), 2);
+    let validated = spec.validate().unwrap().unwrap();
+    assert_eq!(
+        validated.resolved.secrets["DATABASE_URL"].as_str(),
+        "postgres://localhost"
+    );
+
+    // Nothing is written when no one can be asked
+    let spec = spec_with("SESSION_SECRET");
+    assert!(matches!(
+        spec.check_with_prompt(&mut |_, _| Ok(None)),
+        Err(SecretSpecError::RequiredSecretMissing(name)) if name == "SESSION_SECRET"
+    ));
+    assert!(
+        !store
+            .secrets()
+            .keys()
+            .any(|(_, _, key)| key == "SESSION_SECRET")
+    );
+}
+
+#[test]
+fn test_profile_provider() {
+    use crate::ResolutionSource;
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.development]
+provider = "dotenv://.env.development"
+API_KEY = { description = "API key" }
+
+[profiles.production]
+provider = "memory://test_profile_provider"
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    assert!(
+        !config.profiles["development"]
+            .secrets
+            .contains_key("provider")
+    );
+    let global = GlobalConfig {
+        defaults: GlobalDefaults {
+            provider: Some("env".to_string()),
+            ..Default::default()
+        },
+    };
+    let mut spec = Secrets::new(config, Some(global), None, None);
+
+    // Profiles without a provider use the global default
+    assert_eq!(spec.get_provider(None, None).unwrap().name(), "env");
+    assert_eq!(
+        spec.get_provider(None, Some("development")).unwrap().name(),
+        "dotenv"
+    );
+    let resolution = spec.resolution(None, Some("production".to_string()));
+    let provider = resolution.provider.unwrap();
+    assert_eq!(provider.value, "memory://test_profile_provider");
+    assert_eq!(provider.source, ResolutionSource::Profile);
+
+    // The selected profile's provider applies without naming the profile
+    spec.set_profile("production");
+    assert_eq!(spec.get_provider(None, None).unwrap().name(), "memory");
+
+    // An explicit provider overrides the profile's
+    assert_eq!(
+        spec.get_provider(Some("env".to_string()), None)
+            .unwrap()
+            .name(),
+        "env"
+    );
+    spec.set_provider("env");
+    assert_eq!(spec.get_provider(None, None).unwrap().name(), "env");
+}
+
+#[test]
+fn test_profile_provider_cannot_be_empty() {
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+provider = ""
+API_KEY = { description = "API key" }
+"#,
+        None,
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("provider cannot be empty")
+    );
+}
+
+#[test]
+fn test_profile_provider_inherited_from_default() {
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+provider = "env"
+API_KEY = { description = "API key" }
+
+[profiles.development]
+provider = "dotenv://.env.development"
+API_KEY = { description = "API key" }
+
+[profiles.staging]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(config, None, None, None);
+
+    assert_eq!(
+        spec.get_provider(None, Some("staging")).unwrap().name(),
+        "env"
+    );
+    assert_eq!(
+        spec.get_provider(None, Some("development")).unwrap().name(),
+        "dotenv"
+    );
+}
+
+/// A provider that keeps only the first eight characters of each value
+#[derive(Default)]
+struct TruncatingProvider {
+    values: std::sync::Mutex<HashMap<String, String>>,
+}
+
+impl crate::provider::Provider for TruncatingProvider {
+    fn get(&self, _project: &str, key: &str, _profile: &str) -> Result<Option<String>> {
+        Ok(self.values.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&self, _project: &str, key: &str, value: &str, _profile: &str) -> Result<()> {
+        let truncated = value.chars().take(8).collect();
+        self.values
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), truncated);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "truncating"
+    }
+}
+
+#[test]
+fn test_verify_written_reports_lossy_writes() {
+    use crate::provider::Provider;
+    use crate::secrets::verify_written;
+
+    let provider = TruncatingProvider::default();
+    let values = HashMap::from([
+        ("SHORT".to_string(), "abc".to_string()),
+        ("LONG".to_string(), "a-much-longer-value".to_string()),
+    ]);
+    provider.set_many("project", &values, "default").unwrap();
+
+    let mismatched = verify_written(&provider, "project", "default", &values, 1).unwrap();
+    assert_eq!(mismatched, vec!["LONG".to_string()]);
+
+    // A value that was never stored is a mismatch too
+    let missing = HashMap::from([("UNSET".to_string(), "value".to_string())]);
+    let mismatched = verify_written(&provider, "project", "default", &missing, 1).unwrap();
+    assert_eq!(mismatched, vec!["UNSET".to_string()]);
+}
+
+#[test]
+fn test_import_with_verification() {
+    use crate::{MemoryProvider, Verbosity};
+
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let _source = MemoryProvider::with_secrets(
+        "test_import_with_verification_source",
+        "test",
+        "default",
+        [("API_KEY", "abc")],
+    );
+    let target = MemoryProvider::with_secrets(
+        "test_import_with_verification_target",
+        "test",
+        "default",
+        [("UNRELATED", "x")],
+    );
+    let mut spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_import_with_verification_target".to_string()),
+        None,
+    );
+    spec.set_verbosity(Verbosity::Quiet);
+    spec.set_verify_import(true);
+
+    let reads_before = target.get_calls();
+    spec.import("memory://test_import_with_verification_source")
+        .unwrap();
+    // One read to check the target doesn't have it, one to verify it
+    assert_eq!(target.get_calls() - reads_before, 2);
+}
+
+#[test]
+fn test_resolved_merge_prefers_self() {
+    use crate::Resolved;
+
+    let production = Resolved::new(
+        HashMap::from([("API_KEY".to_string(), "production".to_string())]),
+        "keyring".to_string(),
+        "production".to_string(),
+    );
+    let base = Resolved::new(
+        HashMap::from([
+            ("API_KEY".to_string(), "base".to_string()),
+            ("LOG_LEVEL".to_string(), "info".to_string()),
+        ]),
+        "dotenv".to_string(),
+        "default".to_string(),
+    );
+
+    let merged = production.merge(base);
+    assert_eq!(merged.secrets["API_KEY"].as_str(), "production");
+    assert_eq!(merged.secrets["LOG_LEVEL"].as_str(), "info");
+    assert_eq!(merged.provider, "keyring");
+    assert_eq!(merged.profile, "production");
+}
+
+#[test]
+fn test_source_reads_another_projects_secret() {
+    let temp_dir = TempDir::new().unwrap();
+    let service_a = temp_dir.path().join("service-a");
+    let service_b = temp_dir.path().join("service-b");
+    fs::create_dir_all(&service_a).unwrap();
+    fs::create_dir_all(&service_b).unwrap();
+    fs::write(service_a.join(".env"), "DATABASE_URL=postgres://a\n").unwrap();
+    // Both projects use a relative path; service-a's is relative to its own
+    // directory, not to the current one
+    fs::write(
+        service_a.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-a"
+revision = "1.0"
+
+[profiles.default]
+provider = "dotenv://.env"
+DATABASE_URL = { description = "Database URL" }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        service_b.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-b"
+revision = "1.0"
+
+[profiles.default]
+provider = "dotenv://.env"
+DATABASE_URL = { description = "Database URL", source = "../service-a#DATABASE_URL" }
+API_KEY = { description = "API key", required = false }
+"#,
+    )
+    .unwrap();
+
+    let spec = Secrets::load_manifest(&service_b.join("secretspec.toml"), None, None).unwrap();
+    assert_eq!(
+        spec.get_value("DATABASE_URL").unwrap().as_deref(),
+        Some("postgres://a")
+    );
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        validated.resolved.secrets["DATABASE_URL"].as_str(),
+        "postgres://a"
+    );
+
+    // The other project reading this one back is a cycle
+    fs::write(
+        service_a.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-a"
+revision = "1.0"
+
+[profiles.default]
+provider = "memory://test_source_reads_another_projects_secret"
+DATABASE_URL = { description = "Database URL", source = "../service-b/secretspec.toml#DATABASE_URL" }
+"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        spec.get_value("DATABASE_URL"),
+        Err(SecretSpecError::CircularDependency(_))
+    ));
+}
+
+#[test]
+fn test_source_allows_the_same_revision_and_names_a_missing_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let service_a = temp_dir.path().join("service-a");
+    let service_b = temp_dir.path().join("service-b");
+    fs::create_dir_all(&service_a).unwrap();
+    fs::create_dir_all(&service_b).unwrap();
+    fs::write(service_a.join(".env"), "DATABASE_URL=postgres://a\n").unwrap();
+    fs::write(
+        service_a.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-a"
+revision = "2.0"
+
+[profiles.default]
+provider = "dotenv://.env"
+DATABASE_URL = { description = "Database URL" }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        service_b.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-b"
+revision = "2.0"
+
+[profiles.default]
+provider = "dotenv://.env"
+DATABASE_URL = { description = "Database URL", source = "../service-a#DATABASE_URL" }
+CACHE_URL = { description = "Cache URL", source = "../service-c#CACHE_URL", required = false }
+"#,
+    )
+    .unwrap();
+
+    // The revision allowed for this project covers the sourced one too
+    let spec =
+        Secrets::load_manifest(&service_b.join("secretspec.toml"), None, Some("2.0")).unwrap();
+    assert_eq!(
+        spec.get_value("DATABASE_URL").unwrap().as_deref(),
+        Some("postgres://a")
+    );
+
+    // A missing sourced project is reported by its path
+    match spec.get_value("CACHE_URL") {
+        Err(SecretSpecError::Io(e)) => assert!(e.to_string().contains("service-c"), "{}", e),
+        other => panic!("Expected an error naming service-c, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_source_must_name_a_project_and_secret() {
+    for source in ["../service-a", "#DATABASE_URL", "../service-a#not a name"] {
+        let result = parse_spec_from_str(
+            &format!(
+                r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = {{ description = "Database URL", source = "{}" }}
+"#,
+                source
+            ),
+            None,
+        );
+        assert!(result.is_err(), "{}", source);
+    }
+}
+
+#[test]
+fn test_rotate_after_days_reports_old_values() {
+    use crate::MemoryProvider;
+    use crate::validation::unix_now;
+
+    let store = MemoryProvider::with_secrets(
+        "test_rotate_after_days_reports_old_values",
+        "test",
+        "default",
+        [("API_KEY", "abc"), ("DATABASE_URL", "postgres://localhost")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key", rotate_after_days = 90 }
+DATABASE_URL = { description = "Database URL", rotate_after_days = 90 }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_rotate_after_days_reports_old_values".to_string()),
+        None,
+    );
+
+    // DATABASE_URL has no recorded time, so it is never due
+    store.insert_set_at(
+        "test",
+        "default",
+        "API_KEY",
+        unix_now() - 100 * 24 * 60 * 60,
+    );
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        validated.due_for_rotation,
+        vec![("API_KEY".to_string(), 100)]
+    );
+    assert!(validated.is_valid());
+
+    // Setting the secret records when it was set
+    spec.set("API_KEY", Some("def".to_string())).unwrap();
+    let validated = spec.check_result(None, None).unwrap();
+    assert!(validated.due_for_rotation.is_empty());
+
+    let result = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key", rotate_after_days = 0 }
+"#,
+        None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_rotate_after_days_does_not_overflow() {
+    use crate::MemoryProvider;
+    use crate::validation::unix_now;
+
+    let store = MemoryProvider::with_secrets(
+        "test_rotate_after_days_does_not_overflow",
+        "test",
+        "default",
+        [("API_KEY", "abc")],
+    );
+    store.insert_set_at("test", "default", "API_KEY", unix_now() - 1);
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key", rotate_after_days = 300000000000000 }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_rotate_after_days_does_not_overflow".to_string()),
+        None,
+    );
+
+    let validated = spec.check_result(None, None).unwrap();
+    assert!(validated.due_for_rotation.is_empty());
+}
+
+#[test]
+fn test_porcelain_format() {
+    use crate::MemoryProvider;
+
+    MemoryProvider::with_secrets(
+        "test_porcelain_format",
+        "test",
+        "production",
+        [("API_KEY", "abc"), ("UNDECLARED", "x")],
+    );
+    let config = parse_spec_from_str(
+        r#"
+[project]
+name = "test"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+DATABASE_URL = { description = "Database URL" }
+LOG_LEVEL = { description = "Log level", default = "info" }
+SENTRY_DSN = { description = "Sentry DSN", required = false }
+
+[profiles.production]
+API_KEY = { description = "API key" }
+"#,
+        None,
+    )
+    .unwrap();
+    let spec = Secrets::new(
+        config,
+        None,
+        Some("memory://test_porcelain_format".to_string()),
+        Some("production".to_string()),
+    );
+
+    let validated = spec.check_result(None, None).unwrap();
+    assert_eq!(
+        crate::report::format_porcelain(&validated),
+        "PRESENT\tAPI_KEY\tproduction\n\
+         MISSING_REQUIRED\tDATABASE_URL\tproduction\n\
+         DEFAULT\tLOG_LEVEL\tproduction\n\
+         MISSING_OPTIONAL\tSENTRY_DSN\tproduction\n"
+    );
+    assert!(matches!(
+        spec.check_porcelain(),
+        Err(SecretSpecError::RequiredSecretMissing(_))
+    ));
+}
+
+#[test]
+fn test_provider_health_checks_every_provider_in_use() {
+    let temp_dir = TempDir::new().unwrap();
+    let service_a = temp_dir.path().join("service-a");
+    let service_b = temp_dir.path().join("service-b");
+    // service-a's .env is a directory, so reading it fails
+    fs::create_dir_all(service_a.join(".env")).unwrap();
+    fs::create_dir_all(&service_b).unwrap();
+    fs::write(
+        service_a.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-a"
+revision = "1.0"
+
+[profiles.default]
+provider = "dotenv://.env"
+DATABASE_URL = { description = "Database URL" }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        service_b.join("secretspec.toml"),
+        r#"
+[project]
+name = "service-b"
+revision = "1.0"
+
+[profiles.default]
+provider = "env,dotenv://.env"
+DATABASE_URL = { description = "Database URL", source = "../service-a#DATABASE_URL" }
+REPLICA_URL = { description = "Replica URL", source = "../service-a#DATABASE_URL" }
+API_KEY = { description = "API key" }
+"#,
+    )
+    .unwrap();
+
+    let spec = Secrets::load_manifest(&service_b.join("secretspec.toml"), None, None).unwrap();
+    let checks: Vec<(String, String, bool)> = spec
+        .provider_health(None, None)
+        .unwrap()
+        .into_iter()
+        .map(|health| (health.project, health.provider, health.error.is_none()))
+        .collect();
+    assert_eq!(
+        checks,
+        vec![
+            ("service-b".to_string(), "env".to_string(), true),
+            ("service-b".to_string(), "dotenv://.env".to_string(), true),
+            ("service-a".to_string(), "dotenv://.env".to_string(), false),
+        ]
+    );
+}
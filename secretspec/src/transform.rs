@@ -0,0 +1,165 @@
+//! Normalization of secret values read from a provider
+//!
+//! A secret's `transform` field lists transformations joined with `+`, which
+//! are applied left to right to values read from the provider:
+//!
+//! ```toml
+//! [profiles.default]
+//! TLS_KEY = { description = "TLS private key", transform = "trim+base64-decode" }
+//! ```
+//!
+//! Defaults from `secretspec.toml` are used as written.
+
+use crate::error::{Result, SecretSpecError};
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A transformation applied to a secret value after it is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Removes leading and trailing whitespace, including newlines
+    Trim,
+    /// Decodes standard base64, which must yield UTF-8 text
+    Base64Decode,
+}
+
+impl Transform {
+    /// Applies the transformation to `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransformFailed` if the value cannot be decoded
+    pub fn apply(&self, value: String) -> Result<String> {
+        match self {
+            Transform::Trim => Ok(value.trim().to_string()),
+            Transform::Base64Decode => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(value.as_bytes())
+                    .map_err(|e| SecretSpecError::TransformFailed(*self, e.to_string()))?;
+                String::from_utf8(bytes).map_err(|_| {
+                    SecretSpecError::TransformFailed(
+                        *self,
+                        "decoded value is not valid UTF-8".to_string(),
+                    )
+                })
+            }
+        }
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "trim" => Ok(Transform::Trim),
+            "base64-decode" => Ok(Transform::Base64Decode),
+            other => Err(format!(
+                "unknown transform '{}', expected 'trim' or 'base64-decode'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Transform::Trim => "trim",
+            Transform::Base64Decode => "base64-decode",
+        })
+    }
+}
+
+/// Applies `transforms` to `value` in order.
+///
+/// # Errors
+///
+/// Returns the error of the first transformation that fails
+pub(crate) fn apply_all(transforms: &[Transform], value: String) -> Result<String> {
+    transforms
+        .iter()
+        .try_fold(value, |value, transform| transform.apply(value))
+}
+
+/// Serde support for a `+`-separated list of transforms, e.g.
+/// `"trim+base64-decode"`
+pub(crate) mod chain {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        transforms: &[Transform],
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let chain: Vec<String> = transforms.iter().map(Transform::to_string).collect();
+        serializer.serialize_str(&chain.join("+"))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Vec<Transform>, D::Error> {
+        String::deserialize(deserializer)?
+            .split('+')
+            .map(|name| name.trim().parse().map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(
+            Transform::Trim.apply("  token\n".to_string()).unwrap(),
+            "token"
+        );
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(
+            Transform::Base64Decode
+                .apply("c2VjcmV0".to_string())
+                .unwrap(),
+            "secret"
+        );
+
+        match Transform::Base64Decode.apply("not base64!".to_string()) {
+            Err(SecretSpecError::TransformFailed(Transform::Base64Decode, _)) => {}
+            other => panic!("Expected TransformFailed, got {:?}", other),
+        }
+        // Valid base64 of bytes that are not UTF-8
+        assert!(Transform::Base64Decode.apply("/w==".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_chain_applies_in_order() {
+        let chain = [Transform::Trim, Transform::Base64Decode];
+        assert_eq!(
+            apply_all(&chain, "c2VjcmV0\n".to_string()).unwrap(),
+            "secret"
+        );
+        // Decoding first fails on the trailing newline
+        assert!(
+            apply_all(
+                &[Transform::Base64Decode, Transform::Trim],
+                "c2VjcmV0\n".to_string()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!("trim".parse::<Transform>(), Ok(Transform::Trim));
+        assert_eq!(
+            "base64-decode".parse::<Transform>(),
+            Ok(Transform::Base64Decode)
+        );
+        assert!("rot13".parse::<Transform>().is_err());
+    }
+}
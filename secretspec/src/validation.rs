@@ -1,20 +1,272 @@
 //! Validation results for secret checking
 
+use crate::SecretSpecError;
 use crate::config::Resolved;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Restricts which secrets are considered during validation
+///
+/// Secrets excluded by the filter are neither loaded nor reported, so a
+/// missing required secret outside the filter does not fail validation.
+///
+/// # Example
+///
+/// ```no_run
+/// use secretspec::{SecretFilter, Secrets};
+///
+/// let mut spec = Secrets::load().unwrap();
+/// spec.set_filter(SecretFilter::default().only(["DATABASE_URL"]));
+/// spec.check().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretFilter {
+    /// If set, only these secrets are considered
+    only: Option<HashSet<String>>,
+    /// Secrets that are never considered
+    except: HashSet<String>,
+    /// Whether optional secrets are left out of the resolved secrets
+    required_only: bool,
+    /// If set, only secrets in this group are considered
+    group: Option<String>,
+}
+
+impl SecretFilter {
+    /// Considers only the given secrets
+    pub fn only<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.only = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Ignores the given secrets
+    pub fn except<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.except.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Considers only the secrets whose `group` is `group`
+    ///
+    /// Combines with [`only`](Self::only) and [`except`](Self::except): a
+    /// secret must pass all of them.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Returns the group secrets must belong to, if any
+    pub(crate) fn group_name(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Leaves optional secrets out of the resolved secrets, even when they
+    /// have a value
+    ///
+    /// Conditionally required secrets are kept only when their trigger is set.
+    pub fn required_only(mut self, enabled: bool) -> Self {
+        self.required_only = enabled;
+        self
+    }
+
+    /// Returns whether optional secrets are left out
+    pub(crate) fn is_required_only(&self) -> bool {
+        self.required_only
+    }
+
+    /// Returns whether the secret with the given name is considered
+    ///
+    /// The group is not checked, as it depends on the secret's definition.
+    pub fn matches(&self, name: &str) -> bool {
+        self.only.as_ref().is_none_or(|only| only.contains(name)) && !self.except.contains(name)
+    }
+
+    /// Returns every secret name mentioned by the filter
+    pub(crate) fn names(&self) -> impl Iterator<Item = &String> {
+        self.only.iter().flatten().chain(&self.except)
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// A resolved secret value
+///
+/// A plain `String` by default. With the `zeroize` feature it is a
+/// `zeroize::Zeroizing<String>`, which overwrites the value in memory when
+/// it is dropped; it dereferences to `String`, so `value.as_str()` works
+/// either way.
+#[cfg(not(feature = "zeroize"))]
+pub type SecretValue = String;
+
+/// A resolved secret value
+///
+/// A plain `String` by default. With the `zeroize` feature it is a
+/// `zeroize::Zeroizing<String>`, which overwrites the value in memory when
+/// it is dropped; it dereferences to `String`, so `value.as_str()` works
+/// either way.
+#[cfg(feature = "zeroize")]
+pub type SecretValue = zeroize::Zeroizing<String>;
+
+/// Wraps a resolved value as a [`SecretValue`]
+pub(crate) fn secret_value(value: String) -> SecretValue {
+    #[cfg(feature = "zeroize")]
+    let value = zeroize::Zeroizing::new(value);
+    value
+}
 
 /// Container for validated secrets with metadata
 ///
 /// This struct contains the validated secrets along with information about
-/// which secrets are present, missing, or using default values.
+/// which secrets are present, missing, or using default values. Every list
+/// is sorted by secret name.
 pub struct ValidatedSecrets {
     /// Resolved secrets with provider and profile information
-    pub resolved: Resolved<HashMap<String, String>>,
+    pub resolved: Resolved<HashMap<String, SecretValue>>,
+    /// List of required secrets that are missing
+    ///
+    /// Only populated by `Secrets::check_result`; `Secrets::validate`
+    /// reports missing required secrets as `ValidationErrors` instead.
+    pub missing_required: Vec<String>,
     /// List of optional secrets that are missing
     pub missing_optional: Vec<String>,
+    /// Recommended secrets that are missing
+    ///
+    /// Every entry here is also listed in `missing_optional`; they are
+    /// reported with a warning but don't make the secrets invalid.
+    pub missing_recommended: Vec<String>,
     /// List of secrets using their default values (name, default_value)
     pub with_defaults: Vec<(String, String)>,
+    /// Secrets missing from the provider whose defaults were not applied
+    /// (name, default_value)
+    ///
+    /// Only populated when defaults are disabled with
+    /// `Secrets::set_resolve_defaults(false)`; otherwise these secrets are
+    /// listed in `with_defaults`.
+    pub would_default: Vec<(String, String)>,
+    /// Conditionally required secrets that are missing (name, triggering secret)
+    ///
+    /// Every entry here is also listed in `missing_required`.
+    pub missing_conditional: Vec<(String, String)>,
+    /// Secrets whose values violate a value constraint (length, allowed
+    /// values, emptiness) (name, reason)
+    ///
+    /// Only populated by `Secrets::check_result`, like `missing_required`.
+    pub invalid_values: Vec<(String, String)>,
+    /// Keys held by the provider that are not declared in the specification
+    ///
+    /// Always empty for providers that cannot enumerate their keys.
+    pub extra: Vec<String>,
+    /// The profile whose definition was used for each checked secret: the
+    /// selected profile, or `default` for secrets inherited from it
+    pub resolved_from: HashMap<String, String>,
+    /// Secrets set longer ago than their `rotate_after_days` (name, days
+    /// since the value was set)
+    ///
+    /// Only secrets whose provider records when values are set can be
+    /// listed; these are warnings and don't make the secrets invalid.
+    pub due_for_rotation: Vec<(String, u64)>,
+}
+
+impl ValidatedSecrets {
+    /// Check whether all required secrets are present and all values are valid
+    pub fn is_valid(&self) -> bool {
+        self.missing_required.is_empty() && self.invalid_values.is_empty()
+    }
+
+    /// Records which secrets were present, missing or defaulted, without
+    /// their values
+    ///
+    /// The snapshot is meant to be kept for audits, e.g. written to a file at
+    /// deploy time, so it only holds secret names and never a value or
+    /// default.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The name of the project the secrets belong to
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use secretspec::Secrets;
+    ///
+    /// let spec = Secrets::load().unwrap();
+    /// let snapshot = spec.check_result(None, None).unwrap().audit_snapshot("my-app");
+    /// println!("{}", serde_json::to_string_pretty(&snapshot).unwrap());
+    /// ```
+    pub fn audit_snapshot(&self, project: &str) -> AuditSnapshot {
+        let mut secrets = BTreeMap::new();
+        let present = self
+            .resolved
+            .secrets
+            .keys()
+            .chain(self.invalid_values.iter().map(|(name, _)| name));
+        for name in present {
+            secrets.insert(name.clone(), AuditStatus::Present);
+        }
+        for (name, _) in &self.with_defaults {
+            secrets.insert(name.clone(), AuditStatus::Defaulted);
+        }
+        let missing = self
+            .missing_required
+            .iter()
+            .chain(&self.missing_optional)
+            .chain(self.would_default.iter().map(|(name, _)| name));
+        for name in missing {
+            secrets.insert(name.clone(), AuditStatus::Missing);
+        }
+
+        AuditSnapshot {
+            timestamp: unix_now(),
+            project: project.to_string(),
+            profile: self.resolved.profile.clone(),
+            provider: self.resolved.provider.clone(),
+            secrets,
+        }
+    }
+}
+
+/// Whether a secret had a value when an [`AuditSnapshot`] was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    /// The provider holds a value
+    Present,
+    /// Neither the provider nor a default supplied a value
+    Missing,
+    /// The secret uses its default value
+    Defaulted,
+}
+
+/// The status of every checked secret at one point in time, for audits
+///
+/// Created by [`ValidatedSecrets::audit_snapshot`]. Holds secret names and
+/// statuses only, never values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSnapshot {
+    /// When the snapshot was taken, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The project name
+    pub project: String,
+    /// The profile the secrets were checked in
+    pub profile: String,
+    /// The provider the secrets were read from
+    pub provider: String,
+    /// The status of each checked secret, by name
+    pub secrets: BTreeMap<String, AuditStatus>,
 }
 
 /// Container for validation errors
@@ -29,6 +281,15 @@ pub struct ValidationErrors {
     pub missing_optional: Vec<String>,
     /// List of secrets using their default values (name, default_value)
     pub with_defaults: Vec<(String, String)>,
+    /// Conditionally required secrets that are missing (name, triggering secret)
+    ///
+    /// Every entry here is also listed in `missing_required`.
+    pub missing_conditional: Vec<(String, String)>,
+    /// Secrets whose values violate a value constraint (length, allowed
+    /// values, emptiness) (name, reason)
+    pub invalid_values: Vec<(String, String)>,
+    /// Keys held by the provider that are not declared in the specification
+    pub extra: Vec<String>,
     /// The provider name that was used
     pub provider: String,
     /// The profile that was used
@@ -41,6 +302,8 @@ impl ValidationErrors {
         missing_required: Vec<String>,
         missing_optional: Vec<String>,
         with_defaults: Vec<(String, String)>,
+        missing_conditional: Vec<(String, String)>,
+        extra: Vec<String>,
         provider: String,
         profile: String,
     ) -> Self {
@@ -48,25 +311,60 @@ impl ValidationErrors {
             missing_required,
             missing_optional,
             with_defaults,
+            missing_conditional,
+            invalid_values: Vec::new(),
+            extra,
             provider,
             profile,
         }
     }
 
-    /// Check if there are any critical errors (missing required secrets)
+    /// Check if there are any critical errors (missing required secrets or invalid values)
     pub fn has_errors(&self) -> bool {
-        !self.missing_required.is_empty()
+        !self.missing_required.is_empty() || !self.invalid_values.is_empty()
+    }
+
+    /// Convert into the error reported when validation fails
+    ///
+    /// Missing required secrets are reported as
+    /// `SecretSpecError::RequiredSecretMissing`; if any value is invalid, the
+    /// full report is returned as `SecretSpecError::ValidationFailed`.
+    pub fn into_error(self) -> SecretSpecError {
+        if self.invalid_values.is_empty() {
+            SecretSpecError::RequiredSecretMissing(self.missing_required.join(", "))
+        } else {
+            SecretSpecError::ValidationFailed(Box::new(self))
+        }
     }
 }
 
 impl fmt::Display for ValidationErrors {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if !self.missing_required.is_empty() {
-            write!(
-                f,
-                "Missing required secrets: {}",
-                self.missing_required.join(", ")
-            )?;
+            let names: Vec<String> = self
+                .missing_required
+                .iter()
+                .map(
+                    |name| match self.missing_conditional.iter().find(|(n, _)| n == name) {
+                        Some((_, trigger)) => {
+                            format!("{} (required because {} is set)", name, trigger)
+                        }
+                        None => name.clone(),
+                    },
+                )
+                .collect();
+            write!(f, "Missing required secrets: {}", names.join(", "))?;
+        }
+        if !self.invalid_values.is_empty() {
+            if !self.missing_required.is_empty() {
+                write!(f, "; ")?;
+            }
+            let reasons: Vec<String> = self
+                .invalid_values
+                .iter()
+                .map(|(name, reason)| format!("{} {}", name, reason))
+                .collect();
+            write!(f, "Invalid secret values: {}", reasons.join(", "))?;
         }
         Ok(())
     }
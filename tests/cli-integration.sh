@@ -164,6 +164,763 @@ VALUE=$(secretspec get DEFAULT_SECRET)
 [ "$VALUE" = "default_value" ]
 check_success "Default value is used when secret not set"
 
+# Test 12: Providers command
+secretspec providers | grep -q "^dotenv - "
+check_success "Providers command lists dotenv"
+
+secretspec providers --json | grep -q '"name": "keyring"'
+check_success "Providers command prints JSON"
+
+secretspec providers --json | grep -q '"feature": null'
+check_success "Providers JSON gives enabled providers a null feature"
+
+# Test 13: Edit command
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+TEST_SECRET = { description = "Test secret for integration tests" }
+EOF
+
+cat > add-secret.sh << 'EOF'
+#!/bin/sh
+echo 'EDITED_SECRET = { description = "Added by edit" }' >> "$1"
+EOF
+cat > break-spec.sh << 'EOF'
+#!/bin/sh
+echo 'not valid toml [' >> "$1"
+EOF
+chmod +x add-secret.sh break-spec.sh
+
+EDITOR="$PWD/add-secret.sh" secretspec edit < /dev/null
+grep -q "EDITED_SECRET" secretspec.toml
+check_success "Edit command saves a valid configuration"
+
+cp secretspec.toml before-edit.toml
+if EDITOR="$PWD/break-spec.sh" secretspec edit < /dev/null 2>/dev/null; then
+    false
+fi
+cmp -s secretspec.toml before-edit.toml
+check_success "Edit command keeps the original on invalid changes"
+
+# Test 14: Add secret command
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+# Kept by add-secret
+TEST_SECRET = { description = "Test secret for integration tests" }
+EOF
+
+secretspec add-secret API_KEY --description "API key" > /dev/null
+secretspec add-secret LOG_LEVEL --description "Log level" --default info --profile development > /dev/null
+grep -q "# Kept by add-secret" secretspec.toml
+grep -q 'API_KEY = { description = "API key", required = true }' secretspec.toml
+grep -q 'LOG_LEVEL = { description = "Log level", required = false, default = "info" }' secretspec.toml
+check_success "Add secret command appends definitions and keeps comments"
+
+if secretspec add-secret API_KEY --description "API key" 2>/dev/null; then
+    false
+fi
+secretspec add-secret API_KEY --description "Rotated key" --force > /dev/null
+grep -q 'API_KEY = { description = "Rotated key", required = true }' secretspec.toml
+check_success "Add secret command requires --force to replace a secret"
+
+# Test 15: Init into another directory
+mkdir -p services/billing
+secretspec init --output services/billing > /dev/null
+grep -q 'name = "billing"' services/billing/secretspec.toml
+check_success "Init --output names the project after the output directory"
+
+secretspec init --output services/api/secrets.toml > /dev/null
+grep -q 'name = "api"' services/api/secrets.toml
+[ "$(stat -c %a services/api/secrets.toml 2>/dev/null || stat -f %Lp services/api/secrets.toml)" = "600" ]
+check_success "Init --output writes a custom filename with 0600 permissions"
+
+# Test 16: Write an env file for docker-compose
+mkdir -p compose
+cd compose
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+COMPOSE_SECRET = { description = "Secret for compose" }
+EOF
+printf 'COMPOSE_SECRET="p@ss\\$word"\n' > .env
+
+secretspec run --dotenv-out compose.env 2> /dev/null
+grep -qx "COMPOSE_SECRET='p@ss\$word'" compose.env
+[ "$(stat -c %a compose.env 2>/dev/null || stat -f %Lp compose.env)" = "600" ]
+check_success "Run --dotenv-out writes a compose env file with 0600 permissions"
+
+git init -q .
+secretspec run --dotenv-out compose.env 2>&1 | grep -q "inside the git repository"
+check_success "Run --dotenv-out warns when writing inside a git repository"
+cd ..
+
+# Test 17: Validate the spec and its consistency across profiles
+mkdir -p consistency
+cd consistency
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.production]
+API_KEY = { description = "Production API key" }
+EOF
+
+secretspec validate-spec 2>&1 | grep "API_KEY has a different description across profiles" > /dev/null
+check_success "Validate-spec warns about inconsistent definitions"
+
+if secretspec validate-spec --strict > /dev/null 2>&1; then
+    false
+fi
+check_success "Validate-spec --strict fails on inconsistent definitions"
+cd ..
+
+# Test 18: Quiet and verbose output
+mkdir -p verbosity
+cd verbosity
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+QUIET_SECRET = { description = "Secret for verbosity" }
+EOF
+echo "QUIET_SECRET=value" > .env
+
+[ -z "$(secretspec --quiet check --provider dotenv://.env 2>&1)" ]
+check_success "Check --quiet prints nothing when all secrets are present"
+
+[ "$(secretspec -q get QUIET_SECRET --provider dotenv://.env)" = "value" ]
+check_success "Get --quiet still prints the requested value"
+
+secretspec check -v --provider dotenv://.env 2>&1 | grep "^Provider: dotenv.* (from " > /dev/null
+check_success "Check --verbose reports where the provider came from"
+cd ..
+
+# Test 19: Run --strict refuses defaults
+mkdir -p strict
+cd strict
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+STRICT_SECRET = { description = "Stored secret" }
+STRICT_PORT = { description = "Port with a default", required = false, default = "8080" }
+EOF
+echo "STRICT_SECRET=value" > .env
+
+secretspec run --provider dotenv://.env -- true
+check_success "Run uses defaults without --strict"
+
+if secretspec run --strict --provider dotenv://.env -- true 2> strict.err; then
+    false
+fi
+grep "STRICT_PORT" strict.err > /dev/null
+check_success "Run --strict fails and names secrets that would use defaults"
+cd ..
+
+# Test 20: Get several secrets as JSON
+mkdir -p "$TEST_DIR/get-json"
+cd "$TEST_DIR/get-json"
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+DB_HOST = { description = "Database host" }
+DB_PORT = { description = "Database port", required = false, default = "5432" }
+DB_PASSWORD = { description = "Database password" }
+API_KEY = { description = "API key" }
+EOF
+echo 'DB_HOST="db \"primary\""' > .env
+
+secretspec get --provider dotenv://.env --prefix DB_ --format json > get.json
+grep '"DB_HOST": "db \\"primary\\""' get.json > /dev/null \
+    && grep '"DB_PORT": "5432"' get.json > /dev/null \
+    && grep '"DB_PASSWORD": null' get.json > /dev/null \
+    && ! grep API_KEY get.json > /dev/null
+check_success "Get --prefix --format json prints matching secrets, null when missing"
+
+secretspec get DB_HOST DB_PASSWORD --provider dotenv://.env --format json --omit-missing > get.json
+grep DB_HOST get.json > /dev/null && ! grep DB_PASSWORD get.json > /dev/null
+check_success "Get --omit-missing leaves out secrets without a value"
+
+if secretspec get DB_HOST DB_PORT --provider dotenv://.env 2> /dev/null; then
+    false
+fi
+check_success "Get several secrets requires --format json"
+cd "$TEST_DIR"
+
+# Test 21: Check --hide-optional leaves unset optional secrets out
+mkdir -p hide-optional
+cd hide-optional
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+SHOWN_SECRET = { description = "Stored secret" }
+HIDDEN_OPTIONAL = { description = "Unset optional secret", required = false }
+EOF
+echo "SHOWN_SECRET=value" > .env
+
+secretspec check --provider dotenv://.env > check.out
+grep "HIDDEN_OPTIONAL" check.out > /dev/null
+check_success "Check lists unset optional secrets by default"
+
+secretspec check --hide-optional --provider dotenv://.env > check.out
+grep "SHOWN_SECRET" check.out > /dev/null \
+    && ! grep "HIDDEN_OPTIONAL" check.out > /dev/null \
+    && grep "1 optional not shown" check.out > /dev/null
+check_success "Check --hide-optional hides unset optional secrets and counts them"
+cd ..
+
+# Test 22: Info summarizes the project
+mkdir -p info
+cd info
+cat > secretspec.toml << EOF
+[project]
+name = "info-app"
+revision = "1.0"
+description = "Service used by the info test"
+
+[profiles.default]
+INFO_SECRET = { description = "Secret" }
+
+[profiles.production]
+INFO_PROD_SECRET = { description = "Production secret" }
+EOF
+
+secretspec info > info.out
+grep "^Project: info-app" info.out > /dev/null \
+    && grep "^Description: Service used by the info test" info.out > /dev/null \
+    && grep "^Profiles: 2 (default, production)" info.out > /dev/null \
+    && grep "^Secrets: 2" info.out > /dev/null \
+    && grep "^Provider: dotenv" info.out > /dev/null
+check_success "Info prints the project description, profiles, secrets and provider"
+
+echo "INFO_SECRET=value" > .env
+secretspec check --provider dotenv://.env | grep "Service used by the info test" > /dev/null
+check_success "Check prints the project description"
+cd ..
+
+# Test 23: Doctor checks the providers in use
+mkdir -p doctor
+cd doctor
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+EOF
+secretspec doctor --provider "env,dotenv://.env" > doctor.out
+grep -q "^env " doctor.out && grep -q "^dotenv://.env " doctor.out
+check_success "doctor reports every member of a chain"
+
+mkdir .env
+status=0
+secretspec doctor --provider dotenv://.env > /dev/null 2>&1 || status=$?
+[ $status -eq 3 ]
+check_success "doctor fails when a provider cannot be read"
+cd ..
+
+# Test 24: Exit codes tell failure categories apart
+mkdir -p exit-codes
+cd exit-codes
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+REQUIRED_SECRET = { description = "Required secret" }
+EOF
+touch .env
+
+status=0
+secretspec check --provider dotenv://.env > /dev/null 2>&1 || status=$?
+[ "$status" -eq 2 ]
+check_success "Missing required secrets exit with 2"
+
+status=0
+SECRETSPEC_PROVIDER=unknown-provider:// secretspec check > /dev/null 2>&1 || status=$?
+[ "$status" -eq 4 ]
+check_success "An unknown provider exits with 4"
+
+status=0
+SECRETSPEC_PROFILE=no-such-profile secretspec check --provider dotenv://.env > /dev/null 2>&1 || status=$?
+[ "$status" -eq 4 ]
+check_success "An undefined profile exits with 4"
+
+status=0
+secretspec check --provider dotenv://.env --only TYPO > /dev/null 2>&1 || status=$?
+[ "$status" -eq 4 ]
+check_success "An undeclared secret name exits with 4"
+
+echo "not toml" > secretspec.toml
+status=0
+secretspec validate-spec > /dev/null 2>&1 || status=$?
+[ "$status" -eq 4 ]
+check_success "An unparsable secretspec.toml exits with 4"
+
+printf '\xff\xfe' > secretspec.toml
+status=0
+secretspec validate-spec > /dev/null 2>&1 || status=$?
+[ "$status" -eq 1 ]
+check_success "A secretspec.toml that cannot be read as text exits with 1"
+cd ..
+
+# Test 25: Set reads the value from stdin with -
+mkdir -p set-stdin
+cd set-stdin
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+PIPED_SECRET = { description = "Piped secret" }
+EOF
+touch .env
+
+printf 'first line\nsecond line\n' | secretspec set PIPED_SECRET - --provider dotenv://.env > /dev/null
+[ "$(secretspec get PIPED_SECRET --provider dotenv://.env)" = "$(printf 'first line\nsecond line')" ]
+check_success "Set - stores all of stdin without the trailing newline"
+cd ..
+
+# Test 26: Provider and profile can be given before the command
+mkdir -p global-options
+cd global-options
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+GLOBAL_SECRET = { description = "Global secret" }
+
+[profiles.staging]
+GLOBAL_SECRET = { description = "Global secret" }
+EOF
+echo "GLOBAL_SECRET=from-dotenv" > .env
+
+[ "$(secretspec --provider dotenv://.env --profile staging get GLOBAL_SECRET)" = "from-dotenv" ]
+check_success "Global --provider and --profile are accepted before the command"
+
+[ "$(secretspec --provider env get GLOBAL_SECRET --provider dotenv://.env)" = "from-dotenv" ]
+check_success "An option after the command wins over one before it"
+cd ..
+
+# Test 27: Check output formats
+mkdir -p check-format
+cd check-format
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+FORMAT_SECRET = { description = "Format secret" }
+EOF
+echo "FORMAT_SECRET=value" > .env
+
+CLICOLOR_FORCE=1 secretspec check --provider dotenv://.env > check.out
+! grep $'\x1b' check.out > /dev/null \
+    && grep $'^found\tFORMAT_SECRET\tFormat secret' check.out > /dev/null
+check_success "Piped check output is plain, without colors"
+
+secretspec check --format table --provider dotenv://.env | grep "^found *FORMAT_SECRET *Format secret$" > /dev/null
+check_success "Check --format table aligns columns"
+cd ..
+
+# Test 28: Recommended secrets warn without failing
+mkdir -p recommended
+cd recommended
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+SENTRY_DSN = { description = "Sentry DSN", required = "recommended" }
+EOF
+touch .env
+
+secretspec check --format plain --provider dotenv://.env > check.out
+grep $'^recommended\tSENTRY_DSN\tSentry DSN\trecommended' check.out > /dev/null
+check_success "Check marks missing recommended secrets"
+
+secretspec run --provider dotenv://.env -- true 2> run.err
+grep "recommended secrets not set: SENTRY_DSN" run.err > /dev/null
+check_success "Run warns about missing recommended secrets and succeeds"
+cd ..
+
+# Test 29: --no-global-config ignores the global provider
+mkdir -p no-global-config
+cd no-global-config
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+GLOBAL_SECRET = { description = "Global secret" }
+EOF
+echo "GLOBAL_SECRET=from-global" > global.env
+mkdir -p xdg/secretspec
+cat > xdg/secretspec/config.toml << EOF
+[defaults]
+provider = "dotenv://global.env"
+EOF
+
+[ "$(env -u SECRETSPEC_PROVIDER XDG_CONFIG_HOME="$PWD/xdg" secretspec get GLOBAL_SECRET)" = "from-global" ]
+check_success "Global config provides the default provider"
+
+status=0
+env -u SECRETSPEC_PROVIDER XDG_CONFIG_HOME="$PWD/xdg" secretspec --no-global-config get GLOBAL_SECRET 2> get.err || status=$?
+[ $status -ne 0 ] && grep "No provider backend configured" get.err > /dev/null
+check_success "--no-global-config ignores the global provider"
+cd ..
+
+# Test 30: A secretspec.toml without profiles is reported clearly
+mkdir -p no-profiles
+cd no-profiles
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+EOF
+
+status=0
+secretspec check --provider dotenv://.env 2> check.err || status=$?
+[ $status -eq 4 ] && grep "defines no profiles" check.err > /dev/null
+check_success "Loading a secretspec.toml without profiles points to an example"
+cd ..
+
+# Test 31: Shell completion of secret names
+mkdir -p completions
+cd completions
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+DB_URL = { description = "Database URL" }
+DB_PASSWORD = { description = "Database password" }
+API_KEY = { description = "API key" }
+EOF
+
+[ "$(secretspec __complete DB | tr '\n' ' ')" = "DB_PASSWORD DB_URL " ]
+check_success "__complete lists the declared names with a prefix"
+
+completed=$(bash -c 'source <(secretspec completions bash); COMP_WORDS=(secretspec get DB); COMP_CWORD=2; _secretspec; echo "${COMPREPLY[*]}"')
+[ "$completed" = "DB_PASSWORD DB_URL" ]
+check_success "Bash completion offers secret names for get"
+cd ..
+
+[ -z "$(cd / && secretspec __complete)" ]
+check_success "__complete prints nothing without a secretspec.toml"
+
+# Test 32: Reading a secretspec.toml of an unsupported revision
+mkdir -p allow-revision
+cd allow-revision
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "2.0"
+
+[profiles.default]
+NEW_FORMAT_SECRET = { description = "New format secret" }
+EOF
+echo "NEW_FORMAT_SECRET=value" > .env
+
+status=0
+secretspec get NEW_FORMAT_SECRET --provider dotenv://.env 2> /dev/null || status=$?
+[ $status -eq 4 ]
+check_success "An unsupported revision is rejected by default"
+
+[ "$(secretspec get NEW_FORMAT_SECRET --provider dotenv://.env --allow-revision 2.0 2> get.err)" = "value" ] && grep "revision '2.0'" get.err > /dev/null
+check_success "--allow-revision reads the file with a warning"
+
+[ "$(SECRETSPEC_ALLOW_REVISION=2.0 secretspec get NEW_FORMAT_SECRET --provider dotenv://.env 2> /dev/null)" = "value" ]
+check_success "SECRETSPEC_ALLOW_REVISION reads the file"
+cd ..
+
+# Test 33: Running the command in another directory
+mkdir -p run-cd/subdir
+cd run-cd
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+CD_SECRET = { description = "Secret", default = "value" }
+EOF
+
+[ "$(secretspec run --cd subdir -- pwd)" = "$(pwd)/subdir" ]
+check_success "run --cd starts the command in the directory"
+
+status=0
+secretspec run --cd missing -- pwd 2> run.err || status=$?
+[ $status -ne 0 ] && grep "missing does not exist" run.err > /dev/null
+check_success "run --cd rejects a missing directory"
+cd ..
+
+# Test 34: Writing an audit snapshot from check
+mkdir -p audit
+cd audit
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+AUDIT_PRESENT = { description = "Present secret" }
+AUDIT_MISSING = { description = "Missing secret" }
+EOF
+echo "AUDIT_PRESENT=audit-secret-value" > .env
+
+status=0
+secretspec check --provider dotenv://.env --audit-out audit.json < /dev/null > /dev/null 2>&1 || status=$?
+grep '"AUDIT_PRESENT": "present"' audit.json > /dev/null && grep '"AUDIT_MISSING": "missing"' audit.json > /dev/null && ! grep "audit-secret-value" audit.json > /dev/null
+check_success "check --audit-out records statuses without values"
+cd ..
+
+# Test 35: Getting a secret that isn't set
+mkdir -p allow-missing
+cd allow-missing
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+UNSET_SECRET = { description = "Secret without a value", required = false }
+EOF
+
+status=0
+secretspec get UNSET_SECRET --provider dotenv://.env > /dev/null 2>&1 || status=$?
+[ $status -ne 0 ]
+check_success "get fails for a secret without a value or default"
+
+value=$(secretspec get UNSET_SECRET --provider dotenv://.env --allow-missing) && [ -z "$value" ]
+check_success "get --allow-missing prints nothing and succeeds"
+
+status=0
+secretspec get UNDECLARED --provider dotenv://.env --allow-missing > /dev/null 2>&1 || status=$?
+[ $status -ne 0 ]
+check_success "get --allow-missing still rejects undeclared secrets"
+cd ..
+
+# Test 36: Showing the global configuration as JSON
+mkdir -p config-json/xdg/secretspec
+cd config-json
+cat > xdg/secretspec/config.toml << EOF
+[defaults]
+provider = "keyring"
+profile = "development"
+EOF
+
+XDG_CONFIG_HOME="$PWD/xdg" secretspec config show --json > show.json
+grep "\"path\": \"$PWD/xdg/secretspec/config.toml\"" show.json > /dev/null && grep '"exists": true' show.json > /dev/null && grep '"provider": "keyring"' show.json > /dev/null && grep '"profile": "development"' show.json > /dev/null && grep '"cache_ttl": null' show.json > /dev/null
+check_success "config show --json prints the configuration and its path"
+
+XDG_CONFIG_HOME="$PWD/missing" secretspec config show --json > show.json
+grep '"exists": false' show.json > /dev/null && grep '"provider": null' show.json > /dev/null
+check_success "config show --json reports a missing configuration file"
+cd ..
+
+# Test 37: Warning about secrets due for rotation
+mkdir -p rotate-after
+cd rotate-after
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+OLD_TOKEN = { description = "Token rotated every 30 days", rotate_after_days = 30 }
+EOF
+echo "OLD_TOKEN=old" > .env
+echo "OLD_TOKEN=1000000000" > .env.set_at
+
+secretspec check --provider dotenv://.env --format plain > check.out
+grep "^rotate	OLD_TOKEN" check.out > /dev/null && grep "1 due for rotation" check.out > /dev/null
+check_success "check warns about a secret set longer ago than rotate_after_days"
+
+echo "new" | secretspec set OLD_TOKEN --provider dotenv://.env > /dev/null
+secretspec check --provider dotenv://.env --format plain > check.out
+grep "^found	OLD_TOKEN" check.out > /dev/null
+check_success "set records when the secret was set"
+cd ..
+
+# Test 38: Porcelain output of check
+mkdir -p porcelain
+cd porcelain
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+SET_SECRET = { description = "Set secret" }
+UNSET_SECRET = { description = "Unset secret" }
+DEFAULTED_SECRET = { description = "Defaulted secret", default = "x" }
+EOF
+echo "SET_SECRET=value" > .env
+
+status=0
+secretspec check --provider dotenv://.env --porcelain > check.out 2> /dev/null < /dev/null || status=$?
+[ $status -eq 2 ] && [ "$(cat check.out)" = "$(printf 'DEFAULT\tDEFAULTED_SECRET\tdefault\nPRESENT\tSET_SECRET\tdefault\nMISSING_REQUIRED\tUNSET_SECRET\tdefault')" ]
+check_success "check --porcelain prints one line per secret and fails for missing ones"
+
+echo "UNSET_SECRET=value" >> .env
+[ "$(secretspec check --provider dotenv://.env --porcelain | awk '$1 == "PRESENT" { print $2 }' | tr '\n' ' ')" = "SET_SECRET UNSET_SECRET " ]
+check_success "check --porcelain succeeds once every required secret is set"
+cd ..
+
+# Test 39: Sourced secrets are read from the other project's own .env
+mkdir -p source/a source/b
+cat > source/a/secretspec.toml << EOF
+[project]
+name = "service-a"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL" }
+EOF
+cat > source/b/secretspec.toml << EOF
+[project]
+name = "service-b"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL", source = "../a#DATABASE_URL" }
+EOF
+echo "DATABASE_URL=from-a" > source/a/.env
+echo "DATABASE_URL=from-b" > source/b/.env
+cd source/b
+[ "$(secretspec get DATABASE_URL --provider dotenv://.env)" = "from-a" ]
+check_success "source reads the other project's relative dotenv file"
+cd ../..
+
+# Test 40: The env provider reads variables under its prefix
+mkdir -p env-prefix
+cd env-prefix
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+DATABASE_URL = { description = "Database URL" }
+EOF
+[ "$(APP_DATABASE_URL=prefixed DATABASE_URL=unprefixed secretspec get DATABASE_URL --provider 'env://?prefix=APP_')" = "prefixed" ]
+check_success "env://?prefix= reads the prefixed variable"
+status=0
+DATABASE_URL=unprefixed secretspec get DATABASE_URL --provider 'env://?prefix=APP_' > /dev/null 2>&1 || status=$?
+[ $status -ne 0 ]
+check_success "env://?prefix= ignores the unprefixed variable"
+cd ..
+
+# Test 41: Flags take precedence over SECRETSPEC_PROVIDER and SECRETSPEC_PROFILE,
+# which take precedence over secretspec.toml
+mkdir -p precedence
+cd precedence
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+provider = "env"
+API_KEY = { description = "API key" }
+
+[profiles.staging]
+API_KEY = { description = "API key" }
+EOF
+SECRETSPEC_PROVIDER=dotenv://env.env SECRETSPEC_PROFILE=staging secretspec config resolve > resolve.out
+grep -q "^Provider: dotenv://env.env (from environment variable)$" resolve.out
+grep -q "^Profile: staging (from environment variable)$" resolve.out
+check_success "SECRETSPEC_PROVIDER and SECRETSPEC_PROFILE override secretspec.toml"
+
+for args in "--provider dotenv://flag.env config resolve --profile default" "config resolve -p dotenv://flag.env -P default"; do
+    # shellcheck disable=SC2086
+    SECRETSPEC_PROVIDER=dotenv://env.env SECRETSPEC_PROFILE=staging secretspec $args > resolve.out
+    grep -q "^Provider: dotenv://flag.env (from argument)$" resolve.out
+    grep -q "^Profile: default (from argument)$" resolve.out
+done
+check_success "--provider and --profile override the environment before and after the command"
+
+env -u SECRETSPEC_PROVIDER -u SECRETSPEC_PROFILE secretspec config resolve > resolve.out
+grep -q "^Provider: env (from profile in secretspec.toml)$" resolve.out
+check_success "secretspec.toml is used without flags or environment variables"
+cd ..
+
+# Test 42: Relative provider files are next to a secretspec.toml found in a parent directory
+mkdir -p nested/src/deep
+cd nested
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+EOF
+echo "API_KEY=from-root" > .env
+cd src/deep
+[ "$(secretspec get API_KEY --provider dotenv://.env)" = "from-root" ]
+check_success "A subdirectory reads the .env file next to secretspec.toml"
+secretspec set API_KEY from-subdir --provider dotenv://.env > /dev/null
+[ ! -e .env ] && grep -q "^API_KEY=.*from-subdir" ../../.env
+check_success "A subdirectory writes the .env file next to secretspec.toml"
+cd ../../..
+
+# Test 43: check warns once, on stderr, when the provider ignores profiles
+mkdir -p profile-warning
+cd profile-warning
+cat > secretspec.toml << EOF
+[project]
+name = "test-app"
+revision = "1.0"
+
+[profiles.default]
+API_KEY = { description = "API key" }
+
+[profiles.staging]
+EOF
+API_KEY=set secretspec check --provider env --profile staging > check.out 2> check.err
+! grep -q "does not support profiles" check.out
+[ "$(grep -c "does not support profiles" check.err)" -eq 1 ]
+check_success "check prints the profile warning once, on stderr"
+API_KEY=set secretspec check --quiet --provider env --profile staging > check.out 2> check.err
+! grep -q "does not support profiles" check.err
+check_success "check --quiet skips the profile warning"
+cd ..
+
 # Cleanup
 cd ..
 rm -rf "$TEST_DIR"